@@ -25,6 +25,7 @@ const DT: f32 = 0.1;
 const SIMULATION_TIME: f32 = 30.0;
 
 /// Drone state for simulation
+#[allow(dead_code)]
 struct DroneState {
     id: usize,
     position: [f32; 3],
@@ -130,7 +131,7 @@ fn run_simulation(algorithm: AvoidanceAlgorithm) -> (usize, f32, usize) {
     let mut collision_count = 0;
     let mut total_path_length = 0.0f32;
     let steps = (SIMULATION_TIME / DT) as usize;
-    let mut reached_target = vec![false; NUM_DRONES];
+    let mut reached_target = [false; NUM_DRONES];
 
     for _step in 0..steps {
         // Compute safe velocities for each drone
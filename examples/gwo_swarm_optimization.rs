@@ -29,6 +29,7 @@ fn main() {
 }
 
 /// Example 1: Simple optimization using sphere function
+#[allow(clippy::field_reassign_with_default)]
 fn example_sphere_optimization() {
     println!("\n=== Example 1: Sphere Function Optimization ===\n");
     println!("Objective: Minimize f(x) = x1² + x2² + ... + xn²");
@@ -86,6 +87,7 @@ fn example_sphere_optimization() {
 }
 
 /// Example 2: Complex optimization using Rastrigin function
+#[allow(clippy::field_reassign_with_default)]
 fn example_rastrigin_optimization() {
     println!("\n=== Example 2: Rastrigin Function Optimization ===\n");
     println!("Objective: Minimize f(x) = 10n + Σ(xi² - 10cos(2πxi))");
@@ -111,7 +111,7 @@ fn main() {
         total_calc_time += calc_duration;
 
         // 3d. Update Physics (Simple Euler integration for the ego drone)
-        let mut current_state = controller.local_state().clone();
+        let mut current_state = *controller.local_state();
         current_state.position.x += _cmd_vel.vx * TIME_STEP;
         current_state.position.y += _cmd_vel.vy * TIME_STEP;
         current_state.position.z += _cmd_vel.vz * TIME_STEP;
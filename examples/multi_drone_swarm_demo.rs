@@ -110,6 +110,7 @@ impl Drone {
 
 /// Swarm formation patterns
 #[derive(Debug, Clone, Copy)]
+#[allow(dead_code, clippy::enum_variant_names)]
 enum Formation {
     VFormation,
     Circle,
@@ -254,6 +255,7 @@ impl SwarmController {
     }
 
     /// Update swarm using PSO-based formation control
+    #[allow(clippy::needless_range_loop)]
     fn update_pso(&mut self, dt: f32) {
         let formation_positions = self.calculate_formation_positions();
 
@@ -69,7 +69,7 @@ fn example_sphere_function() -> Result<()> {
     print!("  Iteration: ");
 
     for i in 0..100 {
-        let cost = pso.step(cost_fn)?;
+        pso.step(cost_fn)?;
         if i % 10 == 0 {
             print!("{} ", i);
         }
@@ -25,6 +25,7 @@ const NUM_DRONES: usize = 16;
 const SIMULATION_STEPS: usize = 100;
 
 /// Generate simulated drone status
+#[allow(clippy::field_reassign_with_default)]
 fn generate_drone_status(id: u8, step: usize) -> DroneStatus {
     let angle = (step as f32 * 0.1) + (id as f32 * 0.5);
     let radius = 20.0 + (id as f32);
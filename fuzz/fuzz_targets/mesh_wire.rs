@@ -0,0 +1,18 @@
+#![no_main]
+
+use libfuzzer_sys::fuzz_target;
+use drone_swarm_system::mesh_protocol::{MeshMessage, WireFrame, MAX_WIRE_FRAME_SIZE};
+
+fuzz_target!(|data: &[u8]| {
+    // Walk arbitrary bytes as a stream of mesh wire frames
+    let mut offset = 0;
+    while let Ok((frame, used)) = MeshMessage::decode(&data[offset..]) {
+        // Decoded messages must re-encode to a frame that decodes again
+        if let WireFrame::Message(msg) = frame {
+            let mut buf = [0u8; MAX_WIRE_FRAME_SIZE];
+            let len = msg.encode_into(&mut buf).expect("decoded message re-encodes");
+            assert!(MeshMessage::decode(&buf[..len]).is_ok());
+        }
+        offset += used;
+    }
+});
@@ -0,0 +1,626 @@
+//! Drone identity certificates and revocation
+//!
+//! [`KeyStore::add_key`] trusts whatever key it is handed. Certificates tie a
+//! drone's signing key to its identity under a ground-station root key that
+//! every drone is flashed with:
+//! - A [`DroneCertificate`] binds a [`DroneId`] and its Ed25519 key to a
+//!   [`SecurityLevel`], a validity window and [`Capabilities`], signed by the
+//!   root
+//! - A [`TrustAnchor`] on each drone verifies certificates before admitting a
+//!   joining drone to the [`KeyStore`] and [`AccessControl`]
+//! - A [`RevocationList`] signed by the root is flooded through the mesh (see
+//!   [`MeshNode::broadcast_revocations`](crate::esp32_mesh::MeshNode::broadcast_revocations));
+//!   each list is complete and versioned, so a drone only needs the latest one
+//!
+//! The ground station mints certificates for new airframes with a
+//! [`Provisioner`] (requires `std`). Revoked serials drop off the list once
+//! their certificate has expired, which keeps it within one mesh frame.
+
+use crate::crypto::{KeyStore, SIGNATURE_SIZE};
+use crate::security::AccessControl;
+use crate::types::*;
+use ed25519_dalek::{Signature, Verifier, VerifyingKey};
+use heapless::Vec;
+use serde::{Deserialize, Serialize};
+
+#[cfg(feature = "std")]
+use crate::rng::SecureRng;
+#[cfg(feature = "std")]
+use ed25519_dalek::{Signer, SigningKey};
+
+/// Revoked serials a [`RevocationList`] can carry
+///
+/// A full list still encodes into a single wire frame.
+pub const MAX_REVOKED_CERTIFICATES: usize = 40;
+
+/// Domain separation of certificate signatures
+const CERTIFICATE_CONTEXT: &str = "droneswarm-v1 drone certificate";
+
+/// Domain separation of revocation list signatures
+const REVOCATION_CONTEXT: &str = "droneswarm-v1 revocation list";
+
+/// What a drone is cleared to do, as a bit set
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Capabilities(pub u16);
+
+impl Capabilities {
+    /// No capabilities
+    pub const NONE: Self = Self(0);
+    /// Relay mesh traffic for other drones
+    pub const RELAY: Self = Self(1 << 0);
+    /// Act as swarm or formation leader
+    pub const LEADER: Self = Self(1 << 1);
+    /// Link to the ground control station
+    pub const GCS_LINK: Self = Self(1 << 2);
+    /// Carry and release a payload
+    pub const PAYLOAD: Self = Self(1 << 3);
+    /// Record imagery
+    pub const CAMERA: Self = Self(1 << 4);
+
+    /// Check that every capability in `other` is present
+    pub fn contains(self, other: Self) -> bool {
+        self.0 & other.0 == other.0
+    }
+
+    /// Union of two sets
+    pub fn with(self, other: Self) -> Self {
+        Self(self.0 | other.0)
+    }
+}
+
+/// Identity certificate of one airframe, signed by the ground-station root
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct DroneCertificate {
+    /// Serial number, unique per root
+    pub serial: u32,
+    /// Drone the certificate identifies
+    pub drone_id: DroneId,
+    /// Drone's Ed25519 public key
+    pub public_key: [u8; 32],
+    /// Clearance granted in [`AccessControl`]
+    pub level: SecurityLevel,
+    /// Cleared capabilities
+    pub capabilities: Capabilities,
+    /// Start of validity (swarm time, ms)
+    pub not_before_ms: u64,
+    /// End of validity (swarm time, ms)
+    pub not_after_ms: u64,
+    /// Root signature over all fields above
+    pub signature: Vec<u8, SIGNATURE_SIZE>,
+}
+
+impl DroneCertificate {
+    /// Digest covered by the signature
+    fn digest(&self) -> [u8; 32] {
+        let mut hasher = blake3::Hasher::new_derive_key(CERTIFICATE_CONTEXT);
+        hasher.update(&self.serial.to_le_bytes());
+        hasher.update(&self.drone_id.as_u64().to_le_bytes());
+        hasher.update(&self.public_key);
+        hasher.update(&[self.level as u8]);
+        hasher.update(&self.capabilities.0.to_le_bytes());
+        hasher.update(&self.not_before_ms.to_le_bytes());
+        hasher.update(&self.not_after_ms.to_le_bytes());
+        *hasher.finalize().as_bytes()
+    }
+
+    /// Check whether `now_ms` falls in the validity window
+    pub fn is_valid_at(&self, now_ms: u64) -> bool {
+        (self.not_before_ms..=self.not_after_ms).contains(&now_ms)
+    }
+
+    /// Check whether the drone is cleared for `capabilities`
+    pub fn permits(&self, capabilities: Capabilities) -> bool {
+        self.capabilities.contains(capabilities)
+    }
+}
+
+/// Complete list of revoked serials, signed by the ground-station root
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct RevocationList {
+    /// List version; a higher version supersedes a lower one
+    pub version: u32,
+    /// Revoked certificate serials
+    pub serials: Vec<u32, MAX_REVOKED_CERTIFICATES>,
+    /// Root signature over the version and serials
+    pub signature: Vec<u8, SIGNATURE_SIZE>,
+}
+
+impl RevocationList {
+    /// Digest covered by the signature
+    fn digest(&self) -> [u8; 32] {
+        let mut hasher = blake3::Hasher::new_derive_key(REVOCATION_CONTEXT);
+        hasher.update(&self.version.to_le_bytes());
+        for serial in &self.serials {
+            hasher.update(&serial.to_le_bytes());
+        }
+        *hasher.finalize().as_bytes()
+    }
+
+    /// Check whether `serial` is on the list
+    pub fn contains(&self, serial: u32) -> bool {
+        self.serials.contains(&serial)
+    }
+}
+
+/// Verify a root signature over `digest`
+fn verify_root(root: &VerifyingKey, digest: &[u8; 32], signature: &[u8]) -> Result<()> {
+    let signature =
+        Signature::from_slice(signature).map_err(|_| SwarmError::AuthenticationFailed)?;
+    root.verify(digest, &signature)
+        .map_err(|_| SwarmError::AuthenticationFailed)
+}
+
+/// Ground-station root key and current revocations, as held by a drone
+pub struct TrustAnchor {
+    /// Root key certificates must be signed with
+    root: VerifyingKey,
+    /// Latest revocation list accepted
+    revocations: RevocationList,
+}
+
+impl TrustAnchor {
+    /// Trust certificates signed by `root`
+    pub fn new(root: VerifyingKey) -> Self {
+        Self {
+            root,
+            revocations: RevocationList {
+                version: 0,
+                serials: Vec::new(),
+                signature: Vec::new(),
+            },
+        }
+    }
+
+    /// Root key certificates are checked against
+    pub fn root_key(&self) -> &VerifyingKey {
+        &self.root
+    }
+
+    /// Version of the revocation list in force
+    pub fn revocation_version(&self) -> u32 {
+        self.revocations.version
+    }
+
+    /// Check whether a certificate serial has been revoked
+    pub fn is_revoked(&self, serial: u32) -> bool {
+        self.revocations.contains(serial)
+    }
+
+    /// Verify a certificate and return the key it certifies
+    ///
+    /// Fails with `AuthenticationFailed` if the root did not sign it or the key
+    /// is malformed, and with `PermissionDenied` if it is revoked or outside
+    /// its validity window.
+    pub fn verify(&self, certificate: &DroneCertificate, now_ms: u64) -> Result<VerifyingKey> {
+        verify_root(&self.root, &certificate.digest(), &certificate.signature)?;
+        if self.is_revoked(certificate.serial) || !certificate.is_valid_at(now_ms) {
+            return Err(SwarmError::PermissionDenied);
+        }
+        VerifyingKey::from_bytes(&certificate.public_key)
+            .map_err(|_| SwarmError::AuthenticationFailed)
+    }
+
+    /// Admit a joining drone: verify its certificate, then register its key
+    /// and clearance
+    pub fn admit(
+        &self,
+        certificate: &DroneCertificate,
+        keys: &mut KeyStore,
+        access: &mut AccessControl,
+        now_ms: u64,
+    ) -> Result<()> {
+        let public_key = self.verify(certificate, now_ms)?;
+        keys.add_key(certificate.drone_id, public_key)?;
+        access.authorize(certificate.drone_id, certificate.level)
+    }
+
+    /// Check that the root signed a revocation list
+    pub fn verify_revocations(&self, list: &RevocationList) -> Result<()> {
+        verify_root(&self.root, &list.digest(), &list.signature)
+    }
+
+    /// Install a newer revocation list
+    ///
+    /// Returns `false` for a list no newer than the one in force, e.g. a copy
+    /// relayed back by a neighbor. Every list is verified, so a forged one
+    /// fails with `AuthenticationFailed` whatever its version.
+    pub fn apply_revocations(&mut self, list: &RevocationList) -> Result<bool> {
+        self.verify_revocations(list)?;
+        if list.version <= self.revocations.version {
+            return Ok(false);
+        }
+        self.revocations = list.clone();
+        Ok(true)
+    }
+}
+
+/// Identity minted for a new airframe
+#[cfg(feature = "std")]
+pub struct ProvisionedIdentity {
+    /// Ed25519 seed to flash into the drone (see [`CryptoContext::with_keys`](crate::crypto::CryptoContext::with_keys))
+    pub signing_seed: [u8; 32],
+    /// Certificate for the seed's public key
+    pub certificate: DroneCertificate,
+}
+
+/// Ground-station certificate authority
+///
+/// # Security Note
+/// The root seed signs every identity in the swarm. Keep it on the ground
+/// station, never on an airframe.
+#[cfg(feature = "std")]
+pub struct Provisioner {
+    /// Root signing key
+    root: SigningKey,
+    /// Serial of the next certificate
+    next_serial: u32,
+    /// Every certificate minted, in serial order
+    issued: std::vec::Vec<DroneCertificate>,
+    /// Latest signed revocation list
+    revocations: RevocationList,
+}
+
+#[cfg(feature = "std")]
+impl Provisioner {
+    /// Create an authority from a root seed
+    pub fn new(root_seed: [u8; 32]) -> Self {
+        Self {
+            root: SigningKey::from_bytes(&root_seed),
+            next_serial: 1,
+            issued: std::vec::Vec::new(),
+            revocations: RevocationList {
+                version: 0,
+                serials: Vec::new(),
+                signature: Vec::new(),
+            },
+        }
+    }
+
+    /// Create an authority with a root seed from the system RNG
+    pub fn generate() -> Result<Self> {
+        let mut seed = [0u8; 32];
+        SecureRng::new()?.fill_bytes(&mut seed)?;
+        Ok(Self::new(seed))
+    }
+
+    /// Root public key to flash into every drone's [`TrustAnchor`]
+    pub fn root_key(&self) -> VerifyingKey {
+        self.root.verifying_key()
+    }
+
+    /// Trust anchor with the current revocation list applied
+    pub fn trust_anchor(&self) -> TrustAnchor {
+        TrustAnchor {
+            root: self.root_key(),
+            revocations: self.revocations.clone(),
+        }
+    }
+
+    /// Certify an existing drone key
+    pub fn certify(
+        &mut self,
+        drone_id: DroneId,
+        public_key: &VerifyingKey,
+        level: SecurityLevel,
+        capabilities: Capabilities,
+        not_before_ms: u64,
+        not_after_ms: u64,
+    ) -> Result<DroneCertificate> {
+        if not_after_ms < not_before_ms {
+            return Err(SwarmError::InvalidParameter);
+        }
+        let mut certificate = DroneCertificate {
+            serial: self.next_serial,
+            drone_id,
+            public_key: public_key.to_bytes(),
+            level,
+            capabilities,
+            not_before_ms,
+            not_after_ms,
+            signature: Vec::new(),
+        };
+        let signature = self.root.sign(&certificate.digest()).to_bytes();
+        certificate.signature = Vec::from_slice(&signature).map_err(|_| SwarmError::BufferFull)?;
+
+        self.next_serial = self
+            .next_serial
+            .checked_add(1)
+            .ok_or(SwarmError::ResourceExhausted)?;
+        self.issued.push(certificate.clone());
+        Ok(certificate)
+    }
+
+    /// Draw a signing key for a new airframe and certify it from `now_ms`
+    /// for `validity_ms`
+    pub fn provision(
+        &mut self,
+        drone_id: DroneId,
+        level: SecurityLevel,
+        capabilities: Capabilities,
+        now_ms: u64,
+        validity_ms: u64,
+    ) -> Result<ProvisionedIdentity> {
+        let mut signing_seed = [0u8; 32];
+        SecureRng::new()?.fill_bytes(&mut signing_seed)?;
+        let public_key = SigningKey::from_bytes(&signing_seed).verifying_key();
+        let certificate = self.certify(
+            drone_id,
+            &public_key,
+            level,
+            capabilities,
+            now_ms,
+            now_ms.saturating_add(validity_ms),
+        )?;
+        Ok(ProvisionedIdentity {
+            signing_seed,
+            certificate,
+        })
+    }
+
+    /// Certificates minted so far
+    pub fn issued(&self) -> &[DroneCertificate] {
+        &self.issued
+    }
+
+    /// Revoke a certificate and sign the new revocation list to broadcast
+    ///
+    /// Serials of certificates that expired before `now_ms` are dropped from
+    /// the list, since no drone accepts them anyway.
+    pub fn revoke(&mut self, serial: u32, now_ms: u64) -> Result<RevocationList> {
+        if !self.issued.iter().any(|cert| cert.serial == serial) {
+            return Err(SwarmError::InvalidParameter);
+        }
+
+        let mut serials: Vec<u32, MAX_REVOKED_CERTIFICATES> = Vec::new();
+        for &revoked in self.revocations.serials.iter().chain([serial].iter()) {
+            let live = self
+                .issued
+                .iter()
+                .any(|cert| cert.serial == revoked && cert.not_after_ms >= now_ms);
+            if live && !serials.contains(&revoked) {
+                serials
+                    .push(revoked)
+                    .map_err(|_| SwarmError::ResourceExhausted)?;
+            }
+        }
+
+        let mut list = RevocationList {
+            version: self.revocations.version + 1,
+            serials,
+            signature: Vec::new(),
+        };
+        let signature = self.root.sign(&list.digest()).to_bytes();
+        list.signature = Vec::from_slice(&signature).map_err(|_| SwarmError::BufferFull)?;
+        self.revocations = list.clone();
+        Ok(list)
+    }
+
+    /// Latest signed revocation list
+    pub fn revocations(&self) -> &RevocationList {
+        &self.revocations
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::crypto::CryptoContext;
+
+    const HOUR_MS: u64 = 3_600_000;
+
+    fn authority() -> Provisioner {
+        Provisioner::new([42; 32])
+    }
+
+    #[test]
+    fn test_provisioned_identity_verifies() {
+        let mut ca = authority();
+        let identity = ca
+            .provision(
+                DroneId::new(7),
+                SecurityLevel::Sensitive,
+                Capabilities::RELAY.with(Capabilities::CAMERA),
+                1000,
+                HOUR_MS,
+            )
+            .unwrap();
+
+        // The certified key is the one the drone signs with
+        let drone = CryptoContext::with_keys([0; 32], identity.signing_seed);
+        let anchor = TrustAnchor::new(ca.root_key());
+        let key = anchor.verify(&identity.certificate, 2000).unwrap();
+        assert_eq!(&key, drone.public_key());
+        assert!(identity.certificate.permits(Capabilities::CAMERA));
+        assert!(!identity.certificate.permits(Capabilities::LEADER));
+    }
+
+    #[test]
+    fn test_forged_certificate_rejected() {
+        let mut ca = authority();
+        let mut rogue = Provisioner::new([13; 32]);
+        let key = *CryptoContext::new([1; 32]).public_key();
+        let anchor = TrustAnchor::new(ca.root_key());
+
+        let foreign = rogue
+            .certify(
+                DroneId::new(1),
+                &key,
+                SecurityLevel::Critical,
+                Capabilities::NONE,
+                0,
+                HOUR_MS,
+            )
+            .unwrap();
+        assert_eq!(
+            anchor.verify(&foreign, 10).unwrap_err(),
+            SwarmError::AuthenticationFailed
+        );
+
+        // Raising the clearance breaks the signature
+        let mut escalated = ca
+            .certify(
+                DroneId::new(1),
+                &key,
+                SecurityLevel::Internal,
+                Capabilities::NONE,
+                0,
+                HOUR_MS,
+            )
+            .unwrap();
+        escalated.level = SecurityLevel::Critical;
+        assert_eq!(
+            anchor.verify(&escalated, 10).unwrap_err(),
+            SwarmError::AuthenticationFailed
+        );
+    }
+
+    #[test]
+    fn test_validity_window() {
+        let mut ca = authority();
+        let key = *CryptoContext::new([1; 32]).public_key();
+        let cert = ca
+            .certify(
+                DroneId::new(1),
+                &key,
+                SecurityLevel::Internal,
+                Capabilities::NONE,
+                1000,
+                2000,
+            )
+            .unwrap();
+        let anchor = TrustAnchor::new(ca.root_key());
+
+        assert_eq!(
+            anchor.verify(&cert, 999).unwrap_err(),
+            SwarmError::PermissionDenied
+        );
+        assert!(anchor.verify(&cert, 1000).is_ok());
+        assert!(anchor.verify(&cert, 2000).is_ok());
+        assert_eq!(
+            anchor.verify(&cert, 2001).unwrap_err(),
+            SwarmError::PermissionDenied
+        );
+
+        assert_eq!(
+            ca.certify(
+                DroneId::new(1),
+                &key,
+                SecurityLevel::Internal,
+                Capabilities::NONE,
+                5,
+                4
+            )
+            .unwrap_err(),
+            SwarmError::InvalidParameter
+        );
+    }
+
+    #[test]
+    fn test_admit_registers_key_and_clearance() {
+        let mut ca = authority();
+        let identity = ca
+            .provision(
+                DroneId::new(3),
+                SecurityLevel::Internal,
+                Capabilities::RELAY,
+                0,
+                HOUR_MS,
+            )
+            .unwrap();
+        let anchor = TrustAnchor::new(ca.root_key());
+        let mut keys = KeyStore::new();
+        let mut access = AccessControl::new();
+
+        anchor
+            .admit(&identity.certificate, &mut keys, &mut access, 100)
+            .unwrap();
+        assert!(keys.has_key(DroneId::new(3)));
+        assert!(access
+            .check_permission(DroneId::new(3), SecurityLevel::Internal)
+            .is_ok());
+        assert!(access
+            .check_permission(DroneId::new(3), SecurityLevel::Sensitive)
+            .is_err());
+    }
+
+    #[test]
+    fn test_revocation_list() {
+        let mut ca = authority();
+        let first = ca
+            .provision(
+                DroneId::new(1),
+                SecurityLevel::Internal,
+                Capabilities::NONE,
+                0,
+                HOUR_MS,
+            )
+            .unwrap();
+        let second = ca
+            .provision(
+                DroneId::new(2),
+                SecurityLevel::Internal,
+                Capabilities::NONE,
+                0,
+                HOUR_MS,
+            )
+            .unwrap();
+        let mut anchor = TrustAnchor::new(ca.root_key());
+
+        let list = ca.revoke(first.certificate.serial, 10).unwrap();
+        assert!(anchor.apply_revocations(&list).unwrap());
+        assert_eq!(anchor.revocation_version(), 1);
+        assert_eq!(
+            anchor.verify(&first.certificate, 20).unwrap_err(),
+            SwarmError::PermissionDenied
+        );
+        assert!(anchor.verify(&second.certificate, 20).is_ok());
+
+        // Relayed copies are ignored; forged lists rejected
+        assert!(!anchor.apply_revocations(&list).unwrap());
+        let mut forged = list.clone();
+        forged.version = 2;
+        forged.serials.clear();
+        assert_eq!(
+            anchor.apply_revocations(&forged).unwrap_err(),
+            SwarmError::AuthenticationFailed
+        );
+        // Even one too old to install
+        forged.version = 1;
+        assert_eq!(
+            anchor.apply_revocations(&forged).unwrap_err(),
+            SwarmError::AuthenticationFailed
+        );
+        assert!(anchor.is_revoked(first.certificate.serial));
+
+        assert_eq!(ca.revoke(99, 10).unwrap_err(), SwarmError::InvalidParameter);
+    }
+
+    #[test]
+    fn test_expired_serials_pruned() {
+        let mut ca = authority();
+        let short = ca
+            .provision(
+                DroneId::new(1),
+                SecurityLevel::Internal,
+                Capabilities::NONE,
+                0,
+                1000,
+            )
+            .unwrap();
+        let long = ca
+            .provision(
+                DroneId::new(2),
+                SecurityLevel::Internal,
+                Capabilities::NONE,
+                0,
+                HOUR_MS,
+            )
+            .unwrap();
+
+        ca.revoke(short.certificate.serial, 500).unwrap();
+        let list = ca.revoke(long.certificate.serial, 5000).unwrap();
+        assert_eq!(list.version, 2);
+        assert_eq!(&list.serials[..], &[long.certificate.serial]);
+        assert!(ca.trust_anchor().is_revoked(long.certificate.serial));
+    }
+}
@@ -147,4 +147,8 @@ pub enum RoutingProtocol {
     Flooding,
     /// Gradient-based routing
     GradientBased,
+    /// Proactive link-state routing (OLSR-style, MPR flooding)
+    LinkState,
+    /// Greedy perimeter stateless routing (GPSR) on drone positions
+    Geographic,
 }
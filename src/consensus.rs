@@ -8,10 +8,16 @@
 //! - Resource-constrained optimization
 
 use crate::merkle::MerkleTree;
+use crate::rbac::{CommandPolicy, CommandScope};
+use crate::reputation::TrustPolicy;
+use crate::security::IntrusionDetectionSystem;
 use crate::types::*;
 use heapless::{FnvIndexMap, Vec};
 use serde::{Deserialize, Serialize};
 
+/// Maximum log entries replicated in one AppendEntries message
+pub const MAX_ENTRIES_PER_APPEND: usize = 8;
+
 /// Raft node states
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum NodeState {
@@ -30,6 +36,8 @@ pub struct LogEntry {
     pub term: u64,
     /// Command index
     pub index: u64,
+    /// Drone on whose behalf the command was proposed
+    pub issuer: DroneId,
     /// Command data
     pub command: SwarmCommand,
 }
@@ -50,6 +58,8 @@ pub enum SwarmCommand {
     EmergencyStop,
     /// Change formation
     ChangeFormation { formation_type: u8 },
+    /// Ratchet the shared network key to this epoch (see [`crate::key_rotation`])
+    RotateNetworkKey { epoch: u32 },
 }
 
 /// Consensus messages for Raft protocol
@@ -87,6 +97,18 @@ pub enum ConsensusMessage {
     },
 }
 
+impl ConsensusMessage {
+    /// Drone that sent the message
+    pub fn sender(&self) -> DroneId {
+        match self {
+            Self::RequestVote { candidate_id, .. } => *candidate_id,
+            Self::VoteReply { voter_id, .. } => *voter_id,
+            Self::AppendEntries { leader_id, .. } => *leader_id,
+            Self::AppendEntriesReply { follower_id, .. } => *follower_id,
+        }
+    }
+}
+
 /// Raft consensus state machine
 pub struct ConsensusEngine {
     /// This node's ID
@@ -101,8 +123,7 @@ pub struct ConsensusEngine {
     log: Vec<LogEntry, 1000>,
     /// Index of highest log entry known to be committed
     commit_index: u64,
-    /// Index of highest log entry applied to state machine (reserved for state machine integration)
-    #[allow(dead_code)]
+    /// Index of highest log entry handed out by [`take_committed`](Self::take_committed)
     last_applied: u64,
     /// For leaders: next log index to send to each follower
     next_index: FnvIndexMap<u64, u64, 128>,
@@ -120,6 +141,10 @@ pub struct ConsensusEngine {
     votes_received: u8,
     /// Swarm members
     swarm_members: Vec<DroneId, 100>,
+    /// Members whose votes are not counted and who get no vote
+    excluded: Vec<DroneId, 100>,
+    /// Who may propose which command
+    command_policy: CommandPolicy,
 }
 
 impl ConsensusEngine {
@@ -141,6 +166,8 @@ impl ConsensusEngine {
             current_leader: None,
             votes_received: 0,
             swarm_members: Vec::new(),
+            excluded: Vec::new(),
+            command_policy: CommandPolicy::new(),
         }
     }
 
@@ -188,15 +215,41 @@ impl ConsensusEngine {
         }
     }
 
-    /// Propose a new command (leader only)
-    pub fn propose_command(&mut self, command: SwarmCommand) -> Result<u64> {
+    /// Process a consensus message after checking its sender's behavior
+    ///
+    /// The message goes through `ids` first (floods, term inflation); those
+    /// from banned senders are refused with [`SwarmError::PermissionDenied`].
+    /// `msg` must come from an authenticated channel, or a spoofed sender
+    /// could get another drone banned.
+    pub fn process_monitored(
+        &mut self,
+        msg: ConsensusMessage,
+        ids: &mut IntrusionDetectionSystem,
+        now_ms: u64,
+    ) -> Result<Option<ConsensusMessage>> {
+        ids.observe_consensus(msg.sender(), &msg, now_ms)?;
+        self.process_message(msg)
+    }
+
+    /// Propose a new command on behalf of `issuer` (leader only)
+    ///
+    /// Fails with [`SwarmError::PermissionDenied`] unless the command policy
+    /// lets `issuer` order the command swarm-wide.
+    pub fn propose_command(
+        &mut self,
+        issuer: DroneId,
+        command: SwarmCommand,
+        now_ms: u64,
+    ) -> Result<u64> {
         if self.state != NodeState::Leader {
             return Err(SwarmError::ConsensusError);
         }
+        self.authorize(issuer, &command, now_ms)?;
 
         let entry = LogEntry {
             term: self.current_term,
             index: self.log.len() as u64 + 1,
+            issuer,
             command,
         };
 
@@ -208,6 +261,35 @@ impl ConsensusEngine {
         Ok(index)
     }
 
+    /// Policy checked on every proposal and replicated entry
+    pub fn command_policy(&self) -> &CommandPolicy {
+        &self.command_policy
+    }
+
+    /// Policy for assigning roles and accepting delegations
+    ///
+    /// The leader role follows elections automatically.
+    pub fn command_policy_mut(&mut self) -> &mut CommandPolicy {
+        &mut self.command_policy
+    }
+
+    /// Replace the command policy
+    pub fn set_command_policy(&mut self, policy: CommandPolicy) {
+        self.command_policy = policy;
+    }
+
+    /// Check that `issuer` may order `command` swarm-wide
+    fn authorize(&mut self, issuer: DroneId, command: &SwarmCommand, now_ms: u64) -> Result<()> {
+        let leader = if self.state == NodeState::Leader {
+            Some(self.node_id)
+        } else {
+            self.current_leader
+        };
+        self.command_policy.set_leader(leader);
+        self.command_policy
+            .authorize(issuer, command.into(), CommandScope::Swarm, now_ms)
+    }
+
     /// Handle election timeout
     pub fn tick(&mut self) -> Result<Vec<ConsensusMessage, 10>> {
         let current_time = Self::get_time();
@@ -286,7 +368,7 @@ impl ConsensusEngine {
             self.become_follower(term);
         }
 
-        let vote_granted = if term < self.current_term {
+        let vote_granted = if term < self.current_term || self.excluded.contains(&candidate_id) {
             false
         } else if let Some(voted) = self.voted_for {
             voted == candidate_id
@@ -317,9 +399,9 @@ impl ConsensusEngine {
         &mut self,
         term: u64,
         vote_granted: bool,
-        _voter_id: DroneId,
+        voter_id: DroneId,
     ) -> Result<()> {
-        if self.state != NodeState::Candidate {
+        if self.state != NodeState::Candidate || self.excluded.contains(&voter_id) {
             return Ok(());
         }
 
@@ -358,54 +440,63 @@ impl ConsensusEngine {
         self.current_leader = Some(leader_id);
         self.election_timer = Self::get_time(); // Reset election timer
 
-        let success = if term < self.current_term {
-            false
-        } else {
-            // Check log consistency
-            if prev_log_index > 0 {
-                if let Some(entry) = self.log.get(prev_log_index as usize - 1) {
-                    if entry.term != prev_log_term {
-                        false
-                    } else {
-                        // Append entries
-                        for entry in entries {
-                            let index = entry.index as usize;
-                            if index <= self.log.len() {
-                                // Replace conflicting entry
-                                self.log[index - 1] = entry;
-                            } else {
-                                self.log
-                                    .push(entry)
-                                    .map_err(|_| SwarmError::ResourceExhausted)?;
-                            }
-                        }
-                        true
-                    }
-                } else {
-                    false
+        // Check log consistency
+        let consistent = term >= self.current_term
+            && (prev_log_index == 0
+                || self
+                    .log
+                    .get(prev_log_index as usize - 1)
+                    .is_some_and(|entry| entry.term == prev_log_term));
+
+        let mut match_index = self.log.len() as u64;
+        if consistent {
+            // Entries must directly follow prev_log_index
+            if (prev_log_index + 1..)
+                .zip(&entries)
+                .any(|(index, entry)| entry.index != index)
+            {
+                return Err(SwarmError::InvalidMessage);
+            }
+
+            // A leader may only replicate what the policy allows
+            let now_ms = Self::get_time();
+            for entry in &entries {
+                let known = (entry.index as usize)
+                    .checked_sub(1)
+                    .and_then(|slot| self.log.get(slot))
+                    .is_some_and(|existing| existing.term == entry.term);
+                if !known {
+                    self.authorize(entry.issuer, &entry.command, now_ms)?;
                 }
-            } else {
-                // First entry
-                for entry in entries {
-                    self.log
-                        .push(entry)
-                        .map_err(|_| SwarmError::ResourceExhausted)?;
+            }
+
+            match_index = prev_log_index + entries.len() as u64;
+            for entry in entries {
+                let slot = (entry.index as usize)
+                    .checked_sub(1)
+                    .ok_or(SwarmError::InvalidMessage)?;
+                match self.log.get(slot) {
+                    // Already have it
+                    Some(existing) if existing.term == entry.term => continue,
+                    // Conflict: drop it and everything after
+                    Some(_) => self.log.truncate(slot),
+                    None => {}
                 }
-                true
+                self.log
+                    .push(entry)
+                    .map_err(|_| SwarmError::ResourceExhausted)?;
             }
-        };
 
-        if success {
             // Update commit index
             if leader_commit > self.commit_index {
-                self.commit_index = core::cmp::min(leader_commit, self.log.len() as u64);
+                self.commit_index = core::cmp::min(leader_commit, match_index);
             }
         }
 
         Ok(Some(ConsensusMessage::AppendEntriesReply {
             term: self.current_term,
-            success,
-            match_index: self.log.len() as u64,
+            success: consistent,
+            match_index,
             follower_id: self.node_id,
         }))
     }
@@ -490,7 +581,14 @@ impl ConsensusEngine {
             0
         };
 
-        let entries = Vec::new(); // Heartbeat (no entries)
+        // Entries the follower is missing; none makes this a heartbeat
+        let entries = self
+            .log
+            .iter()
+            .skip(prev_log_index as usize)
+            .take(MAX_ENTRIES_PER_APPEND)
+            .cloned()
+            .collect();
 
         Ok(ConsensusMessage::AppendEntries {
             term: self.current_term,
@@ -531,6 +629,27 @@ impl ConsensusEngine {
         self.log.last().map(|e| e.term).unwrap_or(0)
     }
 
+    /// Take the entries committed since the last call, in log order
+    ///
+    /// Returns up to `N` entries; call again until empty to catch up. Every
+    /// entry passed the command policy when it entered this node's log.
+    pub fn take_committed<const N: usize>(&mut self) -> Vec<LogEntry, N> {
+        let mut commands = Vec::new();
+        while self.last_applied < self.commit_index && !commands.is_full() {
+            let Some(entry) = self.log.get(self.last_applied as usize) else {
+                break;
+            };
+            commands.push(entry.clone()).ok();
+            self.last_applied += 1;
+        }
+        commands
+    }
+
+    /// Index of the highest committed log entry
+    pub fn commit_index(&self) -> u64 {
+        self.commit_index
+    }
+
     /// Add swarm member
     pub fn add_member(&mut self, drone_id: DroneId) -> Result<()> {
         if !self.swarm_members.contains(&drone_id) {
@@ -541,6 +660,38 @@ impl ConsensusEngine {
         Ok(())
     }
 
+    /// Stop counting votes from, and granting votes to, untrusted members
+    ///
+    /// The majority is still taken over all members, so excluding peers
+    /// never lowers the quorum. Call again whenever trust scores change.
+    pub fn exclude_untrusted(&mut self, policy: &impl TrustPolicy) {
+        self.excluded.clear();
+        for &member in &self.swarm_members {
+            if member != self.node_id && !policy.is_trusted(member) {
+                // Capacity matches swarm_members
+                self.excluded.push(member).ok();
+            }
+        }
+    }
+
+    /// Get this node's ID
+    pub fn node_id(&self) -> DroneId {
+        self.node_id
+    }
+
+    /// Get current term
+    pub fn current_term(&self) -> u64 {
+        self.current_term
+    }
+
+    /// Get swarm members
+    ///
+    /// Messages returned by [`tick`](Self::tick) are addressed to these members
+    /// in order, skipping this node.
+    pub fn members(&self) -> &[DroneId] {
+        &self.swarm_members
+    }
+
     /// Get current state
     pub fn state(&self) -> NodeState {
         self.state
@@ -603,7 +754,273 @@ mod tests {
             task_id: 42,
         };
 
-        let result = engine.propose_command(cmd);
+        let result = engine.propose_command(DroneId::new(1), cmd, 0);
         assert!(result.is_ok());
     }
+
+    #[test]
+    fn test_propose_authorized() {
+        use crate::rbac::Roles;
+
+        let mut engine = ConsensusEngine::new(DroneId::new(1), 150);
+        engine.state = NodeState::Leader;
+        let policy = engine.command_policy_mut();
+        policy
+            .assign(DroneId::new(0), Roles::GROUND_STATION)
+            .unwrap();
+        policy.assign(DroneId::new(2), Roles::MEMBER).unwrap();
+
+        assert_eq!(
+            engine.propose_command(DroneId::new(2), SwarmCommand::EmergencyStop, 0),
+            Err(SwarmError::PermissionDenied)
+        );
+        assert_eq!(
+            engine
+                .propose_command(DroneId::new(0), SwarmCommand::EmergencyStop, 0)
+                .unwrap(),
+            1
+        );
+    }
+
+    fn entry(term: u64, index: u64) -> LogEntry {
+        LogEntry {
+            term,
+            index,
+            issuer: DroneId::new(1),
+            command: SwarmCommand::ChangeFormation {
+                formation_type: index as u8,
+            },
+        }
+    }
+
+    fn append(
+        term: u64,
+        prev_log_index: u64,
+        prev_log_term: u64,
+        entries: &[LogEntry],
+    ) -> ConsensusMessage {
+        ConsensusMessage::AppendEntries {
+            term,
+            leader_id: DroneId::new(1),
+            prev_log_index,
+            prev_log_term,
+            entries: Vec::from_slice(entries).unwrap(),
+            leader_commit: 0,
+        }
+    }
+
+    #[test]
+    fn test_replication_batches_missing_entries() {
+        let mut leader = ConsensusEngine::new(DroneId::new(1), 150);
+        let mut follower = ConsensusEngine::new(DroneId::new(2), 150);
+        for engine in [&mut leader, &mut follower] {
+            engine.add_member(DroneId::new(1)).unwrap();
+            engine.add_member(DroneId::new(2)).unwrap();
+        }
+        leader.become_leader().unwrap();
+        for formation_type in 0..10 {
+            leader
+                .propose_command(
+                    DroneId::new(1),
+                    SwarmCommand::ChangeFormation { formation_type },
+                    0,
+                )
+                .unwrap();
+        }
+
+        // The follower's log is empty: send the first batch
+        let batch = leader.create_append_entries(DroneId::new(2)).unwrap();
+        let ConsensusMessage::AppendEntries { entries, .. } = &batch else {
+            panic!("expected AppendEntries");
+        };
+        assert_eq!(entries.len(), MAX_ENTRIES_PER_APPEND);
+        let reply = follower.process_message(batch).unwrap().unwrap();
+        leader.process_message(reply).unwrap();
+        assert_eq!(leader.commit_index(), MAX_ENTRIES_PER_APPEND as u64);
+
+        // The rest follows, carrying the new commit index
+        let batch = leader.create_append_entries(DroneId::new(2)).unwrap();
+        let reply = follower.process_message(batch).unwrap().unwrap();
+        leader.process_message(reply).unwrap();
+        assert_eq!(follower.log.len(), 10);
+        assert_eq!(leader.commit_index(), 10);
+        assert_eq!(follower.commit_index(), MAX_ENTRIES_PER_APPEND as u64);
+
+        // Committed entries come out once each, in log order
+        let first = follower.take_committed::<4>();
+        let rest = follower.take_committed::<8>();
+        assert_eq!(
+            first
+                .iter()
+                .chain(&rest)
+                .map(|e| e.index)
+                .collect::<Vec<u64, 16>>(),
+            [1, 2, 3, 4, 5, 6, 7, 8]
+        );
+        assert!(follower.take_committed::<8>().is_empty());
+    }
+
+    #[test]
+    fn test_conflicting_entries_truncated() {
+        let mut follower = ConsensusEngine::new(DroneId::new(2), 150);
+        follower
+            .process_message(append(1, 0, 0, &[entry(1, 1), entry(1, 2), entry(1, 3)]))
+            .unwrap();
+
+        // A new leader overwrites the uncommitted tail
+        let reply = follower
+            .process_message(append(2, 1, 1, &[entry(2, 2)]))
+            .unwrap();
+        assert!(matches!(
+            reply,
+            Some(ConsensusMessage::AppendEntriesReply {
+                success: true,
+                match_index: 2,
+                ..
+            })
+        ));
+        assert_eq!(follower.log.len(), 2);
+        assert_eq!(follower.log[1].term, 2);
+
+        // A repeated batch changes nothing
+        follower
+            .process_message(append(2, 1, 1, &[entry(2, 2)]))
+            .unwrap();
+        assert_eq!(follower.log.len(), 2);
+
+        // Entries that do not follow prev_log_index are refused
+        assert_eq!(
+            follower
+                .process_message(append(2, 2, 2, &[entry(2, 4)]))
+                .unwrap_err(),
+            SwarmError::InvalidMessage
+        );
+        assert_eq!(follower.log.len(), 2);
+    }
+
+    #[test]
+    fn test_follower_refuses_unauthorized_entries() {
+        use crate::rbac::Roles;
+
+        let mut follower = ConsensusEngine::new(DroneId::new(2), 150);
+        follower
+            .command_policy_mut()
+            .assign(DroneId::new(3), Roles::MEMBER)
+            .unwrap();
+        let append = |issuer| {
+            let mut entries = Vec::new();
+            entries
+                .push(LogEntry {
+                    term: 1,
+                    index: 1,
+                    issuer: DroneId::new(issuer),
+                    command: SwarmCommand::EmergencyStop,
+                })
+                .unwrap();
+            ConsensusMessage::AppendEntries {
+                term: 1,
+                leader_id: DroneId::new(1),
+                prev_log_index: 0,
+                prev_log_term: 0,
+                entries,
+                leader_commit: 1,
+            }
+        };
+
+        // A leader cannot slip in a swarm-wide stop from a member
+        assert_eq!(
+            follower.process_message(append(3)).unwrap_err(),
+            SwarmError::PermissionDenied
+        );
+        assert_eq!(follower.take_committed::<4>().len(), 0);
+
+        // Its own commands carry the leader role
+        follower.process_message(append(1)).unwrap();
+        let committed = follower.take_committed::<4>();
+        assert_eq!(committed.len(), 1);
+        assert_eq!(committed[0].issuer, DroneId::new(1));
+    }
+
+    #[test]
+    fn test_vote_flood_refused() {
+        let mut engine = ConsensusEngine::new(DroneId::new(1), 150);
+        let mut ids = IntrusionDetectionSystem::new();
+        let vote = |term| ConsensusMessage::RequestVote {
+            term,
+            candidate_id: DroneId::new(2),
+            last_log_index: 0,
+            last_log_term: 0,
+        };
+
+        // Term inflation and vote flooding quickly exhaust trust
+        let mut refused = false;
+        for term in (1..).step_by(10).take(20) {
+            if engine.process_monitored(vote(term), &mut ids, 0).is_err() {
+                refused = true;
+                break;
+            }
+        }
+        assert!(refused);
+        assert!(ids.is_banned(DroneId::new(2)));
+        assert_eq!(
+            engine
+                .process_monitored(vote(1000), &mut ids, 5000)
+                .unwrap_err(),
+            SwarmError::PermissionDenied
+        );
+    }
+
+    #[test]
+    fn test_untrusted_votes_excluded() {
+        use crate::reputation::{ReputationConfig, ReputationSystem};
+
+        let mut engine = ConsensusEngine::new(DroneId::new(1), 150);
+        for i in 1..=5 {
+            engine.add_member(DroneId::new(i)).unwrap();
+        }
+        let mut reputation = ReputationSystem::new(DroneId::new(1), ReputationConfig::default());
+        reputation.record(DroneId::new(2), 0.0, 20.0).unwrap();
+        reputation.record(DroneId::new(3), 0.0, 20.0).unwrap();
+        engine.exclude_untrusted(&reputation);
+
+        // Untrusted candidates get no vote
+        let reply = engine
+            .process_message(ConsensusMessage::RequestVote {
+                term: 1,
+                candidate_id: DroneId::new(2),
+                last_log_index: 0,
+                last_log_term: 0,
+            })
+            .unwrap();
+        assert!(matches!(
+            reply,
+            Some(ConsensusMessage::VoteReply {
+                vote_granted: false,
+                ..
+            })
+        ));
+
+        // Two untrusted votes do not complete a majority of five
+        engine.start_election().unwrap();
+        let term = engine.current_term();
+        for voter in [2, 3, 4] {
+            engine
+                .process_message(ConsensusMessage::VoteReply {
+                    term,
+                    vote_granted: true,
+                    voter_id: DroneId::new(voter),
+                })
+                .unwrap();
+        }
+        assert_eq!(engine.state(), NodeState::Candidate);
+
+        engine
+            .process_message(ConsensusMessage::VoteReply {
+                term,
+                vote_granted: true,
+                voter_id: DroneId::new(5),
+            })
+            .unwrap();
+        assert_eq!(engine.state(), NodeState::Leader);
+    }
 }
@@ -6,9 +6,18 @@
 //! - X25519 for key exchange
 //! - BLAKE3 for fast hashing
 //! - SHA3 for security-critical hashing
-//! - Replay attack protection via nonces
+//! - Replay attack protection via per-peer sliding nonce windows
 //! - Perfect forward secrecy
+//! - Epoch-numbered key rotation with a one-way ratchet
+//! - ML-KEM-768/X25519 hybrid key exchange and ML-DSA-65/Ed25519 dual
+//!   signatures (`post-quantum` feature)
 
+#[cfg(feature = "post-quantum")]
+use crate::post_quantum::{
+    self, ml_dsa, HybridCiphertext, HybridPublicKey, HybridSecretKey, DUAL_SIGNATURE_CONTEXT,
+};
+#[cfg(feature = "post-quantum")]
+use crate::rng::SecureRng;
 use crate::types::*;
 use crate::KEY_SIZE;
 use aead::{AeadInPlace, KeyInit};
@@ -27,13 +36,57 @@ pub const SIGNATURE_SIZE: usize = 64;
 /// Maximum encrypted message overhead
 pub const CRYPTO_OVERHEAD: usize = TAG_SIZE + SIGNATURE_SIZE + 12; // tag + sig + nonce
 
+/// Maximum overhead of a dual-signed message (ML-DSA-65 signature added)
+#[cfg(feature = "post-quantum")]
+pub const DUAL_CRYPTO_OVERHEAD: usize = CRYPTO_OVERHEAD + ml_dsa::SIGNATURE_SIZE;
+
+/// Capacity of a dual-signed message: 2048 bytes plus the ML-DSA-65 signature
+#[cfg(feature = "post-quantum")]
+pub const DUAL_SEALED_CAPACITY: usize = 2048 + ml_dsa::SIGNATURE_SIZE;
+
 /// Maximum safe encryptions before rekeying recommended
 pub const MAX_SAFE_ENCRYPTIONS: u64 = 1_000_000_000; // 1 billion
 
+/// Maximum epochs a key can be ratcheted forward in one step
+pub const MAX_RATCHET_STEPS: u32 = 16;
+
+/// Maximum epochs a drone that fell behind can skip to catch up
+pub const MAX_RESYNC_STEPS: u32 = 4096;
+
+/// Domain separation for the key ratchet
+const RATCHET_CONTEXT: &str = "droneswarm-v1 network key ratchet";
+
+/// When to move the symmetric key to the next epoch
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct RekeyPolicy {
+    /// Encryptions under one key before rekeying
+    pub max_encryptions: u64,
+    /// Key lifetime (ms), if keys also expire by age
+    pub max_key_age_ms: Option<u64>,
+    /// How long the previous key still decrypts after a rotation (ms)
+    pub acceptance_window_ms: u64,
+}
+
+impl Default for RekeyPolicy {
+    fn default() -> Self {
+        Self {
+            max_encryptions: MAX_SAFE_ENCRYPTIONS,
+            max_key_age_ms: None,
+            acceptance_window_ms: 5000,
+        }
+    }
+}
+
+/// Key of the previous epoch, kept for messages still in flight
+struct RetiredKey {
+    epoch: u32,
+    cipher: ChaCha20Poly1305,
+    expires_ms: u64,
+}
+
 /// Cryptographic context for secure communication
 pub struct CryptoContext {
-    /// Symmetric encryption key (stored for key rotation)
-    #[allow(dead_code)]
+    /// Symmetric encryption key of the current epoch
     symmetric_key: [u8; KEY_SIZE],
     /// Pre-initialized cipher (reusable for better performance)
     cipher: ChaCha20Poly1305,
@@ -41,8 +94,21 @@ pub struct CryptoContext {
     signing_key: SigningKey,
     /// Ed25519 verification key (public)
     verify_key: VerifyingKey,
-    /// Nonce counter for replay protection
+    /// Nonce counter for replay protection (keeps counting across epochs)
     nonce_counter: u64,
+    /// Key epoch, carried in every nonce
+    epoch: u32,
+    /// Encryptions under the current key
+    epoch_encryptions: u64,
+    /// Time the current key was taken into use (ms)
+    epoch_started_ms: u64,
+    /// Previous key within its acceptance window
+    retired: Option<RetiredKey>,
+    /// Rekeying thresholds
+    policy: RekeyPolicy,
+    /// ML-DSA-65 secret and public key, when dual signatures are enabled
+    #[cfg(feature = "post-quantum")]
+    dual: Option<(ml_dsa::SecretKey, ml_dsa::PublicKey)>,
 }
 
 impl CryptoContext {
@@ -71,25 +137,100 @@ impl CryptoContext {
             signing_key,
             verify_key,
             nonce_counter: 0,
+            epoch: 0,
+            epoch_encryptions: 0,
+            epoch_started_ms: 0,
+            retired: None,
+            policy: RekeyPolicy::default(),
+            #[cfg(feature = "post-quantum")]
+            dual: None,
+        }
+    }
+
+    /// Create a context from a shared network key and a per-drone signing seed
+    ///
+    /// Every swarm member encrypts with the same `network_key` but signs with
+    /// its own key, so receivers can tell which drone sealed a message.
+    pub fn with_keys(network_key: [u8; KEY_SIZE], signing_seed: [u8; 32]) -> Self {
+        let signing_key = SigningKey::from_bytes(&signing_seed);
+        let verify_key = signing_key.verifying_key();
+        let cipher =
+            ChaCha20Poly1305::new_from_slice(&network_key).expect("32-byte key is always valid");
+
+        Self {
+            symmetric_key: network_key,
+            cipher,
+            signing_key,
+            verify_key,
+            nonce_counter: 0,
+            epoch: 0,
+            epoch_encryptions: 0,
+            epoch_started_ms: 0,
+            retired: None,
+            policy: RekeyPolicy::default(),
+            #[cfg(feature = "post-quantum")]
+            dual: None,
         }
     }
 
     /// Encrypt and authenticate a message
     ///
-    /// Returns: [nonce || ciphertext || tag || signature]
+    /// Returns: [nonce || ciphertext || tag || signature], where the nonce is
+    /// the message counter followed by the key epoch
     pub fn encrypt_and_sign(
         &mut self,
         plaintext: &[u8],
         associated_data: &[u8],
     ) -> Result<Vec<u8, 2048>> {
+        self.seal(plaintext, associated_data)
+    }
+
+    /// Encrypt a message and sign it with both ML-DSA-65 and Ed25519
+    ///
+    /// Returns: [nonce || ciphertext || tag || signature || ML-DSA signature],
+    /// the ML-DSA-65 signature covering all of the
+    /// [`encrypt_and_sign`](Self::encrypt_and_sign) output. Fails with
+    /// `ConfigError` unless [`enable_dual_signatures`](Self::enable_dual_signatures)
+    /// was called.
+    #[cfg(feature = "post-quantum")]
+    pub fn encrypt_and_sign_dual(
+        &mut self,
+        plaintext: &[u8],
+        associated_data: &[u8],
+    ) -> Result<Vec<u8, DUAL_SEALED_CAPACITY>> {
+        if self.dual.is_none() {
+            return Err(SwarmError::ConfigError);
+        }
+        let mut message: Vec<u8, DUAL_SEALED_CAPACITY> = self.seal(plaintext, associated_data)?;
+
+        let mut rnd = [0u8; 32];
+        SecureRng::new()?.fill_bytes(&mut rnd)?;
+        let (secret, _) = self.dual.as_ref().ok_or(SwarmError::ConfigError)?;
+        let signature = ml_dsa::sign(secret, &message, DUAL_SIGNATURE_CONTEXT, &rnd)
+            .ok_or(SwarmError::CryptoError)?;
+        message
+            .extend_from_slice(&signature)
+            .map_err(|_| SwarmError::BufferFull)?;
+        Ok(message)
+    }
+
+    /// Encrypt and Ed25519-sign into a buffer of `N` bytes
+    fn seal<const N: usize>(
+        &mut self,
+        plaintext: &[u8],
+        associated_data: &[u8],
+    ) -> Result<Vec<u8, N>> {
         // BUG-001 FIX: Generate unique nonce with overflow protection
         self.nonce_counter = self
             .nonce_counter
             .checked_add(1)
             .ok_or(SwarmError::CryptoError)?; // Fail securely on overflow
 
+        self.epoch_encryptions += 1;
+
         let mut nonce_bytes = [0u8; 12];
         nonce_bytes[..8].copy_from_slice(&self.nonce_counter.to_le_bytes());
+        nonce_bytes[8..].copy_from_slice(&self.epoch.to_le_bytes());
         let nonce = Nonce::from_slice(&nonce_bytes);
 
         // BUG-002 FIX: Use pre-initialized cipher (5-10x faster)
@@ -105,7 +246,7 @@ impl CryptoContext {
             .map_err(|_| SwarmError::CryptoError)?;
 
         // Create message: nonce || ciphertext || tag
-        let mut message = Vec::<u8, 2048>::new();
+        let mut message = Vec::<u8, N>::new();
         message
             .extend_from_slice(&nonce_bytes)
             .map_err(|_| SwarmError::BufferFull)?;
@@ -130,12 +271,69 @@ impl CryptoContext {
     /// Verify and decrypt a message
     ///
     /// Expected format: [nonce || ciphertext || tag || signature]
+    ///
+    /// Accepts the current epoch, the previous one within its acceptance
+    /// window and the next one, so messages sealed around a rotation still
+    /// decrypt.
     pub fn verify_and_decrypt(
         &self,
         message: &[u8],
         associated_data: &[u8],
         sender_public_key: &VerifyingKey,
     ) -> Result<Vec<u8, 2048>> {
+        let signed_data = Self::verify_signature(message, sender_public_key)?;
+
+        let epoch = Self::sealed_epoch(signed_data);
+        let next_cipher;
+        let cipher = match &self.retired {
+            _ if epoch == self.epoch => &self.cipher,
+            Some(retired) if retired.epoch == epoch => &retired.cipher,
+            // Sender already rotated; the next key is the ratchet of ours
+            _ if Some(epoch) == self.epoch.checked_add(1) => {
+                next_cipher = ChaCha20Poly1305::new_from_slice(&Self::ratchet_key(
+                    &self.symmetric_key,
+                    epoch,
+                ))
+                .expect("32-byte key is always valid");
+                &next_cipher
+            }
+            _ => return Err(SwarmError::AuthenticationFailed),
+        };
+        Self::decrypt(cipher, signed_data, associated_data)
+    }
+
+    /// Verify and decrypt a message sealed under an epoch too far ahead to
+    /// accept, then move to that epoch
+    ///
+    /// For a drone that missed more rotations than the next-epoch lookahead
+    /// covers, e.g. after a reboot or a partition. The signature is checked
+    /// before any ratcheting, and the epoch is only adopted if the ratcheted
+    /// key decrypts the message. At most [`MAX_RESYNC_STEPS`] epochs are
+    /// skipped.
+    pub fn resync(
+        &mut self,
+        message: &[u8],
+        associated_data: &[u8],
+        sender_public_key: &VerifyingKey,
+        now_ms: u64,
+    ) -> Result<Vec<u8, 2048>> {
+        let signed_data = Self::verify_signature(message, sender_public_key)?;
+
+        let epoch = Self::sealed_epoch(signed_data);
+        let key = self
+            .ratchet_to(epoch, MAX_RESYNC_STEPS)
+            .ok_or(SwarmError::AuthenticationFailed)?;
+        let cipher = ChaCha20Poly1305::new_from_slice(&key).expect("32-byte key is always valid");
+        let plaintext = Self::decrypt(&cipher, signed_data, associated_data)?;
+        self.install_key(epoch, key, now_ms)?;
+        Ok(plaintext)
+    }
+
+    /// Check the trailing Ed25519 signature and return the data it covers
+    fn verify_signature<'a>(
+        message: &'a [u8],
+        sender_public_key: &VerifyingKey,
+    ) -> Result<&'a [u8]> {
         if message.len() < 12 + TAG_SIZE + SIGNATURE_SIZE {
             return Err(SwarmError::InvalidMessage);
         }
@@ -154,20 +352,29 @@ impl CryptoContext {
         sender_public_key
             .verify(signed_data, &signature)
             .map_err(|_| SwarmError::AuthenticationFailed)?;
+        Ok(signed_data)
+    }
 
+    /// Key epoch carried in the nonce of [nonce || ciphertext || tag]
+    fn sealed_epoch(signed_data: &[u8]) -> u32 {
+        let mut epoch = [0u8; 4];
+        epoch.copy_from_slice(&signed_data[8..12]);
+        u32::from_le_bytes(epoch)
+    }
+
+    /// Decrypt [nonce || ciphertext || tag] with `cipher`
+    fn decrypt(
+        cipher: &ChaCha20Poly1305,
+        signed_data: &[u8],
+        associated_data: &[u8],
+    ) -> Result<Vec<u8, 2048>> {
         // Extract nonce, ciphertext, and tag
-        let nonce_bytes = &signed_data[..12];
-        let nonce = Nonce::from_slice(nonce_bytes);
+        let nonce = Nonce::from_slice(&signed_data[..12]);
 
         // Last TAG_SIZE bytes are the authentication tag
-        if signed_data.len() < 12 + TAG_SIZE {
-            return Err(SwarmError::InvalidMessage);
-        }
-
         let ciphertext_end = signed_data.len() - TAG_SIZE;
         let ciphertext = &signed_data[12..ciphertext_end];
-        let tag_bytes = &signed_data[ciphertext_end..];
-        let tag = Tag::from_slice(tag_bytes);
+        let tag = Tag::from_slice(&signed_data[ciphertext_end..]);
 
         // Decrypt using pre-initialized cipher with in-place API
         let mut buffer = Vec::<u8, 2048>::new();
@@ -175,13 +382,68 @@ impl CryptoContext {
             .extend_from_slice(ciphertext)
             .map_err(|_| SwarmError::BufferFull)?;
 
-        self.cipher
+        cipher
             .decrypt_in_place_detached(nonce, associated_data, &mut buffer, tag)
             .map_err(|_| SwarmError::AuthenticationFailed)?;
 
         Ok(buffer)
     }
 
+    /// Verify both signatures of a dual-signed message and decrypt it
+    ///
+    /// Expected format: [nonce || ciphertext || tag || signature || ML-DSA signature]
+    #[cfg(feature = "post-quantum")]
+    pub fn verify_and_decrypt_dual(
+        &self,
+        message: &[u8],
+        associated_data: &[u8],
+        sender_public_key: &VerifyingKey,
+        sender_ml_dsa_key: &ml_dsa::PublicKey,
+    ) -> Result<Vec<u8, 2048>> {
+        let classic = Self::verify_ml_dsa_signature(message, sender_ml_dsa_key)?;
+        self.verify_and_decrypt(classic, associated_data, sender_public_key)
+    }
+
+    /// [`resync`](Self::resync) for a dual-signed message
+    #[cfg(feature = "post-quantum")]
+    pub fn resync_dual(
+        &mut self,
+        message: &[u8],
+        associated_data: &[u8],
+        sender_public_key: &VerifyingKey,
+        sender_ml_dsa_key: &ml_dsa::PublicKey,
+        now_ms: u64,
+    ) -> Result<Vec<u8, 2048>> {
+        let classic = Self::verify_ml_dsa_signature(message, sender_ml_dsa_key)?;
+        self.resync(classic, associated_data, sender_public_key, now_ms)
+    }
+
+    /// Check the trailing ML-DSA-65 signature and return the data it covers
+    #[cfg(feature = "post-quantum")]
+    fn verify_ml_dsa_signature<'a>(
+        message: &'a [u8],
+        sender_ml_dsa_key: &ml_dsa::PublicKey,
+    ) -> Result<&'a [u8]> {
+        // A message without room for the ML-DSA signature is a downgrade attempt
+        let split = message
+            .len()
+            .checked_sub(ml_dsa::SIGNATURE_SIZE)
+            .ok_or(SwarmError::AuthenticationFailed)?;
+        let (classic, signature) = message.split_at(split);
+        let signature: &ml_dsa::Signature = signature
+            .try_into()
+            .map_err(|_| SwarmError::InvalidMessage)?;
+        if !ml_dsa::verify(
+            sender_ml_dsa_key,
+            classic,
+            DUAL_SIGNATURE_CONTEXT,
+            signature,
+        ) {
+            return Err(SwarmError::AuthenticationFailed);
+        }
+        Ok(classic)
+    }
+
     /// Compute BLAKE3 hash (fast, suitable for checksums)
     pub fn fast_hash(data: &[u8]) -> [u8; 32] {
         let mut hasher = Blake3Hasher::new();
@@ -199,7 +461,121 @@ impl CryptoContext {
 
     /// Check if rekeying is recommended
     pub fn needs_rekeying(&self) -> bool {
-        self.nonce_counter >= MAX_SAFE_ENCRYPTIONS
+        self.epoch_encryptions >= self.policy.max_encryptions
+    }
+
+    /// Check if the current key has reached its message count or age limit
+    pub fn rekey_due(&self, now_ms: u64) -> bool {
+        self.needs_rekeying()
+            || self
+                .policy
+                .max_key_age_ms
+                .is_some_and(|age| now_ms.saturating_sub(self.epoch_started_ms) >= age)
+    }
+
+    /// Current key epoch
+    pub fn epoch(&self) -> u32 {
+        self.epoch
+    }
+
+    /// Rekeying thresholds
+    pub fn rekey_policy(&self) -> &RekeyPolicy {
+        &self.policy
+    }
+
+    /// Set the rekeying thresholds
+    pub fn set_rekey_policy(&mut self, policy: RekeyPolicy) {
+        self.policy = policy;
+    }
+
+    /// Key of epoch `epoch` ratcheted from the key of the epoch before
+    ///
+    /// One-way: a captured key reveals later keys, but none of the earlier ones.
+    pub fn ratchet_key(key: &[u8; KEY_SIZE], epoch: u32) -> [u8; KEY_SIZE] {
+        let mut material = [0u8; KEY_SIZE + 4];
+        material[..KEY_SIZE].copy_from_slice(key);
+        material[KEY_SIZE..].copy_from_slice(&epoch.to_le_bytes());
+        blake3::derive_key(RATCHET_CONTEXT, &material)
+    }
+
+    /// Ratchet the symmetric key forward to `epoch`
+    ///
+    /// Every holder of the current key arrives at the same key, so the swarm
+    /// only has to agree on the epoch.
+    pub fn advance_epoch(&mut self, epoch: u32, now_ms: u64) -> Result<()> {
+        let key = self
+            .ratchet_to(epoch, MAX_RATCHET_STEPS)
+            .ok_or(SwarmError::InvalidParameter)?;
+        self.install_key(epoch, key, now_ms)
+    }
+
+    /// Ratchet the symmetric key forward to `epoch` after missing more than
+    /// [`MAX_RATCHET_STEPS`] rotations
+    ///
+    /// Only for an epoch learned from an authenticated source, such as the
+    /// committed consensus log. At most [`MAX_RESYNC_STEPS`] epochs are
+    /// skipped.
+    pub fn resync_epoch(&mut self, epoch: u32, now_ms: u64) -> Result<()> {
+        let key = self
+            .ratchet_to(epoch, MAX_RESYNC_STEPS)
+            .ok_or(SwarmError::InvalidParameter)?;
+        self.install_key(epoch, key, now_ms)
+    }
+
+    /// Key of a later `epoch`, if it is at most `max_steps` ahead
+    fn ratchet_to(&self, epoch: u32, max_steps: u32) -> Option<[u8; KEY_SIZE]> {
+        if epoch <= self.epoch || epoch - self.epoch > max_steps {
+            return None;
+        }
+        let mut key = self.symmetric_key;
+        for step in self.epoch + 1..=epoch {
+            key = Self::ratchet_key(&key, step);
+        }
+        Some(key)
+    }
+
+    /// Take a new symmetric key into use as `epoch`
+    ///
+    /// The current key keeps decrypting for the policy's acceptance window.
+    pub fn install_key(&mut self, epoch: u32, key: [u8; KEY_SIZE], now_ms: u64) -> Result<()> {
+        if epoch <= self.epoch {
+            return Err(SwarmError::InvalidParameter);
+        }
+        let cipher = ChaCha20Poly1305::new_from_slice(&key).expect("32-byte key is always valid");
+        let previous = core::mem::replace(&mut self.cipher, cipher);
+        self.retired = Some(RetiredKey {
+            epoch: self.epoch,
+            cipher: previous,
+            expires_ms: now_ms.saturating_add(self.policy.acceptance_window_ms),
+        });
+        self.symmetric_key = key;
+        self.epoch = epoch;
+        self.epoch_encryptions = 0;
+        self.epoch_started_ms = now_ms;
+        Ok(())
+    }
+
+    /// Drop the previous key once its acceptance window has passed
+    pub fn expire_retired_key(&mut self, now_ms: u64) {
+        if self
+            .retired
+            .as_ref()
+            .is_some_and(|retired| now_ms >= retired.expires_ms)
+        {
+            self.retired = None;
+        }
+    }
+
+    /// Check if the previous epoch's key still decrypts
+    pub fn has_retired_key(&self) -> bool {
+        self.retired.is_some()
+    }
+
+    /// Oldest key epoch that still decrypts
+    pub fn oldest_accepted_epoch(&self) -> u32 {
+        self.retired
+            .as_ref()
+            .map_or(self.epoch, |retired| retired.epoch)
     }
 
     /// Get public verification key
@@ -207,6 +583,33 @@ impl CryptoContext {
         &self.verify_key
     }
 
+    /// Sign a message with the drone's Ed25519 identity key
+    pub fn sign(&self, message: &[u8]) -> [u8; SIGNATURE_SIZE] {
+        self.signing_key.sign(message).to_bytes()
+    }
+
+    /// Set up the ML-DSA-65 key for [`Self::encrypt_and_sign_dual`]
+    ///
+    /// Receivers verify with [`Self::verify_and_decrypt_dual`] and this
+    /// context's [`Self::ml_dsa_public_key`].
+    #[cfg(feature = "post-quantum")]
+    pub fn enable_dual_signatures(&mut self, seed: &[u8; 32]) {
+        let (public, secret) = ml_dsa::key_gen(seed);
+        self.dual = Some((secret, public));
+    }
+
+    /// ML-DSA-65 public key, if dual signatures are enabled
+    #[cfg(feature = "post-quantum")]
+    pub fn ml_dsa_public_key(&self) -> Option<&ml_dsa::PublicKey> {
+        self.dual.as_ref().map(|(_, public)| public)
+    }
+
+    /// Derive the X25519 public key of a private key
+    pub fn exchange_public_key(private_key: &[u8; 32]) -> [u8; 32] {
+        let secret = x25519_dalek::StaticSecret::from(*private_key);
+        *x25519_dalek::PublicKey::from(&secret).as_bytes()
+    }
+
     /// Perform X25519 key exchange
     pub fn key_exchange(private_key: &[u8; 32], public_key: &[u8; 32]) -> Result<[u8; 32]> {
         let secret = x25519_dalek::StaticSecret::from(*private_key);
@@ -215,6 +618,25 @@ impl CryptoContext {
         Ok(*shared.as_bytes())
     }
 
+    /// Encapsulate a fresh shared secret to a hybrid ML-KEM-768/X25519 key
+    #[cfg(feature = "post-quantum")]
+    pub fn hybrid_encapsulate(
+        public_key: &HybridPublicKey,
+    ) -> Result<(HybridCiphertext, [u8; 32])> {
+        let mut randomness = [0u8; 64];
+        SecureRng::new()?.fill_bytes(&mut randomness)?;
+        post_quantum::encapsulate(public_key, &randomness)
+    }
+
+    /// Recover the shared secret of a hybrid ciphertext
+    #[cfg(feature = "post-quantum")]
+    pub fn hybrid_decapsulate(
+        secret_key: &HybridSecretKey,
+        ciphertext: &HybridCiphertext,
+    ) -> Result<[u8; 32]> {
+        secret_key.decapsulate(ciphertext)
+    }
+
     /// Derive session key from shared secret
     pub fn derive_session_key(shared_secret: &[u8; 32], context: &[u8]) -> [u8; 32] {
         let mut hasher = Sha3_256::new();
@@ -225,10 +647,17 @@ impl CryptoContext {
     }
 }
 
+/// Members whose ML-DSA-65 keys a [`KeyStore`] holds (power of 2)
+#[cfg(feature = "post-quantum")]
+pub const MAX_ML_DSA_KEYS: usize = 32;
+
 /// Secure key storage for swarm member keys
 pub struct KeyStore {
     /// Map of drone IDs to their public keys
     keys: heapless::FnvIndexMap<u64, VerifyingKey, 128>,
+    /// ML-DSA-65 keys of members that dual-sign
+    #[cfg(feature = "post-quantum")]
+    ml_dsa_keys: heapless::FnvIndexMap<u64, ml_dsa::PublicKey, MAX_ML_DSA_KEYS>,
 }
 
 impl KeyStore {
@@ -236,6 +665,8 @@ impl KeyStore {
     pub fn new() -> Self {
         Self {
             keys: heapless::FnvIndexMap::new(),
+            #[cfg(feature = "post-quantum")]
+            ml_dsa_keys: heapless::FnvIndexMap::new(),
         }
     }
 
@@ -259,6 +690,8 @@ impl KeyStore {
         self.keys
             .remove(&drone_id.as_u64())
             .ok_or(SwarmError::InvalidDroneId)?;
+        #[cfg(feature = "post-quantum")]
+        self.ml_dsa_keys.remove(&drone_id.as_u64());
         Ok(())
     }
 
@@ -266,6 +699,35 @@ impl KeyStore {
     pub fn has_key(&self, drone_id: DroneId) -> bool {
         self.keys.contains_key(&drone_id.as_u64())
     }
+
+    /// Drones with a registered key
+    pub fn drones(&self) -> impl Iterator<Item = DroneId> + '_ {
+        self.keys.keys().map(|id| DroneId::new(*id))
+    }
+
+    /// Add the ML-DSA-65 key of a member that dual-signs
+    ///
+    /// The member's Ed25519 key must be registered first.
+    #[cfg(feature = "post-quantum")]
+    pub fn add_ml_dsa_key(
+        &mut self,
+        drone_id: DroneId,
+        public_key: ml_dsa::PublicKey,
+    ) -> Result<()> {
+        if !self.has_key(drone_id) {
+            return Err(SwarmError::InvalidDroneId);
+        }
+        self.ml_dsa_keys
+            .insert(drone_id.as_u64(), public_key)
+            .map_err(|_| SwarmError::ResourceExhausted)?;
+        Ok(())
+    }
+
+    /// Get the ML-DSA-65 key of a drone, if it dual-signs
+    #[cfg(feature = "post-quantum")]
+    pub fn get_ml_dsa_key(&self, drone_id: DroneId) -> Option<&ml_dsa::PublicKey> {
+        self.ml_dsa_keys.get(&drone_id.as_u64())
+    }
 }
 
 impl Default for KeyStore {
@@ -274,36 +736,239 @@ impl Default for KeyStore {
     }
 }
 
-/// Replay attack protection via nonce tracking
+/// Nonces below the highest accepted one that are still tracked, per peer
+pub const REPLAY_WINDOW: u64 = 128;
+
+/// Peers a [`NonceTracker`] holds windows for (power of 2)
+///
+/// Covers every mesh node ID, so a mesh member is never turned away.
+pub const MAX_TRACKED_PEERS: usize = 256;
+
+/// Nonces reserved per [`ReplayStore`] write
+pub const REPLAY_RESERVATION: u64 = 1024;
+
+/// Durable storage for replay windows, so a reboot cannot reopen them
+///
+/// Rather than writing every nonce, the tracker reserves nonces in blocks of
+/// [`REPLAY_RESERVATION`]: before accepting a nonce above the reservation it
+/// asks the store to persist a new one. After a reboot,
+/// [`NonceTracker::restore`] treats every reserved nonce as seen, which may
+/// reject up to one block of legitimate messages but never a replay.
+pub trait ReplayStore {
+    /// Persist that nonces of `peer` in `epoch` up to `reserved` may have been
+    /// accepted
+    fn reserve(&mut self, peer: DroneId, epoch: u32, reserved: u64) -> Result<()>;
+}
+
+/// Sliding window over one peer's nonces in one key epoch
+#[derive(Debug, Clone, Copy)]
+struct ReplayWindow {
+    epoch: u32,
+    /// Highest nonce accepted
+    highest: u64,
+    /// Bit `i` set = nonce `highest - i` accepted
+    seen: u128,
+    /// Nonces up to here are persisted as possibly accepted
+    reserved: u64,
+}
+
+impl ReplayWindow {
+    fn new(epoch: u32) -> Self {
+        Self {
+            epoch,
+            highest: 0,
+            seen: 0,
+            reserved: 0,
+        }
+    }
+
+    /// Check whether `nonce` is new and inside the window
+    fn admits(&self, nonce: u64) -> bool {
+        if nonce > self.highest {
+            return true;
+        }
+        let age = self.highest - nonce;
+        age < REPLAY_WINDOW && self.seen & (1 << age) == 0
+    }
+
+    fn accept(&mut self, nonce: u64) {
+        if nonce > self.highest {
+            let shift = nonce - self.highest;
+            self.seen = if shift < REPLAY_WINDOW {
+                self.seen << shift
+            } else {
+                0
+            };
+            self.highest = nonce;
+        }
+        self.seen |= 1 << (self.highest - nonce);
+    }
+}
+
+/// Replay state of one peer
+#[derive(Debug, Clone, Copy)]
+struct PeerWindows {
+    current: ReplayWindow,
+    /// Window of the epoch before, for messages in flight across a rekey
+    previous: Option<ReplayWindow>,
+}
+
+/// Replay attack protection via per-peer sliding windows (as in IPsec)
+///
+/// Nonces may arrive out of order, as on a reordering multi-hop mesh, as long
+/// as they are no more than [`REPLAY_WINDOW`] behind the highest accepted one.
+/// Each key epoch starts a fresh window; the previous epoch's window is kept
+/// while its key still decrypts.
+///
+/// A window is never evicted to make room: a forgotten window would accept
+/// the peer's old nonces again. When the tracker is full, nonces from new
+/// peers are rejected until a slot is freed by [`NonceTracker::forget`] or
+/// [`NonceTracker::retire_epochs_before`].
 pub struct NonceTracker {
-    /// Seen nonces (using sliding window)
-    seen_nonces: heapless::FnvIndexMap<u64, u64, 1024>,
+    /// Windows per peer
+    peers: heapless::FnvIndexMap<u64, PeerWindows, MAX_TRACKED_PEERS>,
+    /// Messages from epochs before this are rejected
+    oldest_epoch: u32,
 }
 
 impl NonceTracker {
     /// Create a new nonce tracker
     pub fn new() -> Self {
         Self {
-            seen_nonces: heapless::FnvIndexMap::new(),
+            peers: heapless::FnvIndexMap::new(),
+            oldest_epoch: 0,
         }
     }
 
-    /// Check and record a nonce
+    /// Check and record a nonce of key epoch 0
     pub fn check_nonce(&mut self, drone_id: DroneId, nonce: u64) -> Result<()> {
-        let last_nonce = self.seen_nonces.get(&drone_id.as_u64()).unwrap_or(&0);
+        self.check(drone_id, 0, nonce)
+    }
 
-        // Reject if nonce is not strictly increasing (replay attack)
-        if nonce <= *last_nonce {
+    /// Check and record a nonce sealed under key epoch `epoch`
+    pub fn check(&mut self, drone_id: DroneId, epoch: u32, nonce: u64) -> Result<()> {
+        self.accept(drone_id, epoch, nonce, None)
+    }
+
+    /// Check and record a nonce, persisting reservations to `store` first
+    ///
+    /// Fails closed: if the store cannot persist, the nonce is rejected.
+    pub fn check_durable(
+        &mut self,
+        drone_id: DroneId,
+        epoch: u32,
+        nonce: u64,
+        store: &mut dyn ReplayStore,
+    ) -> Result<()> {
+        self.accept(drone_id, epoch, nonce, Some(store))
+    }
+
+    fn accept(
+        &mut self,
+        drone_id: DroneId,
+        epoch: u32,
+        nonce: u64,
+        store: Option<&mut dyn ReplayStore>,
+    ) -> Result<()> {
+        // Counters start at 1
+        if nonce == 0 || epoch < self.oldest_epoch {
             return Err(SwarmError::AuthenticationFailed);
         }
 
-        // Update nonce
-        self.seen_nonces
-            .insert(drone_id.as_u64(), nonce)
-            .map_err(|_| SwarmError::ResourceExhausted)?;
+        let mut peer = match self.peers.get(&drone_id.as_u64()) {
+            Some(peer) => *peer,
+            None => PeerWindows {
+                current: ReplayWindow::new(epoch),
+                previous: None,
+            },
+        };
+        if epoch > peer.current.epoch {
+            // The peer rekeyed: start a fresh window
+            let previous = core::mem::replace(&mut peer.current, ReplayWindow::new(epoch));
+            peer.previous = (previous.epoch + 1 == epoch).then_some(previous);
+        }
+
+        let window = if epoch == peer.current.epoch {
+            &mut peer.current
+        } else {
+            match peer.previous.as_mut() {
+                Some(previous) if previous.epoch == epoch => previous,
+                _ => return Err(SwarmError::AuthenticationFailed),
+            }
+        };
+        if !window.admits(nonce) {
+            return Err(SwarmError::AuthenticationFailed);
+        }
+
+        if let Some(store) = store {
+            if nonce > window.reserved {
+                let reserved = nonce.saturating_add(REPLAY_RESERVATION);
+                store.reserve(drone_id, epoch, reserved)?;
+                window.reserved = reserved;
+            }
+        }
+        window.accept(nonce);
+
+        self.store(drone_id, peer)
+    }
 
+    /// Insert a peer's windows, failing if the tracker is full
+    fn store(&mut self, drone_id: DroneId, peer: PeerWindows) -> Result<()> {
+        self.peers
+            .insert(drone_id.as_u64(), peer)
+            .map_err(|_| SwarmError::ResourceExhausted)?;
         Ok(())
     }
+
+    /// Reject epochs before `epoch` and drop the windows kept for them
+    ///
+    /// Call after a rekey once the retired key has expired (see
+    /// [`CryptoContext::oldest_accepted_epoch`]).
+    pub fn retire_epochs_before(&mut self, epoch: u32) {
+        if epoch <= self.oldest_epoch {
+            return;
+        }
+        self.oldest_epoch = epoch;
+        self.peers.retain(|_, peer| {
+            if peer.previous.is_some_and(|previous| previous.epoch < epoch) {
+                peer.previous = None;
+            }
+            peer.current.epoch >= epoch
+        });
+    }
+
+    /// Reload a reservation persisted through a [`ReplayStore`]
+    ///
+    /// Every nonce up to `reserved` in `epoch` is treated as seen.
+    pub fn restore(&mut self, drone_id: DroneId, epoch: u32, reserved: u64) -> Result<()> {
+        let window = ReplayWindow {
+            epoch,
+            highest: reserved,
+            seen: u128::MAX,
+            reserved,
+        };
+        let peer = match self.peers.get(&drone_id.as_u64()) {
+            Some(peer) if peer.current.epoch > epoch => return Ok(()),
+            Some(peer) if peer.current.epoch == epoch && peer.current.reserved >= reserved => {
+                return Ok(())
+            }
+            _ => PeerWindows {
+                current: window,
+                previous: None,
+            },
+        };
+        self.store(drone_id, peer)
+    }
+
+    /// Forget a peer, e.g. when it leaves the swarm
+    pub fn forget(&mut self, drone_id: DroneId) {
+        self.peers.remove(&drone_id.as_u64());
+    }
+
+    /// Number of peers tracked
+    pub fn tracked_peers(&self) -> usize {
+        self.peers.len()
+    }
 }
 
 impl Default for NonceTracker {
@@ -344,6 +1009,227 @@ mod tests {
         assert!(result.is_ok());
     }
 
+    #[test]
+    fn test_shared_network_key_separate_signers() {
+        let mut alice = CryptoContext::with_keys([9u8; 32], [1u8; 32]);
+        let bob = CryptoContext::with_keys([9u8; 32], [2u8; 32]);
+        assert_ne!(alice.public_key(), bob.public_key());
+
+        let sealed = alice.encrypt_and_sign(b"formation", b"mesh").unwrap();
+        let opened = bob
+            .verify_and_decrypt(&sealed, b"mesh", alice.public_key())
+            .unwrap();
+        assert_eq!(&opened[..], b"formation");
+
+        // Signature does not match a different sender
+        assert_eq!(
+            bob.verify_and_decrypt(&sealed, b"mesh", bob.public_key())
+                .unwrap_err(),
+            SwarmError::AuthenticationFailed
+        );
+    }
+
+    #[test]
+    fn test_previous_epoch_accepted_within_window() {
+        let mut alice = CryptoContext::with_keys([9u8; 32], [1u8; 32]);
+        let mut bob = CryptoContext::with_keys([9u8; 32], [2u8; 32]);
+        let in_flight = alice.encrypt_and_sign(b"before", b"").unwrap();
+
+        alice.advance_epoch(1, 1000).unwrap();
+        bob.advance_epoch(1, 1000).unwrap();
+        assert_eq!(alice.epoch(), 1);
+        let sealed = alice.encrypt_and_sign(b"after", b"").unwrap();
+        assert_eq!(
+            &bob.verify_and_decrypt(&sealed, b"", alice.public_key())
+                .unwrap()[..],
+            b"after"
+        );
+        assert!(bob
+            .verify_and_decrypt(&in_flight, b"", alice.public_key())
+            .is_ok());
+
+        // Window over: the old key is gone
+        bob.expire_retired_key(1000 + RekeyPolicy::default().acceptance_window_ms);
+        assert!(!bob.has_retired_key());
+        assert_eq!(
+            bob.verify_and_decrypt(&in_flight, b"", alice.public_key())
+                .unwrap_err(),
+            SwarmError::AuthenticationFailed
+        );
+    }
+
+    #[test]
+    fn test_next_epoch_accepted_before_rotation() {
+        let mut alice = CryptoContext::with_keys([9u8; 32], [1u8; 32]);
+        let bob = CryptoContext::with_keys([9u8; 32], [2u8; 32]);
+
+        alice.advance_epoch(1, 0).unwrap();
+        let sealed = alice.encrypt_and_sign(b"early", b"").unwrap();
+        assert!(bob
+            .verify_and_decrypt(&sealed, b"", alice.public_key())
+            .is_ok());
+
+        // Two epochs ahead is not guessed
+        alice.advance_epoch(2, 0).unwrap();
+        let sealed = alice.encrypt_and_sign(b"far", b"").unwrap();
+        assert!(bob
+            .verify_and_decrypt(&sealed, b"", alice.public_key())
+            .is_err());
+    }
+
+    #[test]
+    fn test_ratchet_is_deterministic_and_bounded() {
+        let mut stepwise = CryptoContext::with_keys([9u8; 32], [1u8; 32]);
+        let mut direct = CryptoContext::with_keys([9u8; 32], [2u8; 32]);
+        for epoch in 1..=3 {
+            stepwise.advance_epoch(epoch, 0).unwrap();
+        }
+        direct.advance_epoch(3, 0).unwrap();
+
+        let sealed = stepwise.encrypt_and_sign(b"same key", b"").unwrap();
+        assert!(direct
+            .verify_and_decrypt(&sealed, b"", stepwise.public_key())
+            .is_ok());
+
+        assert_eq!(
+            direct.advance_epoch(3, 0),
+            Err(SwarmError::InvalidParameter)
+        );
+        assert_eq!(
+            direct.advance_epoch(4 + MAX_RATCHET_STEPS, 0),
+            Err(SwarmError::InvalidParameter)
+        );
+        assert_eq!(
+            direct.install_key(2, [0u8; 32], 0),
+            Err(SwarmError::InvalidParameter)
+        );
+
+        // A drone that fell further behind resynchronizes
+        direct.resync_epoch(4 + MAX_RATCHET_STEPS, 0).unwrap();
+        assert_eq!(direct.epoch(), 4 + MAX_RATCHET_STEPS);
+        assert_eq!(
+            direct.resync_epoch(direct.epoch() + MAX_RESYNC_STEPS + 1, 0),
+            Err(SwarmError::InvalidParameter)
+        );
+    }
+
+    #[test]
+    fn test_resync_from_sealed_message() {
+        let mut alice = CryptoContext::with_keys([9u8; 32], [1u8; 32]);
+        let mut bob = CryptoContext::with_keys([9u8; 32], [2u8; 32]);
+        for epoch in 1..=2 * MAX_RATCHET_STEPS {
+            alice.advance_epoch(epoch, 0).unwrap();
+        }
+        let sealed = alice.encrypt_and_sign(b"catch up", b"ad").unwrap();
+        assert_eq!(
+            bob.verify_and_decrypt(&sealed, b"ad", alice.public_key())
+                .unwrap_err(),
+            SwarmError::AuthenticationFailed
+        );
+
+        // Only a message the sender signed moves the key
+        let mallory = CryptoContext::with_keys([9u8; 32], [3u8; 32]);
+        assert_eq!(
+            bob.resync(&sealed, b"ad", mallory.public_key(), 100)
+                .unwrap_err(),
+            SwarmError::AuthenticationFailed
+        );
+        assert_eq!(bob.epoch(), 0);
+
+        assert_eq!(
+            &bob.resync(&sealed, b"ad", alice.public_key(), 100).unwrap()[..],
+            b"catch up"
+        );
+        assert_eq!(bob.epoch(), 2 * MAX_RATCHET_STEPS);
+        assert!(bob
+            .verify_and_decrypt(&sealed, b"ad", alice.public_key())
+            .is_ok());
+    }
+
+    #[test]
+    fn test_acceptance_window_saturates() {
+        let mut ctx = CryptoContext::with_keys([9u8; 32], [1u8; 32]);
+        ctx.set_rekey_policy(RekeyPolicy {
+            acceptance_window_ms: u64::MAX,
+            ..RekeyPolicy::default()
+        });
+        ctx.advance_epoch(1, 1000).unwrap();
+        ctx.expire_retired_key(u64::MAX - 1);
+        assert!(ctx.has_retired_key());
+    }
+
+    #[test]
+    fn test_rekey_due_by_count_and_age() {
+        let mut ctx = CryptoContext::with_keys([9u8; 32], [1u8; 32]);
+        ctx.set_rekey_policy(RekeyPolicy {
+            max_encryptions: 2,
+            max_key_age_ms: Some(60_000),
+            ..RekeyPolicy::default()
+        });
+
+        ctx.encrypt_and_sign(b"1", b"").unwrap();
+        assert!(!ctx.rekey_due(0));
+        ctx.encrypt_and_sign(b"2", b"").unwrap();
+        assert!(ctx.needs_rekeying());
+
+        ctx.advance_epoch(1, 10_000).unwrap();
+        assert!(!ctx.rekey_due(10_000));
+        assert!(ctx.rekey_due(70_000));
+    }
+
+    #[cfg(feature = "post-quantum")]
+    #[test]
+    fn test_dual_signatures() {
+        let mut alice = CryptoContext::with_keys([3; 32], [1; 32]);
+        let bob = CryptoContext::with_keys([3; 32], [2; 32]);
+        alice.enable_dual_signatures(&[11; 32]);
+        let ml_dsa_key = *alice.ml_dsa_public_key().unwrap();
+
+        let sealed = alice.encrypt_and_sign_dual(b"land", b"ad").unwrap();
+        assert_eq!(sealed.len(), 4 + DUAL_CRYPTO_OVERHEAD);
+        let plaintext = bob
+            .verify_and_decrypt_dual(&sealed, b"ad", alice.public_key(), &ml_dsa_key)
+            .unwrap();
+        assert_eq!(&plaintext[..], b"land");
+
+        // Both signatures are required
+        let classic = &sealed[..sealed.len() - ml_dsa::SIGNATURE_SIZE];
+        assert!(bob
+            .verify_and_decrypt_dual(classic, b"ad", alice.public_key(), &ml_dsa_key)
+            .is_err());
+        let mut forged = sealed.clone();
+        let last = forged.len() - 1;
+        forged[last - 100] ^= 1;
+        assert!(bob
+            .verify_and_decrypt_dual(&forged, b"ad", alice.public_key(), &ml_dsa_key)
+            .is_err());
+
+        // Plain sealing is unchanged; dual sealing needs an ML-DSA key
+        let sealed = alice.encrypt_and_sign(b"land", b"ad").unwrap();
+        assert_eq!(sealed.len(), 4 + CRYPTO_OVERHEAD);
+        assert_eq!(
+            CryptoContext::with_keys([3; 32], [4; 32])
+                .encrypt_and_sign_dual(b"land", b"ad")
+                .unwrap_err(),
+            SwarmError::ConfigError
+        );
+    }
+
+    #[cfg(feature = "post-quantum")]
+    #[test]
+    fn test_hybrid_key_exchange() {
+        let recipient = HybridSecretKey::from_seed(&[6; 32]);
+        let (ciphertext, shared) =
+            CryptoContext::hybrid_encapsulate(recipient.public_key()).unwrap();
+        assert_eq!(
+            CryptoContext::hybrid_decapsulate(&recipient, &ciphertext).unwrap(),
+            shared
+        );
+        // Fresh randomness per encapsulation
+        let (_, again) = CryptoContext::hybrid_encapsulate(recipient.public_key()).unwrap();
+        assert_ne!(again, shared);
+    }
+
     #[test]
     fn test_nonce_replay_protection() {
         let mut tracker = NonceTracker::new();
@@ -0,0 +1,704 @@
+//! Delay-tolerant networking (store-carry-forward) for partitioned swarms
+//!
+//! When no end-to-end route exists, e.g. after a network partition, data is
+//! wrapped in bundles that are stored on board and handed over whenever two
+//! drones meet, so drones flying between sub-swarms physically ferry it.
+//! Provides:
+//! - Bounded bundle store with priority-aware eviction
+//! - Per-bundle expiry
+//! - Spray-and-wait or PRoPHET forwarding
+//! - Custody transfer
+//!
+//! Like the consensus engine the node is transport-agnostic: broadcast the
+//! summary returned by [`DtnNode::tick`] and send the replies returned by
+//! [`DtnNode::process_message`] back to the drone the message came from.
+
+use crate::types::*;
+use heapless::{Deque, FnvIndexMap, Vec};
+use serde::{Deserialize, Serialize};
+
+/// Maximum bundles held in the store
+pub const MAX_BUNDLES: usize = 32;
+
+/// Maximum bundle payload in bytes (a transfer fits a 1280-byte frame)
+pub const MAX_BUNDLE_PAYLOAD: usize = 512;
+
+/// Maximum delivery predictabilities tracked (power of 2 for FnvIndexMap)
+pub const MAX_PREDICTABILITIES: usize = 32;
+
+/// Maximum bundles handed over in reply to one summary
+pub const MAX_BUNDLES_PER_CONTACT: usize = 8;
+
+/// Delivered bundle IDs remembered to suppress duplicates
+const DELIVERED_CACHE: usize = 32;
+
+/// Maximum bundle IDs in a summary vector (stored plus delivered)
+pub const MAX_SUMMARY_IDS: usize = MAX_BUNDLES + DELIVERED_CACHE;
+
+/// Globally unique bundle identifier
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct BundleId {
+    /// Drone that created the bundle
+    pub source: DroneId,
+    /// Sequence number at the source
+    pub sequence: u32,
+}
+
+/// Bundle priority class (lowest first)
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+pub enum BundlePriority {
+    /// Bulk data, evicted first
+    Bulk,
+    /// Normal traffic
+    Normal,
+    /// Expedited traffic, forwarded first and evicted last
+    Expedited,
+}
+
+/// Bundle: a self-contained message carried hop by hop
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Bundle {
+    /// Bundle identifier
+    pub id: BundleId,
+    /// Final destination
+    pub destination: DroneId,
+    /// Priority class
+    pub priority: BundlePriority,
+    /// Creation time at the source (ms)
+    pub created_ms: u64,
+    /// Lifetime after creation (ms)
+    pub lifetime_ms: u64,
+    /// Whether the source requested custody transfer
+    pub custody: bool,
+    /// Spray-and-wait copy tokens held by the carrier
+    pub copies: u8,
+    /// Application data
+    pub payload: Vec<u8, MAX_BUNDLE_PAYLOAD>,
+}
+
+impl Bundle {
+    /// Time at which the bundle expires (ms)
+    pub fn expires_at(&self) -> u64 {
+        self.created_ms.saturating_add(self.lifetime_ms)
+    }
+
+    /// Check if the bundle has expired
+    pub fn is_expired(&self, now_ms: u64) -> bool {
+        now_ms >= self.expires_at()
+    }
+}
+
+/// Bundle forwarding strategy
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum DtnStrategy {
+    /// Binary spray-and-wait: hand half the copies to each new carrier, then
+    /// wait for the destination with the last one
+    SprayAndWait {
+        /// Copies created per bundle
+        copies: u8,
+    },
+    /// PRoPHET: forward to drones more likely to meet the destination
+    Prophet,
+}
+
+/// DTN configuration
+#[derive(Debug, Clone, Copy)]
+pub struct DtnConfig {
+    /// Forwarding strategy
+    pub strategy: DtnStrategy,
+    /// Bundle lifetime used by [`DtnNode::send`] (ms)
+    pub default_lifetime_ms: u64,
+    /// Request custody transfer for new bundles
+    pub custody: bool,
+    /// Interval between summary broadcasts (ms)
+    pub summary_interval_ms: u64,
+    /// PRoPHET encounter predictability (P_encounter)
+    pub p_encounter: f32,
+    /// PRoPHET transitivity scaling (beta)
+    pub beta: f32,
+    /// PRoPHET aging factor per time unit (gamma)
+    pub gamma: f32,
+    /// PRoPHET aging time unit (ms)
+    pub aging_unit_ms: u64,
+}
+
+impl Default for DtnConfig {
+    fn default() -> Self {
+        Self {
+            strategy: DtnStrategy::SprayAndWait { copies: 8 },
+            default_lifetime_ms: 300_000,
+            custody: true,
+            summary_interval_ms: 1000,
+            // RFC 6693 recommended values
+            p_encounter: 0.75,
+            beta: 0.25,
+            gamma: 0.98,
+            aging_unit_ms: 1000,
+        }
+    }
+}
+
+/// DTN protocol messages
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[allow(clippy::large_enum_variant)]
+pub enum DtnMessage {
+    /// Periodic contact announcement: what the sender holds or has received,
+    /// and how likely it is to meet other drones
+    Summary {
+        sender: DroneId,
+        bundles: Vec<BundleId, MAX_SUMMARY_IDS>,
+        predictabilities: Vec<(DroneId, f32), MAX_PREDICTABILITIES>,
+    },
+    /// Bundle handed to the receiver
+    Transfer {
+        sender: DroneId,
+        bundle: Bundle,
+        /// Sender releases its copy once the receiver accepts custody
+        custody: bool,
+    },
+    /// Custody transfer response
+    Custody {
+        sender: DroneId,
+        id: BundleId,
+        accepted: bool,
+    },
+}
+
+/// Result of processing a DTN message
+#[derive(Debug, Default)]
+pub struct DtnOutput {
+    /// Messages to send back to the drone the message came from
+    pub replies: Vec<DtnMessage, MAX_BUNDLES_PER_CONTACT>,
+    /// Bundle addressed to this drone, delivered for the first time
+    pub delivered: Option<Bundle>,
+}
+
+/// DTN statistics
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct DtnStats {
+    /// Bundles created locally
+    pub bundles_created: u64,
+    /// Bundles (or copies) handed to other drones
+    pub bundles_forwarded: u64,
+    /// Bundles delivered to this drone
+    pub bundles_delivered: u64,
+    /// Bundles dropped on expiry
+    pub bundles_expired: u64,
+    /// Bundles evicted to make room
+    pub bundles_evicted: u64,
+    /// Custody transfers accepted from other drones
+    pub custody_accepted: u64,
+    /// Custody transfers refused (store full of higher-priority bundles)
+    pub custody_refused: u64,
+}
+
+/// Bounded bundle store with priority-aware eviction
+#[derive(Debug, Default)]
+pub struct BundleStore {
+    bundles: Vec<Bundle, MAX_BUNDLES>,
+}
+
+impl BundleStore {
+    /// Create an empty store
+    pub fn new() -> Self {
+        Self {
+            bundles: Vec::new(),
+        }
+    }
+
+    /// Insert a bundle, evicting one if the store is full
+    ///
+    /// The victim is the lowest-priority bundle, preferring non-custody ones
+    /// and then the one closest to expiry. A bundle never displaces one of
+    /// higher priority; the store then refuses it with `BufferFull`.
+    /// Returns the evicted bundle, if any.
+    pub fn insert(&mut self, bundle: Bundle) -> Result<Option<Bundle>> {
+        if self.contains(&bundle.id) {
+            return Ok(None);
+        }
+
+        let mut evicted = None;
+        if self.bundles.is_full() {
+            let victim = self
+                .bundles
+                .iter()
+                .enumerate()
+                .min_by_key(|(_, stored)| (stored.priority, stored.custody, stored.expires_at()))
+                .map(|(index, stored)| (index, stored.priority));
+            match victim {
+                Some((index, priority)) if priority <= bundle.priority => {
+                    evicted = Some(self.bundles.swap_remove(index));
+                }
+                _ => return Err(SwarmError::BufferFull),
+            }
+        }
+
+        self.bundles
+            .push(bundle)
+            .map_err(|_| SwarmError::BufferFull)?;
+        Ok(evicted)
+    }
+
+    /// Check whether a bundle is stored
+    pub fn contains(&self, id: &BundleId) -> bool {
+        self.bundles.iter().any(|bundle| bundle.id == *id)
+    }
+
+    /// Get a stored bundle
+    pub fn get(&self, id: &BundleId) -> Option<&Bundle> {
+        self.bundles.iter().find(|bundle| bundle.id == *id)
+    }
+
+    /// Remove a bundle from the store
+    pub fn remove(&mut self, id: &BundleId) -> Option<Bundle> {
+        let index = self.bundles.iter().position(|bundle| bundle.id == *id)?;
+        Some(self.bundles.swap_remove(index))
+    }
+
+    /// Drop expired bundles, returning how many were removed
+    pub fn expire(&mut self, now_ms: u64) -> usize {
+        let before = self.bundles.len();
+        self.bundles.retain(|bundle| !bundle.is_expired(now_ms));
+        before - self.bundles.len()
+    }
+
+    /// Number of stored bundles
+    pub fn len(&self) -> usize {
+        self.bundles.len()
+    }
+
+    /// Check if the store is empty
+    pub fn is_empty(&self) -> bool {
+        self.bundles.is_empty()
+    }
+
+    /// Iterate over stored bundles
+    pub fn iter(&self) -> impl Iterator<Item = &Bundle> {
+        self.bundles.iter()
+    }
+}
+
+/// Store-carry-forward node
+pub struct DtnNode {
+    /// This drone's ID
+    local_id: DroneId,
+    /// Configuration
+    config: DtnConfig,
+    /// Carried bundles
+    store: BundleStore,
+    /// Sequence number for new bundles
+    sequence: u32,
+    /// PRoPHET delivery predictability per destination
+    predictability: FnvIndexMap<u64, f32, MAX_PREDICTABILITIES>,
+    /// Time predictabilities were last aged
+    last_aging: u64,
+    /// Time of the last summary broadcast
+    last_summary: Option<u64>,
+    /// Recently delivered bundles, reported in summaries so carriers stop
+    delivered: Deque<BundleId, DELIVERED_CACHE>,
+    /// Statistics
+    stats: DtnStats,
+}
+
+impl DtnNode {
+    /// Create a DTN node
+    pub fn new(local_id: DroneId, config: DtnConfig) -> Self {
+        Self {
+            local_id,
+            config,
+            store: BundleStore::new(),
+            sequence: 0,
+            predictability: FnvIndexMap::new(),
+            last_aging: crate::get_time_ms(),
+            last_summary: None,
+            delivered: Deque::new(),
+            stats: DtnStats::default(),
+        }
+    }
+
+    /// Create a bundle for `destination` and store it for forwarding
+    pub fn send(
+        &mut self,
+        destination: DroneId,
+        payload: &[u8],
+        priority: BundlePriority,
+    ) -> Result<BundleId> {
+        if destination == self.local_id {
+            return Err(SwarmError::InvalidParameter);
+        }
+        let payload = Vec::from_slice(payload).map_err(|_| SwarmError::BufferFull)?;
+
+        self.sequence = self.sequence.wrapping_add(1);
+        let id = BundleId {
+            source: self.local_id,
+            sequence: self.sequence,
+        };
+        let copies = match self.config.strategy {
+            DtnStrategy::SprayAndWait { copies } => copies.max(1),
+            DtnStrategy::Prophet => 1,
+        };
+        let bundle = Bundle {
+            id,
+            destination,
+            priority,
+            created_ms: crate::get_time_ms(),
+            lifetime_ms: self.config.default_lifetime_ms,
+            custody: self.config.custody,
+            copies,
+            payload,
+        };
+
+        self.store_bundle(bundle)?;
+        self.stats.bundles_created += 1;
+        Ok(id)
+    }
+
+    /// Periodic maintenance (call in main loop)
+    ///
+    /// Expires bundles, ages predictabilities and returns a summary to
+    /// broadcast when one is due.
+    pub fn tick(&mut self) -> Result<Option<DtnMessage>> {
+        let now = crate::get_time_ms();
+        self.stats.bundles_expired += self.store.expire(now) as u64;
+        self.age_predictabilities(now);
+
+        let due = self
+            .last_summary
+            .is_none_or(|last| now.saturating_sub(last) >= self.config.summary_interval_ms);
+        if !due {
+            return Ok(None);
+        }
+        self.last_summary = Some(now);
+        Ok(Some(self.summary()))
+    }
+
+    /// Build a summary vector of stored and delivered bundles
+    pub fn summary(&self) -> DtnMessage {
+        let mut bundles = Vec::new();
+        for id in self
+            .store
+            .iter()
+            .map(|bundle| bundle.id)
+            .chain(self.delivered.iter().copied())
+        {
+            bundles.push(id).ok();
+        }
+
+        let mut predictabilities = Vec::new();
+        for (destination, p) in self.predictability.iter() {
+            predictabilities.push((DroneId::new(*destination), *p)).ok();
+        }
+
+        DtnMessage::Summary {
+            sender: self.local_id,
+            bundles,
+            predictabilities,
+        }
+    }
+
+    /// Process an incoming DTN message
+    pub fn process_message(&mut self, message: DtnMessage) -> Result<DtnOutput> {
+        match message {
+            DtnMessage::Summary {
+                sender,
+                bundles,
+                predictabilities,
+            } => self.handle_summary(sender, &bundles, &predictabilities),
+            DtnMessage::Transfer {
+                sender: _,
+                bundle,
+                custody,
+            } => self.handle_transfer(bundle, custody),
+            DtnMessage::Custody {
+                sender: _,
+                id,
+                accepted,
+            } => {
+                // Custody moved on: this drone is no longer responsible
+                if accepted {
+                    self.store.remove(&id);
+                }
+                Ok(DtnOutput::default())
+            }
+        }
+    }
+
+    /// Handle a contact: learn from the peer and hand over useful bundles
+    fn handle_summary(
+        &mut self,
+        peer: DroneId,
+        peer_bundles: &[BundleId],
+        peer_predictabilities: &[(DroneId, f32)],
+    ) -> Result<DtnOutput> {
+        if peer == self.local_id {
+            return Ok(DtnOutput::default());
+        }
+        self.record_encounter(peer, peer_predictabilities);
+
+        // The peer reports bundles addressed to it as received: stop carrying them
+        let mut done: Vec<BundleId, MAX_BUNDLES> = Vec::new();
+        for bundle in self.store.iter() {
+            if bundle.destination == peer && peer_bundles.contains(&bundle.id) {
+                done.push(bundle.id).ok();
+            }
+        }
+        for id in done.iter() {
+            self.store.remove(id);
+        }
+
+        // Highest priority first, then the most urgent
+        let mut candidates: Vec<(BundlePriority, u64, BundleId), MAX_BUNDLES> = Vec::new();
+        for bundle in self.store.iter() {
+            if !peer_bundles.contains(&bundle.id) {
+                candidates
+                    .push((bundle.priority, bundle.expires_at(), bundle.id))
+                    .ok();
+            }
+        }
+        candidates.sort_unstable_by(|a, b| b.0.cmp(&a.0).then(a.1.cmp(&b.1)));
+
+        let mut output = DtnOutput::default();
+        for (_, _, id) in candidates {
+            if output.replies.is_full() {
+                break;
+            }
+            if let Some(transfer) = self.offer(peer, peer_predictabilities, &id) {
+                output.replies.push(transfer).ok();
+                self.stats.bundles_forwarded += 1;
+            }
+        }
+        Ok(output)
+    }
+
+    /// Decide whether to hand a stored bundle to `peer`
+    fn offer(
+        &mut self,
+        peer: DroneId,
+        peer_predictabilities: &[(DroneId, f32)],
+        id: &BundleId,
+    ) -> Option<DtnMessage> {
+        let destination = self.store.get(id)?.destination;
+        let ours = self.delivery_predictability(destination);
+        let theirs = peer_predictabilities
+            .iter()
+            .find(|(drone, _)| *drone == destination)
+            .map_or(0.0, |(_, p)| *p);
+        let strategy = self.config.strategy;
+        let sender = self.local_id;
+        let bundle = self.store.bundles.iter_mut().find(|b| b.id == *id)?;
+
+        if bundle.destination == peer {
+            // Direct delivery: all remaining copies go
+            return Some(DtnMessage::Transfer {
+                sender,
+                bundle: bundle.clone(),
+                custody: bundle.custody,
+            });
+        }
+
+        match strategy {
+            DtnStrategy::SprayAndWait { .. } => {
+                if bundle.copies < 2 {
+                    // Wait phase: only the destination gets the last copy
+                    return None;
+                }
+                let handed = bundle.copies / 2;
+                bundle.copies -= handed;
+                let mut copy = bundle.clone();
+                copy.copies = handed;
+                Some(DtnMessage::Transfer {
+                    sender,
+                    bundle: copy,
+                    custody: false,
+                })
+            }
+            DtnStrategy::Prophet => (theirs > ours).then(|| DtnMessage::Transfer {
+                sender,
+                bundle: bundle.clone(),
+                custody: bundle.custody,
+            }),
+        }
+    }
+
+    /// Handle a bundle handed over by another drone
+    fn handle_transfer(&mut self, bundle: Bundle, custody: bool) -> Result<DtnOutput> {
+        let mut output = DtnOutput::default();
+        let id = bundle.id;
+        let now = crate::get_time_ms();
+
+        let accepted = if bundle.is_expired(now) {
+            self.stats.bundles_expired += 1;
+            false
+        } else if bundle.destination == self.local_id {
+            if !self.delivered.iter().any(|seen| *seen == id) {
+                if self.delivered.is_full() {
+                    self.delivered.pop_front();
+                }
+                self.delivered.push_back(id).ok();
+                self.stats.bundles_delivered += 1;
+                output.delivered = Some(bundle);
+            }
+            true
+        } else if let Some(stored) = self.store.bundles.iter_mut().find(|b| b.id == id) {
+            // Another copy of a bundle we carry: merge spray tokens
+            stored.copies = stored.copies.saturating_add(bundle.copies);
+            true
+        } else {
+            self.store_bundle(bundle).is_ok()
+        };
+
+        if custody {
+            if accepted {
+                self.stats.custody_accepted += 1;
+            } else {
+                self.stats.custody_refused += 1;
+            }
+            output
+                .replies
+                .push(DtnMessage::Custody {
+                    sender: self.local_id,
+                    id,
+                    accepted,
+                })
+                .ok();
+        }
+        Ok(output)
+    }
+
+    /// Store a bundle, counting any eviction
+    fn store_bundle(&mut self, bundle: Bundle) -> Result<()> {
+        if self.store.insert(bundle)?.is_some() {
+            self.stats.bundles_evicted += 1;
+        }
+        Ok(())
+    }
+
+    /// PRoPHET encounter and transitivity updates (RFC 6693 2.1.2)
+    fn record_encounter(&mut self, peer: DroneId, peer_predictabilities: &[(DroneId, f32)]) {
+        let now = crate::get_time_ms();
+        self.age_predictabilities(now);
+
+        let old = self.delivery_predictability(peer);
+        let direct = old + (1.0 - old) * self.config.p_encounter;
+        self.set_predictability(peer, direct);
+
+        for (destination, through_peer) in peer_predictabilities {
+            if *destination == self.local_id || *destination == peer {
+                continue;
+            }
+            let current = self.delivery_predictability(*destination);
+            let transitive = direct * through_peer * self.config.beta;
+            if transitive > current {
+                self.set_predictability(*destination, transitive);
+            }
+        }
+    }
+
+    /// Age predictabilities by gamma per elapsed time unit
+    fn age_predictabilities(&mut self, now_ms: u64) {
+        let unit = self.config.aging_unit_ms.max(1);
+        let units = now_ms.saturating_sub(self.last_aging) / unit;
+        if units == 0 {
+            return;
+        }
+        self.last_aging += units * unit;
+
+        let factor = libm::powf(self.config.gamma, units as f32);
+        for p in self.predictability.values_mut() {
+            *p *= factor;
+        }
+    }
+
+    /// Record a predictability, replacing the weakest entry when full
+    fn set_predictability(&mut self, destination: DroneId, p: f32) {
+        let key = destination.as_u64();
+        if !self.predictability.contains_key(&key)
+            && self.predictability.len() == MAX_PREDICTABILITIES
+        {
+            let weakest = self
+                .predictability
+                .iter()
+                .min_by(|a, b| a.1.total_cmp(b.1))
+                .map(|(key, p)| (*key, *p));
+            match weakest {
+                Some((weakest, old)) if old < p => {
+                    self.predictability.remove(&weakest);
+                }
+                _ => return,
+            }
+        }
+        self.predictability.insert(key, p).ok();
+    }
+
+    /// Delivery predictability from this drone to `destination`
+    pub fn delivery_predictability(&self, destination: DroneId) -> f32 {
+        self.predictability
+            .get(&destination.as_u64())
+            .copied()
+            .unwrap_or(0.0)
+    }
+
+    /// Get the bundle store
+    pub fn store(&self) -> &BundleStore {
+        &self.store
+    }
+
+    /// Get the node's configuration
+    pub fn config(&self) -> &DtnConfig {
+        &self.config
+    }
+
+    /// Get DTN statistics
+    pub fn statistics(&self) -> &DtnStats {
+        &self.stats
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn summary_from(sender: u64, predictabilities: &[(u64, f32)]) -> DtnMessage {
+        let mut table = Vec::new();
+        for (id, p) in predictabilities {
+            table.push((DroneId::new(*id), *p)).unwrap();
+        }
+        DtnMessage::Summary {
+            sender: DroneId::new(sender),
+            bundles: Vec::new(),
+            predictabilities: table,
+        }
+    }
+
+    #[test]
+    fn test_prophet_encounter_and_transitivity() {
+        let mut node = DtnNode::new(DroneId::new(1), DtnConfig::default());
+
+        node.process_message(summary_from(2, &[(3, 0.8)])).unwrap();
+
+        let direct = node.delivery_predictability(DroneId::new(2));
+        assert!((direct - 0.75).abs() < 1e-6);
+        // P(1,3) = P(1,2) * P(2,3) * beta
+        let transitive = node.delivery_predictability(DroneId::new(3));
+        assert!((transitive - 0.75 * 0.8 * 0.25).abs() < 1e-6);
+
+        // A second encounter reinforces the direct link
+        node.process_message(summary_from(2, &[])).unwrap();
+        let reinforced = node.delivery_predictability(DroneId::new(2));
+        assert!((reinforced - (0.75 + 0.25 * 0.75)).abs() < 1e-6);
+    }
+
+    #[test]
+    fn test_prophet_aging() {
+        let mut node = DtnNode::new(DroneId::new(1), DtnConfig::default());
+        node.set_predictability(DroneId::new(2), 0.5);
+        let start = node.last_aging;
+
+        node.age_predictabilities(start + 2500);
+
+        let aged = node.delivery_predictability(DroneId::new(2));
+        assert!((aged - 0.5 * 0.98 * 0.98).abs() < 1e-6);
+        // The partial unit carries over to the next aging step
+        assert_eq!(node.last_aging, start + 2000);
+    }
+}
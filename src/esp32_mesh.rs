@@ -5,7 +5,13 @@
 //! - Automatic node discovery
 //! - Multi-hop message routing
 //! - Position synchronization
-//! - Command distribution
+//! - Command distribution with acknowledgment and retransmission
+//! - Authenticated, encrypted radio frames (see [`crate::mesh_security`])
+//! - Role-based command authorization (see [`crate::rbac`])
+//! - Behavioral intrusion detection of authenticated senders (see
+//!   [`crate::security`])
+//! - Frequency hopping and interference-driven channel migration (see
+//!   [`crate::mesh_channel`])
 //!
 //! # Features
 //! - `std` - Desktop simulation mode (for testing without hardware)
@@ -20,9 +26,14 @@
 //! node.broadcast_heartbeat(current_time_ms);
 //! ```
 
+use crate::certificate::RevocationList;
+use crate::mesh_channel::{ChannelConfig, ChannelEvent, ChannelManager};
 use crate::mesh_protocol::*;
+use crate::mesh_security::{FrameRejection, MeshFrame, MeshSecurity, MAX_MESH_FRAME_SIZE};
+use crate::rbac::CommandPolicy;
+use crate::security::IntrusionDetectionSystem;
 use crate::types::*;
-use heapless::Vec;
+use heapless::{Deque, Vec};
 
 /// Maximum neighbors to track
 pub const MAX_NEIGHBORS: usize = 32;
@@ -33,6 +44,24 @@ pub const MAX_PENDING_MESSAGES: usize = 16;
 /// Maximum routes in routing table
 pub const MAX_ROUTES: usize = 64;
 
+/// Maximum commands awaiting acknowledgment
+pub const MAX_PENDING_COMMANDS: usize = 8;
+
+/// Maximum command groups a node can join
+pub const MAX_GROUPS: usize = 8;
+
+/// Maximum finished command reports held for [`MeshNode::poll_delivery_report`]
+pub const MAX_DELIVERY_REPORTS: usize = 8;
+
+/// Recently executed commands remembered to suppress retransmitted duplicates
+const RECENT_COMMANDS: usize = 32;
+
+/// Queue occupancy (percent) at which a node counts as congested
+pub const CONGESTION_THRESHOLD_PERCENT: u8 = 75;
+
+/// Maximum slowdown applied to periodic traffic under congestion
+pub const MAX_RATE_DIVISOR: u8 = 8;
+
 /// Mesh node state
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum NodeState {
@@ -57,7 +86,7 @@ pub struct MeshConfig {
     pub node_id: MeshNodeId,
     /// Mesh network ID (all nodes must match)
     pub mesh_id: [u8; 6],
-    /// WiFi channel (1-13)
+    /// WiFi home channel (1-13)
     pub channel: u8,
     /// Maximum layer depth
     pub max_layer: u8,
@@ -67,6 +96,21 @@ pub struct MeshConfig {
     pub node_timeout_ms: u64,
     /// Enable encryption
     pub encryption_enabled: bool,
+    /// Time to wait for command ACKs before the first retransmission (ms)
+    ///
+    /// Doubles after every retransmission.
+    pub ack_timeout_ms: u64,
+    /// Retransmissions before unacknowledged targets are reported as timed out
+    pub max_retries: u8,
+    /// Command groups this node belongs to (see [`CommandTarget::Group`])
+    pub groups: Vec<u8, MAX_GROUPS>,
+    /// Weighted-fair scheduling weights for High, Normal and Low messages
+    ///
+    /// Critical messages are always sent first. Among the rest, each round
+    /// sends up to this many messages of each class.
+    pub queue_weights: [u8; 3],
+    /// Frequency hopping and channel migration
+    pub channels: ChannelConfig,
 }
 
 impl Default for MeshConfig {
@@ -79,10 +123,72 @@ impl Default for MeshConfig {
             heartbeat_interval_ms: HEARTBEAT_INTERVAL_MS,
             node_timeout_ms: NODE_TIMEOUT_MS,
             encryption_enabled: true,
+            ack_timeout_ms: 250,
+            max_retries: 4,
+            groups: Vec::new(),
+            queue_weights: [4, 2, 1],
+            channels: ChannelConfig::default(),
         }
     }
 }
 
+/// Delivery state of a command at one target node
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DeliveryStatus {
+    /// No ACK yet
+    Pending,
+    /// Target acknowledged the command
+    Acknowledged(AckStatus),
+    /// Retries exhausted without an ACK
+    TimedOut,
+}
+
+/// Per-target delivery tracking for a reliable command
+#[derive(Debug, Clone)]
+pub struct CommandDelivery {
+    /// Message ID of the command (ACKs reference it)
+    pub msg_id: u32,
+    /// Command target
+    pub target: CommandTarget,
+    /// Command action
+    pub action: CommandAction,
+    /// Delivery outcome per expected target node
+    pub outcomes: Vec<(MeshNodeId, DeliveryStatus), MAX_MESH_NODES>,
+    /// Retransmissions so far
+    pub retries: u8,
+    /// Copy of the command for retransmission
+    message: MeshMessage,
+    /// Time of the next retransmission (ms)
+    next_retry_ms: u64,
+    /// Current retransmission timeout (ms)
+    timeout_ms: u64,
+}
+
+impl CommandDelivery {
+    /// Delivery outcome at `node`, if it is an expected target
+    pub fn status(&self, node: MeshNodeId) -> Option<DeliveryStatus> {
+        self.outcomes
+            .iter()
+            .find(|(id, _)| *id == node)
+            .map(|(_, status)| *status)
+    }
+
+    /// Nodes that acknowledged the command
+    pub fn acknowledged(&self) -> impl Iterator<Item = MeshNodeId> + '_ {
+        self.outcomes
+            .iter()
+            .filter(|(_, status)| matches!(status, DeliveryStatus::Acknowledged(_)))
+            .map(|(id, _)| *id)
+    }
+
+    /// Check if every target has acknowledged or timed out
+    pub fn is_complete(&self) -> bool {
+        self.outcomes
+            .iter()
+            .all(|(_, status)| *status != DeliveryStatus::Pending)
+    }
+}
+
 /// Mesh network node
 pub struct MeshNode {
     /// Configuration
@@ -109,6 +215,24 @@ pub struct MeshNode {
     msg_sequence: u32,
     /// Last heartbeat time (ms)
     last_heartbeat_ms: u64,
+    /// Commands awaiting acknowledgment
+    pending_commands: Vec<CommandDelivery, MAX_PENDING_COMMANDS>,
+    /// Finished command deliveries not yet collected
+    delivery_reports: Deque<CommandDelivery, MAX_DELIVERY_REPORTS>,
+    /// Recently executed commands as (source, msg_id)
+    recent_commands: Deque<(MeshNodeId, u32), RECENT_COMMANDS>,
+    /// Remaining weighted-fair credits for High, Normal and Low
+    queue_credits: [u8; 3],
+    /// Current slowdown of periodic traffic (1 = nominal rate)
+    rate_divisor: u8,
+    /// Keys for sealing and opening radio frames
+    security: Option<MeshSecurity>,
+    /// Who may issue which command; unrestricted when unset
+    command_policy: Option<CommandPolicy>,
+    /// Behavioral checks of authenticated senders
+    ids: Option<IntrusionDetectionSystem>,
+    /// Channel plan, interference and switch coordination
+    channels: ChannelManager,
     /// Statistics
     stats: MeshStats,
 }
@@ -126,6 +250,27 @@ pub struct MeshStats {
     pub drop_count: u32,
     /// Heartbeats sent
     pub heartbeat_count: u32,
+    /// Command retransmissions
+    pub retransmit_count: u32,
+    /// Command ACKs received for our own commands
+    pub ack_count: u32,
+    /// Messages sent per priority (indexed by `MessagePriority as usize`)
+    pub tx_by_priority: [u32; 4],
+    /// Messages dropped per priority (indexed by `MessagePriority as usize`)
+    pub drop_by_priority: [u32; 4],
+    /// Heartbeat intervals that found the node or a neighbor congested
+    pub congestion_events: u32,
+    /// Frames rejected for lacking authentication (plain or unknown sender)
+    pub unauthenticated_rejects: u32,
+    /// Frames whose signature or AEAD tag failed to verify, and commands
+    /// without a valid issuer signature
+    pub auth_failures: u32,
+    /// Authentic frames rejected as replays
+    pub replay_rejects: u32,
+    /// Commands refused because the sender's roles do not allow them
+    pub unauthorized_commands: u32,
+    /// Messages dropped because the IDS banned their sender
+    pub intrusion_drops: u32,
     /// Current neighbor count
     pub neighbor_count: u8,
     /// Active neighbor count
@@ -139,6 +284,7 @@ impl MeshNode {
             node_id,
             ..MeshConfig::default()
         };
+        let channels = ChannelManager::new(node_id, config.channel, config.channels.clone());
 
         Self {
             config,
@@ -152,12 +298,23 @@ impl MeshNode {
             rx_queue: Vec::new(),
             msg_sequence: 0,
             last_heartbeat_ms: 0,
+            pending_commands: Vec::new(),
+            delivery_reports: Deque::new(),
+            recent_commands: Deque::new(),
+            queue_credits: [0; 3],
+            rate_divisor: 1,
+            security: None,
+            command_policy: None,
+            ids: None,
+            channels,
             stats: MeshStats::default(),
         }
     }
 
     /// Create with custom configuration
     pub fn with_config(config: MeshConfig) -> Self {
+        let channels = ChannelManager::new(config.node_id, config.channel, config.channels.clone());
+
         Self {
             config,
             state: NodeState::Initializing,
@@ -170,6 +327,15 @@ impl MeshNode {
             rx_queue: Vec::new(),
             msg_sequence: 0,
             last_heartbeat_ms: 0,
+            pending_commands: Vec::new(),
+            delivery_reports: Deque::new(),
+            recent_commands: Deque::new(),
+            queue_credits: [0; 3],
+            rate_divisor: 1,
+            security: None,
+            command_policy: None,
+            ids: None,
+            channels,
             stats: MeshStats::default(),
         }
     }
@@ -222,6 +388,60 @@ impl MeshNode {
         self.battery_percent = percent.min(100);
     }
 
+    /// Install the keys used to seal and open radio frames
+    pub fn set_security(&mut self, security: MeshSecurity) {
+        self.security = Some(security);
+    }
+
+    /// Frame security context, e.g. to register member keys
+    pub fn security_mut(&mut self) -> Option<&mut MeshSecurity> {
+        self.security.as_mut()
+    }
+
+    /// Refuse commands the issuer's roles do not allow
+    ///
+    /// The issuer is the member whose end-to-end signature the command
+    /// carries (see [`MeshSecurity::verify_command`]), however many relays
+    /// it crossed. Without frame keys installed there is no such proof, and
+    /// every command is refused while a policy is installed.
+    pub fn set_command_policy(&mut self, policy: CommandPolicy) {
+        self.command_policy = Some(policy);
+    }
+
+    /// Command policy, e.g. to record the current leader or a delegation
+    pub fn command_policy_mut(&mut self) -> Option<&mut CommandPolicy> {
+        self.command_policy.as_mut()
+    }
+
+    /// Check the behavior of authenticated senders and drop banned ones
+    ///
+    /// Position updates are checked for impossible movement and shared
+    /// positions. Only messages in frames sealed by their source are
+    /// attributed, so spoofed reports cannot get another drone banned.
+    pub fn set_intrusion_detection(&mut self, ids: IntrusionDetectionSystem) {
+        self.ids = Some(ids);
+    }
+
+    /// Intrusion detection, e.g. to poll detections or read trust
+    pub fn intrusion_detection_mut(&mut self) -> Option<&mut IntrusionDetectionSystem> {
+        self.ids.as_mut()
+    }
+
+    /// Channel to transmit and listen on at mesh time `current_time_ms`
+    pub fn current_channel(&self, current_time_ms: u64) -> u8 {
+        self.channels.channel_at(current_time_ms)
+    }
+
+    /// Channel plan, interference statistics and switch state
+    pub fn channels(&self) -> &ChannelManager {
+        &self.channels
+    }
+
+    /// Record a radio observation, e.g. a noise floor sample, for a channel
+    pub fn record_channel_event(&mut self, channel: u8, event: ChannelEvent) {
+        self.channels.record(channel, event);
+    }
+
     /// Get statistics
     pub fn stats(&self) -> &MeshStats {
         &self.stats
@@ -244,13 +464,16 @@ impl MeshNode {
 
     /// Broadcast heartbeat message
     pub fn broadcast_heartbeat(&mut self, current_time_ms: u64) -> Result<()> {
-        let msg = MeshMessage::heartbeat(
+        let mut msg = MeshMessage::heartbeat(
             self.config.node_id,
             self.position,
             self.battery_percent,
             self.neighbor_count() as u8,
             current_time_ms,
         );
+        if let MeshMessageType::Heartbeat { queue_load, .. } = &mut msg.payload {
+            *queue_load = self.queue_load();
+        }
 
         self.queue_message(msg)?;
         self.last_heartbeat_ms = current_time_ms;
@@ -272,15 +495,123 @@ impl MeshNode {
         self.queue_message(msg)
     }
 
-    /// Send command to target
+    /// Send command to target, retransmitting until it is acknowledged
+    ///
+    /// The expected targets are the addressed node for [`CommandTarget::Node`]
+    /// and the active neighbors for [`CommandTarget::Broadcast`]; use
+    /// [`send_command_to`](Self::send_command_to) to name the members of a
+    /// group or of a multi-hop swarm. Returns the command's message ID.
     pub fn send_command(
         &mut self,
         target: CommandTarget,
         action: CommandAction,
         current_time_ms: u64,
-    ) -> Result<()> {
-        let msg = MeshMessage::command(self.config.node_id, target, action, current_time_ms);
-        self.queue_message(msg)
+    ) -> Result<u32> {
+        let mut expected: Vec<MeshNodeId, MAX_MESH_NODES> = Vec::new();
+        match &target {
+            CommandTarget::Node(id) => {
+                expected.push(*id).ok();
+            }
+            CommandTarget::Broadcast => {
+                for neighbor in self.neighbors.iter().filter(|n| n.is_active) {
+                    expected.push(neighbor.node_id).ok();
+                }
+            }
+            CommandTarget::Group(_) => {}
+        }
+        self.send_command_to(target, action, &expected, current_time_ms)
+    }
+
+    /// Send command and track its delivery at each of `expected`
+    ///
+    /// Unacknowledged commands are retransmitted with exponential backoff
+    /// (see [`MeshConfig::ack_timeout_ms`]). Once every target has answered
+    /// or `max_retries` is exhausted the outcome is available from
+    /// [`poll_delivery_report`](Self::poll_delivery_report). A command with
+    /// no expected targets is sent once and not tracked.
+    pub fn send_command_to(
+        &mut self,
+        target: CommandTarget,
+        action: CommandAction,
+        expected: &[MeshNodeId],
+        current_time_ms: u64,
+    ) -> Result<u32> {
+        let mut outcomes = Vec::new();
+        for node in expected.iter().filter(|node| **node != self.config.node_id) {
+            if !outcomes.iter().any(|(id, _)| id == node) {
+                outcomes
+                    .push((*node, DeliveryStatus::Pending))
+                    .map_err(|_| SwarmError::BufferFull)?;
+            }
+        }
+        if !outcomes.is_empty() && self.pending_commands.is_full() {
+            return Err(SwarmError::BufferFull);
+        }
+
+        let mut msg = MeshMessage::command(
+            self.config.node_id,
+            target.clone(),
+            action.clone(),
+            current_time_ms,
+        );
+        msg.msg_id = self.next_sequence();
+        if let Some(security) = &self.security {
+            security.sign_command(&mut msg)?;
+        }
+        self.enqueue(msg.clone())?;
+
+        if !outcomes.is_empty() {
+            let delivery = CommandDelivery {
+                msg_id: msg.msg_id,
+                target,
+                action,
+                outcomes,
+                retries: 0,
+                message: msg.clone(),
+                next_retry_ms: current_time_ms + self.config.ack_timeout_ms,
+                timeout_ms: self.config.ack_timeout_ms,
+            };
+            self.pending_commands
+                .push(delivery)
+                .map_err(|_| SwarmError::BufferFull)?;
+        }
+        Ok(msg.msg_id)
+    }
+
+    /// Delivery state of a command still awaiting acknowledgment
+    pub fn command_delivery(&self, msg_id: u32) -> Option<&CommandDelivery> {
+        self.pending_commands.iter().find(|c| c.msg_id == msg_id)
+    }
+
+    /// Number of commands awaiting acknowledgment
+    pub fn pending_command_count(&self) -> usize {
+        self.pending_commands.len()
+    }
+
+    /// Take the next finished command delivery report
+    pub fn poll_delivery_report(&mut self) -> Option<CommandDelivery> {
+        self.delivery_reports.pop_front()
+    }
+
+    /// Join a command group
+    pub fn join_group(&mut self, group: u8) -> Result<()> {
+        if self.config.groups.contains(&group) {
+            return Ok(());
+        }
+        self.config
+            .groups
+            .push(group)
+            .map_err(|_| SwarmError::BufferFull)
+    }
+
+    /// Leave a command group
+    pub fn leave_group(&mut self, group: u8) {
+        self.config.groups.retain(|g| *g != group);
+    }
+
+    /// Check group membership
+    pub fn is_group_member(&self, group: u8) -> bool {
+        self.config.groups.contains(&group)
     }
 
     /// Broadcast emergency
@@ -296,73 +627,275 @@ impl MeshNode {
             current_time_ms,
         );
 
-        // Emergency messages go to front of queue
-        if self.tx_queue.len() >= MAX_PENDING_MESSAGES {
-            // Remove lowest priority message
-            if let Some(idx) = self
-                .tx_queue
-                .iter()
-                .position(|m| m.priority == MessagePriority::Low)
-            {
-                self.tx_queue.swap_remove(idx);
-            }
-        }
+        // Critical priority: displaces queued lower-priority traffic if full
+        self.queue_message(msg)
+    }
 
-        self.tx_queue
-            .push(msg)
-            .map_err(|_| SwarmError::BufferFull)?;
-        Ok(())
+    /// Flood a certificate revocation list through the mesh
+    ///
+    /// Every node with a [`TrustAnchor`](crate::certificate::TrustAnchor)
+    /// installs it and drops the revoked members; a list the root did not
+    /// sign is not relayed.
+    pub fn broadcast_revocations(
+        &mut self,
+        list: RevocationList,
+        current_time_ms: u64,
+    ) -> Result<()> {
+        if let Some(security) = self.security.as_mut() {
+            security.apply_revocations(&list)?;
+        }
+        let msg = MeshMessage::new(
+            self.config.node_id,
+            None,
+            MessagePriority::High,
+            MeshMessageType::Revocations { list },
+            current_time_ms,
+        );
+        self.queue_message(msg)
     }
 
     /// Queue a message for transmission
     fn queue_message(&mut self, mut msg: MeshMessage) -> Result<()> {
         msg.msg_id = self.next_sequence();
+        self.enqueue(msg)
+    }
 
-        if self.tx_queue.len() >= MAX_PENDING_MESSAGES {
-            self.stats.drop_count += 1;
+    /// Queue a message keeping its message ID
+    ///
+    /// When the queue is full the oldest message of the lowest queued
+    /// priority is dropped to make room, provided it ranks below `msg`;
+    /// otherwise `msg` itself is dropped. Under congestion Low messages are
+    /// shed once the queue is half full.
+    fn enqueue(&mut self, msg: MeshMessage) -> Result<()> {
+        let shed_low = self.rate_divisor > 1
+            && msg.priority == MessagePriority::Low
+            && self.tx_queue.len() >= MAX_PENDING_MESSAGES / 2;
+        if shed_low {
+            self.record_drop(msg.priority);
             return Err(SwarmError::BufferFull);
         }
 
+        if self.tx_queue.len() >= MAX_PENDING_MESSAGES {
+            let victim = self
+                .tx_queue
+                .iter()
+                .enumerate()
+                .min_by_key(|(i, m)| (m.priority as u8, *i))
+                .map(|(i, m)| (i, m.priority));
+            match victim {
+                Some((index, priority)) if (priority as u8) < (msg.priority as u8) => {
+                    self.tx_queue.remove(index);
+                    self.record_drop(priority);
+                }
+                _ => {
+                    self.record_drop(msg.priority);
+                    return Err(SwarmError::BufferFull);
+                }
+            }
+        }
+
         self.tx_queue
             .push(msg)
             .map_err(|_| SwarmError::BufferFull)?;
         Ok(())
     }
 
+    fn record_drop(&mut self, priority: MessagePriority) {
+        self.stats.drop_count += 1;
+        self.stats.drop_by_priority[priority as usize] += 1;
+    }
+
     /// Get next message to transmit
+    ///
+    /// Critical messages go first (strict priority). High, Normal and Low
+    /// share the remaining capacity by [`MeshConfig::queue_weights`], FIFO
+    /// within each class, so Low telemetry is slowed but never starved.
     pub fn get_next_tx_message(&mut self) -> Option<MeshMessage> {
+        let index = self.next_tx_index()?;
+        let msg = self.tx_queue.remove(index);
+        self.stats.tx_count += 1;
+        self.stats.tx_by_priority[msg.priority as usize] += 1;
+        Some(msg)
+    }
+
+    /// Get next message to transmit as an encoded radio frame
+    ///
+    /// With [`MeshConfig::encryption_enabled`] the message is sealed with the
+    /// keys from [`MeshNode::set_security`]; sending without keys installed is
+    /// a configuration error rather than a silent plaintext fallback.
+    pub fn next_tx_frame(&mut self) -> Result<Option<Vec<u8, MAX_MESH_FRAME_SIZE>>> {
+        if self.config.encryption_enabled && self.security.is_none() {
+            return Err(SwarmError::ConfigError);
+        }
+        let Some(msg) = self.get_next_tx_message() else {
+            return Ok(None);
+        };
+
+        let frame = match (&mut self.security, self.config.encryption_enabled) {
+            (Some(security), true) => security.seal(&msg)?,
+            _ => MeshFrame::Plain(msg),
+        };
+        frame.to_bytes().map(Some)
+    }
+
+    /// Pick the queue index to send next
+    fn next_tx_index(&mut self) -> Option<usize> {
         if self.tx_queue.is_empty() {
             return None;
         }
+        if let Some(index) = self.oldest_of(MessagePriority::Critical) {
+            return Some(index);
+        }
 
-        // Find highest priority message
-        let mut best_idx = 0;
-        let mut best_priority = self.tx_queue[0].priority as u8;
-
-        for (i, msg) in self.tx_queue.iter().enumerate().skip(1) {
-            if (msg.priority as u8) > best_priority {
-                best_priority = msg.priority as u8;
-                best_idx = i;
+        const CLASSES: [MessagePriority; 3] = [
+            MessagePriority::High,
+            MessagePriority::Normal,
+            MessagePriority::Low,
+        ];
+        // Second pass runs after a credit refill
+        for _ in 0..2 {
+            for (class, priority) in CLASSES.iter().enumerate() {
+                if self.queue_credits[class] == 0 {
+                    continue;
+                }
+                if let Some(index) = self.oldest_of(*priority) {
+                    self.queue_credits[class] -= 1;
+                    return Some(index);
+                }
+            }
+            for (credits, weight) in self
+                .queue_credits
+                .iter_mut()
+                .zip(self.config.queue_weights.iter())
+            {
+                *credits = (*weight).max(1);
             }
         }
+        None
+    }
 
-        let msg = self.tx_queue.swap_remove(best_idx);
-        self.stats.tx_count += 1;
-        Some(msg)
+    fn oldest_of(&self, priority: MessagePriority) -> Option<usize> {
+        self.tx_queue.iter().position(|m| m.priority == priority)
+    }
+
+    /// Transmit queue occupancy (percent)
+    pub fn queue_load(&self) -> u8 {
+        (self.tx_queue.len() * 100 / MAX_PENDING_MESSAGES) as u8
+    }
+
+    /// Number of messages waiting to be sent
+    pub fn queued_message_count(&self) -> usize {
+        self.tx_queue.len()
+    }
+
+    /// Current slowdown of periodic traffic (1 = nominal rate)
+    ///
+    /// The heartbeat interval is multiplied by this factor; applications
+    /// should scale their own position and telemetry rates the same way.
+    pub fn rate_divisor(&self) -> u8 {
+        self.rate_divisor
+    }
+
+    /// Check if this node or an active neighbor reports congestion
+    pub fn is_congested(&self) -> bool {
+        self.queue_load() >= CONGESTION_THRESHOLD_PERCENT
+            || self
+                .neighbors
+                .iter()
+                .any(|n| n.is_active && n.queue_load >= CONGESTION_THRESHOLD_PERCENT)
+    }
+
+    /// Multiplicative slowdown under congestion, additive recovery otherwise
+    fn adapt_rate(&mut self) {
+        if self.is_congested() {
+            self.rate_divisor = self.rate_divisor.saturating_mul(2).min(MAX_RATE_DIVISOR);
+            self.stats.congestion_events += 1;
+        } else {
+            self.rate_divisor = self.rate_divisor.saturating_sub(1).max(1);
+        }
+    }
+
+    /// Process a received radio frame
+    ///
+    /// With [`MeshConfig::encryption_enabled`] only sealed frames from
+    /// registered members are accepted; anything else is dropped and counted
+    /// in [`MeshStats`] before the message is looked at.
+    pub fn process_frame(
+        &mut self,
+        bytes: &[u8],
+        rssi: i8,
+        current_time_ms: u64,
+    ) -> Result<ProcessResult> {
+        let channel = self.channels.channel_at(current_time_ms);
+        let Ok(frame) = MeshFrame::from_bytes(bytes) else {
+            self.channels.record(channel, ChannelEvent::Corrupted);
+            self.stats.drop_count += 1;
+            return Ok(ProcessResult::Dropped);
+        };
+
+        let sender = match &frame {
+            MeshFrame::Secured { sender, .. } => Some(*sender),
+            MeshFrame::Plain(_) => None,
+        };
+        let opened = match (&mut self.security, frame) {
+            (_, MeshFrame::Plain(msg)) if !self.config.encryption_enabled => Ok(msg),
+            (Some(security), frame) => match security.open(&frame) {
+                // Possibly sealed after rotations this drone missed
+                Err(FrameRejection::AuthenticationFailed) => {
+                    security.resync(&frame, current_time_ms)
+                }
+                opened => opened,
+            },
+            (None, _) => Err(FrameRejection::Unauthenticated),
+        };
+
+        match opened {
+            Ok(msg) => self.receive(msg, sender, rssi, current_time_ms),
+            Err(rejection) => {
+                match rejection {
+                    FrameRejection::Unauthenticated | FrameRejection::UnknownSender => {
+                        self.stats.unauthenticated_rejects += 1
+                    }
+                    FrameRejection::AuthenticationFailed | FrameRejection::Malformed => {
+                        self.channels.record(channel, ChannelEvent::Corrupted);
+                        self.stats.auth_failures += 1
+                    }
+                    FrameRejection::Replay => self.stats.replay_rejects += 1,
+                }
+                self.stats.drop_count += 1;
+                Ok(ProcessResult::Dropped)
+            }
+        }
     }
 
     /// Process received message
+    ///
+    /// The frame is not authenticated; with frame keys installed, commands
+    /// are still checked against their issuer's signature.
     pub fn process_message(
         &mut self,
         msg: MeshMessage,
         rssi: i8,
         current_time_ms: u64,
+    ) -> Result<ProcessResult> {
+        self.receive(msg, None, rssi, current_time_ms)
+    }
+
+    /// Process a message that arrived in a frame sealed by `sender`, if any
+    fn receive(
+        &mut self,
+        msg: MeshMessage,
+        sender: Option<MeshNodeId>,
+        rssi: i8,
+        current_time_ms: u64,
     ) -> Result<ProcessResult> {
         // Verify checksum
         if !msg.verify_checksum() {
             self.stats.drop_count += 1;
             return Ok(ProcessResult::Dropped);
         }
+        let channel = self.channels.channel_at(current_time_ms);
+        self.channels.record(channel, ChannelEvent::Received);
 
         self.stats.rx_count += 1;
 
@@ -375,6 +908,30 @@ impl MeshNode {
             return Ok(ProcessResult::Ignored);
         }
 
+        // Only a frame sealed by the message's source proves who sent it
+        let source = sender
+            .filter(|sender| *sender == msg.source)
+            .and_then(|sender| self.security.as_ref()?.identity(sender));
+        if let (Some(ids), Some(source)) = (self.ids.as_mut(), source) {
+            let admitted = match &msg.payload {
+                MeshMessageType::PositionUpdate {
+                    node_id,
+                    position,
+                    timestamp_ms,
+                    ..
+                } if *node_id == msg.source => {
+                    ids.observe_position(source, *position, *timestamp_ms)
+                }
+                _ if ids.is_banned(source) => Err(SwarmError::PermissionDenied),
+                _ => Ok(()),
+            };
+            if admitted.is_err() {
+                self.stats.intrusion_drops += 1;
+                self.stats.drop_count += 1;
+                return Ok(ProcessResult::Dropped);
+            }
+        }
+
         // Process message
         let result = match &msg.payload {
             MeshMessageType::Heartbeat {
@@ -382,8 +939,12 @@ impl MeshNode {
                 position,
                 battery_percent,
                 neighbors_count: _,
+                queue_load,
             } => {
                 self.update_neighbor(*node_id, *position, *battery_percent, rssi, current_time_ms);
+                if let Some(neighbor) = self.neighbors.iter_mut().find(|n| n.node_id == *node_id) {
+                    neighbor.queue_load = *queue_load;
+                }
                 ProcessResult::Processed
             }
 
@@ -400,19 +961,66 @@ impl MeshNode {
                 ProcessResult::Processed
             }
 
-            MeshMessageType::Command {
-                command_id: _,
-                target,
-                action,
-            } => {
+            MeshMessageType::Command { target, action, .. } => {
+                // Relays re-seal every hop, so only the issuer's own
+                // signature proves who sent the command
+                let issuer = self
+                    .security
+                    .as_ref()
+                    .and_then(|security| security.verify_command(&msg));
+                if self.security.is_some() && issuer.is_none() {
+                    self.stats.auth_failures += 1;
+                    self.stats.drop_count += 1;
+                    return Ok(ProcessResult::Dropped);
+                }
+
                 let applies_to_us = match target {
                     CommandTarget::Broadcast => true,
                     CommandTarget::Node(id) => *id == self.config.node_id,
-                    CommandTarget::Group(_) => false, // TODO: implement groups
+                    CommandTarget::Group(group) => self.config.groups.contains(group),
                 };
 
-                if applies_to_us {
-                    ProcessResult::Command(action.clone())
+                let permitted = self.command_policy.as_ref().is_none_or(|policy| {
+                    issuer.is_some_and(|issuer| {
+                        policy
+                            .authorize(issuer, action.into(), target.into(), current_time_ms)
+                            .is_ok()
+                    })
+                });
+
+                if applies_to_us && !permitted {
+                    // Tell the sender so it stops retransmitting
+                    let nack = MeshMessage::ack(
+                        self.config.node_id,
+                        msg.source,
+                        msg.msg_id,
+                        AckStatus::Failed,
+                        current_time_ms,
+                    );
+                    self.queue_message(nack).ok();
+                    self.stats.unauthorized_commands += 1;
+                    ProcessResult::Dropped
+                } else if applies_to_us {
+                    // Acknowledge every copy: the previous ACK may have been lost
+                    let ack = MeshMessage::ack(
+                        self.config.node_id,
+                        msg.source,
+                        msg.msg_id,
+                        AckStatus::Success,
+                        current_time_ms,
+                    );
+                    self.queue_message(ack).ok();
+
+                    let key = (msg.source, msg.msg_id);
+                    if self.recent_commands.iter().any(|seen| *seen == key) {
+                        ProcessResult::Processed
+                    } else {
+                        if self.recent_commands.is_full() {
+                            self.recent_commands.pop_front();
+                        }
+                        self.recent_commands.push_back(key).ok();
+                        ProcessResult::Command(action.clone())
+                    }
                 } else {
                     ProcessResult::Processed
                 }
@@ -430,17 +1038,41 @@ impl MeshNode {
 
             MeshMessageType::Telemetry { .. } => ProcessResult::Processed,
             MeshMessageType::FormationUpdate { .. } => ProcessResult::Processed,
-            MeshMessageType::Ack { .. } => ProcessResult::Processed,
+            MeshMessageType::Ack {
+                original_msg_id,
+                status,
+            } => {
+                if msg.destination == Some(self.config.node_id) {
+                    self.handle_ack(msg.source, *original_msg_id, *status);
+                }
+                ProcessResult::Processed
+            }
             MeshMessageType::RouteRequest { .. } => ProcessResult::Processed,
             MeshMessageType::RouteResponse { .. } => ProcessResult::Processed,
             MeshMessageType::Custom { .. } => ProcessResult::Processed,
+            MeshMessageType::ChannelReport { .. } | MeshMessageType::ChannelSwitch { .. } => {
+                if let Some(reply) = self.channels.handle(&msg, current_time_ms) {
+                    self.queue_message(reply).ok();
+                }
+                ProcessResult::Processed
+            }
+            MeshMessageType::Revocations { list } => {
+                let applied = self
+                    .security
+                    .as_mut()
+                    .map(|security| security.apply_revocations(list));
+                if let Some(Err(SwarmError::AuthenticationFailed)) = applied {
+                    // Not signed by the root: do not relay it
+                    return Ok(ProcessResult::Dropped);
+                }
+                ProcessResult::Processed
+            }
         };
 
         // Forward if needed (broadcast or not for us)
         if msg.should_forward(self.config.node_id) {
             let mut forward_msg = msg.clone();
-            if forward_msg.decrement_ttl() {
-                self.tx_queue.push(forward_msg).ok();
+            if forward_msg.decrement_ttl() && self.enqueue(forward_msg).is_ok() {
                 self.stats.forward_count += 1;
             }
         }
@@ -448,6 +1080,77 @@ impl MeshNode {
         Ok(result)
     }
 
+    /// Record an ACK for one of our commands
+    fn handle_ack(&mut self, from: MeshNodeId, msg_id: u32, status: AckStatus) {
+        let Some(index) = self
+            .pending_commands
+            .iter()
+            .position(|c| c.msg_id == msg_id)
+        else {
+            return;
+        };
+
+        let delivery = &mut self.pending_commands[index];
+        if let Some((_, outcome)) = delivery
+            .outcomes
+            .iter_mut()
+            .find(|(id, outcome)| *id == from && *outcome == DeliveryStatus::Pending)
+        {
+            *outcome = DeliveryStatus::Acknowledged(status);
+            self.stats.ack_count += 1;
+        }
+
+        if delivery.is_complete() {
+            let delivery = self.pending_commands.swap_remove(index);
+            self.report_delivery(delivery);
+        }
+    }
+
+    /// Retransmit overdue commands and time out exhausted ones
+    fn retransmit_commands(&mut self, current_time_ms: u64) {
+        let mut index = 0;
+        while index < self.pending_commands.len() {
+            let delivery = &mut self.pending_commands[index];
+            if current_time_ms < delivery.next_retry_ms {
+                index += 1;
+                continue;
+            }
+
+            if delivery.retries >= self.config.max_retries {
+                let mut delivery = self.pending_commands.swap_remove(index);
+                for (_, outcome) in delivery.outcomes.iter_mut() {
+                    if *outcome == DeliveryStatus::Pending {
+                        *outcome = DeliveryStatus::TimedOut;
+                    }
+                }
+                self.report_delivery(delivery);
+                continue;
+            }
+
+            self.channels.record(
+                self.channels.channel_at(current_time_ms),
+                ChannelEvent::TxFailed,
+            );
+            let delivery = &mut self.pending_commands[index];
+            delivery.retries += 1;
+            delivery.timeout_ms = delivery.timeout_ms.saturating_mul(2);
+            delivery.next_retry_ms = current_time_ms + delivery.timeout_ms;
+            let message = delivery.message.clone();
+            if self.enqueue(message).is_ok() {
+                self.stats.retransmit_count += 1;
+            }
+            index += 1;
+        }
+    }
+
+    /// Queue a finished delivery report, dropping the oldest if full
+    fn report_delivery(&mut self, delivery: CommandDelivery) {
+        if self.delivery_reports.is_full() {
+            self.delivery_reports.pop_front();
+        }
+        self.delivery_reports.push_back(delivery).ok();
+    }
+
     /// Update neighbor information
     fn update_neighbor(
         &mut self,
@@ -493,9 +1196,25 @@ impl MeshNode {
         }
 
         self.update_neighbor_stats();
+        self.retransmit_commands(current_time_ms);
 
-        // Auto-send heartbeat if needed
-        if current_time_ms - self.last_heartbeat_ms >= self.config.heartbeat_interval_ms {
+        if let Some(security) = self.security.as_mut() {
+            security.expire_members(current_time_ms)?;
+        }
+
+        let active: Vec<MeshNodeId, MAX_NEIGHBORS> = self
+            .neighbors
+            .iter()
+            .filter(|n| n.is_active)
+            .map(|n| n.node_id)
+            .collect();
+        for msg in self.channels.tick(current_time_ms, &active) {
+            self.queue_message(msg).ok();
+        }
+
+        // Auto-send heartbeat if needed, adapting the rate once per interval
+        if self.is_heartbeat_due(current_time_ms) {
+            self.adapt_rate();
             self.broadcast_heartbeat(current_time_ms)?;
         }
 
@@ -516,7 +1235,8 @@ impl MeshNode {
 
     /// Check if heartbeat is due
     pub fn is_heartbeat_due(&self, current_time_ms: u64) -> bool {
-        current_time_ms - self.last_heartbeat_ms >= self.config.heartbeat_interval_ms
+        let interval = self.config.heartbeat_interval_ms * self.rate_divisor as u64;
+        current_time_ms - self.last_heartbeat_ms >= interval
     }
 
     /// Get closest neighbor
@@ -675,6 +1395,625 @@ mod tests {
         }
     }
 
+    /// Deliver every queued message from `from` to `to`
+    fn exchange(from: &mut MeshNode, to: &mut MeshNode, now: u64) -> std::vec::Vec<ProcessResult> {
+        let mut results = std::vec::Vec::new();
+        while let Some(msg) = from.get_next_tx_message() {
+            results.push(to.process_message(msg, -40, now).unwrap());
+        }
+        results
+    }
+
+    /// Like [`exchange`], but through sealed radio frames
+    fn exchange_frames(
+        from: &mut MeshNode,
+        to: &mut MeshNode,
+        now: u64,
+    ) -> std::vec::Vec<ProcessResult> {
+        let mut results = std::vec::Vec::new();
+        while let Some(frame) = from.next_tx_frame().unwrap() {
+            results.push(to.process_frame(&frame, -40, now).unwrap());
+        }
+        results
+    }
+
+    #[test]
+    fn test_command_ack_completes_delivery() {
+        let mut gcs = MeshNode::new(MeshNodeId::new(0));
+        let mut drone = MeshNode::new(MeshNodeId::new(1));
+
+        let msg_id = gcs
+            .send_command(
+                CommandTarget::Node(MeshNodeId::new(1)),
+                CommandAction::ReturnToLaunch,
+                1000,
+            )
+            .unwrap();
+        assert_eq!(gcs.pending_command_count(), 1);
+
+        let results = exchange(&mut gcs, &mut drone, 1000);
+        assert!(matches!(
+            results[..],
+            [ProcessResult::Command(CommandAction::ReturnToLaunch)]
+        ));
+        exchange(&mut drone, &mut gcs, 1010);
+
+        let report = gcs.poll_delivery_report().unwrap();
+        assert_eq!(report.msg_id, msg_id);
+        assert_eq!(
+            report.status(MeshNodeId::new(1)),
+            Some(DeliveryStatus::Acknowledged(AckStatus::Success))
+        );
+        assert_eq!(gcs.pending_command_count(), 0);
+        assert_eq!(gcs.stats().ack_count, 1);
+    }
+
+    #[test]
+    fn test_member_cannot_stop_swarm() {
+        use crate::rbac::{CommandPolicy, Roles};
+
+        let mut policy = CommandPolicy::new();
+        policy
+            .assign(DroneId::new(0), Roles::GROUND_STATION)
+            .unwrap();
+        policy.assign(DroneId::new(2), Roles::MEMBER).unwrap();
+        let mut drone = secured_among(1, &[0, 1, 2]);
+        drone.set_command_policy(policy);
+
+        let mut member = secured_among(2, &[0, 1, 2]);
+        let msg_id = member
+            .send_command_to(
+                CommandTarget::Broadcast,
+                CommandAction::EmergencyStop,
+                &[MeshNodeId::new(1)],
+                1000,
+            )
+            .unwrap();
+        let results = exchange_frames(&mut member, &mut drone, 1000);
+        assert!(matches!(results[..], [ProcessResult::Dropped]));
+        assert_eq!(drone.stats().unauthorized_commands, 1);
+
+        // The sender learns of the refusal instead of retransmitting
+        exchange_frames(&mut drone, &mut member, 1010);
+        let report = member.poll_delivery_report().unwrap();
+        assert_eq!(report.msg_id, msg_id);
+        assert_eq!(
+            report.status(MeshNodeId::new(1)),
+            Some(DeliveryStatus::Acknowledged(AckStatus::Failed))
+        );
+
+        // A member may still stop a single drone, and the GCS the swarm
+        member
+            .send_command(
+                CommandTarget::Node(MeshNodeId::new(1)),
+                CommandAction::EmergencyStop,
+                2000,
+            )
+            .unwrap();
+        let results = exchange_frames(&mut member, &mut drone, 2000);
+        assert!(matches!(
+            results[..],
+            [ProcessResult::Command(CommandAction::EmergencyStop)]
+        ));
+
+        let mut gcs = secured_among(0, &[0, 1, 2]);
+        gcs.send_command(CommandTarget::Broadcast, CommandAction::EmergencyStop, 3000)
+            .unwrap();
+        let results = exchange_frames(&mut gcs, &mut drone, 3000);
+        assert!(matches!(
+            results[..],
+            [ProcessResult::Command(CommandAction::EmergencyStop)]
+        ));
+    }
+
+    #[test]
+    fn test_command_needs_issuer_signature() {
+        // No policy: authenticity alone is checked
+        let mut drone = secured_among(1, &[0, 1, 2]);
+
+        // A member relaying, or claiming to be, the ground station
+        let claimed = MeshMessage::command(
+            MeshNodeId::new(0),
+            CommandTarget::Broadcast,
+            CommandAction::EmergencyStop,
+            1000,
+        );
+        let mut member = secured_among(2, &[0, 1, 2]);
+        member.queue_message(claimed.clone()).unwrap();
+        let results = exchange_frames(&mut member, &mut drone, 1000);
+        assert!(matches!(results[..], [ProcessResult::Dropped]));
+
+        // A genuine signature does not cover a different action
+        let mut gcs = secured_among(0, &[0, 1, 2]);
+        gcs.send_command(CommandTarget::Broadcast, CommandAction::Land, 1000)
+            .unwrap();
+        let mut tampered = gcs.get_next_tx_message().unwrap();
+        if let MeshMessageType::Command { action, .. } = &mut tampered.payload {
+            *action = CommandAction::EmergencyStop;
+        }
+        let result = drone.process_message(tampered, -40, 1000).unwrap();
+        assert!(matches!(result, ProcessResult::Dropped));
+
+        // Messages handed over without a frame are checked all the same
+        let result = drone.process_message(claimed, -40, 1000).unwrap();
+        assert!(matches!(result, ProcessResult::Dropped));
+        assert_eq!(drone.stats().auth_failures, 3);
+        assert_eq!(drone.stats().unauthorized_commands, 0);
+    }
+
+    #[test]
+    fn test_relayed_command_authorized_by_issuer() {
+        use crate::rbac::{CommandPolicy, Roles};
+
+        let mut policy = CommandPolicy::new();
+        policy
+            .assign(DroneId::new(0), Roles::GROUND_STATION)
+            .unwrap();
+        let mut gcs = secured_among(0, &[0, 1, 2, 3]);
+        let mut relay = secured_among(2, &[0, 1, 2, 3]);
+        let mut second_relay = secured_among(3, &[0, 1, 2, 3]);
+        let mut drone = secured_among(1, &[0, 1, 2, 3]);
+        drone.set_command_policy(policy);
+
+        // gcs -> relay -> second relay -> drone, re-sealed at every hop
+        let msg_id = gcs
+            .send_command(
+                CommandTarget::Node(MeshNodeId::new(1)),
+                CommandAction::Disarm,
+                1000,
+            )
+            .unwrap();
+        exchange_frames(&mut gcs, &mut relay, 1000);
+        exchange_frames(&mut relay, &mut second_relay, 1010);
+        let results = exchange_frames(&mut second_relay, &mut drone, 1020);
+        assert!(matches!(
+            results[..],
+            [ProcessResult::Command(CommandAction::Disarm)]
+        ));
+        assert_eq!(drone.stats().auth_failures, 0);
+
+        // The ACK takes the same relays back
+        exchange_frames(&mut drone, &mut second_relay, 1030);
+        exchange_frames(&mut second_relay, &mut relay, 1040);
+        exchange_frames(&mut relay, &mut gcs, 1050);
+        let report = gcs.poll_delivery_report().unwrap();
+        assert_eq!(report.msg_id, msg_id);
+        assert_eq!(
+            report.status(MeshNodeId::new(1)),
+            Some(DeliveryStatus::Acknowledged(AckStatus::Success))
+        );
+    }
+
+    #[test]
+    fn test_teleporting_member_banned() {
+        let mut drone = secured(1);
+        drone.set_intrusion_detection(IntrusionDetectionSystem::new());
+        let mut member = secured(2);
+
+        member.set_position([0.0, 0.0, 10.0]);
+        member.broadcast_position(0.0, 1000).unwrap();
+        exchange_frames(&mut member, &mut drone, 1000);
+        for step in 1..=3u64 {
+            member.set_position([5000.0 * step as f32, 0.0, 10.0]);
+            member.broadcast_position(0.0, 1000 + step * 100).unwrap();
+            exchange_frames(&mut member, &mut drone, 1000 + step * 100);
+        }
+
+        let ids = drone.intrusion_detection_mut().unwrap();
+        assert!(ids.is_banned(DroneId::new(2)));
+        assert!(drone.stats().intrusion_drops > 0);
+
+        // Nothing else from the banned drone gets through
+        member.broadcast_heartbeat(2000).unwrap();
+        let results = exchange_frames(&mut member, &mut drone, 2000);
+        assert!(matches!(results[..], [ProcessResult::Dropped]));
+    }
+
+    #[test]
+    fn test_retransmit_backoff_then_timeout() {
+        let mut gcs = MeshNode::new(MeshNodeId::new(0));
+        gcs.send_command(
+            CommandTarget::Node(MeshNodeId::new(1)),
+            CommandAction::Land,
+            0,
+        )
+        .unwrap();
+        while gcs.get_next_tx_message().is_some() {}
+
+        // Retransmissions at 250, 250+500, 750+1000, 1750+2000 ms
+        let mut retransmitted_at = std::vec::Vec::new();
+        for now in (0..8000).step_by(50) {
+            gcs.update(now).unwrap();
+            while let Some(msg) = gcs.get_next_tx_message() {
+                if matches!(msg.payload, MeshMessageType::Command { .. }) {
+                    retransmitted_at.push(now);
+                }
+            }
+        }
+
+        assert_eq!(retransmitted_at, [250, 750, 1750, 3750]);
+        let report = gcs.poll_delivery_report().unwrap();
+        assert_eq!(report.retries, 4);
+        assert_eq!(
+            report.status(MeshNodeId::new(1)),
+            Some(DeliveryStatus::TimedOut)
+        );
+    }
+
+    #[test]
+    fn test_retransmitted_command_executes_once() {
+        let mut gcs = MeshNode::new(MeshNodeId::new(0));
+        let mut drone = MeshNode::new(MeshNodeId::new(1));
+        gcs.send_command(CommandTarget::Broadcast, CommandAction::Arm, 0)
+            .unwrap();
+        let command = gcs.get_next_tx_message().unwrap();
+
+        let first = drone.process_message(command.clone(), -40, 0).unwrap();
+        let second = drone.process_message(command, -40, 300).unwrap();
+
+        assert!(matches!(first, ProcessResult::Command(CommandAction::Arm)));
+        assert!(matches!(second, ProcessResult::Processed));
+        // Both copies were acknowledged in case the first ACK was lost
+        let acks = core::iter::from_fn(|| drone.get_next_tx_message())
+            .filter(|msg| matches!(msg.payload, MeshMessageType::Ack { .. }))
+            .count();
+        assert_eq!(acks, 2);
+    }
+
+    #[test]
+    fn test_group_command_tracks_members() {
+        let mut gcs = MeshNode::new(MeshNodeId::new(0));
+        let mut member = MeshNode::new(MeshNodeId::new(1));
+        let mut outsider = MeshNode::new(MeshNodeId::new(2));
+        member.join_group(3).unwrap();
+        assert!(member.is_group_member(3));
+
+        let members = [MeshNodeId::new(1), MeshNodeId::new(4)];
+        gcs.send_command_to(CommandTarget::Group(3), CommandAction::Land, &members, 0)
+            .unwrap();
+        let command = gcs.get_next_tx_message().unwrap();
+
+        let at_member = member.process_message(command.clone(), -40, 0).unwrap();
+        let at_outsider = outsider.process_message(command, -40, 0).unwrap();
+        assert!(matches!(
+            at_member,
+            ProcessResult::Command(CommandAction::Land)
+        ));
+        assert!(matches!(at_outsider, ProcessResult::Processed));
+
+        exchange(&mut member, &mut gcs, 10);
+        let delivery = gcs.command_delivery(1).unwrap();
+        assert_eq!(
+            delivery.acknowledged().collect::<std::vec::Vec<_>>(),
+            [MeshNodeId::new(1)]
+        );
+        assert_eq!(
+            delivery.status(MeshNodeId::new(4)),
+            Some(DeliveryStatus::Pending)
+        );
+    }
+
+    /// Relayed broadcast of the given priority from drone 9
+    fn relayed(priority: MessagePriority, timestamp_ms: u64) -> MeshMessage {
+        MeshMessage::new(
+            MeshNodeId::new(9),
+            None,
+            priority,
+            MeshMessageType::Telemetry {
+                node_id: MeshNodeId::new(9),
+                battery_voltage: 15.2,
+                cpu_usage: 40,
+                rssi: -60,
+                gps_fix: true,
+                armed: true,
+            },
+            timestamp_ms,
+        )
+    }
+
+    #[test]
+    fn test_critical_first_then_weighted_fair() {
+        let mut node = MeshNode::new(MeshNodeId::new(1));
+        for (priority, count) in [
+            (MessagePriority::Low, 4),
+            (MessagePriority::Normal, 4),
+            (MessagePriority::High, 6),
+        ] {
+            for i in 0..count {
+                node.process_message(relayed(priority, i), -40, 0).unwrap();
+            }
+        }
+        node.broadcast_emergency(EmergencyType::MotorFailure, 0)
+            .unwrap();
+
+        let order: std::vec::Vec<MessagePriority> =
+            core::iter::from_fn(|| node.get_next_tx_message().map(|m| m.priority))
+                .take(8)
+                .collect();
+
+        use MessagePriority::*;
+        assert_eq!(
+            order,
+            [Critical, High, High, High, High, Normal, Normal, Low]
+        );
+        assert_eq!(node.stats().tx_by_priority[Low as usize], 1);
+    }
+
+    #[test]
+    fn test_fifo_within_priority() {
+        let mut node = MeshNode::new(MeshNodeId::new(1));
+        for timestamp in 0..5 {
+            node.process_message(relayed(MessagePriority::Normal, timestamp), -40, 0)
+                .unwrap();
+        }
+
+        let timestamps: std::vec::Vec<u64> =
+            core::iter::from_fn(|| node.get_next_tx_message().map(|m| m.timestamp_ms)).collect();
+        assert_eq!(timestamps, [0, 1, 2, 3, 4]);
+    }
+
+    #[test]
+    fn test_full_queue_drops_low_before_critical() {
+        let mut node = MeshNode::new(MeshNodeId::new(1));
+        for i in 0..MAX_PENDING_MESSAGES as u64 {
+            node.process_message(relayed(MessagePriority::Low, i), -40, 0)
+                .unwrap();
+        }
+
+        assert!(node.broadcast_emergency(EmergencyType::Geofence, 0).is_ok());
+        // A Low message cannot displace another Low one
+        node.process_message(relayed(MessagePriority::Low, 99), -40, 0)
+            .unwrap();
+
+        let stats = node.stats();
+        assert_eq!(stats.drop_by_priority[MessagePriority::Low as usize], 2);
+        assert_eq!(
+            stats.drop_by_priority[MessagePriority::Critical as usize],
+            0
+        );
+        assert_eq!(
+            node.get_next_tx_message().unwrap().priority,
+            MessagePriority::Critical
+        );
+        // The oldest Low message made room
+        assert_eq!(node.get_next_tx_message().unwrap().timestamp_ms, 1);
+    }
+
+    #[test]
+    fn test_congested_neighbor_slows_heartbeats() {
+        let mut node = MeshNode::new(MeshNodeId::new(1));
+        let mut heartbeat =
+            MeshMessage::heartbeat(MeshNodeId::new(2), [0.0, 0.0, 10.0], 80, 1, 500);
+        if let MeshMessageType::Heartbeat { queue_load, .. } = &mut heartbeat.payload {
+            *queue_load = 100;
+        }
+        node.process_message(heartbeat, -40, 500).unwrap();
+        assert!(node.is_congested());
+
+        node.update(1000).unwrap();
+        assert_eq!(node.rate_divisor(), 2);
+        assert!(!node.is_heartbeat_due(2500));
+        assert!(node.is_heartbeat_due(3000));
+
+        // The neighbor recovers: additive speed-up back to nominal
+        let calm = MeshMessage::heartbeat(MeshNodeId::new(2), [0.0, 0.0, 10.0], 80, 1, 2900);
+        node.process_message(calm, -40, 2900).unwrap();
+        while node.get_next_tx_message().is_some() {}
+        node.update(3000).unwrap();
+        assert_eq!(node.rate_divisor(), 1);
+        assert_eq!(node.stats().congestion_events, 1);
+    }
+
+    /// Node with frame keys; drones 1 and 2 know each other
+    fn secured(id: u8) -> MeshNode {
+        secured_among(id, &[1, 2])
+    }
+
+    /// Node with frame keys of every drone in `members`
+    fn secured_among(id: u8, members: &[u8]) -> MeshNode {
+        let mut node = MeshNode::new(MeshNodeId::new(id));
+        let mesh_id = MeshConfig::default().mesh_id;
+        let mut security = MeshSecurity::new(MeshNodeId::new(id), mesh_id, [7; 32], [id; 32]);
+        for &member in members {
+            let key = MeshSecurity::new(MeshNodeId::new(member), mesh_id, [7; 32], [member; 32]);
+            security
+                .add_member(MeshNodeId::new(member), *key.public_key())
+                .unwrap();
+        }
+        node.set_security(security);
+        node
+    }
+
+    #[test]
+    fn test_sealed_command_accepted() {
+        let mut gcs = secured(1);
+        let mut drone = secured(2);
+        gcs.send_command(
+            CommandTarget::Node(MeshNodeId::new(2)),
+            CommandAction::Land,
+            1000,
+        )
+        .unwrap();
+
+        let frame = gcs.next_tx_frame().unwrap().unwrap();
+        let result = drone.process_frame(&frame, -40, 1000).unwrap();
+        assert!(matches!(
+            result,
+            ProcessResult::Command(CommandAction::Land)
+        ));
+        assert_eq!(drone.stats().drop_count, 0);
+    }
+
+    #[test]
+    fn test_injected_plain_emergency_stop_rejected() {
+        let mut drone = secured(2);
+        let forged = MeshMessage::command(
+            MeshNodeId::new(1),
+            CommandTarget::Broadcast,
+            CommandAction::EmergencyStop,
+            1000,
+        );
+        let bytes = MeshFrame::Plain(forged).to_bytes().unwrap();
+
+        let result = drone.process_frame(&bytes, -40, 1000).unwrap();
+        assert!(matches!(result, ProcessResult::Dropped));
+        assert_eq!(drone.stats().unauthenticated_rejects, 1);
+        assert_eq!(drone.stats().rx_count, 0);
+
+        // Garbage never reaches the security layer
+        drone.process_frame(&[0xFF; 8], -40, 1000).unwrap();
+        assert_eq!(drone.stats().drop_count, 2);
+    }
+
+    #[test]
+    fn test_replayed_frame_rejected() {
+        let mut gcs = secured(1);
+        let mut drone = secured(2);
+        gcs.send_command(CommandTarget::Broadcast, CommandAction::Arm, 1000)
+            .unwrap();
+        let frame = gcs.next_tx_frame().unwrap().unwrap();
+
+        drone.process_frame(&frame, -40, 1000).unwrap();
+        let replay = drone.process_frame(&frame, -40, 1500).unwrap();
+        assert!(matches!(replay, ProcessResult::Dropped));
+        assert_eq!(drone.stats().replay_rejects, 1);
+    }
+
+    #[test]
+    fn test_encryption_requires_keys() {
+        let mut node = MeshNode::new(MeshNodeId::new(1));
+        node.broadcast_heartbeat(1000).unwrap();
+        assert_eq!(node.next_tx_frame().unwrap_err(), SwarmError::ConfigError);
+
+        let mut plain = MeshNode::with_config(MeshConfig {
+            node_id: MeshNodeId::new(1),
+            encryption_enabled: false,
+            ..MeshConfig::default()
+        });
+        let mut peer = MeshNode::with_config(MeshConfig {
+            node_id: MeshNodeId::new(2),
+            encryption_enabled: false,
+            ..MeshConfig::default()
+        });
+        plain.broadcast_heartbeat(1000).unwrap();
+        let frame = plain.next_tx_frame().unwrap().unwrap();
+        peer.process_frame(&frame, -40, 1000).unwrap();
+        assert_eq!(peer.neighbor_count(), 1);
+    }
+
+    #[test]
+    fn test_revocation_flood_drops_member() {
+        use crate::certificate::{Capabilities, Provisioner};
+
+        let mut ca = Provisioner::new([9; 32]);
+        let mesh_id = MeshConfig::default().mesh_id;
+        let intruder = MeshSecurity::new(MeshNodeId::new(3), mesh_id, [7; 32], [3; 32]);
+        let cert = ca
+            .certify(
+                DroneId::new(3),
+                intruder.public_key(),
+                SecurityLevel::Internal,
+                Capabilities::RELAY,
+                0,
+                60_000,
+            )
+            .unwrap();
+
+        let mut gcs = secured(1);
+        let mut drone = secured(2);
+        for node in [&mut gcs, &mut drone] {
+            let security = node.security_mut().unwrap();
+            security.set_trust_anchor(ca.trust_anchor());
+            security
+                .admit_member(MeshNodeId::new(3), &cert, 1000)
+                .unwrap();
+        }
+
+        let list = ca.revoke(cert.serial, 2000).unwrap();
+        gcs.broadcast_revocations(list, 2000).unwrap();
+        assert!(!gcs.security_mut().unwrap().is_member(MeshNodeId::new(3)));
+
+        let frame = gcs.next_tx_frame().unwrap().unwrap();
+        drone.process_frame(&frame, -40, 2000).unwrap();
+        let security = drone.security_mut().unwrap();
+        assert!(!security.is_member(MeshNodeId::new(3)));
+        assert!(security.is_member(MeshNodeId::new(1)));
+        assert_eq!(
+            security
+                .admit_member(MeshNodeId::new(3), &cert, 2500)
+                .unwrap_err(),
+            SwarmError::PermissionDenied
+        );
+
+        // A forged list that would reinstate drone 3 goes no further
+        let mut forged = ca.revocations().clone();
+        forged.version += 1;
+        forged.serials.clear();
+        let msg = MeshMessage::new(
+            MeshNodeId::new(1),
+            None,
+            MessagePriority::High,
+            MeshMessageType::Revocations { list: forged },
+            3000,
+        );
+        let queued = drone.queued_message_count();
+        let result = drone.process_message(msg, -40, 3000).unwrap();
+        assert!(matches!(result, ProcessResult::Dropped));
+        assert_eq!(drone.queued_message_count(), queued);
+    }
+
+    #[test]
+    fn test_lagging_member_resyncs_key() {
+        use crate::crypto::MAX_RATCHET_STEPS;
+
+        let mut ahead = secured(1);
+        let mut behind = secured(2);
+        let crypto = ahead.security_mut().unwrap().crypto_mut();
+        for epoch in 1..=MAX_RATCHET_STEPS + 4 {
+            crypto.advance_epoch(epoch, 0).unwrap();
+        }
+
+        ahead.broadcast_heartbeat(1000).unwrap();
+        let frame = ahead.next_tx_frame().unwrap().unwrap();
+        behind.process_frame(&frame, -40, 1000).unwrap();
+        assert_eq!(behind.neighbor_count(), 1);
+        let crypto = behind.security_mut().unwrap().crypto_mut();
+        assert_eq!(crypto.epoch(), MAX_RATCHET_STEPS + 4);
+    }
+
+    #[test]
+    fn test_expired_member_dropped_on_update() {
+        use crate::certificate::{Capabilities, Provisioner};
+
+        let mut ca = Provisioner::new([9; 32]);
+        let mesh_id = MeshConfig::default().mesh_id;
+        let member = MeshSecurity::new(MeshNodeId::new(3), mesh_id, [7; 32], [3; 32]);
+        let cert = ca
+            .certify(
+                DroneId::new(3),
+                member.public_key(),
+                SecurityLevel::Internal,
+                Capabilities::RELAY,
+                0,
+                60_000,
+            )
+            .unwrap();
+
+        let mut drone = secured(2);
+        let security = drone.security_mut().unwrap();
+        security.set_trust_anchor(ca.trust_anchor());
+        security
+            .admit_member(MeshNodeId::new(3), &cert, 1000)
+            .unwrap();
+
+        drone.update(60_000).unwrap();
+        assert!(drone.security_mut().unwrap().is_member(MeshNodeId::new(3)));
+        drone.update(60_001).unwrap();
+        let security = drone.security_mut().unwrap();
+        assert!(!security.is_member(MeshNodeId::new(3)));
+        assert!(security.is_member(MeshNodeId::new(1)));
+    }
+
     #[test]
     fn test_swarm_center_calculation() {
         let mut node = MeshNode::new(MeshNodeId::new(1));
@@ -8,6 +8,7 @@
 //! - Blockchain-based verification (concept)
 
 use crate::crypto::KeyStore;
+use crate::reputation::TrustPolicy;
 use crate::types::*;
 use chacha20poly1305::{
     aead::{AeadInPlace, KeyInit},
@@ -91,6 +92,8 @@ pub struct FederatedCoordinator {
     update_history: FnvIndexMap<u64, Vec<Round, 100>, 128>,
     /// Key store for signature verification (BUG-005 FIX)
     key_store: KeyStore,
+    /// Participants whose updates are refused
+    excluded: Vec<DroneId, 128>,
 }
 
 impl FederatedCoordinator {
@@ -105,6 +108,7 @@ impl FederatedCoordinator {
             bft_enabled: true,
             update_history: FnvIndexMap::new(),
             key_store,
+            excluded: Vec::new(),
         }
     }
 
@@ -115,6 +119,10 @@ impl FederatedCoordinator {
             return Err(SwarmError::InvalidMessage);
         }
 
+        if self.excluded.contains(&update.drone_id) {
+            return Err(SwarmError::PermissionDenied);
+        }
+
         // BUG-005 FIX: Verify signature
         let public_key = self.key_store.get_key(update.drone_id)?;
 
@@ -291,6 +299,23 @@ impl FederatedCoordinator {
         self.min_participants = min;
     }
 
+    /// Refuse updates from participants the policy does not trust
+    ///
+    /// Pending updates of the current round from those participants are
+    /// dropped. Call again whenever trust scores change.
+    pub fn exclude_untrusted(&mut self, policy: &impl TrustPolicy) {
+        self.excluded.clear();
+        for drone in self.key_store.drones() {
+            if !policy.is_trusted(drone) {
+                // Capacity matches the key store
+                self.excluded.push(drone).ok();
+            }
+        }
+        let excluded = &self.excluded;
+        self.pending_updates
+            .retain(|update| !excluded.contains(&update.drone_id));
+    }
+
     /// Enable/disable Byzantine fault tolerance
     pub fn set_bft_enabled(&mut self, enabled: bool) {
         self.bft_enabled = enabled;
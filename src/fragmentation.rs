@@ -0,0 +1,747 @@
+//! Message fragmentation and reassembly for payloads above the link MTU
+//!
+//! Federated model updates, Raft log batches and formation updates can be
+//! several kilobytes, far above what a LoRa-class radio (or even a 1280-byte
+//! IPv6 frame) carries. This layer splits a message into fragments that fit
+//! the configured MTU and reassembles them at the receiver. Provides:
+//! - Fragment headers with message ID, index, count and byte offset
+//! - Bounded reassembly buffers with stall timeouts
+//! - Selective retransmission: the receiver NACKs with a bitmap of the
+//!   fragments it holds and the sender resends only the missing ones
+//! - End-to-end ACKs so the sender can release its copy
+//!
+//! Like the DTN node the layer is transport-agnostic: send whatever
+//! [`FragmentationLayer::poll_transmit`] returns to the drone it names, and
+//! feed every received [`FragmentMessage`] to [`FragmentationLayer::receive`].
+
+use crate::transport::MAX_FRAME_SIZE;
+use crate::types::*;
+use heapless::{Deque, Vec};
+use serde::{Deserialize, Serialize};
+
+/// Maximum size of a message before fragmentation (bytes)
+///
+/// Fits a full `AppendEntries` batch or a federated model update.
+pub const MAX_MESSAGE_PAYLOAD: usize = 16384;
+
+/// Maximum fragments per message
+pub const MAX_FRAGMENTS: usize = 256;
+
+/// Smallest supported link MTU (bytes)
+pub const MIN_MTU: usize = 128;
+
+/// Worst-case encoded size of a fragment's framing (bytes)
+pub const FRAGMENT_OVERHEAD: usize = 32;
+
+/// Maximum fragment data (bytes), reached when the MTU is a full frame
+pub const MAX_FRAGMENT_DATA: usize = MAX_FRAME_SIZE - FRAGMENT_OVERHEAD;
+
+/// Messages awaiting acknowledgment at the sender
+pub const MAX_OUTGOING_MESSAGES: usize = 2;
+
+/// Messages being reassembled concurrently
+pub const MAX_REASSEMBLY_BUFFERS: usize = 4;
+
+/// Completed messages remembered to suppress duplicates and re-ACK
+const COMPLETED_CACHE: usize = 16;
+
+/// Fragments queued for transmission
+const TRANSMIT_QUEUE: usize = MAX_FRAGMENTS * MAX_OUTGOING_MESSAGES;
+
+/// ACKs and NACKs queued for transmission
+const CONTROL_QUEUE: usize = 8;
+
+/// Fragment header
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct FragmentHeader {
+    /// Drone that fragmented the message
+    pub source: DroneId,
+    /// Message identifier, unique per source
+    pub message_id: u16,
+    /// Fragment index (0-based)
+    pub index: u16,
+    /// Total fragments in the message
+    pub count: u16,
+    /// Byte offset of this fragment in the message
+    pub offset: u16,
+    /// Total message length (bytes)
+    pub total_len: u16,
+}
+
+/// Bitmap of fragment indices
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct FragmentMask([u64; MAX_FRAGMENTS / 64]);
+
+impl FragmentMask {
+    /// Create an empty mask
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Mark a fragment
+    pub fn set(&mut self, index: u16) {
+        let index = index as usize;
+        if index < MAX_FRAGMENTS {
+            self.0[index / 64] |= 1 << (index % 64);
+        }
+    }
+
+    /// Check if a fragment is marked
+    pub fn contains(&self, index: u16) -> bool {
+        let index = index as usize;
+        index < MAX_FRAGMENTS && self.0[index / 64] & (1 << (index % 64)) != 0
+    }
+
+    /// Number of marked fragments
+    pub fn count(&self) -> usize {
+        self.0.iter().map(|word| word.count_ones() as usize).sum()
+    }
+
+    /// Check if all of the first `count` fragments are marked
+    pub fn is_complete(&self, count: u16) -> bool {
+        (0..count).all(|index| self.contains(index))
+    }
+
+    /// Iterate over unmarked indices below `count`
+    pub fn missing(&self, count: u16) -> impl Iterator<Item = u16> + '_ {
+        (0..count).filter(move |index| !self.contains(*index))
+    }
+}
+
+/// Fragmentation protocol messages
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[allow(clippy::large_enum_variant)]
+pub enum FragmentMessage {
+    /// Part of a larger message
+    Fragment {
+        header: FragmentHeader,
+        data: Vec<u8, MAX_FRAGMENT_DATA>,
+    },
+    /// Receiver has the whole message
+    Ack { sender: DroneId, message_id: u16 },
+    /// Receiver is missing fragments; `received` marks the ones it holds
+    Nack {
+        sender: DroneId,
+        message_id: u16,
+        received: FragmentMask,
+    },
+}
+
+/// Fragmentation configuration
+#[derive(Debug, Clone, Copy)]
+pub struct FragmentConfig {
+    /// Link MTU in bytes (clamped to `MIN_MTU..=MAX_FRAME_SIZE`)
+    pub mtu: usize,
+    /// Time without new fragments before the receiver NACKs (ms)
+    pub nack_timeout_ms: u64,
+    /// Time without an ACK or NACK before the sender probes again (ms)
+    pub retransmit_timeout_ms: u64,
+    /// Probes without a response before the sender gives up
+    pub max_retries: u8,
+    /// Time without new fragments before a reassembly buffer is dropped (ms)
+    pub reassembly_timeout_ms: u64,
+}
+
+impl Default for FragmentConfig {
+    fn default() -> Self {
+        Self {
+            // Matches `NetworkConfig::mtu`
+            mtu: 1280,
+            nack_timeout_ms: 200,
+            retransmit_timeout_ms: 1000,
+            max_retries: 5,
+            reassembly_timeout_ms: 5000,
+        }
+    }
+}
+
+/// Fragmentation statistics
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct FragmentStats {
+    /// Messages accepted for sending
+    pub messages_sent: u64,
+    /// Messages acknowledged by their destination
+    pub messages_acked: u64,
+    /// Messages abandoned after `max_retries` probes
+    pub messages_failed: u64,
+    /// Messages reassembled and delivered
+    pub messages_delivered: u64,
+    /// Fragments transmitted (including retransmissions)
+    pub fragments_sent: u64,
+    /// Fragments retransmitted after a NACK or probe
+    pub fragments_retransmitted: u64,
+    /// Fragments received
+    pub fragments_received: u64,
+    /// Fragments already held (or of a completed message)
+    pub duplicate_fragments: u64,
+    /// NACKs sent
+    pub nacks_sent: u64,
+    /// Reassembly buffers dropped after `reassembly_timeout_ms`
+    pub reassembly_timeouts: u64,
+    /// Fragments refused because every reassembly buffer was busy
+    pub reassembly_overflows: u64,
+}
+
+/// Message waiting for an ACK
+struct OutgoingMessage {
+    destination: DroneId,
+    message_id: u16,
+    payload: Vec<u8, MAX_MESSAGE_PAYLOAD>,
+    count: u16,
+    /// Time of the last send, ACK/NACK or probe (ms)
+    last_activity: u64,
+    retries: u8,
+}
+
+/// Partially received message
+struct ReassemblyBuffer {
+    source: DroneId,
+    message_id: u16,
+    count: u16,
+    /// Data bytes in every fragment but the last (the sender's fragment size)
+    fragment_size: usize,
+    data: Vec<u8, MAX_MESSAGE_PAYLOAD>,
+    received: FragmentMask,
+    /// Time the last new fragment arrived (ms)
+    last_progress: u64,
+    /// Time of the last NACK (ms)
+    last_nack: u64,
+}
+
+/// Fragment size implied by a fragment of `len` bytes
+///
+/// The sender cuts every fragment but the last to the same size, so fragment
+/// `index` must start at `index * size`. `None` unless the fragment sits
+/// exactly there and `count` fragments of that size cover the message.
+fn implied_fragment_size(header: &FragmentHeader, len: usize) -> Option<usize> {
+    let index = header.index as usize;
+    let offset = header.offset as usize;
+    let last = header.index + 1 == header.count;
+    let size = if !last || index == 0 {
+        len
+    } else if offset.is_multiple_of(index) {
+        offset / index
+    } else {
+        return None;
+    };
+    let total_len = header.total_len as usize;
+    let tiles = size > 0
+        && len <= size
+        && offset == index * size
+        && total_len.div_ceil(size) == header.count as usize
+        && (!last || offset + len == total_len);
+    tiles.then_some(size)
+}
+
+/// Queued ACK or NACK
+#[derive(Debug, Clone, Copy)]
+enum Control {
+    Ack {
+        to: DroneId,
+        message_id: u16,
+    },
+    Nack {
+        to: DroneId,
+        message_id: u16,
+        received: FragmentMask,
+    },
+}
+
+/// Fragmenting sender and reassembling receiver for one drone
+pub struct FragmentationLayer {
+    /// This drone's ID
+    local_id: DroneId,
+    /// Configuration
+    config: FragmentConfig,
+    /// Next message ID
+    next_message_id: u16,
+    /// Messages awaiting acknowledgment
+    outgoing: Vec<OutgoingMessage, MAX_OUTGOING_MESSAGES>,
+    /// Fragments to transmit, as (message ID, index)
+    transmit_queue: Deque<(u16, u16), TRANSMIT_QUEUE>,
+    /// ACKs and NACKs to transmit
+    control_queue: Deque<Control, CONTROL_QUEUE>,
+    /// Messages being reassembled
+    reassembly: Vec<ReassemblyBuffer, MAX_REASSEMBLY_BUFFERS>,
+    /// Recently completed messages, as (source, message ID)
+    completed: Deque<(DroneId, u16), COMPLETED_CACHE>,
+    /// Statistics
+    stats: FragmentStats,
+}
+
+impl FragmentationLayer {
+    /// Create a fragmentation layer
+    pub fn new(local_id: DroneId, config: FragmentConfig) -> Self {
+        Self {
+            local_id,
+            config,
+            next_message_id: 0,
+            outgoing: Vec::new(),
+            transmit_queue: Deque::new(),
+            control_queue: Deque::new(),
+            reassembly: Vec::new(),
+            completed: Deque::new(),
+            stats: FragmentStats::default(),
+        }
+    }
+
+    /// Fragment data bytes carried per fragment at the configured MTU
+    pub fn fragment_size(&self) -> usize {
+        self.config.mtu.clamp(MIN_MTU, MAX_FRAME_SIZE) - FRAGMENT_OVERHEAD
+    }
+
+    /// Queue `payload` for `destination`, returning its message ID
+    ///
+    /// Fails with `BufferFull` while `MAX_OUTGOING_MESSAGES` messages are
+    /// still awaiting acknowledgment.
+    pub fn send(&mut self, destination: DroneId, payload: &[u8]) -> Result<u16> {
+        self.send_with(destination, |buf| {
+            buf.get_mut(..payload.len())
+                .ok_or(SwarmError::BufferFull)?
+                .copy_from_slice(payload);
+            Ok(payload.len())
+        })
+    }
+
+    /// Serialize `message` with postcard and queue it for `destination`
+    pub fn send_serialized<M: Serialize>(
+        &mut self,
+        destination: DroneId,
+        message: &M,
+    ) -> Result<u16> {
+        self.send_with(destination, |buf| {
+            postcard::to_slice(message, buf)
+                .map(|encoded| encoded.len())
+                .map_err(|_| SwarmError::SerializationError)
+        })
+    }
+
+    /// Queue a message written into the outgoing buffer by `write`
+    fn send_with<F>(&mut self, destination: DroneId, write: F) -> Result<u16>
+    where
+        F: FnOnce(&mut [u8]) -> Result<usize>,
+    {
+        if destination == self.local_id {
+            return Err(SwarmError::InvalidParameter);
+        }
+        if self.outgoing.is_full() {
+            return Err(SwarmError::BufferFull);
+        }
+
+        let mut payload = Vec::new();
+        payload
+            .resize(MAX_MESSAGE_PAYLOAD, 0)
+            .map_err(|_| SwarmError::BufferFull)?;
+        let len = write(&mut payload)?;
+        if len == 0 {
+            return Err(SwarmError::InvalidParameter);
+        }
+        payload.truncate(len);
+
+        let count = len.div_ceil(self.fragment_size());
+        if count > MAX_FRAGMENTS {
+            return Err(SwarmError::BufferFull);
+        }
+        let count = count as u16;
+
+        let message_id = self.next_message_id;
+        self.next_message_id = self.next_message_id.wrapping_add(1);
+
+        for index in 0..count {
+            self.transmit_queue
+                .push_back((message_id, index))
+                .map_err(|_| SwarmError::BufferFull)?;
+        }
+        self.outgoing
+            .push(OutgoingMessage {
+                destination,
+                message_id,
+                payload,
+                count,
+                last_activity: crate::get_time_ms(),
+                retries: 0,
+            })
+            .map_err(|_| SwarmError::BufferFull)?;
+
+        self.stats.messages_sent += 1;
+        Ok(message_id)
+    }
+
+    /// Next message to transmit and the drone to send it to
+    ///
+    /// ACKs and NACKs go first, then queued fragments. Call until it returns
+    /// `None` after every [`send`](Self::send), [`receive`](Self::receive) and
+    /// [`tick`](Self::tick).
+    pub fn poll_transmit(&mut self) -> Option<(DroneId, FragmentMessage)> {
+        if let Some(control) = self.control_queue.pop_front() {
+            return Some(match control {
+                Control::Ack { to, message_id } => (
+                    to,
+                    FragmentMessage::Ack {
+                        sender: self.local_id,
+                        message_id,
+                    },
+                ),
+                Control::Nack {
+                    to,
+                    message_id,
+                    received,
+                } => (
+                    to,
+                    FragmentMessage::Nack {
+                        sender: self.local_id,
+                        message_id,
+                        received,
+                    },
+                ),
+            });
+        }
+
+        while let Some((message_id, index)) = self.transmit_queue.pop_front() {
+            // The message may have been acknowledged or abandoned meanwhile
+            if let Some(fragment) = self.build_fragment(message_id, index) {
+                // Probe timing starts once the burst has actually left
+                let now = crate::get_time_ms();
+                if let Some(message) = self
+                    .outgoing
+                    .iter_mut()
+                    .find(|message| message.message_id == message_id)
+                {
+                    message.last_activity = now;
+                }
+                self.stats.fragments_sent += 1;
+                return Some(fragment);
+            }
+        }
+        None
+    }
+
+    /// Build fragment `index` of an outgoing message
+    fn build_fragment(&self, message_id: u16, index: u16) -> Option<(DroneId, FragmentMessage)> {
+        let message = self
+            .outgoing
+            .iter()
+            .find(|message| message.message_id == message_id)?;
+
+        let size = self.fragment_size();
+        let start = index as usize * size;
+        let end = (start + size).min(message.payload.len());
+        let data = Vec::from_slice(message.payload.get(start..end)?).ok()?;
+
+        Some((
+            message.destination,
+            FragmentMessage::Fragment {
+                header: FragmentHeader {
+                    source: self.local_id,
+                    message_id,
+                    index,
+                    count: message.count,
+                    offset: start as u16,
+                    total_len: message.payload.len() as u16,
+                },
+                data,
+            },
+        ))
+    }
+
+    /// Process a received message
+    ///
+    /// Returns the source and payload of a message once all of its fragments
+    /// have arrived.
+    pub fn receive(
+        &mut self,
+        message: FragmentMessage,
+    ) -> Result<Option<(DroneId, Vec<u8, MAX_MESSAGE_PAYLOAD>)>> {
+        match message {
+            FragmentMessage::Fragment { header, data } => self.handle_fragment(header, &data),
+            FragmentMessage::Ack { sender, message_id } => {
+                if let Some(index) = self.outgoing_index(sender, message_id) {
+                    self.outgoing.swap_remove(index);
+                    self.stats.messages_acked += 1;
+                }
+                Ok(None)
+            }
+            FragmentMessage::Nack {
+                sender,
+                message_id,
+                received,
+            } => {
+                self.handle_nack(sender, message_id, &received)?;
+                Ok(None)
+            }
+        }
+    }
+
+    fn handle_fragment(
+        &mut self,
+        header: FragmentHeader,
+        data: &[u8],
+    ) -> Result<Option<(DroneId, Vec<u8, MAX_MESSAGE_PAYLOAD>)>> {
+        let total_len = header.total_len as usize;
+        let offset = header.offset as usize;
+        if header.count == 0
+            || header.count as usize > MAX_FRAGMENTS
+            || header.index >= header.count
+            || total_len > MAX_MESSAGE_PAYLOAD
+            || data.is_empty()
+            || offset + data.len() > total_len
+        {
+            return Err(SwarmError::InvalidMessage);
+        }
+        let fragment_size =
+            implied_fragment_size(&header, data.len()).ok_or(SwarmError::InvalidMessage)?;
+        self.stats.fragments_received += 1;
+
+        let key = (header.source, header.message_id);
+        if self.completed.iter().any(|done| *done == key) {
+            // Our ACK was lost: repeat it
+            self.stats.duplicate_fragments += 1;
+            self.queue_control(Control::Ack {
+                to: header.source,
+                message_id: header.message_id,
+            });
+            return Ok(None);
+        }
+
+        let now = crate::get_time_ms();
+        let slot = match self
+            .reassembly
+            .iter()
+            .position(|buffer| (buffer.source, buffer.message_id) == key)
+        {
+            Some(slot) => slot,
+            None => {
+                let mut buffer = ReassemblyBuffer {
+                    source: header.source,
+                    message_id: header.message_id,
+                    count: header.count,
+                    fragment_size,
+                    data: Vec::new(),
+                    received: FragmentMask::new(),
+                    last_progress: now,
+                    last_nack: now,
+                };
+                buffer
+                    .data
+                    .resize(total_len, 0)
+                    .map_err(|_| SwarmError::BufferFull)?;
+                if self.reassembly.push(buffer).is_err() {
+                    self.stats.reassembly_overflows += 1;
+                    return Err(SwarmError::BufferFull);
+                }
+                self.reassembly.len() - 1
+            }
+        };
+
+        let buffer = &mut self.reassembly[slot];
+        if buffer.count != header.count
+            || buffer.fragment_size != fragment_size
+            || buffer.data.len() != total_len
+        {
+            return Err(SwarmError::InvalidMessage);
+        }
+        if buffer.received.contains(header.index) {
+            self.stats.duplicate_fragments += 1;
+        } else {
+            buffer.data[offset..offset + data.len()].copy_from_slice(data);
+            buffer.received.set(header.index);
+            buffer.last_progress = now;
+        }
+
+        if buffer.received.is_complete(buffer.count) {
+            let buffer = self.reassembly.swap_remove(slot);
+            if self.completed.is_full() {
+                self.completed.pop_front();
+            }
+            self.completed.push_back(key).ok();
+            self.queue_control(Control::Ack {
+                to: buffer.source,
+                message_id: buffer.message_id,
+            });
+            self.stats.messages_delivered += 1;
+            return Ok(Some((buffer.source, buffer.data)));
+        }
+
+        // The last fragment arrived with gaps: ask for them right away
+        if header.index + 1 == header.count {
+            let received = buffer.received;
+            buffer.last_nack = now;
+            self.send_nack(header.source, header.message_id, received);
+        }
+        Ok(None)
+    }
+
+    fn handle_nack(
+        &mut self,
+        sender: DroneId,
+        message_id: u16,
+        received: &FragmentMask,
+    ) -> Result<()> {
+        let Some(index) = self.outgoing_index(sender, message_id) else {
+            return Ok(());
+        };
+        let message = &mut self.outgoing[index];
+        message.last_activity = crate::get_time_ms();
+        message.retries = 0;
+
+        let count = message.count;
+        for fragment in received.missing(count) {
+            let queued = self
+                .transmit_queue
+                .iter()
+                .any(|entry| *entry == (message_id, fragment));
+            if !queued {
+                self.transmit_queue
+                    .push_back((message_id, fragment))
+                    .map_err(|_| SwarmError::BufferFull)?;
+                self.stats.fragments_retransmitted += 1;
+            }
+        }
+        Ok(())
+    }
+
+    /// Periodic maintenance (call in main loop)
+    ///
+    /// NACKs stalled reassemblies, drops abandoned ones, and probes or gives
+    /// up on unacknowledged messages.
+    pub fn tick(&mut self) -> Result<()> {
+        let now = crate::get_time_ms();
+
+        let before = self.reassembly.len();
+        let timeout = self.config.reassembly_timeout_ms;
+        self.reassembly
+            .retain(|buffer| now.saturating_sub(buffer.last_progress) < timeout);
+        self.stats.reassembly_timeouts += (before - self.reassembly.len()) as u64;
+
+        let mut nacks: Vec<(DroneId, u16, FragmentMask), MAX_REASSEMBLY_BUFFERS> = Vec::new();
+        for buffer in self.reassembly.iter_mut() {
+            let stalled = now.saturating_sub(buffer.last_progress) >= self.config.nack_timeout_ms;
+            let nack_due = now.saturating_sub(buffer.last_nack) >= self.config.nack_timeout_ms;
+            if stalled && nack_due {
+                buffer.last_nack = now;
+                nacks
+                    .push((buffer.source, buffer.message_id, buffer.received))
+                    .ok();
+            }
+        }
+        for (source, message_id, received) in nacks {
+            self.send_nack(source, message_id, received);
+        }
+
+        let mut index = 0;
+        while index < self.outgoing.len() {
+            let message = &mut self.outgoing[index];
+            if now.saturating_sub(message.last_activity) < self.config.retransmit_timeout_ms {
+                index += 1;
+                continue;
+            }
+            if message.retries >= self.config.max_retries {
+                self.outgoing.swap_remove(index);
+                self.stats.messages_failed += 1;
+                continue;
+            }
+
+            // Resending the last fragment makes the receiver answer with an
+            // ACK (if only the ACK was lost) or a NACK listing the gaps
+            message.retries += 1;
+            message.last_activity = now;
+            let probe = (message.message_id, message.count - 1);
+            if !self.transmit_queue.iter().any(|entry| *entry == probe) {
+                self.transmit_queue
+                    .push_back(probe)
+                    .map_err(|_| SwarmError::BufferFull)?;
+                self.stats.fragments_retransmitted += 1;
+            }
+            index += 1;
+        }
+        Ok(())
+    }
+
+    fn send_nack(&mut self, to: DroneId, message_id: u16, received: FragmentMask) {
+        self.queue_control(Control::Nack {
+            to,
+            message_id,
+            received,
+        });
+        self.stats.nacks_sent += 1;
+    }
+
+    fn queue_control(&mut self, control: Control) {
+        // Control messages are repeated on timeouts, so dropping is safe
+        self.control_queue.push_back(control).ok();
+    }
+
+    fn outgoing_index(&self, destination: DroneId, message_id: u16) -> Option<usize> {
+        self.outgoing.iter().position(|message| {
+            message.destination == destination && message.message_id == message_id
+        })
+    }
+
+    /// Number of messages awaiting acknowledgment
+    pub fn pending_messages(&self) -> usize {
+        self.outgoing.len()
+    }
+
+    /// Number of messages being reassembled
+    pub fn reassembling(&self) -> usize {
+        self.reassembly.len()
+    }
+
+    /// Get configuration
+    pub fn config(&self) -> &FragmentConfig {
+        &self.config
+    }
+
+    /// Get statistics
+    pub fn statistics(&self) -> &FragmentStats {
+        &self.stats
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_mask_missing() {
+        let mut mask = FragmentMask::new();
+        mask.set(0);
+        mask.set(2);
+        mask.set(200);
+
+        assert_eq!(mask.count(), 3);
+        assert!(mask.contains(200));
+        assert!(!mask.is_complete(3));
+        let missing: Vec<u16, 4> = mask.missing(4).collect();
+        assert_eq!(&missing[..], &[1, 3]);
+    }
+
+    #[test]
+    fn test_framing_fits_minimum_mtu() {
+        let config = FragmentConfig {
+            mtu: MIN_MTU,
+            ..FragmentConfig::default()
+        };
+        let mut layer = FragmentationLayer::new(DroneId::new(u64::MAX), config);
+        layer.send(DroneId::new(2), &[0xAB; 1000]).unwrap();
+
+        let mut buf = [0u8; MAX_FRAME_SIZE];
+        while let Some((_, message)) = layer.poll_transmit() {
+            let encoded = postcard::to_slice(&message, &mut buf).unwrap();
+            assert!(encoded.len() <= MIN_MTU);
+        }
+
+        let mut received = FragmentMask::new();
+        for index in 0..MAX_FRAGMENTS as u16 {
+            if index % 2 == 0 {
+                received.set(index);
+            }
+        }
+        let nack = FragmentMessage::Nack {
+            sender: DroneId::new(u64::MAX),
+            message_id: u16::MAX,
+            received,
+        };
+        assert!(postcard::to_slice(&nack, &mut buf).unwrap().len() <= MIN_MTU);
+    }
+}
@@ -0,0 +1,713 @@
+//! Swarm group key management with member revocation
+//!
+//! The shared network key of [`CryptoContext`] is handed out through a
+//! logical key hierarchy (LKH): a binary tree whose leaves are the members and
+//! whose root is the group key. Each member holds the keys on the path from
+//! its leaf to the root, the [`GroupKeyServer`] (normally run by the Raft
+//! leader) holds the whole tree.
+//!
+//! When a member joins or leaves, the server replaces every key on that
+//! member's path and broadcasts a signed [`GroupRekey`]: each new key wrapped
+//! under the keys of its two children. That is at most `2 * TREE_DEPTH`
+//! wrapped keys regardless of swarm size, and every remaining member can
+//! unwrap its way up to the new root.
+//!
+//! - A removed or captured drone only holds the replaced path keys, so it
+//!   cannot unwrap anything in the rekey and cannot read traffic afterwards
+//! - A joining drone only learns the freshly drawn path keys, so it cannot
+//!   read traffic from before it joined
+//!
+//! A joining member gets its path keys in a [`GroupWelcome`], sealed over
+//! its authenticated [`Session`] with the server.
+
+use crate::consensus::SwarmCommand;
+use crate::crypto::{CryptoContext, SIGNATURE_SIZE, TAG_SIZE};
+use crate::rng::SecureRng;
+use crate::session::Session;
+use crate::types::*;
+use crate::KEY_SIZE;
+use aead::{AeadInPlace, KeyInit};
+use chacha20poly1305::{ChaCha20Poly1305, Nonce, Tag};
+use ed25519_dalek::{Signature, Verifier, VerifyingKey};
+use heapless::{FnvIndexMap, Vec};
+use serde::{Deserialize, Serialize};
+
+/// Maximum members of the key tree (power of 2)
+pub const MAX_GROUP_MEMBERS: usize = 64;
+
+/// Levels between a leaf and the root
+pub const TREE_DEPTH: usize = MAX_GROUP_MEMBERS.trailing_zeros() as usize;
+
+/// Keys a member holds: its leaf up to the root
+pub const PATH_LENGTH: usize = TREE_DEPTH + 1;
+
+/// Maximum wrapped keys in one rekey
+pub const MAX_WRAPPED_KEYS: usize = 2 * TREE_DEPTH;
+
+/// Tree nodes, heap-indexed from the root at 1
+const TREE_NODES: usize = 2 * MAX_GROUP_MEMBERS;
+
+/// Index of the root node
+const ROOT: usize = 1;
+
+/// Domain separation for the rekey signature
+const REKEY_CONTEXT: &str = "droneswarm-v1 group rekey";
+
+/// Associated data of a sealed welcome
+const WELCOME_AD: &[u8] = b"droneswarm-v1 group welcome";
+
+/// A new tree key wrapped under the key of one of its children
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct WrappedKey {
+    /// Node whose key this is
+    pub node: u8,
+    /// Child node whose key wraps it
+    pub under: u8,
+    /// Encrypted key
+    pub ciphertext: [u8; KEY_SIZE],
+    /// Authentication tag
+    pub tag: [u8; TAG_SIZE],
+}
+
+/// Broadcast replacing the keys on one member's path
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct GroupRekey {
+    /// Key epoch the new group key takes
+    pub epoch: u32,
+    /// New keys, ordered from the leaves up
+    pub keys: Vec<WrappedKey, MAX_WRAPPED_KEYS>,
+    /// Server signature over the epoch and the wrapped keys
+    pub signature: Vec<u8, SIGNATURE_SIZE>,
+}
+
+impl GroupRekey {
+    /// Digest covered by the signature
+    fn digest(&self) -> [u8; 32] {
+        let mut hasher = blake3::Hasher::new_derive_key(REKEY_CONTEXT);
+        hasher.update(&self.epoch.to_le_bytes());
+        for wrapped in &self.keys {
+            hasher.update(&[wrapped.node, wrapped.under]);
+            hasher.update(&wrapped.ciphertext);
+            hasher.update(&wrapped.tag);
+        }
+        *hasher.finalize().as_bytes()
+    }
+}
+
+/// Path keys for a member, sent only over its session
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct GroupWelcome {
+    /// Member the keys are for
+    pub member: DroneId,
+    /// Key epoch of the group key
+    pub epoch: u32,
+    /// Leaf node of the member
+    pub leaf: u8,
+    /// Keys from the leaf up to the root
+    pub path: Vec<[u8; KEY_SIZE], PATH_LENGTH>,
+}
+
+impl GroupWelcome {
+    /// Encrypt the welcome for the member
+    pub fn seal(&self, session: &mut Session) -> Result<Vec<u8, 2048>> {
+        let mut buf = [0u8; 512];
+        let encoded =
+            postcard::to_slice(self, &mut buf).map_err(|_| SwarmError::SerializationError)?;
+        session.encrypt(encoded, WELCOME_AD)
+    }
+
+    /// Decrypt a welcome sealed by the server
+    pub fn open(message: &[u8], session: &mut Session) -> Result<Self> {
+        let plaintext = session.decrypt(message, WELCOME_AD)?;
+        postcard::from_bytes(&plaintext).map_err(|_| SwarmError::SerializationError)
+    }
+}
+
+/// Group key statistics
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct GroupKeyStats {
+    /// Members added
+    pub joins: u32,
+    /// Members removed
+    pub removals: u32,
+    /// Rekeys issued or applied
+    pub rekeys: u32,
+    /// Wrapped keys broadcast
+    pub keys_sent: u32,
+}
+
+/// AEAD nonce for a key wrap; every child key wraps its parent once per epoch
+fn wrap_nonce(epoch: u32, node: u8, under: u8) -> [u8; 12] {
+    let mut nonce = [0u8; 12];
+    nonce[..4].copy_from_slice(&epoch.to_le_bytes());
+    nonce[4] = node;
+    nonce[5] = under;
+    nonce
+}
+
+/// Encrypt a tree key under a child's key
+fn wrap(
+    wrapping_key: &[u8; KEY_SIZE],
+    epoch: u32,
+    node: u8,
+    under: u8,
+    key: &[u8; KEY_SIZE],
+) -> Result<WrappedKey> {
+    let cipher =
+        ChaCha20Poly1305::new_from_slice(wrapping_key).expect("32-byte key is always valid");
+    let mut ciphertext = *key;
+    let tag = cipher
+        .encrypt_in_place_detached(
+            Nonce::from_slice(&wrap_nonce(epoch, node, under)),
+            &[],
+            &mut ciphertext,
+        )
+        .map_err(|_| SwarmError::CryptoError)?;
+    Ok(WrappedKey {
+        node,
+        under,
+        ciphertext,
+        tag: tag.into(),
+    })
+}
+
+/// Decrypt a tree key with a child's key
+fn unwrap(
+    wrapping_key: &[u8; KEY_SIZE],
+    epoch: u32,
+    wrapped: &WrappedKey,
+) -> Result<[u8; KEY_SIZE]> {
+    let cipher =
+        ChaCha20Poly1305::new_from_slice(wrapping_key).expect("32-byte key is always valid");
+    let mut key = wrapped.ciphertext;
+    cipher
+        .decrypt_in_place_detached(
+            Nonce::from_slice(&wrap_nonce(epoch, wrapped.node, wrapped.under)),
+            &[],
+            &mut key,
+            Tag::from_slice(&wrapped.tag),
+        )
+        .map_err(|_| SwarmError::AuthenticationFailed)?;
+    Ok(key)
+}
+
+/// Holder of the full key tree, issuing rekeys on membership changes
+pub struct GroupKeyServer {
+    /// Node keys; `None` for empty subtrees
+    keys: [Option<[u8; KEY_SIZE]>; TREE_NODES],
+    /// Leaf node of each member
+    members: FnvIndexMap<u64, u8, MAX_GROUP_MEMBERS>,
+    /// Epoch of the current group key
+    epoch: u32,
+    stats: GroupKeyStats,
+}
+
+impl GroupKeyServer {
+    /// Create a server with an empty tree
+    pub fn new() -> Self {
+        Self {
+            keys: [None; TREE_NODES],
+            members: FnvIndexMap::new(),
+            epoch: 0,
+            stats: GroupKeyStats::default(),
+        }
+    }
+
+    /// Epoch of the current group key
+    pub fn epoch(&self) -> u32 {
+        self.epoch
+    }
+
+    /// Check if a drone holds keys of the tree
+    pub fn is_member(&self, drone: DroneId) -> bool {
+        self.members.contains_key(&drone.as_u64())
+    }
+
+    /// Number of members
+    pub fn member_count(&self) -> usize {
+        self.members.len()
+    }
+
+    /// Statistics
+    pub fn statistics(&self) -> &GroupKeyStats {
+        &self.stats
+    }
+
+    /// Add a member and rekey its path
+    ///
+    /// The new group key is installed into `crypto`. Send the returned rekey
+    /// to the swarm and [`Self::welcome`] to the new member.
+    pub fn add_member(
+        &mut self,
+        drone: DroneId,
+        crypto: &mut CryptoContext,
+        now_ms: u64,
+    ) -> Result<GroupRekey> {
+        if self.is_member(drone) {
+            return Err(SwarmError::InvalidParameter);
+        }
+        let leaf = (MAX_GROUP_MEMBERS..TREE_NODES)
+            .find(|&node| self.keys[node].is_none())
+            .ok_or(SwarmError::ResourceExhausted)?;
+
+        self.keys[leaf] = Some(Self::fresh_key()?);
+        self.members
+            .insert(drone.as_u64(), leaf as u8)
+            .map_err(|_| SwarmError::ResourceExhausted)?;
+        self.stats.joins += 1;
+        self.rekey_path(leaf, crypto, now_ms)
+    }
+
+    /// Remove a member and rekey its path
+    ///
+    /// None of the keys the drone held stay in use, so it cannot follow any
+    /// rekey from here on. The new group key is installed into `crypto`.
+    pub fn remove_member(
+        &mut self,
+        drone: DroneId,
+        crypto: &mut CryptoContext,
+        now_ms: u64,
+    ) -> Result<GroupRekey> {
+        let leaf = self
+            .members
+            .remove(&drone.as_u64())
+            .ok_or(SwarmError::InvalidDroneId)? as usize;
+
+        self.keys[leaf] = None;
+        self.stats.removals += 1;
+        self.rekey_path(leaf, crypto, now_ms)
+    }
+
+    /// Current path keys of a member
+    pub fn welcome(&self, drone: DroneId) -> Result<GroupWelcome> {
+        let leaf = *self
+            .members
+            .get(&drone.as_u64())
+            .ok_or(SwarmError::InvalidDroneId)?;
+
+        let mut path = Vec::new();
+        let mut node = leaf as usize;
+        while node >= ROOT {
+            let key = self.keys[node].ok_or(SwarmError::CryptoError)?;
+            path.push(key).map_err(|_| SwarmError::BufferFull)?;
+            node /= 2;
+        }
+        Ok(GroupWelcome {
+            member: drone,
+            epoch: self.epoch,
+            leaf,
+            path,
+        })
+    }
+
+    /// Apply a committed membership command
+    ///
+    /// Returns the rekey to broadcast, if membership changed.
+    pub fn apply(
+        &mut self,
+        command: &SwarmCommand,
+        crypto: &mut CryptoContext,
+        now_ms: u64,
+    ) -> Result<Option<GroupRekey>> {
+        match command {
+            SwarmCommand::AddDrone { drone } if !self.is_member(*drone) => {
+                self.add_member(*drone, crypto, now_ms).map(Some)
+            }
+            SwarmCommand::RemoveDrone { drone } if self.is_member(*drone) => {
+                self.remove_member(*drone, crypto, now_ms).map(Some)
+            }
+            _ => Ok(None),
+        }
+    }
+
+    /// Replace every key above `leaf` and wrap each under its children
+    fn rekey_path(
+        &mut self,
+        leaf: usize,
+        crypto: &mut CryptoContext,
+        now_ms: u64,
+    ) -> Result<GroupRekey> {
+        // Stay ahead of ratchet rotations of the same context
+        let epoch = self
+            .epoch
+            .max(crypto.epoch())
+            .checked_add(1)
+            .ok_or(SwarmError::CryptoError)?;
+
+        let mut keys = Vec::new();
+        let mut node = leaf / 2;
+        while node >= ROOT {
+            let children = [2 * node, 2 * node + 1];
+            if node != ROOT && children.iter().all(|&child| self.keys[child].is_none()) {
+                self.keys[node] = None;
+            } else {
+                let key = Self::fresh_key()?;
+                for child in children {
+                    if let Some(child_key) = self.keys[child] {
+                        keys.push(wrap(&child_key, epoch, node as u8, child as u8, &key)?)
+                            .map_err(|_| SwarmError::BufferFull)?;
+                    }
+                }
+                self.keys[node] = Some(key);
+            }
+            node /= 2;
+        }
+
+        let group_key = self.keys[ROOT].ok_or(SwarmError::CryptoError)?;
+        crypto.install_key(epoch, group_key, now_ms)?;
+        self.epoch = epoch;
+
+        let mut rekey = GroupRekey {
+            epoch,
+            keys,
+            signature: Vec::new(),
+        };
+        rekey.signature =
+            Vec::from_slice(&crypto.sign(&rekey.digest())).map_err(|_| SwarmError::BufferFull)?;
+        self.stats.rekeys += 1;
+        self.stats.keys_sent += rekey.keys.len() as u32;
+        Ok(rekey)
+    }
+
+    /// Draw a new tree key
+    fn fresh_key() -> Result<[u8; KEY_SIZE]> {
+        let mut key = [0u8; KEY_SIZE];
+        SecureRng::new()?.fill_bytes(&mut key)?;
+        Ok(key)
+    }
+}
+
+impl Default for GroupKeyServer {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// A member's view of the key tree: the keys on its own path
+pub struct GroupMember {
+    /// Leaf node of this member
+    leaf: u8,
+    /// Keys from the leaf up to the root
+    path: [[u8; KEY_SIZE]; PATH_LENGTH],
+    /// Epoch of the group key
+    epoch: u32,
+    /// Server identity that signs rekeys
+    server_key: VerifyingKey,
+    stats: GroupKeyStats,
+}
+
+impl GroupMember {
+    /// Join with the keys of a welcome and install the group key
+    pub fn join(
+        welcome: &GroupWelcome,
+        server_key: VerifyingKey,
+        crypto: &mut CryptoContext,
+        now_ms: u64,
+    ) -> Result<Self> {
+        let leaf = welcome.leaf as usize;
+        if !(MAX_GROUP_MEMBERS..TREE_NODES).contains(&leaf) {
+            return Err(SwarmError::InvalidParameter);
+        }
+        let path: [[u8; KEY_SIZE]; PATH_LENGTH] = welcome
+            .path
+            .as_slice()
+            .try_into()
+            .map_err(|_| SwarmError::InvalidMessage)?;
+
+        if welcome.epoch > crypto.epoch() {
+            crypto.install_key(welcome.epoch, path[TREE_DEPTH], now_ms)?;
+        }
+        Ok(Self {
+            leaf: welcome.leaf,
+            path,
+            epoch: welcome.epoch,
+            server_key,
+            stats: GroupKeyStats::default(),
+        })
+    }
+
+    /// Epoch of the group key
+    pub fn epoch(&self) -> u32 {
+        self.epoch
+    }
+
+    /// Leaf node of this member
+    pub fn leaf(&self) -> u8 {
+        self.leaf
+    }
+
+    /// Current group key
+    pub fn group_key(&self) -> &[u8; KEY_SIZE] {
+        &self.path[TREE_DEPTH]
+    }
+
+    /// Statistics
+    pub fn statistics(&self) -> &GroupKeyStats {
+        &self.stats
+    }
+
+    /// Follow a rekey and install the new group key
+    ///
+    /// Returns `false` for rekeys already applied. Fails if the rekey is not
+    /// signed by the server or does not lead to a new group key for this
+    /// member, i.e. it was removed or missed an earlier rekey and needs a new
+    /// welcome.
+    pub fn apply_rekey(
+        &mut self,
+        rekey: &GroupRekey,
+        crypto: &mut CryptoContext,
+        now_ms: u64,
+    ) -> Result<bool> {
+        if rekey.epoch <= self.epoch {
+            return Ok(false);
+        }
+        let signature = Signature::from_slice(&rekey.signature)
+            .map_err(|_| SwarmError::AuthenticationFailed)?;
+        self.server_key
+            .verify(&rekey.digest(), &signature)
+            .map_err(|_| SwarmError::AuthenticationFailed)?;
+
+        // Keys arrive leaf-first, so each child key is current before its
+        // parent is unwrapped with it
+        let mut path = self.path;
+        let mut root_updated = false;
+        for wrapped in &rekey.keys {
+            let Some(level) = (1..PATH_LENGTH).find(|&level| self.path_node(level) == wrapped.node)
+            else {
+                continue;
+            };
+            if self.path_node(level - 1) != wrapped.under {
+                continue;
+            }
+            path[level] = unwrap(&path[level - 1], rekey.epoch, wrapped)?;
+            root_updated |= level == TREE_DEPTH;
+        }
+        if !root_updated {
+            return Err(SwarmError::AuthenticationFailed);
+        }
+
+        crypto.install_key(rekey.epoch, path[TREE_DEPTH], now_ms)?;
+        self.path = path;
+        self.epoch = rekey.epoch;
+        self.stats.rekeys += 1;
+        Ok(true)
+    }
+
+    /// Tree node of this member's path at `level` above the leaf
+    fn path_node(&self, level: usize) -> u8 {
+        self.leaf >> level
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    struct Drone {
+        id: DroneId,
+        crypto: CryptoContext,
+        member: GroupMember,
+    }
+
+    fn server_crypto() -> CryptoContext {
+        CryptoContext::with_keys([0x5A; KEY_SIZE], [0xEE; 32])
+    }
+
+    /// Server plus `count` members, each following every later rekey
+    fn swarm(count: u64) -> (GroupKeyServer, CryptoContext, std::vec::Vec<Drone>) {
+        let mut server = GroupKeyServer::new();
+        let mut crypto = server_crypto();
+        let mut drones: std::vec::Vec<Drone> = std::vec::Vec::new();
+        for id in 1..=count {
+            let id = DroneId::new(id);
+            let rekey = server.add_member(id, &mut crypto, 0).unwrap();
+            for drone in &mut drones {
+                assert!(drone
+                    .member
+                    .apply_rekey(&rekey, &mut drone.crypto, 0)
+                    .unwrap());
+            }
+            let mut drone_crypto =
+                CryptoContext::with_keys([0x5A; KEY_SIZE], [id.as_u64() as u8; 32]);
+            let member = GroupMember::join(
+                &server.welcome(id).unwrap(),
+                *crypto.public_key(),
+                &mut drone_crypto,
+                0,
+            )
+            .unwrap();
+            drones.push(Drone {
+                id,
+                crypto: drone_crypto,
+                member,
+            });
+        }
+        (server, crypto, drones)
+    }
+
+    #[test]
+    fn test_members_share_group_key() {
+        let (server, crypto, mut drones) = swarm(5);
+        assert_eq!(server.member_count(), 5);
+        for drone in &drones {
+            assert_eq!(drone.member.epoch(), server.epoch());
+            assert_eq!(drone.crypto.epoch(), crypto.epoch());
+        }
+
+        let mut sender = CryptoContext::with_keys([0; KEY_SIZE], [9; 32]);
+        sender
+            .install_key(server.epoch(), *drones[0].member.group_key(), 0)
+            .unwrap();
+        let sealed = sender.encrypt_and_sign(b"formation", b"ad").unwrap();
+        for drone in &mut drones {
+            assert_eq!(
+                &drone
+                    .crypto
+                    .verify_and_decrypt(&sealed, b"ad", sender.public_key())
+                    .unwrap()[..],
+                b"formation"
+            );
+        }
+    }
+
+    #[test]
+    fn test_removed_member_is_locked_out() {
+        let (mut server, mut crypto, mut drones) = swarm(6);
+        let removed = drones.remove(2);
+        let mut removed_crypto = removed.crypto;
+        let mut removed_member = removed.member;
+
+        let rekey = server.remove_member(removed.id, &mut crypto, 100).unwrap();
+        assert!(!server.is_member(removed.id));
+        assert!(rekey.keys.len() <= MAX_WRAPPED_KEYS);
+        for drone in &mut drones {
+            assert!(drone
+                .member
+                .apply_rekey(&rekey, &mut drone.crypto, 100)
+                .unwrap());
+            assert_eq!(drone.member.group_key(), &server.keys[ROOT].unwrap());
+        }
+
+        // The removed drone cannot follow the rekey ...
+        assert!(removed_member
+            .apply_rekey(&rekey, &mut removed_crypto, 100)
+            .is_err());
+
+        // ... nor read traffic under the new key, even past the retired window
+        let sealed = crypto.encrypt_and_sign(b"after", b"ad").unwrap();
+        let signer = *crypto.public_key();
+        removed_crypto.expire_retired_key(u64::MAX);
+        assert!(removed_crypto
+            .verify_and_decrypt(&sealed, b"ad", &signer)
+            .is_err());
+        assert!(drones[0]
+            .crypto
+            .verify_and_decrypt(&sealed, b"ad", &signer)
+            .is_ok());
+    }
+
+    #[test]
+    fn test_rekey_is_logarithmic() {
+        let (mut server, mut crypto, _) = swarm(MAX_GROUP_MEMBERS as u64);
+        assert!(server
+            .add_member(DroneId::new(1000), &mut crypto, 0)
+            .is_err());
+
+        let rekey = server
+            .remove_member(DroneId::new(7), &mut crypto, 0)
+            .unwrap();
+        // The emptied leaf's parent is wrapped under the sibling only
+        assert_eq!(rekey.keys.len(), 2 * TREE_DEPTH - 1);
+    }
+
+    #[test]
+    fn test_joining_member_gets_fresh_keys() {
+        let (mut server, mut crypto, drones) = swarm(3);
+        let old_root = *drones[0].member.group_key();
+
+        server.add_member(DroneId::new(4), &mut crypto, 0).unwrap();
+        let welcome = server.welcome(DroneId::new(4)).unwrap();
+        assert!(!welcome.path.contains(&old_root));
+    }
+
+    #[test]
+    fn test_forged_and_stale_rekeys_rejected() {
+        let (mut server, mut crypto, mut drones) = swarm(3);
+        let mut rekey = server
+            .remove_member(DroneId::new(3), &mut crypto, 0)
+            .unwrap();
+        let drone = &mut drones[0];
+
+        let genuine = rekey.clone();
+        rekey.keys[0].tag[0] ^= 1;
+        assert_eq!(
+            drone.member.apply_rekey(&rekey, &mut drone.crypto, 0),
+            Err(SwarmError::AuthenticationFailed)
+        );
+
+        assert!(drone
+            .member
+            .apply_rekey(&genuine, &mut drone.crypto, 0)
+            .unwrap());
+        assert!(!drone
+            .member
+            .apply_rekey(&genuine, &mut drone.crypto, 0)
+            .unwrap());
+    }
+
+    #[test]
+    fn test_apply_membership_commands() {
+        let mut server = GroupKeyServer::new();
+        let mut crypto = server_crypto();
+        let drone = DroneId::new(12);
+
+        let add = SwarmCommand::AddDrone { drone };
+        assert!(server.apply(&add, &mut crypto, 0).unwrap().is_some());
+        assert!(server.apply(&add, &mut crypto, 0).unwrap().is_none());
+
+        let remove = SwarmCommand::RemoveDrone { drone };
+        assert!(server.apply(&remove, &mut crypto, 0).unwrap().is_some());
+        assert!(server.apply(&remove, &mut crypto, 0).unwrap().is_none());
+        assert_eq!(server.statistics().joins, 1);
+        assert_eq!(server.statistics().removals, 1);
+        assert_eq!(crypto.epoch(), 2);
+    }
+
+    #[test]
+    fn test_welcome_travels_over_session() {
+        use crate::crypto::KeyStore;
+        use crate::session::Handshake;
+
+        let (leader, drone) = (server_crypto(), CryptoContext::new([7; 32]));
+        let (leader_id, drone_id) = (DroneId::new(1), DroneId::new(7));
+        let mut keys = KeyStore::new();
+        keys.add_key(leader_id, *leader.public_key()).unwrap();
+        keys.add_key(drone_id, *drone.public_key()).unwrap();
+
+        let (mut initiator, init) = Handshake::initiate(leader_id, [1; 32], b"");
+        let mut responder = Handshake::respond(drone_id, [2; 32], b"");
+        let response = responder
+            .read_message(&init, &drone, &keys)
+            .unwrap()
+            .unwrap();
+        let finish = initiator
+            .read_message(&response, &leader, &keys)
+            .unwrap()
+            .unwrap();
+        responder.read_message(&finish, &drone, &keys).unwrap();
+        let mut leader_session = initiator.into_session().unwrap();
+        let mut drone_session = responder.into_session().unwrap();
+
+        let mut server = GroupKeyServer::new();
+        let mut server_crypto = leader;
+        server.add_member(drone_id, &mut server_crypto, 0).unwrap();
+        let welcome = server.welcome(drone_id).unwrap();
+
+        let sealed = welcome.seal(&mut leader_session).unwrap();
+        assert!(!sealed.windows(KEY_SIZE).any(|w| w == welcome.path[0]));
+        assert_eq!(
+            GroupWelcome::open(&sealed, &mut drone_session).unwrap(),
+            welcome
+        );
+    }
+}
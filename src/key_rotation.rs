@@ -0,0 +1,112 @@
+//! Swarm-wide rotation of the shared network key
+//!
+//! Every member encrypts with the network key of its [`CryptoContext`]. Keys
+//! are numbered by epoch and each epoch's key is a one-way ratchet of the one
+//! before ([`CryptoContext::ratchet_key`]), so no key material travels: the
+//! swarm only has to agree on *when* to move on.
+//!
+//! The Raft leader proposes [`SwarmCommand::RotateNetworkKey`] once its
+//! [`RekeyPolicy`](crate::crypto::RekeyPolicy) says the key is due, by message
+//! count or age. Every member ratchets when the command commits. Frames sealed
+//! around the switch still open: the previous key is accepted for the
+//! policy's acceptance window and the next key is accepted ahead of the
+//! commit. A drone that missed too many rotations to follow them one by one
+//! catches up from a frame sealed under the current key
+//! ([`MeshSecurity::resync`](crate::mesh_security::MeshSecurity::resync)).
+
+use crate::consensus::{ConsensusEngine, NodeState, SwarmCommand};
+use crate::crypto::{CryptoContext, MAX_RATCHET_STEPS};
+use crate::types::*;
+
+/// Time after which an uncommitted rotation is proposed again (ms)
+pub const ROTATION_RETRY_MS: u64 = 5000;
+
+/// Key rotation statistics
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct RotationStats {
+    /// Rotations proposed as leader
+    pub proposed: u32,
+    /// Rotations applied
+    pub applied: u32,
+}
+
+/// Drives network key rotation through consensus
+#[derive(Debug, Default)]
+pub struct KeyRotation {
+    /// Epoch proposed and the time of the proposal (ms)
+    proposed: Option<(u32, u64)>,
+    stats: RotationStats,
+}
+
+impl KeyRotation {
+    /// Create the rotation driver
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Statistics
+    pub fn statistics(&self) -> &RotationStats {
+        &self.stats
+    }
+
+    /// Periodic work: retire the previous key and, as leader, propose a
+    /// rotation once the key is due
+    ///
+    /// Returns the log index of a new proposal.
+    pub fn tick(
+        &mut self,
+        crypto: &mut CryptoContext,
+        consensus: &mut ConsensusEngine,
+        now_ms: u64,
+    ) -> Result<Option<u64>> {
+        crypto.expire_retired_key(now_ms);
+        if consensus.state() != NodeState::Leader || !crypto.rekey_due(now_ms) {
+            return Ok(None);
+        }
+
+        let epoch = crypto
+            .epoch()
+            .checked_add(1)
+            .ok_or(SwarmError::CryptoError)?;
+        let in_flight = self.proposed.is_some_and(|(proposed, at_ms)| {
+            proposed == epoch && now_ms.saturating_sub(at_ms) < ROTATION_RETRY_MS
+        });
+        if in_flight {
+            return Ok(None);
+        }
+
+        let index = consensus.propose_command(
+            consensus.node_id(),
+            SwarmCommand::RotateNetworkKey { epoch },
+            now_ms,
+        )?;
+        self.proposed = Some((epoch, now_ms));
+        self.stats.proposed += 1;
+        Ok(Some(index))
+    }
+
+    /// Apply a committed command
+    ///
+    /// Returns `true` if it moved the network key to a new epoch. Rotations
+    /// to an epoch already reached are ignored; a drone more than
+    /// [`MAX_RATCHET_STEPS`] epochs behind resynchronizes.
+    pub fn apply(
+        &mut self,
+        command: &SwarmCommand,
+        crypto: &mut CryptoContext,
+        now_ms: u64,
+    ) -> Result<bool> {
+        match command {
+            SwarmCommand::RotateNetworkKey { epoch } if *epoch > crypto.epoch() => {
+                if *epoch - crypto.epoch() > MAX_RATCHET_STEPS {
+                    crypto.resync_epoch(*epoch, now_ms)?;
+                } else {
+                    crypto.advance_epoch(*epoch, now_ms)?;
+                }
+                self.stats.applied += 1;
+                Ok(true)
+            }
+            _ => Ok(false),
+        }
+    }
+}
@@ -5,7 +5,7 @@
 //!
 //! ## Features
 //! - Memory-safe Rust implementation for embedded systems
-//! - Post-quantum cryptography ready
+//! - Post-quantum hybrid key exchange and signatures (`post-quantum` feature)
 //! - Decentralized mesh networking
 //! - Raft consensus protocol for swarm coordination
 //! - Federated learning with blockchain verification
@@ -45,6 +45,8 @@
 
 /// Ant Colony Optimization (ACO) for path planning and resource allocation
 pub mod aco;
+/// Drone identity certificates, revocation lists and provisioning
+pub mod certificate;
 /// Advanced collision avoidance algorithms (VO, RVO, ORCA, APF)
 pub mod collision_avoidance;
 /// System configuration and parameter management
@@ -53,6 +55,8 @@ pub mod config;
 pub mod consensus;
 /// Cryptographic primitives (ChaCha20Poly1305, Ed25519, key management)
 pub mod crypto;
+/// Delay-tolerant store-carry-forward bundles for partitioned swarms
+pub mod dtn;
 /// ESP32 WiFi mesh networking module
 pub mod esp32_mesh;
 /// Failsafe behaviors for drone safety
@@ -61,8 +65,14 @@ pub mod failsafe;
 pub mod fault_tolerance;
 /// Federated learning with differential privacy and blockchain verification
 pub mod federated;
+/// Fragmentation and reassembly of messages larger than the link MTU
+pub mod fragmentation;
+/// Group key management (logical key hierarchy) with member revocation
+pub mod group_key;
 /// Grey Wolf Optimizer (GWO) for multi-objective optimization
 pub mod gwo;
+/// Swarm-wide network key rotation agreed through consensus
+pub mod key_rotation;
 /// MAVLink flight controller interface (requires simulation feature)
 #[cfg(feature = "simulation")]
 pub mod mavlink_controller;
@@ -71,20 +81,36 @@ pub mod mavlink_controller;
 pub mod multi_drone_coordinator;
 /// Merkle Tree for tamper-evident logging (SwarmRaft)
 pub mod merkle;
+/// Channel management and frequency hopping for the ESP32 mesh
+pub mod mesh_channel;
 /// Mesh network protocol for drone swarm communication
 pub mod mesh_protocol;
+/// Authenticated, encrypted framing for mesh protocol messages
+pub mod mesh_security;
 /// Mission planning and waypoint management
 pub mod mission_planning;
 /// Mesh networking, routing, and message passing
 pub mod network;
+/// Deterministic multi-node network simulator (requires std)
+#[cfg(feature = "std")]
+pub mod netsim;
+/// Post-quantum hybrid key exchange and dual signatures (requires post-quantum feature)
+#[cfg(feature = "post-quantum")]
+pub mod post_quantum;
 /// Particle Swarm Optimization (PSO) for formation control
 pub mod pso;
 /// Advanced PSO variants with adaptive parameters
 pub mod pso_advanced;
+/// Role-based authorization of swarm commands
+pub mod rbac;
+/// Distributed reputation and trust scores
+pub mod reputation;
 /// Cryptographically secure random number generation
 pub mod rng;
 /// Multi-layer security framework and intrusion detection
 pub mod security;
+/// Authenticated session key establishment (Noise XX handshake)
+pub mod session;
 /// High-level swarm coordination and behavior management
 pub mod swarm;
 /// Telemetry monitoring and alerting system
@@ -93,6 +119,10 @@ pub mod telemetry;
 pub mod task_allocation;
 /// Platform-agnostic time abstraction for embedded systems
 pub mod time_abstraction;
+/// Swarm-wide time synchronization over the mesh (FTSP-style)
+pub mod time_sync;
+/// Link-layer transports (loopback, UDP) for the mesh network
+pub mod transport;
 /// Core types (Position, Velocity, DroneId, NetworkAddress, etc.)
 pub mod types;
 /// Whale Optimization Algorithm (WOA) for advanced path planning
@@ -7,6 +7,10 @@
 use crate::crypto::CryptoContext;
 use crate::types::{Result, SwarmError};
 use heapless::Vec;
+use serde::{Deserialize, Serialize};
+
+/// Deepest inclusion proof supported (trees of up to 2^16 leaves)
+pub const MAX_PROOF_DEPTH: usize = 16;
 
 /// Merkle Tree node
 #[derive(Debug, Clone, PartialEq)]
@@ -36,6 +40,38 @@ pub struct MerkleTree<const N: usize> {
     nodes: Vec<MerkleNode, N>,
     /// Indices of the current level being built
     current_level_indices: Vec<usize, N>,
+    /// Leaves of the last computed tree
+    leaf_count: usize,
+}
+
+/// Sibling hashes from a leaf up to the root
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct MerkleProof {
+    /// Position of the leaf in the tree
+    pub leaf_index: u32,
+    /// Sibling hash at each level, leaf level first
+    pub siblings: Vec<[u8; 32], MAX_PROOF_DEPTH>,
+}
+
+impl MerkleProof {
+    /// Check that `leaf_data` is part of the tree with root `root`
+    pub fn verify(&self, leaf_data: &[u8], root: &[u8; 32]) -> bool {
+        let mut hash = CryptoContext::secure_hash(leaf_data);
+        let mut index = self.leaf_index;
+        for sibling in &self.siblings {
+            let mut combined = [0u8; 64];
+            let (left, right) = if index.is_multiple_of(2) {
+                (&hash, sibling)
+            } else {
+                (sibling, &hash)
+            };
+            combined[..32].copy_from_slice(left);
+            combined[32..].copy_from_slice(right);
+            hash = CryptoContext::secure_hash(&combined);
+            index /= 2;
+        }
+        hash == *root
+    }
 }
 
 impl<const N: usize> Default for MerkleTree<N> {
@@ -50,6 +86,7 @@ impl<const N: usize> MerkleTree<N> {
         Self {
             nodes: Vec::new(),
             current_level_indices: Vec::new(),
+            leaf_count: 0,
         }
     }
 
@@ -59,13 +96,14 @@ impl<const N: usize> MerkleTree<N> {
     /// an incremental update strategy would be more efficient,
     /// but this ensures correctness for the foundation phase.
     pub fn compute_root(&mut self, data_items: &[&[u8]]) -> Result<[u8; 32]> {
+        self.nodes.clear();
+        self.current_level_indices.clear();
+        self.leaf_count = data_items.len();
+
         if data_items.is_empty() {
             return Ok([0u8; 32]); // Empty tree hash
         }
 
-        self.nodes.clear();
-        self.current_level_indices.clear();
-
         // 1. Create leaves
         for item in data_items {
             let hash = CryptoContext::secure_hash(item);
@@ -139,6 +177,34 @@ impl<const N: usize> MerkleTree<N> {
             Ok([0u8; 32])
         }
     }
+
+    /// Inclusion proof for a leaf of the last computed tree
+    pub fn proof(&self, leaf_index: usize) -> Result<MerkleProof> {
+        if leaf_index >= self.leaf_count {
+            return Err(SwarmError::InvalidParameter);
+        }
+
+        // Levels are stored one after another, leaves first
+        let mut proof = MerkleProof {
+            leaf_index: leaf_index as u32,
+            siblings: Vec::new(),
+        };
+        let mut offset = 0;
+        let mut width = self.leaf_count;
+        let mut index = leaf_index;
+        while width > 1 {
+            // An odd node out is paired with itself
+            let sibling = if index ^ 1 < width { index ^ 1 } else { index };
+            proof
+                .siblings
+                .push(self.nodes[offset + sibling].hash())
+                .map_err(|_| SwarmError::BufferFull)?;
+            offset += width;
+            width = width.div_ceil(2);
+            index /= 2;
+        }
+        Ok(proof)
+    }
 }
 
 #[cfg(test)]
@@ -188,4 +254,21 @@ mod tests {
 
         assert_ne!(root1, root2);
     }
+
+    #[test]
+    fn test_inclusion_proofs() {
+        let mut tree = MerkleTree::<100>::new();
+        for count in 1..=9u8 {
+            let items: std::vec::Vec<[u8; 1]> = (0..count).map(|i| [i]).collect();
+            let data: std::vec::Vec<&[u8]> = items.iter().map(|item| item.as_slice()).collect();
+            let root = tree.compute_root(&data).unwrap();
+
+            for (index, item) in data.iter().enumerate() {
+                let proof = tree.proof(index).unwrap();
+                assert!(proof.verify(item, &root));
+                assert!(!proof.verify(b"forged", &root));
+            }
+            assert!(tree.proof(data.len()).is_err());
+        }
+    }
 }
@@ -0,0 +1,833 @@
+//! Channel management for the ESP32 mesh
+//!
+//! A mesh pinned to one WiFi channel goes down with that channel. The
+//! [`ChannelManager`] hosted by each [`MeshNode`](crate::esp32_mesh::MeshNode)
+//! keeps the swarm on the air:
+//! - Frequency hopping: with [`ChannelConfig::hopping`] every node derives the
+//!   same pseudo-random hop sequence from a shared secret, the plan epoch and
+//!   the mesh time, so jamming one channel only costs its share of slots
+//! - Interference tracking: reception errors, transmit failures and the noise
+//!   floor are averaged per channel
+//! - Channel maps: nodes advertise the channels that are clean where they are
+//! - Migration: a node that sees the current plan degrade proposes a new one
+//!   built from channels clean at every neighbor; once a majority of its
+//!   neighbors accept, the switch is committed for a common mesh time
+//!
+//! Times are mesh times (see [`crate::time_sync`]): nodes hop and switch in
+//! step only as far as their clocks agree.
+
+use crate::esp32_mesh::MAX_NEIGHBORS;
+use crate::mesh_protocol::*;
+use heapless::Vec;
+
+/// Lowest WiFi channel
+pub const MIN_CHANNEL: u8 = 1;
+
+/// Highest WiFi channel
+pub const MAX_CHANNEL: u8 = 13;
+
+/// Number of WiFi channels
+pub const CHANNEL_COUNT: usize = 13;
+
+/// Mask of every WiFi channel (bit `n` = channel `n`)
+pub const ALL_CHANNELS: u16 = 0x3FFE;
+
+/// Fewest channels a hop set is allowed to shrink to
+pub const MIN_HOP_CHANNELS: u32 = 3;
+
+/// Smoothing factor of the per-channel averages
+const QUALITY_ALPHA: f32 = 0.8;
+
+/// Mask bit of a channel (0 for invalid channels)
+pub fn channel_bit(channel: u8) -> u16 {
+    if (MIN_CHANNEL..=MAX_CHANNEL).contains(&channel) {
+        1 << channel
+    } else {
+        0
+    }
+}
+
+/// Channels in a mask, lowest first
+pub fn channels_in(mask: u16) -> impl Iterator<Item = u8> {
+    (MIN_CHANNEL..=MAX_CHANNEL).filter(move |channel| mask & channel_bit(*channel) != 0)
+}
+
+/// Channel of the hop sequence for `plan` at mesh time `time_ms`
+///
+/// Keyed BLAKE3 of the slot number and plan epoch picks a channel from the
+/// hop set; without the secret the sequence cannot be predicted.
+pub fn hop_channel(secret: &[u8; 32], plan: &ChannelPlan, dwell_ms: u64, time_ms: u64) -> u8 {
+    let mask = plan.hop_mask & ALL_CHANNELS;
+    let count = mask.count_ones();
+    if count == 0 {
+        return plan.home;
+    }
+
+    let slot = time_ms / dwell_ms.max(1);
+    let mut input = [0u8; 10];
+    input[..8].copy_from_slice(&slot.to_le_bytes());
+    input[8..].copy_from_slice(&plan.epoch.to_le_bytes());
+    let hash = blake3::keyed_hash(secret, &input);
+    let mut pick = [0u8; 4];
+    pick.copy_from_slice(&hash.as_bytes()[..4]);
+
+    let index = u32::from_le_bytes(pick) % count;
+    channels_in(mask).nth(index as usize).unwrap_or(plan.home)
+}
+
+/// Channel management configuration
+#[derive(Debug, Clone)]
+pub struct ChannelConfig {
+    /// Hop across the plan's channel set instead of staying on the home channel
+    pub hopping: bool,
+    /// Shared secret the hop sequence is derived from (same on every drone)
+    pub hop_secret: [u8; 32],
+    /// Time spent on each hop (ms)
+    pub dwell_ms: u64,
+    /// Channels the hop sequence starts with
+    pub hop_channels: u16,
+    /// Track interference, exchange channel maps and migrate away from bad channels
+    pub adaptive: bool,
+    /// Interference level (0.0 - 1.0) at which a channel counts as unusable
+    pub interference_threshold: f32,
+    /// Noise floor (dBm) at which a channel counts as unusable
+    pub noise_threshold_dbm: i8,
+    /// Lead time between proposing a switch and performing it (ms)
+    pub switch_delay_ms: u64,
+    /// Time to collect votes before a proposal is abandoned (ms)
+    pub vote_timeout_ms: u64,
+    /// Minimum time between migration attempts (ms)
+    pub min_switch_interval_ms: u64,
+    /// Interval between channel reports (ms)
+    pub report_interval_ms: u64,
+}
+
+impl Default for ChannelConfig {
+    fn default() -> Self {
+        Self {
+            hopping: false,
+            hop_secret: [0; 32],
+            dwell_ms: 50,
+            hop_channels: 0x0FFE, // 1-11
+            adaptive: false,
+            interference_threshold: 0.5,
+            noise_threshold_dbm: -70,
+            switch_delay_ms: 2000,
+            vote_timeout_ms: 1000,
+            min_switch_interval_ms: 5000,
+            report_interval_ms: 1000,
+        }
+    }
+}
+
+/// Observation about a channel
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ChannelEvent {
+    /// Frame received intact
+    Received,
+    /// Frame received but failed its checksum or authentication
+    Corrupted,
+    /// Transmission went unanswered
+    TxFailed,
+    /// Noise floor sample (dBm) from the radio's energy detection
+    Noise(i8),
+}
+
+/// Interference statistics of one channel
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct ChannelQuality {
+    /// Smoothed share of failed events (0.0 - 1.0)
+    pub interference: f32,
+    /// Smoothed noise floor (dBm), once sampled
+    pub noise_dbm: Option<f32>,
+    /// Frames received intact
+    pub frames_ok: u32,
+    /// Frames received corrupted
+    pub frames_bad: u32,
+    /// Unanswered transmissions
+    pub tx_failures: u32,
+}
+
+impl ChannelQuality {
+    fn record(&mut self, event: ChannelEvent) {
+        let failed = match event {
+            ChannelEvent::Received => {
+                self.frames_ok += 1;
+                false
+            }
+            ChannelEvent::Corrupted => {
+                self.frames_bad += 1;
+                true
+            }
+            ChannelEvent::TxFailed => {
+                self.tx_failures += 1;
+                true
+            }
+            ChannelEvent::Noise(dbm) => {
+                let sample = dbm as f32;
+                self.noise_dbm = Some(match self.noise_dbm {
+                    None => sample,
+                    Some(noise) => QUALITY_ALPHA * noise + (1.0 - QUALITY_ALPHA) * sample,
+                });
+                return;
+            }
+        };
+        let sample = if failed { 1.0 } else { 0.0 };
+        self.interference = QUALITY_ALPHA * self.interference + (1.0 - QUALITY_ALPHA) * sample;
+    }
+
+    /// Let an unobserved channel regain trust
+    fn decay(&mut self) {
+        self.interference *= QUALITY_ALPHA;
+        self.noise_dbm = None;
+    }
+}
+
+/// Channel management statistics
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct ChannelStats {
+    /// Channel plans taken into use
+    pub switches: u32,
+    /// Switches this node proposed
+    pub proposals: u32,
+    /// Own proposals that did not reach a majority
+    pub failed_proposals: u32,
+    /// Proposals this node voted against
+    pub rejected_proposals: u32,
+}
+
+/// Switch under vote or committed
+#[derive(Debug, Clone)]
+struct PendingSwitch {
+    proposer: MeshNodeId,
+    plan: ChannelPlan,
+    switch_at_ms: u64,
+    committed: bool,
+    /// Votes are collected until then
+    deadline_ms: u64,
+    /// Neighbors asked to vote (own proposals)
+    expected: usize,
+    accepted: Vec<MeshNodeId, MAX_NEIGHBORS>,
+    rejected: Vec<MeshNodeId, MAX_NEIGHBORS>,
+}
+
+impl PendingSwitch {
+    /// Proposer plus accepting neighbors outnumber the rest
+    fn has_majority(&self) -> bool {
+        2 * (self.accepted.len() + 1) > self.expected + 1
+    }
+
+    /// Too many rejections for a majority
+    fn is_defeated(&self) -> bool {
+        2 * self.rejected.len() > self.expected
+    }
+}
+
+/// Per-node channel state: current plan, interference, neighbor maps and switches
+pub struct ChannelManager {
+    local_id: MeshNodeId,
+    config: ChannelConfig,
+    plan: ChannelPlan,
+    quality: [ChannelQuality; CHANNEL_COUNT],
+    /// Usable channel mask reported by each neighbor
+    neighbor_maps: Vec<(MeshNodeId, u16), MAX_NEIGHBORS>,
+    pending: Option<PendingSwitch>,
+    last_report_ms: Option<u64>,
+    last_attempt_ms: Option<u64>,
+    stats: ChannelStats,
+}
+
+impl ChannelManager {
+    /// Create the channel manager for `local_id`, starting on `home`
+    pub fn new(local_id: MeshNodeId, home: u8, config: ChannelConfig) -> Self {
+        let plan = ChannelPlan {
+            epoch: 0,
+            home,
+            hop_mask: config.hop_channels & ALL_CHANNELS,
+        };
+        Self {
+            local_id,
+            config,
+            plan,
+            quality: [ChannelQuality::default(); CHANNEL_COUNT],
+            neighbor_maps: Vec::new(),
+            pending: None,
+            last_report_ms: None,
+            last_attempt_ms: None,
+            stats: ChannelStats::default(),
+        }
+    }
+
+    /// Channel plan in use
+    pub fn plan(&self) -> &ChannelPlan {
+        &self.plan
+    }
+
+    /// Configuration
+    pub fn config(&self) -> &ChannelConfig {
+        &self.config
+    }
+
+    /// Statistics
+    pub fn statistics(&self) -> &ChannelStats {
+        &self.stats
+    }
+
+    /// Interference statistics of a channel
+    pub fn quality(&self, channel: u8) -> Option<&ChannelQuality> {
+        if channel_bit(channel) == 0 {
+            return None;
+        }
+        self.quality.get((channel - MIN_CHANNEL) as usize)
+    }
+
+    /// Usable channel mask last reported by a neighbor
+    pub fn neighbor_map(&self, node: MeshNodeId) -> Option<u16> {
+        self.neighbor_maps
+            .iter()
+            .find(|(id, _)| *id == node)
+            .map(|(_, mask)| *mask)
+    }
+
+    /// Switch waiting for its time, as (plan, switch time)
+    pub fn committed_switch(&self) -> Option<(ChannelPlan, u64)> {
+        self.pending
+            .as_ref()
+            .filter(|pending| pending.committed)
+            .map(|pending| (pending.plan, pending.switch_at_ms))
+    }
+
+    /// Channel to transmit and listen on at mesh time `now_ms`
+    pub fn channel_at(&self, now_ms: u64) -> u8 {
+        if self.config.hopping {
+            hop_channel(
+                &self.config.hop_secret,
+                &self.plan,
+                self.config.dwell_ms,
+                now_ms,
+            )
+        } else {
+            self.plan.home
+        }
+    }
+
+    /// Record an observation about a channel
+    pub fn record(&mut self, channel: u8, event: ChannelEvent) {
+        if channel_bit(channel) == 0 {
+            return;
+        }
+        self.quality[(channel - MIN_CHANNEL) as usize].record(event);
+    }
+
+    /// Check if a channel is free of interference here
+    pub fn is_clean(&self, channel: u8) -> bool {
+        self.quality(channel).is_some_and(|quality| {
+            quality.interference < self.config.interference_threshold
+                && quality
+                    .noise_dbm
+                    .is_none_or(|noise| noise < self.config.noise_threshold_dbm as f32)
+        })
+    }
+
+    /// Mask of the channels clean here
+    pub fn usable_channels(&self) -> u16 {
+        channels_in(ALL_CHANNELS)
+            .filter(|channel| self.is_clean(*channel))
+            .fold(0, |mask, channel| mask | channel_bit(channel))
+    }
+
+    /// Periodic work: perform due switches, time out votes, propose
+    /// migrations and report channel maps
+    ///
+    /// `active` lists the active neighbors, which vote on proposals.
+    /// Returns the messages to broadcast.
+    pub fn tick(&mut self, now_ms: u64, active: &[MeshNodeId]) -> Vec<MeshMessage, 2> {
+        let mut out = Vec::new();
+        self.neighbor_maps.retain(|(id, _)| active.contains(id));
+
+        if let Some(pending) = &self.pending {
+            if pending.committed && now_ms >= pending.switch_at_ms {
+                let plan = pending.plan;
+                self.adopt(plan);
+            } else if !pending.committed && now_ms >= pending.deadline_ms {
+                if pending.proposer == self.local_id {
+                    self.stats.failed_proposals += 1;
+                }
+                self.pending = None;
+            }
+        }
+
+        if !self.config.adaptive {
+            return out;
+        }
+
+        let settled = self
+            .last_attempt_ms
+            .is_none_or(|last| now_ms - last >= self.config.min_switch_interval_ms);
+        if self.pending.is_none() && settled && self.is_degraded() {
+            if let Some(plan) = self.candidate_plan() {
+                self.last_attempt_ms = Some(now_ms);
+                self.stats.proposals += 1;
+                let mut pending = PendingSwitch {
+                    proposer: self.local_id,
+                    plan,
+                    switch_at_ms: now_ms + self.config.switch_delay_ms,
+                    committed: false,
+                    deadline_ms: now_ms + self.config.vote_timeout_ms,
+                    expected: active.len(),
+                    accepted: Vec::new(),
+                    rejected: Vec::new(),
+                };
+                // Alone, the proposer is its own majority
+                pending.committed = pending.has_majority();
+                let phase = if pending.committed {
+                    SwitchPhase::Commit
+                } else {
+                    SwitchPhase::Propose
+                };
+                out.push(self.switch_message(phase, &pending, None, now_ms))
+                    .ok();
+                self.pending = Some(pending);
+            }
+        }
+
+        let report_due = self
+            .last_report_ms
+            .is_none_or(|last| now_ms - last >= self.config.report_interval_ms);
+        if report_due {
+            self.last_report_ms = Some(now_ms);
+            self.decay_unobserved();
+
+            // Committed switches are re-announced until they take effect
+            if out.is_empty() {
+                if let Some(pending) = self.pending.as_ref().filter(|p| p.committed) {
+                    let commit = self.switch_message(SwitchPhase::Commit, pending, None, now_ms);
+                    out.push(commit).ok();
+                }
+            }
+            let report = MeshMessage::new(
+                self.local_id,
+                None,
+                MessagePriority::Normal,
+                MeshMessageType::ChannelReport {
+                    node_id: self.local_id,
+                    usable: self.usable_channels(),
+                    plan: self.plan,
+                },
+                now_ms,
+            );
+            out.push(report).ok();
+        }
+        out
+    }
+
+    /// Handle a channel report or switch message
+    ///
+    /// Returns the vote or commit to send in reply, if any.
+    pub fn handle(&mut self, msg: &MeshMessage, now_ms: u64) -> Option<MeshMessage> {
+        match &msg.payload {
+            MeshMessageType::ChannelReport {
+                node_id,
+                usable,
+                plan,
+            } => {
+                self.record_map(*node_id, *usable);
+                // A drone that missed a commit catches up with its neighbors
+                if plan.epoch > self.plan.epoch {
+                    self.adopt(*plan);
+                }
+                None
+            }
+            MeshMessageType::ChannelSwitch {
+                phase,
+                plan,
+                switch_at_ms,
+            } => match phase {
+                SwitchPhase::Propose => self.vote(msg.source, *plan, *switch_at_ms, now_ms),
+                SwitchPhase::Accept | SwitchPhase::Reject => {
+                    if msg.destination == Some(self.local_id) {
+                        self.count_vote(msg.source, *plan, *phase == SwitchPhase::Accept, now_ms)
+                    } else {
+                        None
+                    }
+                }
+                SwitchPhase::Commit => {
+                    self.commit(msg.source, *plan, *switch_at_ms);
+                    None
+                }
+            },
+            _ => None,
+        }
+    }
+
+    /// Answer a proposal
+    fn vote(
+        &mut self,
+        proposer: MeshNodeId,
+        plan: ChannelPlan,
+        switch_at_ms: u64,
+        now_ms: u64,
+    ) -> Option<MeshMessage> {
+        // Of concurrent proposals the one from the lower node ID wins
+        let free = match &self.pending {
+            None => true,
+            Some(pending) => !pending.committed && proposer.0 <= pending.proposer.0,
+        };
+        let accept = free
+            && plan.epoch > self.plan.epoch
+            && self.plan_channels(&plan) & !self.usable_channels() == 0;
+        if accept
+            && self
+                .pending
+                .as_ref()
+                .is_some_and(|pending| pending.proposer == self.local_id)
+        {
+            self.stats.failed_proposals += 1;
+        }
+
+        let pending = PendingSwitch {
+            proposer,
+            plan,
+            switch_at_ms,
+            committed: false,
+            deadline_ms: now_ms + self.config.vote_timeout_ms,
+            expected: 0,
+            accepted: Vec::new(),
+            rejected: Vec::new(),
+        };
+        let phase = if accept {
+            SwitchPhase::Accept
+        } else {
+            self.stats.rejected_proposals += 1;
+            SwitchPhase::Reject
+        };
+        let reply = self.switch_message(phase, &pending, Some(proposer), now_ms);
+        if accept {
+            self.pending = Some(pending);
+        }
+        Some(reply)
+    }
+
+    /// Count a vote on our own proposal
+    fn count_vote(
+        &mut self,
+        voter: MeshNodeId,
+        plan: ChannelPlan,
+        accept: bool,
+        now_ms: u64,
+    ) -> Option<MeshMessage> {
+        let local_id = self.local_id;
+        let pending = self.pending.as_mut()?;
+        if pending.proposer != local_id || pending.plan != plan || pending.committed {
+            return None;
+        }
+        let votes = if accept {
+            &mut pending.accepted
+        } else {
+            &mut pending.rejected
+        };
+        if !votes.contains(&voter) {
+            votes.push(voter).ok();
+        }
+
+        if pending.has_majority() {
+            pending.committed = true;
+            let pending = pending.clone();
+            return Some(self.switch_message(SwitchPhase::Commit, &pending, None, now_ms));
+        }
+        if pending.is_defeated() {
+            self.pending = None;
+            self.stats.failed_proposals += 1;
+        }
+        None
+    }
+
+    /// Schedule a committed switch
+    ///
+    /// Of two conflicting commits for the same epoch, the one from the lower
+    /// node ID wins.
+    fn commit(&mut self, source: MeshNodeId, plan: ChannelPlan, switch_at_ms: u64) {
+        if plan.epoch <= self.plan.epoch {
+            return;
+        }
+        if let Some(pending) = &self.pending {
+            let keep = pending.committed
+                && (pending.plan == plan
+                    || pending.plan.epoch > plan.epoch
+                    || (pending.plan.epoch == plan.epoch && pending.proposer.0 <= source.0));
+            if keep {
+                return;
+            }
+        }
+        self.pending = Some(PendingSwitch {
+            proposer: source,
+            plan,
+            switch_at_ms,
+            committed: true,
+            deadline_ms: switch_at_ms,
+            expected: 0,
+            accepted: Vec::new(),
+            rejected: Vec::new(),
+        });
+    }
+
+    /// Take a plan into use
+    fn adopt(&mut self, plan: ChannelPlan) {
+        self.plan = plan;
+        self.pending = None;
+        self.stats.switches += 1;
+    }
+
+    fn record_map(&mut self, node: MeshNodeId, usable: u16) {
+        match self.neighbor_maps.iter_mut().find(|(id, _)| *id == node) {
+            Some((_, mask)) => *mask = usable,
+            None => {
+                self.neighbor_maps.push((node, usable)).ok();
+            }
+        }
+    }
+
+    /// Channels a plan transmits on
+    fn plan_channels(&self, plan: &ChannelPlan) -> u16 {
+        if self.config.hopping {
+            plan.hop_mask & ALL_CHANNELS
+        } else {
+            channel_bit(plan.home)
+        }
+    }
+
+    /// Check if any channel of the current plan has become unusable
+    fn is_degraded(&self) -> bool {
+        self.plan_channels(&self.plan) & !self.usable_channels() != 0
+    }
+
+    /// Next plan from channels clean here and at every neighbor
+    fn candidate_plan(&self) -> Option<ChannelPlan> {
+        let candidates = self
+            .neighbor_maps
+            .iter()
+            .fold(self.usable_channels(), |mask, (_, usable)| mask & usable);
+        let cleanest = |exclude: u16| {
+            channels_in(candidates & !exclude).min_by(|a, b| {
+                let a = self.quality[(a - MIN_CHANNEL) as usize].interference;
+                let b = self.quality[(b - MIN_CHANNEL) as usize].interference;
+                a.total_cmp(&b)
+            })
+        };
+
+        let mut plan = ChannelPlan {
+            epoch: self.plan.epoch.wrapping_add(1),
+            ..self.plan
+        };
+        if self.config.hopping {
+            let mut mask = self.plan.hop_mask & candidates;
+            while mask.count_ones() < MIN_HOP_CHANNELS {
+                mask |= channel_bit(cleanest(mask)?);
+            }
+            if mask == self.plan.hop_mask {
+                return None;
+            }
+            plan.hop_mask = mask;
+        } else {
+            plan.home = cleanest(channel_bit(self.plan.home))?;
+        }
+        Some(plan)
+    }
+
+    /// Let channels outside the current plan regain trust
+    fn decay_unobserved(&mut self) {
+        let in_use = self.plan_channels(&self.plan);
+        for channel in channels_in(ALL_CHANNELS & !in_use) {
+            self.quality[(channel - MIN_CHANNEL) as usize].decay();
+        }
+    }
+
+    fn switch_message(
+        &self,
+        phase: SwitchPhase,
+        pending: &PendingSwitch,
+        destination: Option<MeshNodeId>,
+        now_ms: u64,
+    ) -> MeshMessage {
+        MeshMessage::new(
+            self.local_id,
+            destination,
+            MessagePriority::High,
+            MeshMessageType::ChannelSwitch {
+                phase,
+                plan: pending.plan,
+                switch_at_ms: pending.switch_at_ms,
+            },
+            now_ms,
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn adaptive() -> ChannelConfig {
+        ChannelConfig {
+            adaptive: true,
+            ..ChannelConfig::default()
+        }
+    }
+
+    fn jam(manager: &mut ChannelManager, channel: u8) {
+        for _ in 0..10 {
+            manager.record(channel, ChannelEvent::Corrupted);
+        }
+    }
+
+    fn switch_phase(msg: &MeshMessage) -> Option<SwitchPhase> {
+        match msg.payload {
+            MeshMessageType::ChannelSwitch { phase, .. } => Some(phase),
+            _ => None,
+        }
+    }
+
+    #[test]
+    fn test_hop_sequence_is_shared_and_keyed() {
+        let plan = ChannelPlan {
+            epoch: 0,
+            home: 6,
+            hop_mask: 0x0FFE,
+        };
+        let sequence = |secret: &[u8; 32]| -> std::vec::Vec<u8> {
+            (0..64)
+                .map(|t| hop_channel(secret, &plan, 50, t * 50))
+                .collect()
+        };
+
+        let a = sequence(&[1; 32]);
+        assert_eq!(a, sequence(&[1; 32]));
+        assert_ne!(a, sequence(&[2; 32]));
+        assert!(a.iter().all(|ch| plan.hop_mask & channel_bit(*ch) != 0));
+        // The sequence actually hops
+        assert!(a.iter().any(|ch| *ch != a[0]));
+        // Constant within a dwell period
+        assert_eq!(
+            hop_channel(&[1; 32], &plan, 50, 100),
+            hop_channel(&[1; 32], &plan, 50, 149)
+        );
+    }
+
+    #[test]
+    fn test_interference_marks_channel_unusable() {
+        let mut manager = ChannelManager::new(MeshNodeId::new(1), 6, adaptive());
+        assert!(manager.is_clean(6));
+
+        jam(&mut manager, 6);
+        assert!(!manager.is_clean(6));
+        assert_eq!(manager.usable_channels() & channel_bit(6), 0);
+
+        manager.record(1, ChannelEvent::Noise(-50));
+        assert!(!manager.is_clean(1));
+        assert_eq!(manager.quality(6).unwrap().frames_bad, 10);
+    }
+
+    #[test]
+    fn test_lone_node_switches_immediately() {
+        let mut manager = ChannelManager::new(MeshNodeId::new(1), 1, adaptive());
+        jam(&mut manager, 1);
+
+        let out = manager.tick(0, &[]);
+        assert_eq!(switch_phase(&out[0]), Some(SwitchPhase::Commit));
+        let (plan, switch_at) = manager.committed_switch().unwrap();
+        assert_eq!(plan.home, 2);
+
+        manager.tick(switch_at, &[]);
+        assert_eq!(manager.plan().home, 2);
+        assert_eq!(manager.plan().epoch, 1);
+        assert_eq!(manager.channel_at(switch_at), 2);
+    }
+
+    #[test]
+    fn test_majority_vote_migrates_mesh() {
+        let ids = [MeshNodeId::new(1), MeshNodeId::new(2), MeshNodeId::new(3)];
+        let mut nodes: std::vec::Vec<ChannelManager> = ids
+            .iter()
+            .map(|id| ChannelManager::new(*id, 6, adaptive()))
+            .collect();
+        // Channel 1 is bad at drone 3, so the mesh must avoid it
+        jam(&mut nodes[2], 1);
+        for i in 0..3 {
+            let others: std::vec::Vec<MeshNodeId> =
+                ids.iter().copied().filter(|id| *id != ids[i]).collect();
+            for msg in nodes[i].tick(0, &others) {
+                for (j, node) in nodes.iter_mut().enumerate() {
+                    if j != i {
+                        node.handle(&msg, 0);
+                    }
+                }
+            }
+        }
+
+        jam(&mut nodes[0], 6);
+        let proposal = nodes[0].tick(100, &ids[1..]).remove(0);
+        assert_eq!(switch_phase(&proposal), Some(SwitchPhase::Propose));
+
+        let vote = nodes[1].handle(&proposal, 110).unwrap();
+        assert_eq!(switch_phase(&vote), Some(SwitchPhase::Accept));
+        assert_eq!(vote.destination, Some(ids[0]));
+        // Two of three is a majority
+        let commit = nodes[0].handle(&vote, 120).unwrap();
+        assert_eq!(switch_phase(&commit), Some(SwitchPhase::Commit));
+        nodes[1].handle(&commit, 130);
+        nodes[2].handle(&commit, 130);
+
+        let (plan, switch_at) = nodes[0].committed_switch().unwrap();
+        assert_eq!(plan.home, 2);
+        for node in nodes.iter_mut() {
+            node.tick(switch_at, &[]);
+            assert_eq!(*node.plan(), plan);
+        }
+        assert_eq!(nodes[0].statistics().proposals, 1);
+    }
+
+    #[test]
+    fn test_proposal_rejected_when_channel_bad_locally() {
+        let mut proposer = ChannelManager::new(MeshNodeId::new(1), 6, adaptive());
+        let mut voter = ChannelManager::new(MeshNodeId::new(2), 6, adaptive());
+        jam(&mut proposer, 6);
+        let proposal = proposer.tick(0, &[MeshNodeId::new(2)]).remove(0);
+
+        // The voter's channel 1 went bad after it last reported
+        jam(&mut voter, 1);
+        let vote = voter.handle(&proposal, 10).unwrap();
+        assert_eq!(switch_phase(&vote), Some(SwitchPhase::Reject));
+        assert!(proposer.handle(&vote, 20).is_none());
+        assert!(proposer.committed_switch().is_none());
+        assert_eq!(proposer.statistics().failed_proposals, 1);
+
+        // No new attempt before the minimum switch interval
+        let out = proposer.tick(1000, &[MeshNodeId::new(2)]);
+        assert!(out.iter().all(|msg| switch_phase(msg).is_none()));
+    }
+
+    #[test]
+    fn test_report_with_newer_plan_catches_up() {
+        let mut manager = ChannelManager::new(MeshNodeId::new(1), 6, adaptive());
+        let plan = ChannelPlan {
+            epoch: 3,
+            home: 11,
+            hop_mask: 0x0FFE,
+        };
+        let report = MeshMessage::new(
+            MeshNodeId::new(2),
+            None,
+            MessagePriority::Normal,
+            MeshMessageType::ChannelReport {
+                node_id: MeshNodeId::new(2),
+                usable: ALL_CHANNELS,
+                plan,
+            },
+            0,
+        );
+
+        manager.handle(&report, 0);
+        assert_eq!(*manager.plan(), plan);
+        assert_eq!(manager.neighbor_map(MeshNodeId::new(2)), Some(ALL_CHANNELS));
+    }
+}
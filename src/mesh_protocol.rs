@@ -6,10 +6,27 @@
 //! - Position sharing
 //! - Command distribution
 //! - Telemetry aggregation
+//! - Channel coordination (see [`crate::mesh_channel`])
+//! - Certificate revocation (see [`crate::certificate`])
 //! - Encrypted communication
 //!
 //! Protocol is designed for `no_std` environments (ESP32, STM32).
-
+//!
+//! # Wire format
+//! [`MeshMessage::encode_into`] produces a versioned on-air frame:
+//!
+//! ```text
+//! magic (2) | version (1) | type (1) | body length (2, LE) | body | CRC32 (4, LE)
+//! ```
+//!
+//! The body is the postcard encoding of the header fields and payload; the
+//! CRC covers everything before it. The first six bytes keep this layout in
+//! every version, so [`MeshMessage::decode`] can step over frames with a newer
+//! version or an unknown message type instead of failing the whole stream.
+
+use crate::certificate::RevocationList;
+use crate::crypto::SIGNATURE_SIZE;
+use crate::types::{Result, SwarmError};
 use heapless::Vec;
 use serde::{Deserialize, Serialize};
 
@@ -22,6 +39,21 @@ pub const MAX_PAYLOAD_SIZE: usize = 256;
 /// Maximum hops for message routing
 pub const MAX_HOPS: u8 = 10;
 
+/// Magic bytes opening every wire frame
+pub const WIRE_MAGIC: [u8; 2] = [0xD5, 0x4D];
+
+/// Wire format version produced by this build
+pub const WIRE_VERSION: u8 = 1;
+
+/// Fixed wire frame header: magic, version, type and body length (bytes)
+pub const WIRE_HEADER_SIZE: usize = 6;
+
+/// Trailing CRC32 (bytes)
+pub const WIRE_CRC_SIZE: usize = 4;
+
+/// Maximum encoded wire frame (bytes)
+pub const MAX_WIRE_FRAME_SIZE: usize = 320;
+
 /// Heartbeat interval in milliseconds
 pub const HEARTBEAT_INTERVAL_MS: u64 = 1000;
 
@@ -64,6 +96,8 @@ pub enum MeshMessageType {
         position: [f32; 3],
         battery_percent: u8,
         neighbors_count: u8,
+        /// Sender's transmit queue occupancy (percent), for congestion control
+        queue_load: u8,
     },
 
     /// Position update (high frequency)
@@ -80,6 +114,9 @@ pub enum MeshMessageType {
         command_id: u16,
         target: CommandTarget,
         action: CommandAction,
+        /// Issuer's end-to-end signature, forwarded unchanged by relays;
+        /// empty when unsigned
+        signature: Vec<u8, SIGNATURE_SIZE>,
     },
 
     /// Acknowledgment
@@ -129,6 +166,76 @@ pub enum MeshMessageType {
 
     /// Custom application data
     Custom { data: Vec<u8, MAX_PAYLOAD_SIZE> },
+
+    /// Channels the sender can use and the plan it is on
+    ChannelReport {
+        node_id: MeshNodeId,
+        /// Bit `n` set = channel `n` is clean at the sender
+        usable: u16,
+        plan: ChannelPlan,
+    },
+
+    /// Step of an agreed channel switch
+    ChannelSwitch {
+        phase: SwitchPhase,
+        plan: ChannelPlan,
+        /// Mesh time at which every node moves to `plan` (ms)
+        switch_at_ms: u64,
+    },
+
+    /// Latest certificate revocation list, flooded swarm-wide
+    Revocations { list: RevocationList },
+}
+
+impl MeshMessageType {
+    /// Number of message types known to this build
+    pub const WIRE_TYPE_COUNT: u8 = 13;
+
+    /// Stable on-air type code
+    ///
+    /// Codes follow declaration order; new variants must be appended so older
+    /// receivers skip them rather than misparse them.
+    pub fn wire_type(&self) -> u8 {
+        match self {
+            Self::Heartbeat { .. } => 0,
+            Self::PositionUpdate { .. } => 1,
+            Self::Command { .. } => 2,
+            Self::Ack { .. } => 3,
+            Self::FormationUpdate { .. } => 4,
+            Self::Telemetry { .. } => 5,
+            Self::Emergency { .. } => 6,
+            Self::RouteRequest { .. } => 7,
+            Self::RouteResponse { .. } => 8,
+            Self::Custom { .. } => 9,
+            Self::ChannelReport { .. } => 10,
+            Self::ChannelSwitch { .. } => 11,
+            Self::Revocations { .. } => 12,
+        }
+    }
+}
+
+/// Radio channels the mesh operates on
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ChannelPlan {
+    /// Plan generation; a higher epoch supersedes a lower one
+    pub epoch: u16,
+    /// Channel used when not hopping (1-13)
+    pub home: u8,
+    /// Channels the hop sequence visits (bit `n` = channel `n`)
+    pub hop_mask: u16,
+}
+
+/// Phase of a channel switch
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum SwitchPhase {
+    /// Proposer asks the mesh to move
+    Propose,
+    /// Vote for the proposal (addressed to the proposer)
+    Accept,
+    /// Vote against the proposal (addressed to the proposer)
+    Reject,
+    /// A majority accepted; switch at the given time
+    Commit,
 }
 
 /// Command target specification
@@ -247,6 +354,7 @@ impl MeshMessage {
                 position,
                 battery_percent,
                 neighbors_count,
+                queue_load: 0,
             },
             timestamp_ms,
         )
@@ -295,6 +403,27 @@ impl MeshMessage {
                 command_id: 0, // Will be set by sender
                 target,
                 action,
+                signature: Vec::new(),
+            },
+            timestamp_ms,
+        )
+    }
+
+    /// Create acknowledgment of `original_msg_id` addressed to its sender
+    pub fn ack(
+        source: MeshNodeId,
+        destination: MeshNodeId,
+        original_msg_id: u32,
+        status: AckStatus,
+        timestamp_ms: u64,
+    ) -> Self {
+        Self::new(
+            source,
+            Some(destination),
+            MessagePriority::High,
+            MeshMessageType::Ack {
+                original_msg_id,
+                status,
             },
             timestamp_ms,
         )
@@ -345,9 +474,12 @@ impl MeshMessage {
     }
 
     /// Decrement TTL for routing
+    ///
+    /// The checksum covers the TTL, so it is recomputed for the next hop.
     pub fn decrement_ttl(&mut self) -> bool {
         if self.ttl > 0 {
             self.ttl -= 1;
+            self.checksum = self.compute_checksum();
             true
         } else {
             false
@@ -359,18 +491,110 @@ impl MeshMessage {
         self.ttl > 0 && self.source != my_id && self.destination != Some(my_id)
     }
 
-    // Note: Serialization functions require postcard with use-std feature.
-    // For now, messages are passed directly in memory.
-    // Add serialization when implementing actual network transport:
-    //
-    // #[cfg(feature = "use-std")]
-    // pub fn to_bytes(&self) -> Result<std::vec::Vec<u8>> {
-    //     postcard::to_stdvec(self).map_err(|_| SwarmError::SerializationError)
-    // }
-    //
-    // pub fn from_bytes(bytes: &[u8]) -> Result<Self> {
-    //     postcard::from_bytes(bytes).map_err(|_| SwarmError::SerializationError)
-    // }
+    /// Encode as a wire frame into `buf`, returning the frame length
+    ///
+    /// The in-memory `checksum` is not sent; the frame CRC replaces it.
+    pub fn encode_into(&self, buf: &mut [u8]) -> Result<usize> {
+        if buf.len() < WIRE_HEADER_SIZE + WIRE_CRC_SIZE {
+            return Err(SwarmError::BufferFull);
+        }
+        let body_end = buf.len() - WIRE_CRC_SIZE;
+        let body = (
+            self.msg_id,
+            self.source,
+            self.destination,
+            self.ttl,
+            self.priority,
+            self.timestamp_ms,
+            &self.payload,
+        );
+        let body_len = postcard::to_slice(&body, &mut buf[WIRE_HEADER_SIZE..body_end])
+            .map_err(|_| SwarmError::BufferFull)?
+            .len();
+
+        buf[..2].copy_from_slice(&WIRE_MAGIC);
+        buf[2] = WIRE_VERSION;
+        buf[3] = self.payload.wire_type();
+        buf[4..6].copy_from_slice(&(body_len as u16).to_le_bytes());
+
+        let crc_at = WIRE_HEADER_SIZE + body_len;
+        let crc = crc32(&buf[..crc_at]);
+        buf[crc_at..crc_at + WIRE_CRC_SIZE].copy_from_slice(&crc.to_le_bytes());
+        Ok(crc_at + WIRE_CRC_SIZE)
+    }
+
+    /// Decode the wire frame at the start of `bytes`
+    ///
+    /// Returns the frame and the number of bytes it occupied, so a receive
+    /// buffer holding several frames can be walked. Frames with a newer
+    /// version or an unknown type are returned as [`WireFrame::Skipped`].
+    pub fn decode(bytes: &[u8]) -> Result<(WireFrame, usize)> {
+        if bytes.len() < WIRE_HEADER_SIZE + WIRE_CRC_SIZE || bytes[..2] != WIRE_MAGIC {
+            return Err(SwarmError::InvalidMessage);
+        }
+        let version = bytes[2];
+        let wire_type = bytes[3];
+        let body_len = u16::from_le_bytes([bytes[4], bytes[5]]) as usize;
+        let crc_at = WIRE_HEADER_SIZE + body_len;
+        let frame_len = crc_at + WIRE_CRC_SIZE;
+        if bytes.len() < frame_len {
+            return Err(SwarmError::InvalidMessage);
+        }
+
+        let mut crc = [0u8; WIRE_CRC_SIZE];
+        crc.copy_from_slice(&bytes[crc_at..frame_len]);
+        if crc32(&bytes[..crc_at]) != u32::from_le_bytes(crc) {
+            return Err(SwarmError::InvalidMessage);
+        }
+
+        if version != WIRE_VERSION || wire_type >= MeshMessageType::WIRE_TYPE_COUNT {
+            return Ok((WireFrame::Skipped { version, wire_type }, frame_len));
+        }
+
+        let (msg_id, source, destination, ttl, priority, timestamp_ms, payload): (
+            u32,
+            MeshNodeId,
+            Option<MeshNodeId>,
+            u8,
+            MessagePriority,
+            u64,
+            MeshMessageType,
+        ) = postcard::from_bytes(&bytes[WIRE_HEADER_SIZE..crc_at])
+            .map_err(|_| SwarmError::SerializationError)?;
+        if payload.wire_type() != wire_type {
+            return Err(SwarmError::InvalidMessage);
+        }
+
+        let mut msg = Self {
+            msg_id,
+            source,
+            destination,
+            ttl,
+            priority,
+            timestamp_ms,
+            payload,
+            checksum: 0,
+        };
+        msg.checksum = msg.compute_checksum();
+        Ok((WireFrame::Message(msg), frame_len))
+    }
+}
+
+/// Result of decoding one wire frame
+#[derive(Debug, Clone)]
+#[allow(clippy::large_enum_variant)]
+pub enum WireFrame {
+    /// Frame decoded into a message
+    Message(MeshMessage),
+    /// Intact frame this build does not understand
+    Skipped { version: u8, wire_type: u8 },
+}
+
+/// CRC32 of a byte slice
+fn crc32(bytes: &[u8]) -> u32 {
+    !bytes
+        .iter()
+        .fold(0xFFFFFFFF, |crc, &byte| crc32_byte(crc, byte))
 }
 
 /// Simple CRC32 byte update
@@ -405,6 +629,8 @@ pub struct MeshNeighbor {
     pub is_active: bool,
     /// Hop count to reach this node
     pub hop_count: u8,
+    /// Transmit queue occupancy last reported by the node (percent)
+    pub queue_load: u8,
 }
 
 impl MeshNeighbor {
@@ -418,6 +644,7 @@ impl MeshNeighbor {
             battery_percent: 0,
             is_active: false,
             hop_count: 255,
+            queue_load: 0,
         }
     }
 
@@ -539,6 +766,27 @@ mod tests {
         assert!(msg.destination.is_none());
     }
 
+    #[test]
+    fn test_ack_message() {
+        let msg = MeshMessage::ack(
+            MeshNodeId::new(3),
+            MeshNodeId::new(0),
+            42,
+            AckStatus::Success,
+            2500,
+        );
+
+        assert_eq!(msg.destination, Some(MeshNodeId::new(0)));
+        assert!(matches!(
+            msg.payload,
+            MeshMessageType::Ack {
+                original_msg_id: 42,
+                status: AckStatus::Success
+            }
+        ));
+        assert!(msg.verify_checksum());
+    }
+
     #[test]
     fn test_emergency_message() {
         let msg = MeshMessage::emergency(
@@ -578,9 +826,157 @@ mod tests {
         // Decrement TTL
         for _ in 0..MAX_HOPS {
             assert!(msg.decrement_ttl());
+            assert!(msg.verify_checksum());
         }
         // TTL exhausted
         assert!(!msg.decrement_ttl());
         assert!(!msg.should_forward(MeshNodeId::new(2)));
     }
+
+    /// Re-stamp the CRC after patching a frame header
+    fn restamp(frame: &mut [u8]) {
+        let crc_at = frame.len() - WIRE_CRC_SIZE;
+        let crc = crc32(&frame[..crc_at]);
+        frame[crc_at..].copy_from_slice(&crc.to_le_bytes());
+    }
+
+    #[test]
+    fn test_wire_roundtrip() {
+        let mut cmd = MeshMessage::command(
+            MeshNodeId::new(0),
+            CommandTarget::Group(3),
+            CommandAction::GoTo {
+                position: [1.0, 2.0, 3.0],
+            },
+            4242,
+        );
+        cmd.msg_id = 77;
+        cmd.ttl = 4;
+        let custom = MeshMessage::new(
+            MeshNodeId::new(5),
+            Some(MeshNodeId::new(6)),
+            MessagePriority::Low,
+            MeshMessageType::Custom {
+                data: Vec::from_slice(&[0xAB; MAX_PAYLOAD_SIZE]).unwrap(),
+            },
+            9,
+        );
+
+        for msg in [cmd, custom] {
+            let mut buf = [0u8; MAX_WIRE_FRAME_SIZE];
+            let len = msg.encode_into(&mut buf).unwrap();
+            assert_eq!(&buf[..2], &WIRE_MAGIC);
+            assert_eq!(buf[2], WIRE_VERSION);
+            assert_eq!(buf[3], msg.payload.wire_type());
+
+            let (frame, used) = MeshMessage::decode(&buf[..len]).unwrap();
+            assert_eq!(used, len);
+            let WireFrame::Message(decoded) = frame else {
+                panic!("expected a message");
+            };
+            assert_eq!(decoded.msg_id, msg.msg_id);
+            assert_eq!(decoded.source, msg.source);
+            assert_eq!(decoded.destination, msg.destination);
+            assert_eq!(decoded.ttl, msg.ttl);
+            assert_eq!(decoded.timestamp_ms, msg.timestamp_ms);
+            assert_eq!(decoded.payload.wire_type(), msg.payload.wire_type());
+            assert!(decoded.verify_checksum());
+        }
+    }
+
+    #[test]
+    fn test_full_revocation_list_fits_frame() {
+        use crate::certificate::MAX_REVOKED_CERTIFICATES;
+
+        let mut list = RevocationList {
+            version: u32::MAX,
+            serials: Vec::new(),
+            signature: Vec::from_slice(&[0xFF; 64]).unwrap(),
+        };
+        for _ in 0..MAX_REVOKED_CERTIFICATES {
+            list.serials.push(u32::MAX).unwrap();
+        }
+        let msg = MeshMessage::new(
+            MeshNodeId::new(0),
+            None,
+            MessagePriority::High,
+            MeshMessageType::Revocations { list },
+            u64::MAX,
+        );
+
+        let mut buf = [0u8; MAX_WIRE_FRAME_SIZE];
+        let len = msg.encode_into(&mut buf).unwrap();
+        let (frame, _) = MeshMessage::decode(&buf[..len]).unwrap();
+        assert!(matches!(
+            frame,
+            WireFrame::Message(MeshMessage {
+                payload: MeshMessageType::Revocations { .. },
+                ..
+            })
+        ));
+    }
+
+    #[test]
+    fn test_wire_rejects_corruption() {
+        let msg = MeshMessage::heartbeat(MeshNodeId::new(1), [0.0; 3], 90, 2, 1000);
+        let mut buf = [0u8; MAX_WIRE_FRAME_SIZE];
+        let len = msg.encode_into(&mut buf).unwrap();
+
+        let mut flipped = buf;
+        flipped[WIRE_HEADER_SIZE + 1] ^= 0x10;
+        assert_eq!(
+            MeshMessage::decode(&flipped[..len]).unwrap_err(),
+            SwarmError::InvalidMessage
+        );
+        assert!(MeshMessage::decode(&buf[..len - 1]).is_err());
+        assert!(MeshMessage::decode(&[0u8; 16]).is_err());
+
+        // The type byte must match the body
+        let mut retyped = buf;
+        retyped[3] = 2;
+        restamp(&mut retyped[..len]);
+        assert!(MeshMessage::decode(&retyped[..len]).is_err());
+
+        assert_eq!(
+            msg.encode_into(&mut [0u8; 12]).unwrap_err(),
+            SwarmError::BufferFull
+        );
+    }
+
+    #[test]
+    fn test_wire_skips_unknown_frames() {
+        let first = MeshMessage::heartbeat(MeshNodeId::new(1), [0.0; 3], 90, 2, 1000);
+        let last = MeshMessage::emergency(
+            MeshNodeId::new(1),
+            EmergencyType::LowBattery,
+            [0.0; 3],
+            1001,
+        );
+
+        // Heartbeat, future message type, future version, emergency
+        let mut stream = [0u8; 4 * MAX_WIRE_FRAME_SIZE];
+        let mut end = 0;
+        for patch in [None, Some((3, 200)), Some((2, WIRE_VERSION + 1)), None] {
+            let msg = if end == 0 { &first } else { &last };
+            let len = msg.encode_into(&mut stream[end..]).unwrap();
+            if let Some((at, value)) = patch {
+                stream[end + at] = value;
+                restamp(&mut stream[end..end + len]);
+            }
+            end += len;
+        }
+
+        let mut kinds = [0u8; 4];
+        let mut offset = 0;
+        for kind in kinds.iter_mut() {
+            let (frame, used) = MeshMessage::decode(&stream[offset..end]).unwrap();
+            *kind = match frame {
+                WireFrame::Message(msg) => msg.payload.wire_type(),
+                WireFrame::Skipped { wire_type, .. } => wire_type,
+            };
+            offset += used;
+        }
+        assert_eq!(offset, end);
+        assert_eq!(kinds, [0, 200, 6, 6]);
+    }
 }
@@ -0,0 +1,715 @@
+//! Authenticated and encrypted framing for mesh messages
+//!
+//! The CRC32 checksum on [`MeshMessage`] only catches corruption: anyone on
+//! the channel can forge a valid `Command { action: EmergencyStop }`. When
+//! [`MeshConfig::encryption_enabled`](crate::esp32_mesh::MeshConfig) is set,
+//! each hop seals the postcard-encoded message with:
+//! - ChaCha20-Poly1305 under the swarm's shared network key (confidentiality
+//!   and integrity)
+//! - An Ed25519 signature by the transmitting drone (origin authentication),
+//!   joined by an ML-DSA-65 signature for members registered with
+//!   [`MeshSecurity::add_member_ml_dsa_key`] (`post-quantum` feature)
+//! - A per-sender nonce checked against a sliding window by [`NonceTracker`]
+//!   (replay protection that tolerates reordering)
+//!
+//! With a [`TrustAnchor`] installed, members join through
+//! [`MeshSecurity::admit_member`] with a root-signed certificate and are
+//! dropped again when a [`RevocationList`] naming them arrives or their
+//! certificate expires (see [`MeshSecurity::expire_members`]).
+//!
+//! The mesh ID and sender are bound to the frame as associated data, so frames
+//! from another swarm or relabelled senders fail authentication. Sealing is
+//! hop-by-hop: a relay opens, decrements the TTL and re-seals under its own key.
+//! Commands therefore also carry their issuer's own signature
+//! ([`MeshSecurity::sign_command`]), which relays forward unchanged.
+
+use crate::certificate::{DroneCertificate, RevocationList, TrustAnchor};
+#[cfg(not(feature = "post-quantum"))]
+use crate::crypto::CRYPTO_OVERHEAD;
+#[cfg(feature = "post-quantum")]
+use crate::crypto::DUAL_CRYPTO_OVERHEAD;
+use crate::crypto::{CryptoContext, KeyStore, NonceTracker, ReplayStore};
+use crate::mesh_protocol::{MeshMessage, MeshMessageType, MeshNodeId, MAX_MESH_NODES};
+#[cfg(feature = "post-quantum")]
+use crate::post_quantum::ml_dsa;
+use crate::types::*;
+use crate::KEY_SIZE;
+use ed25519_dalek::{Signature, Verifier, VerifyingKey};
+use heapless::{FnvIndexMap, Vec};
+use serde::{Deserialize, Serialize};
+
+/// Maximum postcard-encoded [`MeshMessage`] (bytes)
+pub const MAX_MESSAGE_BYTES: usize = 384;
+
+/// Maximum sealed body: nonce, ciphertext, tag and signature (bytes)
+#[cfg(not(feature = "post-quantum"))]
+pub const MAX_SEALED_SIZE: usize = MAX_MESSAGE_BYTES + CRYPTO_OVERHEAD;
+
+/// Maximum sealed body: nonce, ciphertext, tag and both signatures (bytes)
+#[cfg(feature = "post-quantum")]
+pub const MAX_SEALED_SIZE: usize = MAX_MESSAGE_BYTES + DUAL_CRYPTO_OVERHEAD;
+
+/// Maximum encoded [`MeshFrame`] (bytes)
+pub const MAX_MESH_FRAME_SIZE: usize = MAX_SEALED_SIZE + 16;
+
+/// Domain separation of end-to-end command signatures
+const COMMAND_CONTEXT: &str = "droneswarm-v1 mesh command";
+
+/// Mesh frame as sent on the radio
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[allow(clippy::large_enum_variant)]
+pub enum MeshFrame {
+    /// Unprotected message (only accepted with encryption disabled)
+    Plain(MeshMessage),
+    /// Sealed message: nonce || ciphertext || tag || signature
+    Secured {
+        sender: MeshNodeId,
+        sealed: Vec<u8, MAX_SEALED_SIZE>,
+    },
+}
+
+impl MeshFrame {
+    /// Encode the frame with postcard
+    pub fn to_bytes(&self) -> Result<Vec<u8, MAX_MESH_FRAME_SIZE>> {
+        let mut buf = [0u8; MAX_MESH_FRAME_SIZE];
+        let len = postcard::to_slice(self, &mut buf)
+            .map_err(|_| SwarmError::SerializationError)?
+            .len();
+        Vec::from_slice(&buf[..len]).map_err(|_| SwarmError::BufferFull)
+    }
+
+    /// Decode a frame
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self> {
+        postcard::from_bytes(bytes).map_err(|_| SwarmError::SerializationError)
+    }
+}
+
+/// Why a received frame was rejected
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FrameRejection {
+    /// Plain frame while encryption is required, or no keys installed
+    Unauthenticated,
+    /// Sender has no registered public key
+    UnknownSender,
+    /// Signature, AEAD tag or associated data did not verify
+    AuthenticationFailed,
+    /// Nonce was already accepted from the sender or fell behind its window
+    Replay,
+    /// Frame or message could not be decoded
+    Malformed,
+}
+
+/// Key material and replay state for sealing mesh frames
+pub struct MeshSecurity {
+    /// This drone's ID (bound into sealed frames)
+    local_id: MeshNodeId,
+    /// Mesh network ID (bound into sealed frames)
+    mesh_id: [u8; 6],
+    /// Shared network key and this drone's signing key
+    crypto: CryptoContext,
+    /// Public keys of swarm members
+    keys: KeyStore,
+    /// Last nonce accepted per sender
+    nonces: NonceTracker,
+    /// Root key and revocations for certificate-based membership
+    trust: Option<TrustAnchor>,
+    /// Certificate of each member admitted by certificate
+    certified: FnvIndexMap<u8, Certified, MAX_MESH_NODES>,
+}
+
+/// Certificate a member was admitted with
+#[derive(Debug, Clone, Copy)]
+struct Certified {
+    serial: u32,
+    not_after_ms: u64,
+}
+
+impl MeshSecurity {
+    /// Create the security context for `local_id`
+    pub fn new(
+        local_id: MeshNodeId,
+        mesh_id: [u8; 6],
+        network_key: [u8; KEY_SIZE],
+        signing_seed: [u8; 32],
+    ) -> Self {
+        Self {
+            local_id,
+            mesh_id,
+            crypto: CryptoContext::with_keys(network_key, signing_seed),
+            keys: KeyStore::new(),
+            nonces: NonceTracker::new(),
+            trust: None,
+            certified: FnvIndexMap::new(),
+        }
+    }
+
+    /// This drone's public signing key, to distribute to swarm members
+    pub fn public_key(&self) -> &VerifyingKey {
+        self.crypto.public_key()
+    }
+
+    /// Network key and signing key, e.g. to rotate the network key
+    pub fn crypto_mut(&mut self) -> &mut CryptoContext {
+        &mut self.crypto
+    }
+
+    /// Register a swarm member's public key
+    pub fn add_member(&mut self, node: MeshNodeId, public_key: VerifyingKey) -> Result<()> {
+        self.keys.add_key(member_id(node), public_key)
+    }
+
+    /// Register a member's ML-DSA-65 key; its frames must be dual-signed
+    /// from now on (see [`CryptoContext::enable_dual_signatures`])
+    #[cfg(feature = "post-quantum")]
+    pub fn add_member_ml_dsa_key(
+        &mut self,
+        node: MeshNodeId,
+        public_key: ml_dsa::PublicKey,
+    ) -> Result<()> {
+        self.keys.add_ml_dsa_key(member_id(node), public_key)
+    }
+
+    /// Remove a swarm member; its frames are rejected from now on
+    pub fn remove_member(&mut self, node: MeshNodeId) -> Result<()> {
+        self.certified.remove(&node.as_u8());
+        self.nonces.forget(member_id(node));
+        self.keys.remove_key(member_id(node))
+    }
+
+    /// Install the ground-station root key members are certified under
+    pub fn set_trust_anchor(&mut self, anchor: TrustAnchor) {
+        self.trust = Some(anchor);
+    }
+
+    /// Trust anchor, if certificates are in use
+    pub fn trust_anchor(&self) -> Option<&TrustAnchor> {
+        self.trust.as_ref()
+    }
+
+    /// Register a joining member whose certificate verifies
+    ///
+    /// The certificate must identify `node`. Fails with `ConfigError` if no
+    /// trust anchor is installed.
+    pub fn admit_member(
+        &mut self,
+        node: MeshNodeId,
+        certificate: &DroneCertificate,
+        now_ms: u64,
+    ) -> Result<()> {
+        let trust = self.trust.as_ref().ok_or(SwarmError::ConfigError)?;
+        if certificate.drone_id != member_id(node) {
+            return Err(SwarmError::InvalidDroneId);
+        }
+        let public_key = trust.verify(certificate, now_ms)?;
+        self.keys.add_key(member_id(node), public_key)?;
+        let certified = Certified {
+            serial: certificate.serial,
+            not_after_ms: certificate.not_after_ms,
+        };
+        self.certified
+            .insert(node.as_u8(), certified)
+            .map_err(|_| SwarmError::ResourceExhausted)?;
+        Ok(())
+    }
+
+    /// Install a revocation list and drop the members it revokes
+    ///
+    /// Returns the number of members removed; stale lists remove none.
+    pub fn apply_revocations(&mut self, list: &RevocationList) -> Result<usize> {
+        let trust = self.trust.as_mut().ok_or(SwarmError::ConfigError)?;
+        if !trust.apply_revocations(list)? {
+            return Ok(0);
+        }
+
+        self.remove_certified(|certified| list.contains(certified.serial))
+    }
+
+    /// Drop the members whose certificate expired before `now_ms`
+    ///
+    /// Call periodically (see [`MeshNode::update`](crate::esp32_mesh::MeshNode::update)).
+    /// Returns the number of members removed; members added without a
+    /// certificate are unaffected.
+    pub fn expire_members(&mut self, now_ms: u64) -> Result<usize> {
+        self.remove_certified(|certified| certified.not_after_ms < now_ms)
+    }
+
+    /// Remove the certified members matching `predicate`
+    fn remove_certified(&mut self, predicate: impl Fn(&Certified) -> bool) -> Result<usize> {
+        let mut removed: Vec<u8, MAX_MESH_NODES> = Vec::new();
+        for (&node, certified) in &self.certified {
+            if predicate(certified) {
+                removed.push(node).map_err(|_| SwarmError::BufferFull)?;
+            }
+        }
+        for &node in &removed {
+            self.remove_member(MeshNodeId::new(node))?;
+        }
+        Ok(removed.len())
+    }
+
+    /// Check if a member's key is registered
+    pub fn is_member(&self, node: MeshNodeId) -> bool {
+        self.keys.has_key(member_id(node))
+    }
+
+    /// Drone identity whose key authenticates frames from `node`
+    pub fn identity(&self, node: MeshNodeId) -> Option<DroneId> {
+        self.is_member(node).then(|| member_id(node))
+    }
+
+    /// Sign a command we issue, end to end
+    ///
+    /// The signature covers the issuer, message ID and command payload, but
+    /// not the TTL, so it survives every relay.
+    pub fn sign_command(&self, message: &mut MeshMessage) -> Result<()> {
+        if message.source != self.local_id {
+            return Err(SwarmError::InvalidDroneId);
+        }
+        let digest = command_digest(message).ok_or(SwarmError::InvalidMessage)?;
+        let signed = self.crypto.sign(&digest);
+        if let MeshMessageType::Command { signature, .. } = &mut message.payload {
+            *signature = Vec::from_slice(&signed).map_err(|_| SwarmError::BufferFull)?;
+        }
+        Ok(())
+    }
+
+    /// Drone identity of a command's issuer, if its end-to-end signature
+    /// verifies under the issuer's registered key
+    pub fn verify_command(&self, message: &MeshMessage) -> Option<DroneId> {
+        let MeshMessageType::Command { signature, .. } = &message.payload else {
+            return None;
+        };
+        let digest = command_digest(message)?;
+        let issuer = member_id(message.source);
+        let public_key = self.keys.get_key(issuer).ok()?;
+        let signature = Signature::from_slice(signature).ok()?;
+        public_key.verify(&digest, &signature).ok()?;
+        Some(issuer)
+    }
+
+    /// Encrypt and sign a message for transmission
+    pub fn seal(&mut self, message: &MeshMessage) -> Result<MeshFrame> {
+        let mut plaintext = [0u8; MAX_MESSAGE_BYTES];
+        let encoded = postcard::to_slice(message, &mut plaintext)
+            .map_err(|_| SwarmError::SerializationError)?;
+
+        let aad = self.associated_data(self.local_id);
+        #[cfg(feature = "post-quantum")]
+        let sealed = if self.crypto.ml_dsa_public_key().is_some() {
+            Vec::from_slice(&self.crypto.encrypt_and_sign_dual(encoded, &aad)?)
+        } else {
+            Vec::from_slice(&self.crypto.encrypt_and_sign(encoded, &aad)?)
+        };
+        #[cfg(not(feature = "post-quantum"))]
+        let sealed = Vec::from_slice(&self.crypto.encrypt_and_sign(encoded, &aad)?);
+        Ok(MeshFrame::Secured {
+            sender: self.local_id,
+            sealed: sealed.map_err(|_| SwarmError::BufferFull)?,
+        })
+    }
+
+    /// Verify, decrypt and replay-check a received frame
+    pub fn open(&mut self, frame: &MeshFrame) -> core::result::Result<MeshMessage, FrameRejection> {
+        self.open_with(frame, None, None)
+    }
+
+    /// [`open`](Self::open), persisting replay windows to `store` so a reboot
+    /// cannot reopen them
+    pub fn open_durable(
+        &mut self,
+        frame: &MeshFrame,
+        store: &mut dyn ReplayStore,
+    ) -> core::result::Result<MeshMessage, FrameRejection> {
+        self.open_with(frame, Some(store), None)
+    }
+
+    /// Open a frame sealed under a key epoch too far ahead for
+    /// [`open`](Self::open), and move the network key to that epoch
+    ///
+    /// Lets a drone that missed rotations rejoin; see
+    /// [`CryptoContext::resync`]. Frames from the current, previous or next
+    /// epoch are rejected without any cryptographic work.
+    pub fn resync(
+        &mut self,
+        frame: &MeshFrame,
+        now_ms: u64,
+    ) -> core::result::Result<MeshMessage, FrameRejection> {
+        let MeshFrame::Secured { sealed, .. } = frame else {
+            return Err(FrameRejection::Unauthenticated);
+        };
+        let lookahead = self.crypto.epoch().saturating_add(1);
+        if sealed_epoch(sealed).is_none_or(|epoch| epoch <= lookahead) {
+            return Err(FrameRejection::AuthenticationFailed);
+        }
+        self.open_with(frame, None, Some(now_ms))
+    }
+
+    /// Open a frame, ratcheting to its epoch at `resync_ms` if given
+    fn open_with(
+        &mut self,
+        frame: &MeshFrame,
+        store: Option<&mut dyn ReplayStore>,
+        resync_ms: Option<u64>,
+    ) -> core::result::Result<MeshMessage, FrameRejection> {
+        let MeshFrame::Secured { sender, sealed } = frame else {
+            return Err(FrameRejection::Unauthenticated);
+        };
+        let public_key = *self
+            .keys
+            .get_key(member_id(*sender))
+            .map_err(|_| FrameRejection::UnknownSender)?;
+
+        let aad = self.associated_data(*sender);
+        #[cfg(feature = "post-quantum")]
+        let opened = match (self.keys.get_ml_dsa_key(member_id(*sender)), resync_ms) {
+            (Some(ml_dsa_key), Some(now_ms)) => {
+                self.crypto
+                    .resync_dual(sealed, &aad, &public_key, ml_dsa_key, now_ms)
+            }
+            (Some(ml_dsa_key), None) => {
+                self.crypto
+                    .verify_and_decrypt_dual(sealed, &aad, &public_key, ml_dsa_key)
+            }
+            (None, Some(now_ms)) => self.crypto.resync(sealed, &aad, &public_key, now_ms),
+            (None, None) => self.crypto.verify_and_decrypt(sealed, &aad, &public_key),
+        };
+        #[cfg(not(feature = "post-quantum"))]
+        let opened = match resync_ms {
+            Some(now_ms) => self.crypto.resync(sealed, &aad, &public_key, now_ms),
+            None => self.crypto.verify_and_decrypt(sealed, &aad, &public_key),
+        };
+        let plaintext = opened.map_err(|error| match error {
+            SwarmError::AuthenticationFailed => FrameRejection::AuthenticationFailed,
+            _ => FrameRejection::Malformed,
+        })?;
+
+        // Only checked once authentic, so forged frames cannot burn nonces
+        let mut counter = [0u8; 8];
+        counter.copy_from_slice(&sealed[..8]);
+        let counter = u64::from_le_bytes(counter);
+        let epoch = sealed_epoch(sealed).ok_or(FrameRejection::Malformed)?;
+        self.nonces
+            .retire_epochs_before(self.crypto.oldest_accepted_epoch());
+        let checked = match store {
+            Some(store) => self
+                .nonces
+                .check_durable(member_id(*sender), epoch, counter, store),
+            None => self.nonces.check(member_id(*sender), epoch, counter),
+        };
+        checked.map_err(|_| FrameRejection::Replay)?;
+
+        postcard::from_bytes(&plaintext).map_err(|_| FrameRejection::Malformed)
+    }
+
+    /// Associated data binding a frame to this mesh and its sender
+    fn associated_data(&self, sender: MeshNodeId) -> [u8; 7] {
+        let mut aad = [0u8; 7];
+        aad[..6].copy_from_slice(&self.mesh_id);
+        aad[6] = sender.as_u8();
+        aad
+    }
+}
+
+/// Key epoch carried in the nonce of a sealed body
+fn sealed_epoch(sealed: &[u8]) -> Option<u32> {
+    let epoch = sealed.get(8..12)?;
+    Some(u32::from_le_bytes(epoch.try_into().ok()?))
+}
+
+/// Key store identity of a mesh node
+fn member_id(node: MeshNodeId) -> DroneId {
+    DroneId::new(node.as_u8() as u64)
+}
+
+/// Digest a command's issuer signs: issuer, message ID and payload
+fn command_digest(message: &MeshMessage) -> Option<[u8; 32]> {
+    let MeshMessageType::Command {
+        command_id,
+        target,
+        action,
+        ..
+    } = &message.payload
+    else {
+        return None;
+    };
+    let mut buf = [0u8; MAX_MESSAGE_BYTES];
+    let signed = (message.source, message.msg_id, command_id, target, action);
+    let encoded = postcard::to_slice(&signed, &mut buf).ok()?;
+    let mut hasher = blake3::Hasher::new_derive_key(COMMAND_CONTEXT);
+    hasher.update(encoded);
+    Some(*hasher.finalize().as_bytes())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::mesh_protocol::{CommandAction, CommandTarget};
+
+    const MESH_ID: [u8; 6] = [0x44, 0x52, 0x4F, 0x4E, 0x45, 0x53];
+
+    fn pair() -> (MeshSecurity, MeshSecurity) {
+        let mut a = MeshSecurity::new(MeshNodeId::new(1), MESH_ID, [7; 32], [1; 32]);
+        let mut b = MeshSecurity::new(MeshNodeId::new(2), MESH_ID, [7; 32], [2; 32]);
+        a.add_member(MeshNodeId::new(2), *b.public_key()).unwrap();
+        b.add_member(MeshNodeId::new(1), *a.public_key()).unwrap();
+        (a, b)
+    }
+
+    fn stop() -> MeshMessage {
+        MeshMessage::command(
+            MeshNodeId::new(1),
+            CommandTarget::Broadcast,
+            CommandAction::EmergencyStop,
+            1000,
+        )
+    }
+
+    #[test]
+    fn test_rotation_keeps_replay_state() {
+        let (mut a, mut b) = pair();
+        let before = a.seal(&stop()).unwrap();
+        assert!(b.open(&before).is_ok());
+
+        a.crypto_mut().advance_epoch(1, 0).unwrap();
+        b.crypto_mut().advance_epoch(1, 0).unwrap();
+        // Counters keep rising across epochs, so new frames are not replays
+        assert!(b.open(&a.seal(&stop()).unwrap()).is_ok());
+        assert_eq!(b.open(&before).unwrap_err(), FrameRejection::Replay);
+    }
+
+    #[test]
+    fn test_seal_open_roundtrip() {
+        let (mut a, mut b) = pair();
+        let frame = a.seal(&stop()).unwrap();
+        let decoded = MeshFrame::from_bytes(&frame.to_bytes().unwrap()).unwrap();
+
+        let msg = b.open(&decoded).unwrap();
+        assert_eq!(msg.source, MeshNodeId::new(1));
+        assert!(msg.verify_checksum());
+    }
+
+    #[test]
+    fn test_plain_frame_rejected() {
+        let (_, mut b) = pair();
+        let rejection = b.open(&MeshFrame::Plain(stop())).unwrap_err();
+        assert_eq!(rejection, FrameRejection::Unauthenticated);
+    }
+
+    #[test]
+    fn test_tampered_and_relabelled_frames_rejected() {
+        let (mut a, mut b) = pair();
+        let MeshFrame::Secured { mut sealed, .. } = a.seal(&stop()).unwrap() else {
+            panic!("expected a sealed frame");
+        };
+
+        // Claiming to be drone 2 breaks the associated data
+        let relabelled = MeshFrame::Secured {
+            sender: MeshNodeId::new(2),
+            sealed: sealed.clone(),
+        };
+        b.add_member(MeshNodeId::new(2), *a.public_key()).unwrap();
+        assert_eq!(
+            b.open(&relabelled).unwrap_err(),
+            FrameRejection::AuthenticationFailed
+        );
+
+        sealed[20] ^= 0x01;
+        let tampered = MeshFrame::Secured {
+            sender: MeshNodeId::new(1),
+            sealed,
+        };
+        assert_eq!(
+            b.open(&tampered).unwrap_err(),
+            FrameRejection::AuthenticationFailed
+        );
+    }
+
+    #[test]
+    fn test_unknown_sender_and_foreign_mesh_rejected() {
+        let (mut a, _) = pair();
+        let frame = a.seal(&stop()).unwrap();
+
+        let mut stranger = MeshSecurity::new(MeshNodeId::new(3), MESH_ID, [7; 32], [3; 32]);
+        assert_eq!(
+            stranger.open(&frame).unwrap_err(),
+            FrameRejection::UnknownSender
+        );
+
+        let mut other_mesh = MeshSecurity::new(MeshNodeId::new(2), [0; 6], [7; 32], [2; 32]);
+        other_mesh
+            .add_member(MeshNodeId::new(1), *a.public_key())
+            .unwrap();
+        assert_eq!(
+            other_mesh.open(&frame).unwrap_err(),
+            FrameRejection::AuthenticationFailed
+        );
+    }
+
+    #[test]
+    fn test_replay_rejected() {
+        let (mut a, mut b) = pair();
+        let first = a.seal(&stop()).unwrap();
+        let second = a.seal(&stop()).unwrap();
+
+        assert!(b.open(&first).is_ok());
+        assert_eq!(b.open(&first).unwrap_err(), FrameRejection::Replay);
+        assert!(b.open(&second).is_ok());
+        assert_eq!(b.open(&first).unwrap_err(), FrameRejection::Replay);
+    }
+
+    #[test]
+    fn test_reordered_frames_accepted() {
+        let (mut a, mut b) = pair();
+        let first = a.seal(&stop()).unwrap();
+        let second = a.seal(&stop()).unwrap();
+
+        // Overtaken on another path
+        assert!(b.open(&second).is_ok());
+        assert!(b.open(&first).is_ok());
+        assert_eq!(b.open(&first).unwrap_err(), FrameRejection::Replay);
+    }
+
+    #[test]
+    fn test_durable_replay_window_survives_reboot() {
+        struct Flash(Option<(DroneId, u32, u64)>);
+        impl ReplayStore for Flash {
+            fn reserve(&mut self, peer: DroneId, epoch: u32, reserved: u64) -> Result<()> {
+                self.0 = Some((peer, epoch, reserved));
+                Ok(())
+            }
+        }
+
+        let (mut a, mut b) = pair();
+        let mut flash = Flash(None);
+        let frame = a.seal(&stop()).unwrap();
+        assert!(b.open_durable(&frame, &mut flash).is_ok());
+
+        // Reboot: fresh state, reservation reloaded from flash
+        let (_, mut rebooted) = pair();
+        let (peer, epoch, reserved) = flash.0.unwrap();
+        rebooted.nonces.restore(peer, epoch, reserved).unwrap();
+        assert_eq!(rebooted.open(&frame).unwrap_err(), FrameRejection::Replay);
+    }
+
+    #[test]
+    fn test_removed_member_rejected() {
+        let (mut a, mut b) = pair();
+        b.remove_member(MeshNodeId::new(1)).unwrap();
+        assert!(!b.is_member(MeshNodeId::new(1)));
+
+        let frame = a.seal(&stop()).unwrap();
+        assert_eq!(b.open(&frame).unwrap_err(), FrameRejection::UnknownSender);
+    }
+
+    #[test]
+    fn test_certified_membership() {
+        use crate::certificate::{Capabilities, Provisioner, TrustAnchor};
+
+        let mut ca = Provisioner::new([9; 32]);
+        let (a, mut b) = pair();
+        let cert = ca
+            .certify(
+                DroneId::new(1),
+                a.public_key(),
+                SecurityLevel::Internal,
+                Capabilities::NONE,
+                0,
+                60_000,
+            )
+            .unwrap();
+        b.remove_member(MeshNodeId::new(1)).unwrap();
+
+        assert_eq!(
+            b.admit_member(MeshNodeId::new(1), &cert, 1000).unwrap_err(),
+            SwarmError::ConfigError
+        );
+        b.set_trust_anchor(TrustAnchor::new(ca.root_key()));
+        // The certificate names drone 1, not 3
+        assert_eq!(
+            b.admit_member(MeshNodeId::new(3), &cert, 1000).unwrap_err(),
+            SwarmError::InvalidDroneId
+        );
+        b.admit_member(MeshNodeId::new(1), &cert, 1000).unwrap();
+        assert!(b.is_member(MeshNodeId::new(1)));
+
+        // Members added without a certificate are unaffected
+        b.add_member(MeshNodeId::new(4), *a.public_key()).unwrap();
+
+        let list = ca.revoke(cert.serial, 2000).unwrap();
+        assert_eq!(b.apply_revocations(&list).unwrap(), 1);
+        assert!(!b.is_member(MeshNodeId::new(1)));
+        assert!(b.is_member(MeshNodeId::new(4)));
+        assert_eq!(b.apply_revocations(&list).unwrap(), 0);
+
+        // Readmitted under a new certificate until that one expires
+        let renewed = ca
+            .certify(
+                DroneId::new(1),
+                a.public_key(),
+                SecurityLevel::Internal,
+                Capabilities::NONE,
+                0,
+                5000,
+            )
+            .unwrap();
+        b.admit_member(MeshNodeId::new(1), &renewed, 3000).unwrap();
+        assert_eq!(b.expire_members(5000).unwrap(), 0);
+        assert_eq!(b.expire_members(5001).unwrap(), 1);
+        assert!(!b.is_member(MeshNodeId::new(1)));
+        assert!(b.is_member(MeshNodeId::new(4)));
+    }
+
+    #[cfg(feature = "post-quantum")]
+    #[test]
+    fn test_dual_signed_frame_through_fragmenter() {
+        use crate::fragmentation::{FragmentConfig, FragmentationLayer};
+        use crate::transport::MAX_FRAME_SIZE;
+
+        let (mut a, mut b) = pair();
+        a.crypto_mut().enable_dual_signatures(&[21; 32]);
+        let ml_dsa_key = *a.crypto.ml_dsa_public_key().unwrap();
+        b.add_member_ml_dsa_key(MeshNodeId::new(1), ml_dsa_key)
+            .unwrap();
+
+        let bytes = a.seal(&stop()).unwrap().to_bytes().unwrap();
+        assert!(bytes.len() > MAX_FRAME_SIZE);
+
+        // ESP-NOW sized fragments
+        let config = FragmentConfig {
+            mtu: 250,
+            ..FragmentConfig::default()
+        };
+        let mut tx = FragmentationLayer::new(DroneId::new(1), config);
+        let mut rx = FragmentationLayer::new(DroneId::new(2), config);
+        tx.send(DroneId::new(2), &bytes).unwrap();
+        let mut reassembled = None;
+        while let Some((_, fragment)) = tx.poll_transmit() {
+            if let Some((_, payload)) = rx.receive(fragment).unwrap() {
+                reassembled = Some(payload);
+            }
+        }
+
+        let frame = MeshFrame::from_bytes(&reassembled.unwrap()).unwrap();
+        assert_eq!(b.open(&frame).unwrap().source, MeshNodeId::new(1));
+    }
+
+    #[cfg(feature = "post-quantum")]
+    #[test]
+    fn test_missing_ml_dsa_signature_rejected() {
+        let (mut a, mut b) = pair();
+        let ml_dsa_key = {
+            let mut pq = CryptoContext::with_keys([7; 32], [1; 32]);
+            pq.enable_dual_signatures(&[21; 32]);
+            *pq.ml_dsa_public_key().unwrap()
+        };
+        b.add_member_ml_dsa_key(MeshNodeId::new(1), ml_dsa_key)
+            .unwrap();
+
+        // Ed25519 alone no longer suffices once the member dual-signs
+        let frame = a.seal(&stop()).unwrap();
+        assert_eq!(
+            b.open(&frame).unwrap_err(),
+            FrameRejection::AuthenticationFailed
+        );
+    }
+}
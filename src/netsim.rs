@@ -0,0 +1,1120 @@
+//! Deterministic discrete-event network simulator
+//!
+//! Hosts many nodes in one process and carries their traffic over a virtual
+//! radio channel with per-link packet loss, latency, jitter, bandwidth and
+//! distance-based range. All randomness comes from a seeded [`SimRng`] and the
+//! nodes run against the virtual clock (see
+//! [`set_virtual_time_us`](crate::time_abstraction::set_virtual_time_us)), so a
+//! scenario replays identically for the same seed.
+//!
+//! Adapters are provided for the swarm's protocol engines:
+//! - [`SimMeshNode`] - ESP32 mesh node ([`MeshNode`])
+//! - [`SimMeshNetwork`] - routed mesh network ([`MeshNetwork`]) over [`SimTransport`]
+//! - [`SimConsensus`] - Raft engine ([`ConsensusEngine`])
+//! - [`SimDtnNode`] - store-carry-forward bundle node ([`DtnNode`])
+//! - [`SimFragmentNode`] - fragmenting endpoint ([`FragmentationLayer`])
+//! - [`SimTimeSyncNode`] - time sync service ([`TimeSyncNode`]) on a drifting clock
+//!
+//! Other protocols plug in by implementing [`SimNode`].
+//!
+//! # Example
+//! ```rust
+//! use drone_swarm_system::netsim::*;
+//! use drone_swarm_system::{DroneId, Position};
+//!
+//! let mut sim = NetworkSimulator::new(SimConfig { seed: 42, ..SimConfig::default() });
+//! for i in 0..3 {
+//!     let position = Position { x: i as f32 * 50.0, y: 0.0, z: 10.0 };
+//!     sim.add_node_with(position, |index| SimMeshNetwork::new(DroneId::new(i + 1), index));
+//! }
+//!
+//! sim.run_for(2000);
+//! assert_eq!(sim.node(0).network.neighbor_count(), 2);
+//! ```
+
+use crate::config::{NetworkConfig, RoutingProtocol};
+use crate::consensus::{ConsensusEngine, ConsensusMessage};
+use crate::dtn::{Bundle, DtnMessage, DtnNode};
+use crate::esp32_mesh::{MeshNode, ProcessResult};
+use crate::fragmentation::{FragmentMessage, FragmentationLayer};
+use crate::mesh_channel::ChannelEvent;
+use crate::mesh_protocol::MeshMessage;
+use crate::network::MeshNetwork;
+use crate::time_abstraction::{get_time_us, set_virtual_time_us};
+use crate::time_sync::{SyncBeacon, TimeSyncNode};
+use crate::transport::{Transport, MAX_FRAME_SIZE};
+use crate::types::*;
+use serde::Serialize;
+use std::collections::{BTreeMap, BTreeSet, VecDeque};
+use std::vec::Vec;
+
+/// Index of a node inside the simulator
+pub type NodeIndex = usize;
+
+/// Addressing of an outgoing message
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Destination {
+    /// Every node in range
+    Broadcast,
+    /// A single node
+    Unicast(NodeIndex),
+}
+
+/// Radio characteristics of a link
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct LinkModel {
+    /// Probability that a frame is lost (0.0 - 1.0)
+    pub loss_rate: f32,
+    /// Base one-way latency (ms)
+    pub latency_ms: u32,
+    /// Maximum additional random delay (ms)
+    pub jitter_ms: u32,
+    /// Link bandwidth in bits per second (0 = unlimited)
+    pub bandwidth_bps: u32,
+    /// Maximum distance at which frames are received (meters)
+    pub range_m: f32,
+}
+
+impl LinkModel {
+    /// Lossless link with no delay and unlimited range
+    pub fn ideal() -> Self {
+        Self {
+            loss_rate: 0.0,
+            latency_ms: 0,
+            jitter_ms: 0,
+            bandwidth_bps: 0,
+            range_m: f32::INFINITY,
+        }
+    }
+}
+
+impl Default for LinkModel {
+    /// Typical 802.11 mesh link between airborne drones
+    fn default() -> Self {
+        Self {
+            loss_rate: 0.01,
+            latency_ms: 5,
+            jitter_ms: 2,
+            bandwidth_bps: 1_000_000,
+            range_m: 300.0,
+        }
+    }
+}
+
+/// Simulator configuration
+#[derive(Debug, Clone)]
+pub struct SimConfig {
+    /// RNG seed (same seed = same run)
+    pub seed: u64,
+    /// Interval between node ticks (ms)
+    pub tick_interval_ms: u32,
+    /// Link model used unless overridden with [`NetworkSimulator::set_link`]
+    pub default_link: LinkModel,
+}
+
+impl Default for SimConfig {
+    fn default() -> Self {
+        Self {
+            seed: 0,
+            tick_interval_ms: 10,
+            default_link: LinkModel::default(),
+        }
+    }
+}
+
+/// Simulation statistics (one entry per frame and receiver)
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct SimStats {
+    /// Frames offered to the channel
+    pub frames_sent: u64,
+    /// Frames handed to the receiving node
+    pub frames_delivered: u64,
+    /// Frames lost to random packet loss
+    pub dropped_loss: u64,
+    /// Frames whose receiver was out of range
+    pub dropped_range: u64,
+    /// Frames blocked by a network partition
+    pub dropped_partition: u64,
+    /// Frames whose receiver was offline
+    pub dropped_offline: u64,
+}
+
+/// Seedable pseudo-random generator (xorshift64*) for reproducible scenarios
+#[derive(Debug, Clone)]
+pub struct SimRng {
+    state: u64,
+}
+
+impl SimRng {
+    /// Create a generator from a seed
+    pub fn new(seed: u64) -> Self {
+        // SplitMix64 scramble so that small seeds still give well-mixed state
+        let mut z = seed.wrapping_add(0x9E3779B97F4A7C15);
+        z = (z ^ (z >> 30)).wrapping_mul(0xBF58476D1CE4E5B9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94D049BB133111EB);
+        Self {
+            state: (z ^ (z >> 31)) | 1,
+        }
+    }
+
+    /// Generate a random u64
+    pub fn next_u64(&mut self) -> u64 {
+        let mut x = self.state;
+        x ^= x >> 12;
+        x ^= x << 25;
+        x ^= x >> 27;
+        self.state = x;
+        x.wrapping_mul(0x2545F4914F6CDD1D)
+    }
+
+    /// Generate a random f32 in range [0.0, 1.0)
+    pub fn next_f32(&mut self) -> f32 {
+        (self.next_u64() >> 40) as f32 * (1.0 / 16777216.0)
+    }
+
+    /// Generate a random integer in range [0, bound]
+    pub fn next_inclusive(&mut self, bound: u32) -> u32 {
+        (self.next_u64() % (bound as u64 + 1)) as u32
+    }
+}
+
+/// Messages produced by a node during one callback
+pub struct Outbox<M> {
+    messages: Vec<(Destination, M)>,
+}
+
+impl<M> Outbox<M> {
+    fn new() -> Self {
+        Self {
+            messages: Vec::new(),
+        }
+    }
+
+    /// Send a message to every node in range
+    pub fn broadcast(&mut self, message: M) {
+        self.messages.push((Destination::Broadcast, message));
+    }
+
+    /// Send a message to a single node
+    pub fn unicast(&mut self, to: NodeIndex, message: M) {
+        self.messages.push((Destination::Unicast(to), message));
+    }
+}
+
+/// A node that can be hosted by the [`NetworkSimulator`]
+pub trait SimNode {
+    /// Message type carried over the virtual channel
+    type Message: Clone;
+
+    /// Periodic processing, called once per simulator tick
+    fn tick(&mut self, now_ms: u64, outbox: &mut Outbox<Self::Message>);
+
+    /// Handle a message delivered from node `from`
+    fn receive(
+        &mut self,
+        from: NodeIndex,
+        message: Self::Message,
+        rssi: i8,
+        now_ms: u64,
+        outbox: &mut Outbox<Self::Message>,
+    );
+
+    /// On-air size of a message in bytes (used for bandwidth modelling)
+    fn message_size(message: &Self::Message) -> usize;
+
+    /// Called when the simulator moves the node
+    fn set_position(&mut self, _position: Position) {}
+}
+
+struct HostedNode<N> {
+    node: N,
+    position: Position,
+    online: bool,
+}
+
+struct Delivery<M> {
+    from: NodeIndex,
+    to: NodeIndex,
+    rssi: i8,
+    message: M,
+}
+
+/// Discrete-event simulator hosting a swarm of [`SimNode`]s
+///
+/// While a simulator is alive it drives the calling thread's virtual clock,
+/// so only one simulator should run per thread at a time.
+pub struct NetworkSimulator<N: SimNode> {
+    config: SimConfig,
+    rng: SimRng,
+    /// Current virtual time (us)
+    now_us: u64,
+    /// Time of the next node tick (us)
+    next_tick_us: u64,
+    nodes: Vec<HostedNode<N>>,
+    /// Per-link overrides, keyed by (lower index, higher index)
+    links: BTreeMap<(NodeIndex, NodeIndex), LinkModel>,
+    /// Links cut by a partition, keyed by (lower index, higher index)
+    blocked: BTreeSet<(NodeIndex, NodeIndex)>,
+    /// Time each directed link finishes its current transmission (us)
+    link_busy_until: BTreeMap<(NodeIndex, NodeIndex), u64>,
+    /// Pending deliveries ordered by (arrival time, sequence)
+    events: BTreeMap<(u64, u64), Delivery<N::Message>>,
+    event_sequence: u64,
+    stats: SimStats,
+}
+
+impl<N: SimNode> NetworkSimulator<N> {
+    /// Create an empty simulator at virtual time zero
+    pub fn new(config: SimConfig) -> Self {
+        set_virtual_time_us(Some(0));
+        Self {
+            rng: SimRng::new(config.seed),
+            config,
+            now_us: 0,
+            next_tick_us: 0,
+            nodes: Vec::new(),
+            links: BTreeMap::new(),
+            blocked: BTreeSet::new(),
+            link_busy_until: BTreeMap::new(),
+            events: BTreeMap::new(),
+            event_sequence: 0,
+            stats: SimStats::default(),
+        }
+    }
+
+    /// Add a node at `position` and return its index
+    pub fn add_node(&mut self, mut node: N, position: Position) -> NodeIndex {
+        node.set_position(position);
+        self.nodes.push(HostedNode {
+            node,
+            position,
+            online: true,
+        });
+        self.nodes.len() - 1
+    }
+
+    /// Add a node built from the index it will be hosted at
+    pub fn add_node_with<F>(&mut self, position: Position, build: F) -> NodeIndex
+    where
+        F: FnOnce(NodeIndex) -> N,
+    {
+        let node = build(self.nodes.len());
+        self.add_node(node, position)
+    }
+
+    /// Number of hosted nodes
+    pub fn node_count(&self) -> usize {
+        self.nodes.len()
+    }
+
+    /// Get a hosted node
+    pub fn node(&self, index: NodeIndex) -> &N {
+        &self.nodes[index].node
+    }
+
+    /// Get a hosted node mutably
+    pub fn node_mut(&mut self, index: NodeIndex) -> &mut N {
+        &mut self.nodes[index].node
+    }
+
+    /// Get a node's position
+    pub fn position(&self, index: NodeIndex) -> Position {
+        self.nodes[index].position
+    }
+
+    /// Move a node
+    pub fn set_position(&mut self, index: NodeIndex, position: Position) {
+        let hosted = &mut self.nodes[index];
+        hosted.position = position;
+        hosted.node.set_position(position);
+    }
+
+    /// Crash (`false`) or restart (`true`) a node
+    ///
+    /// Offline nodes are not ticked and lose every frame addressed to them.
+    pub fn set_online(&mut self, index: NodeIndex, online: bool) {
+        self.nodes[index].online = online;
+    }
+
+    /// Check if a node is online
+    pub fn is_online(&self, index: NodeIndex) -> bool {
+        self.nodes[index].online
+    }
+
+    /// Override the link model between two nodes (both directions)
+    pub fn set_link(&mut self, a: NodeIndex, b: NodeIndex, link: LinkModel) {
+        self.links.insert(link_key(a, b), link);
+    }
+
+    /// Link model in effect between two nodes
+    pub fn link(&self, a: NodeIndex, b: NodeIndex) -> LinkModel {
+        self.links
+            .get(&link_key(a, b))
+            .copied()
+            .unwrap_or(self.config.default_link)
+    }
+
+    /// Split the network so that `group` cannot reach any other node
+    pub fn partition(&mut self, group: &[NodeIndex]) {
+        for a in 0..self.nodes.len() {
+            for b in (a + 1)..self.nodes.len() {
+                if group.contains(&a) != group.contains(&b) {
+                    self.blocked.insert((a, b));
+                }
+            }
+        }
+    }
+
+    /// Remove all partitions
+    pub fn heal(&mut self) {
+        self.blocked.clear();
+    }
+
+    /// Inject a message on behalf of node `from`
+    pub fn send(&mut self, from: NodeIndex, destination: Destination, message: N::Message) {
+        self.transmit(from, destination, message);
+    }
+
+    /// Current virtual time (ms)
+    pub fn now_ms(&self) -> u64 {
+        self.now_us / 1000
+    }
+
+    /// Get simulation statistics
+    pub fn stats(&self) -> &SimStats {
+        &self.stats
+    }
+
+    /// Number of frames still in flight
+    pub fn pending_frames(&self) -> usize {
+        self.events.len()
+    }
+
+    /// Advance virtual time by `duration_ms`
+    pub fn run_for(&mut self, duration_ms: u64) {
+        let end_us = self.now_us + duration_ms * 1000;
+        while self.step_before(end_us) {}
+        self.set_time(end_us);
+    }
+
+    /// Advance until `condition` holds or `max_duration_ms` elapses
+    ///
+    /// Returns whether the condition was met.
+    pub fn run_until<F>(&mut self, max_duration_ms: u64, mut condition: F) -> bool
+    where
+        F: FnMut(&Self) -> bool,
+    {
+        let end_us = self.now_us + max_duration_ms * 1000;
+        loop {
+            if condition(self) {
+                return true;
+            }
+            if !self.step_before(end_us) {
+                self.set_time(end_us);
+                return condition(self);
+            }
+        }
+    }
+
+    /// Process the next event if it happens no later than `end_us`
+    fn step_before(&mut self, end_us: u64) -> bool {
+        let next_event_us = self.events.keys().next().map(|(time, _)| *time);
+
+        match next_event_us {
+            // Deliveries scheduled at the same instant as a tick go first
+            Some(time) if time <= self.next_tick_us => {
+                if time > end_us {
+                    return false;
+                }
+                self.set_time(time);
+                if let Some((_, delivery)) = self.events.pop_first() {
+                    self.deliver(delivery);
+                }
+            }
+            _ => {
+                if self.next_tick_us > end_us {
+                    return false;
+                }
+                self.set_time(self.next_tick_us);
+                self.tick_nodes();
+                self.next_tick_us += self.config.tick_interval_ms.max(1) as u64 * 1000;
+            }
+        }
+        true
+    }
+
+    fn set_time(&mut self, time_us: u64) {
+        self.now_us = time_us;
+        set_virtual_time_us(Some(time_us));
+    }
+
+    fn tick_nodes(&mut self) {
+        let now_ms = self.now_ms();
+        for index in 0..self.nodes.len() {
+            if !self.nodes[index].online {
+                continue;
+            }
+            let mut outbox = Outbox::new();
+            self.nodes[index].node.tick(now_ms, &mut outbox);
+            self.flush(index, outbox);
+        }
+    }
+
+    fn deliver(&mut self, delivery: Delivery<N::Message>) {
+        if !self.nodes[delivery.to].online {
+            self.stats.dropped_offline += 1;
+            return;
+        }
+        self.stats.frames_delivered += 1;
+
+        let now_ms = self.now_ms();
+        let mut outbox = Outbox::new();
+        self.nodes[delivery.to].node.receive(
+            delivery.from,
+            delivery.message,
+            delivery.rssi,
+            now_ms,
+            &mut outbox,
+        );
+        self.flush(delivery.to, outbox);
+    }
+
+    fn flush(&mut self, from: NodeIndex, outbox: Outbox<N::Message>) {
+        for (destination, message) in outbox.messages {
+            self.transmit(from, destination, message);
+        }
+    }
+
+    /// Put a frame on the channel, scheduling one delivery per receiver
+    fn transmit(&mut self, from: NodeIndex, destination: Destination, message: N::Message) {
+        let size_bits = N::message_size(&message) as u64 * 8;
+
+        match destination {
+            Destination::Unicast(to) => self.schedule(from, to, size_bits, message),
+            Destination::Broadcast => {
+                for to in 0..self.nodes.len() {
+                    self.schedule(from, to, size_bits, message.clone());
+                }
+            }
+        }
+    }
+
+    /// Apply the link model to one receiver and queue the delivery
+    fn schedule(&mut self, from: NodeIndex, to: NodeIndex, size_bits: u64, message: N::Message) {
+        if to == from || to >= self.nodes.len() {
+            return;
+        }
+        self.stats.frames_sent += 1;
+
+        if self.blocked.contains(&link_key(from, to)) {
+            self.stats.dropped_partition += 1;
+            return;
+        }
+
+        let link = self.link(from, to);
+        let distance = self.nodes[from]
+            .position
+            .distance_to(&self.nodes[to].position);
+        if distance > link.range_m {
+            self.stats.dropped_range += 1;
+            return;
+        }
+
+        // Serialization delay queues frames behind earlier ones on the same link
+        let busy_until = self.link_busy_until.entry((from, to)).or_insert(0);
+        let start_us = (*busy_until).max(self.now_us);
+        let airtime_us = (size_bits * 1_000_000)
+            .checked_div(link.bandwidth_bps as u64)
+            .unwrap_or(0);
+        *busy_until = start_us + airtime_us;
+
+        // Lost frames still occupy the link for their airtime
+        if self.rng.next_f32() < link.loss_rate {
+            self.stats.dropped_loss += 1;
+            return;
+        }
+
+        let jitter_us = if link.jitter_ms > 0 {
+            self.rng.next_inclusive(link.jitter_ms * 1000) as u64
+        } else {
+            0
+        };
+        let arrival_us = start_us + airtime_us + link.latency_ms as u64 * 1000 + jitter_us;
+
+        self.event_sequence += 1;
+        self.events.insert(
+            (arrival_us, self.event_sequence),
+            Delivery {
+                from,
+                to,
+                rssi: rssi_at(distance),
+                message,
+            },
+        );
+    }
+}
+
+impl<N: SimNode> Drop for NetworkSimulator<N> {
+    fn drop(&mut self) {
+        set_virtual_time_us(None);
+    }
+}
+
+fn link_key(a: NodeIndex, b: NodeIndex) -> (NodeIndex, NodeIndex) {
+    if a < b {
+        (a, b)
+    } else {
+        (b, a)
+    }
+}
+
+/// Received signal strength for a distance (log-distance path loss, 2.4 GHz)
+fn rssi_at(distance_m: f32) -> i8 {
+    let rssi = -40.0 - 25.0 * libm::log10f(distance_m.max(1.0));
+    rssi.clamp(-120.0, 0.0) as i8
+}
+
+/// Encoded size of a serde message, as postcard would put it on air
+fn encoded_size<T: Serialize>(message: &T) -> usize {
+    let mut buf = [0u8; MAX_FRAME_SIZE * 2];
+    postcard::to_slice(message, &mut buf)
+        .map(|frame| frame.len())
+        .unwrap_or(buf.len())
+}
+
+/// Network address used by [`SimTransport`] for a node index
+pub fn sim_address(index: NodeIndex) -> NetworkAddress {
+    // fd00::/8 unique-local prefix with the node index in the low bytes
+    let mut addr = [0u8; 16];
+    addr[0] = 0xFD;
+    addr[8..].copy_from_slice(&(index as u64).to_be_bytes());
+    NetworkAddress::new(addr, 0)
+}
+
+/// Node index encoded in a [`sim_address`]
+pub fn sim_index(address: NetworkAddress) -> Option<NodeIndex> {
+    if address.addr[0] != 0xFD || address.addr[1..8] != [0; 7] {
+        return None;
+    }
+    let mut index = [0u8; 8];
+    index.copy_from_slice(&address.addr[8..]);
+    Some(u64::from_be_bytes(index) as NodeIndex)
+}
+
+/// ESP32 mesh node hosted by the simulator
+///
+/// Every message is sent as a broadcast on the node's current WiFi channel,
+/// matching ESP-NOW behaviour; receivers tuned to another channel miss it and
+/// [`MeshNode::process_message`] filters by destination.
+pub struct SimMeshNode {
+    /// The hosted node
+    pub node: MeshNode,
+    /// Commands and emergencies received, in arrival order
+    pub events: Vec<ProcessResult>,
+    /// Share of frames destroyed by a jammer near this node, per channel
+    pub jamming: BTreeMap<u8, f32>,
+    jam_rng: SimRng,
+}
+
+impl SimMeshNode {
+    /// Wrap a mesh node
+    pub fn new(node: MeshNode) -> Self {
+        let jam_rng = SimRng::new(node.node_id().as_u8() as u64);
+        Self {
+            node,
+            events: Vec::new(),
+            jamming: BTreeMap::new(),
+            jam_rng,
+        }
+    }
+
+    fn drain_tx(&mut self, now_ms: u64, outbox: &mut Outbox<(u8, MeshMessage)>) {
+        let channel = self.node.current_channel(now_ms);
+        while let Some(msg) = self.node.get_next_tx_message() {
+            outbox.broadcast((channel, msg));
+        }
+    }
+}
+
+impl SimNode for SimMeshNode {
+    type Message = (u8, MeshMessage);
+
+    fn tick(&mut self, now_ms: u64, outbox: &mut Outbox<(u8, MeshMessage)>) {
+        self.node.update(now_ms).ok();
+        self.drain_tx(now_ms, outbox);
+    }
+
+    fn receive(
+        &mut self,
+        _from: NodeIndex,
+        (channel, message): (u8, MeshMessage),
+        rssi: i8,
+        now_ms: u64,
+        outbox: &mut Outbox<(u8, MeshMessage)>,
+    ) {
+        if channel != self.node.current_channel(now_ms) {
+            return;
+        }
+        let jam_rate = self.jamming.get(&channel).copied().unwrap_or(0.0);
+        if jam_rate > 0.0 && self.jam_rng.next_f32() < jam_rate {
+            self.node
+                .record_channel_event(channel, ChannelEvent::Corrupted);
+            return;
+        }
+
+        if let Ok(result) = self.node.process_message(message, rssi, now_ms) {
+            if matches!(
+                result,
+                ProcessResult::Command(_) | ProcessResult::Emergency { .. }
+            ) {
+                self.events.push(result);
+            }
+        }
+        self.drain_tx(now_ms, outbox);
+    }
+
+    fn message_size((_, message): &(u8, MeshMessage)) -> usize {
+        encoded_size(message)
+    }
+
+    fn set_position(&mut self, position: Position) {
+        self.node.set_position([position.x, position.y, position.z]);
+    }
+}
+
+/// [`Transport`] endpoint backed by the simulator's virtual channel
+pub struct SimTransport {
+    address: NetworkAddress,
+    outbox: Vec<(Option<NetworkAddress>, Vec<u8>)>,
+    inbox: VecDeque<(NetworkAddress, Vec<u8>)>,
+}
+
+impl SimTransport {
+    /// Create the endpoint for the node hosted at `index`
+    pub fn new(index: NodeIndex) -> Self {
+        Self {
+            address: sim_address(index),
+            outbox: Vec::new(),
+            inbox: VecDeque::new(),
+        }
+    }
+
+    /// Address this endpoint is bound to
+    pub fn local_address(&self) -> NetworkAddress {
+        self.address
+    }
+}
+
+impl Transport for SimTransport {
+    fn send_to(&mut self, destination: NetworkAddress, frame: &[u8]) -> Result<()> {
+        if frame.len() > MAX_FRAME_SIZE {
+            return Err(SwarmError::BufferFull);
+        }
+        self.outbox.push((Some(destination), frame.to_vec()));
+        Ok(())
+    }
+
+    fn broadcast(&mut self, frame: &[u8]) -> Result<()> {
+        if frame.len() > MAX_FRAME_SIZE {
+            return Err(SwarmError::BufferFull);
+        }
+        self.outbox.push((None, frame.to_vec()));
+        Ok(())
+    }
+
+    fn poll_recv(&mut self, buf: &mut [u8]) -> Result<Option<(usize, NetworkAddress)>> {
+        match self.inbox.pop_front() {
+            Some((from, frame)) => {
+                let dest = buf.get_mut(..frame.len()).ok_or(SwarmError::BufferFull)?;
+                dest.copy_from_slice(&frame);
+                Ok(Some((frame.len(), from)))
+            }
+            None => Ok(None),
+        }
+    }
+}
+
+/// Routed mesh network node hosted by the simulator
+///
+/// Broadcasts a Hello every `hello_interval_ms`, runs route maintenance
+/// every tick, and collects the data payloads and reassembled messages
+/// addressed to it.
+pub struct SimMeshNetwork {
+    /// The hosted network stack
+    pub network: MeshNetwork<SimTransport>,
+    /// Data payloads delivered to this node, with their source
+    pub delivered: Vec<(DroneId, heapless::Vec<u8, 1024>)>,
+    /// Messages reassembled from fragments, with their source
+    pub reassembled: Vec<(DroneId, Vec<u8>)>,
+    /// Interval between Hello broadcasts (ms)
+    pub hello_interval_ms: u64,
+    position: Position,
+    last_hello_ms: Option<u64>,
+}
+
+impl SimMeshNetwork {
+    /// Create the network stack for drone `id` hosted at `index`
+    pub fn new(id: DroneId, index: NodeIndex) -> Self {
+        Self::with_routing(id, index, RoutingProtocol::AdaptiveMesh)
+    }
+
+    /// Create the network stack for drone `id` using a specific routing protocol
+    pub fn with_routing(id: DroneId, index: NodeIndex, protocol: RoutingProtocol) -> Self {
+        let mut config = NetworkConfig::new(sim_address(index));
+        config.routing_protocol = protocol;
+        Self {
+            network: MeshNetwork::with_config(id, SimTransport::new(index), &config),
+            delivered: Vec::new(),
+            reassembled: Vec::new(),
+            hello_interval_ms: 1000,
+            position: Position {
+                x: 0.0,
+                y: 0.0,
+                z: 0.0,
+            },
+            last_hello_ms: None,
+        }
+    }
+
+    fn poll_and_flush(&mut self, outbox: &mut Outbox<Vec<u8>>) {
+        loop {
+            while let Ok(Some(delivered)) = self.network.poll() {
+                self.delivered.push(delivered);
+            }
+            // poll() pauses while a reassembled message waits
+            let Some((source, message)) = self.network.take_reassembled() else {
+                break;
+            };
+            self.reassembled.push((source, message.to_vec()));
+        }
+
+        for (address, frame) in self.network.transport_mut().outbox.drain(..) {
+            match address {
+                None => outbox.broadcast(frame),
+                Some(address) => {
+                    if let Some(to) = sim_index(address) {
+                        outbox.unicast(to, frame);
+                    }
+                }
+            }
+        }
+    }
+}
+
+impl SimNode for SimMeshNetwork {
+    type Message = Vec<u8>;
+
+    fn tick(&mut self, now_ms: u64, outbox: &mut Outbox<Vec<u8>>) {
+        let hello_due = self
+            .last_hello_ms
+            .is_none_or(|last| now_ms - last >= self.hello_interval_ms);
+        if hello_due {
+            // Heartbeats and probes feed the neighbors' link estimators
+            self.network.broadcast_hello(self.position).ok();
+            self.network.send_heartbeat().ok();
+            self.network.send_probe().ok();
+            self.last_hello_ms = Some(now_ms);
+        }
+        // Neighbors silent for three hello intervals are considered gone
+        self.network
+            .prune_neighbors((3 * self.hello_interval_ms) as u32);
+        self.network.update().ok();
+        self.poll_and_flush(outbox);
+    }
+
+    fn receive(
+        &mut self,
+        from: NodeIndex,
+        frame: Vec<u8>,
+        rssi: i8,
+        _now_ms: u64,
+        outbox: &mut Outbox<Vec<u8>>,
+    ) {
+        self.network
+            .transport_mut()
+            .inbox
+            .push_back((sim_address(from), frame));
+        self.poll_and_flush(outbox);
+        // After processing, so a Hello's sender is already a neighbor
+        self.network.record_rssi(sim_address(from), rssi);
+    }
+
+    fn message_size(frame: &Vec<u8>) -> usize {
+        frame.len()
+    }
+
+    fn set_position(&mut self, position: Position) {
+        self.position = position;
+        self.network.set_position(position);
+    }
+}
+
+/// Raft consensus engine hosted by the simulator
+///
+/// `members[i]` must be hosted at node index `i`, so that consensus
+/// messages can be addressed by drone ID.
+pub struct SimConsensus {
+    /// The hosted engine
+    pub engine: ConsensusEngine,
+    indices: BTreeMap<u64, NodeIndex>,
+}
+
+impl SimConsensus {
+    /// Create an engine for `id` that knows every drone in `members`
+    pub fn new(id: DroneId, election_timeout_ms: u32, members: &[DroneId]) -> Self {
+        let mut engine = ConsensusEngine::new(id, election_timeout_ms);
+        let mut indices = BTreeMap::new();
+        for (index, member) in members.iter().enumerate() {
+            engine.add_member(*member).ok();
+            indices.insert(member.as_u64(), index);
+        }
+        Self { engine, indices }
+    }
+}
+
+impl SimNode for SimConsensus {
+    type Message = ConsensusMessage;
+
+    fn tick(&mut self, _now_ms: u64, outbox: &mut Outbox<ConsensusMessage>) {
+        let Ok(messages) = self.engine.tick() else {
+            return;
+        };
+
+        // tick() emits one message per member, in member order, skipping itself
+        let local_id = self.engine.node_id();
+        let targets = self
+            .engine
+            .members()
+            .iter()
+            .filter(|member| **member != local_id);
+        for (member, message) in targets.zip(messages) {
+            if let Some(to) = self.indices.get(&member.as_u64()) {
+                outbox.unicast(*to, message);
+            }
+        }
+    }
+
+    fn receive(
+        &mut self,
+        from: NodeIndex,
+        message: ConsensusMessage,
+        _rssi: i8,
+        _now_ms: u64,
+        outbox: &mut Outbox<ConsensusMessage>,
+    ) {
+        if let Ok(Some(reply)) = self.engine.process_message(message) {
+            outbox.unicast(from, reply);
+        }
+    }
+
+    fn message_size(message: &ConsensusMessage) -> usize {
+        encoded_size(message)
+    }
+}
+
+/// Store-carry-forward node hosted by the simulator
+///
+/// Broadcasts its summary vector on every tick it is due and answers each
+/// message to the drone that sent it.
+pub struct SimDtnNode {
+    /// The hosted DTN node
+    pub dtn: DtnNode,
+    /// Bundles delivered to this drone
+    pub delivered: Vec<Bundle>,
+}
+
+impl SimDtnNode {
+    /// Host a DTN node
+    pub fn new(dtn: DtnNode) -> Self {
+        Self {
+            dtn,
+            delivered: Vec::new(),
+        }
+    }
+}
+
+impl SimNode for SimDtnNode {
+    type Message = DtnMessage;
+
+    fn tick(&mut self, _now_ms: u64, outbox: &mut Outbox<DtnMessage>) {
+        if let Ok(Some(summary)) = self.dtn.tick() {
+            outbox.broadcast(summary);
+        }
+    }
+
+    fn receive(
+        &mut self,
+        from: NodeIndex,
+        message: DtnMessage,
+        _rssi: i8,
+        _now_ms: u64,
+        outbox: &mut Outbox<DtnMessage>,
+    ) {
+        let Ok(output) = self.dtn.process_message(message) else {
+            return;
+        };
+        for reply in output.replies {
+            outbox.unicast(from, reply);
+        }
+        if let Some(bundle) = output.delivered {
+            self.delivered.push(bundle);
+        }
+    }
+
+    fn message_size(message: &DtnMessage) -> usize {
+        encoded_size(message)
+    }
+}
+
+/// Fragmenting endpoint hosted by the simulator
+///
+/// Drones are addressed by [`DroneId`]; register the node index of each
+/// peer with [`SimFragmentNode::add_peer`] before sending to it.
+pub struct SimFragmentNode {
+    /// The hosted fragmentation layer
+    pub layer: FragmentationLayer,
+    /// Reassembled messages as (source, payload)
+    pub delivered: Vec<(DroneId, Vec<u8>)>,
+    /// Largest message handed to the channel (bytes)
+    pub largest_frame: usize,
+    peers: BTreeMap<u64, NodeIndex>,
+}
+
+impl SimFragmentNode {
+    /// Host a fragmentation layer
+    pub fn new(layer: FragmentationLayer) -> Self {
+        Self {
+            layer,
+            delivered: Vec::new(),
+            largest_frame: 0,
+            peers: BTreeMap::new(),
+        }
+    }
+
+    /// Map a drone to the node index hosting it
+    pub fn add_peer(&mut self, drone: DroneId, index: NodeIndex) {
+        self.peers.insert(drone.as_u64(), index);
+    }
+
+    fn flush(&mut self, outbox: &mut Outbox<FragmentMessage>) {
+        while let Some((to, message)) = self.layer.poll_transmit() {
+            if let Some(&index) = self.peers.get(&to.as_u64()) {
+                self.largest_frame = self.largest_frame.max(encoded_size(&message));
+                outbox.unicast(index, message);
+            }
+        }
+    }
+}
+
+impl SimNode for SimFragmentNode {
+    type Message = FragmentMessage;
+
+    fn tick(&mut self, _now_ms: u64, outbox: &mut Outbox<FragmentMessage>) {
+        self.layer.tick().ok();
+        self.flush(outbox);
+    }
+
+    fn receive(
+        &mut self,
+        _from: NodeIndex,
+        message: FragmentMessage,
+        _rssi: i8,
+        _now_ms: u64,
+        outbox: &mut Outbox<FragmentMessage>,
+    ) {
+        if let Ok(Some((source, payload))) = self.layer.receive(message) {
+            self.delivered.push((source, payload.to_vec()));
+        }
+        self.flush(outbox);
+    }
+
+    fn message_size(message: &FragmentMessage) -> usize {
+        encoded_size(message)
+    }
+}
+
+/// Time sync node whose local clock is offset from and drifts against the
+/// simulator clock, like a real crystal
+pub struct SimTimeSyncNode {
+    /// The hosted time sync service
+    pub sync: TimeSyncNode,
+    /// Local clock reading at simulator time zero (us)
+    pub clock_offset_us: i64,
+    /// Local clock rate error (parts per million)
+    pub clock_skew_ppm: f64,
+}
+
+impl SimTimeSyncNode {
+    /// Host a time sync service on a clock with the given offset and skew
+    pub fn new(sync: TimeSyncNode, clock_offset_us: i64, clock_skew_ppm: f64) -> Self {
+        Self {
+            sync,
+            clock_offset_us,
+            clock_skew_ppm,
+        }
+    }
+
+    /// Local clock reading at simulator time `sim_us`
+    pub fn local_time_us(&self, sim_us: u64) -> u64 {
+        let drift = sim_us as f64 * self.clock_skew_ppm * 1e-6;
+        (sim_us as i64 + self.clock_offset_us + drift as i64) as u64
+    }
+
+    /// Swarm time this node reports at simulator time `sim_us`
+    pub fn swarm_time_us(&self, sim_us: u64) -> u64 {
+        self.sync.swarm_time_us_at(self.local_time_us(sim_us))
+    }
+}
+
+impl SimNode for SimTimeSyncNode {
+    type Message = SyncBeacon;
+
+    fn tick(&mut self, _now_ms: u64, outbox: &mut Outbox<SyncBeacon>) {
+        let local_us = self.local_time_us(get_time_us());
+        if let Some(beacon) = self.sync.tick(local_us) {
+            outbox.broadcast(beacon);
+        }
+    }
+
+    fn receive(
+        &mut self,
+        _from: NodeIndex,
+        message: SyncBeacon,
+        _rssi: i8,
+        _now_ms: u64,
+        _outbox: &mut Outbox<SyncBeacon>,
+    ) {
+        let local_us = self.local_time_us(get_time_us());
+        self.sync.process_beacon(message, local_us);
+    }
+
+    fn message_size(message: &SyncBeacon) -> usize {
+        encoded_size(message)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_rng_is_reproducible() {
+        let mut a = SimRng::new(7);
+        let mut b = SimRng::new(7);
+        let mut c = SimRng::new(8);
+
+        let seq_a: Vec<u64> = (0..8).map(|_| a.next_u64()).collect();
+        let seq_b: Vec<u64> = (0..8).map(|_| b.next_u64()).collect();
+        let seq_c: Vec<u64> = (0..8).map(|_| c.next_u64()).collect();
+
+        assert_eq!(seq_a, seq_b);
+        assert_ne!(seq_a, seq_c);
+    }
+
+    #[test]
+    fn test_sim_address_round_trip() {
+        assert_eq!(sim_index(sim_address(0)), Some(0));
+        assert_eq!(sim_index(sim_address(4097)), Some(4097));
+        assert_eq!(sim_index(NetworkAddress::new([0; 16], 0)), None);
+    }
+}
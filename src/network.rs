@@ -2,13 +2,27 @@
 //!
 //! Implements:
 //! - Adaptive mesh routing with automatic topology optimization
-//! - Multi-hop communication with distance vector routing
-//! - Link quality monitoring
+//! - Multi-hop communication with on-demand (AODV) route discovery
+//! - Proactive link-state routing (OLSR-style) with multipoint relays
+//! - Controlled flooding and sink-gradient (convergecast) routing
+//! - Geographic routing (GPSR) with a flooded location service
+//! - Link quality estimation (ETX, RSSI trend, probe round-trip time)
 //! - Automatic neighbor discovery
 //! - Network resilience and self-healing
+//! - Messages above the data payload size, fragmented to the link MTU (see
+//!   [`crate::fragmentation`]) and routed hop by hop
+//!
+//! Protocol messages are postcard-encoded into frames and sent through a
+//! pluggable [`Transport`] (see [`crate::transport`]).
 
+use crate::config::{NetworkConfig, RoutingProtocol};
+use crate::fragmentation::{
+    FragmentConfig, FragmentMessage, FragmentationLayer, MAX_MESSAGE_PAYLOAD,
+};
+use crate::reputation::TrustPolicy;
+use crate::transport::{NullTransport, Transport, MAX_FRAME_SIZE};
 use crate::types::*;
-use heapless::{FnvIndexMap, Vec};
+use heapless::{Deque, FnvIndexMap, Vec};
 use serde::{Deserialize, Serialize};
 
 /// Maximum number of neighbors in mesh network (must be power of 2 for FnvIndexMap)
@@ -20,6 +34,69 @@ pub const MAX_ROUTES: usize = 128;
 /// Maximum network hops (prevents routing loops)
 pub const MAX_NETWORK_HOPS: u8 = 15;
 
+/// Time an unused route stays valid (ms)
+pub const ACTIVE_ROUTE_TIMEOUT_MS: u64 = 3000;
+
+/// Time to wait for a route reply before retrying discovery (ms)
+///
+/// Twice the per-hop traversal time (40 ms) across the network diameter;
+/// doubled on every retry.
+pub const ROUTE_DISCOVERY_TIMEOUT_MS: u64 = 2 * 40 * MAX_NETWORK_HOPS as u64;
+
+/// Route requests sent before queued messages are dropped
+pub const MAX_ROUTE_REQUEST_RETRIES: u8 = 2;
+
+/// Maximum destinations listed in one route error
+pub const MAX_UNREACHABLE: usize = 16;
+
+/// Flooded messages remembered for duplicate suppression
+const SEEN_BROADCAST_CACHE: usize = 64;
+
+/// Interval between link state updates in link-state mode (ms)
+pub const LINK_STATE_INTERVAL_MS: u64 = 2000;
+
+/// Time a topology entry stays valid without a fresh update (ms)
+pub const TOPOLOGY_HOLD_TIME_MS: u64 = 3 * LINK_STATE_INTERVAL_MS;
+
+/// Maximum drones tracked in the topology database (power of 2 for FnvIndexMap)
+pub const MAX_TOPOLOGY_ENTRIES: usize = 64;
+
+/// Interval between sink beacons in gradient mode (ms)
+pub const SINK_BEACON_INTERVAL_MS: u64 = 1000;
+
+/// Interval between location updates in geographic mode (ms)
+pub const LOCATION_UPDATE_INTERVAL_MS: u64 = 2000;
+
+/// Time a known location stays valid without a fresh update (ms)
+pub const LOCATION_TIMEOUT_MS: u64 = 3 * LOCATION_UPDATE_INTERVAL_MS;
+
+/// Maximum drones in the location table (power of 2 for FnvIndexMap)
+pub const MAX_LOCATIONS: usize = 64;
+
+/// Smoothing factor of the link estimator's moving averages
+pub const LINK_ALPHA: f32 = 0.8;
+
+/// Delivery ratio floor, bounding the ETX of a failing link
+pub const MIN_DELIVERY_RATIO: f32 = 0.05;
+
+/// Delivery ratio assumed for a link before any heartbeat measured it
+pub const NEW_LINK_RATIO: f32 = 0.5;
+
+/// Missed heartbeats counted from one sequence gap
+pub const MAX_COUNTED_GAP: u32 = 16;
+
+/// RSSI at or above which signal strength adds no cost (dBm)
+pub const RSSI_GOOD_DBM: f32 = -85.0;
+
+/// Round-trip time costing as much as one extra transmission (ms)
+pub const RTT_COST_MS: f32 = 100.0;
+
+/// Worst-case framing a routed fragment adds to its fragment message (bytes)
+///
+/// Variant tag, source and destination IDs (varints), hop count and the
+/// largest [`FragmentDelivery`]: a geographic target with a perimeter header.
+pub const ROUTED_FRAGMENT_OVERHEAD: usize = 1 + 10 + 10 + 1 + (1 + 12 + 1 + 12 + 12 + 10 + 10);
+
 /// Message types for mesh networking
 #[derive(Debug, Clone, Serialize, Deserialize)]
 #[allow(clippy::large_enum_variant)]
@@ -30,8 +107,19 @@ pub enum NetworkMessage {
         position: Position,
         sequence: u32,
     },
-    /// Heartbeat to maintain connections
-    Heartbeat { sender: DroneId, timestamp: u64 },
+    /// Heartbeat to maintain connections and measure delivery ratios
+    Heartbeat {
+        sender: DroneId,
+        timestamp: u64,
+        /// Heartbeat counter; gaps reveal lost heartbeats
+        sequence: u32,
+        /// Sender's reception ratio from each neighbor (0-255 = 0.0-1.0)
+        reception: Vec<(DroneId, u8), MAX_NEIGHBORS>,
+    },
+    /// Link probe; neighbors answer with an [`NetworkMessage::Echo`]
+    Probe { sender: DroneId, timestamp: u64 },
+    /// Answer to a probe, returning its timestamp to measure round-trip time
+    Echo { sender: DroneId, timestamp: u64 },
     /// Data message
     Data {
         source: DroneId,
@@ -39,25 +127,258 @@ pub enum NetworkMessage {
         payload: Vec<u8, 1024>,
         hop_count: u8,
     },
-    /// Route request (AODV-style)
+    /// Route request (AODV-style), flooded until it reaches a node with a route
     RouteRequest {
         source: DroneId,
         destination: DroneId,
+        /// Originator sequence number, also identifies the request
         sequence: u32,
+        /// Hops travelled so far
+        hop_count: u8,
+        /// Link cost accumulated so far
+        metric: f32,
     },
-    /// Route reply
+    /// Route reply, unicast back along the reverse path
     RouteReply {
+        /// Drone the route leads to
         source: DroneId,
+        /// Drone that requested the route
         destination: DroneId,
+        /// Drone that transmitted this copy of the reply
         next_hop: DroneId,
+        /// Hops from the transmitter to `source`
         hop_count: u8,
+        /// Sequence number of `source` (route freshness)
+        sequence: u32,
+        /// Link cost from the transmitter to `source`
+        metric: f32,
     },
-    /// Link state update
+    /// Link state update (OLSR-style), flooded through multipoint relays
     LinkStateUpdate {
         sender: DroneId,
+        /// Sender's neighbors with link quality
         neighbors: Vec<(DroneId, f32), MAX_NEIGHBORS>,
         sequence: u32,
+        /// Neighbors the sender selected as multipoint relays
+        mprs: Vec<DroneId, MAX_NEIGHBORS>,
+        /// Hops travelled so far (0 when received from the sender itself)
+        hop_count: u8,
+    },
+    /// Route error: `sender` can no longer reach these destinations
+    RouteError {
+        sender: DroneId,
+        unreachable: Vec<DroneId, MAX_UNREACHABLE>,
+    },
+    /// Data message rebroadcast by every node until it reaches its destination
+    Flood {
+        source: DroneId,
+        destination: DroneId,
+        /// Originator sequence number, identifies the message
+        sequence: u32,
+        payload: Vec<u8, 1024>,
+        hop_count: u8,
+    },
+    /// Sink beacon building the cost gradient toward `sink`
+    SinkBeacon {
+        sink: DroneId,
+        sequence: u32,
+        /// Hops travelled so far
+        hop_count: u8,
+        /// Link cost accumulated so far
+        metric: f32,
+    },
+    /// Position-addressed data (GPSR)
+    GeoData {
+        source: DroneId,
+        destination: DroneId,
+        /// Destination position from the location service
+        target: Position,
+        payload: Vec<u8, 1024>,
+        hop_count: u8,
+        /// Perimeter-mode state; `None` while forwarding greedily
+        perimeter: Option<PerimeterState>,
+    },
+    /// Location service update, flooded through the swarm
+    LocationUpdate {
+        drone: DroneId,
+        position: Position,
+        sequence: u32,
+        /// Hops travelled so far
+        hop_count: u8,
+    },
+    /// Part of a message too large for [`NetworkMessage::Data`]
+    Fragment {
+        source: DroneId,
+        destination: DroneId,
+        fragment: FragmentMessage,
+        hop_count: u8,
+        /// How the fragment travels, following the sender's routing mode
+        delivery: FragmentDelivery,
+    },
+}
+
+/// How a [`NetworkMessage::Fragment`] travels to its destination
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub enum FragmentDelivery {
+    /// Along the routing table, like [`NetworkMessage::Data`]
+    Routed,
+    /// Rebroadcast by every node, like [`NetworkMessage::Flood`]
+    Flood {
+        /// Originator sequence number, identifies the copy
+        sequence: u32,
     },
+    /// Forwarded on positions, like [`NetworkMessage::GeoData`]
+    Geographic {
+        /// Destination position from the location service
+        target: Position,
+        /// Perimeter-mode state; `None` while forwarding greedily
+        perimeter: Option<PerimeterState>,
+    },
+}
+
+/// GPSR perimeter-mode header
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct PerimeterState {
+    /// Where the packet entered perimeter mode (Lp)
+    pub entered_at: Position,
+    /// Where the packet entered the current face (Lf)
+    pub face_entry: Position,
+    /// First edge traversed on the current face (e0), as (from, to)
+    pub first_edge: (DroneId, DroneId),
+}
+
+/// Link estimator combining delivery ratios, signal strength and round-trip time
+///
+/// ETX is `1 / (df * dr)`: `dr` comes from heartbeat sequence gaps and `df`
+/// is reported back by the neighbor. A silent neighbor is charged a miss per
+/// heartbeat interval. [`LinkEstimator::cost`] adds penalties for a weak or
+/// fading signal and a slow round trip.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct LinkEstimator {
+    /// Delivery ratio from the neighbor to us
+    pub reverse_ratio: f32,
+    /// Delivery ratio from us to the neighbor
+    pub forward_ratio: f32,
+    /// Smoothed RSSI (dBm), once the radio has reported one
+    pub rssi_dbm: Option<f32>,
+    /// Smoothed RSSI change per sample (dB); negative while the link fades
+    pub rssi_trend: f32,
+    /// Smoothed probe round-trip time (ms), once measured
+    pub rtt_ms: Option<f32>,
+    /// Last heartbeat sequence heard
+    pub last_heartbeat: Option<u32>,
+    /// Whether a heartbeat arrived in the current interval
+    pub heard_in_interval: bool,
+    /// Misses already charged for silence since the last heartbeat
+    pub charged_misses: u32,
+}
+
+impl Default for LinkEstimator {
+    /// Estimate for a newly discovered link, which has to prove itself
+    /// with heartbeats before it beats an established one
+    fn default() -> Self {
+        Self {
+            reverse_ratio: NEW_LINK_RATIO,
+            forward_ratio: NEW_LINK_RATIO,
+            rssi_dbm: None,
+            rssi_trend: 0.0,
+            rtt_ms: None,
+            last_heartbeat: None,
+            heard_in_interval: false,
+            charged_misses: 0,
+        }
+    }
+}
+
+impl LinkEstimator {
+    /// Record a heartbeat, counting the ones missed since the last
+    pub fn record_heartbeat(&mut self, sequence: u32) {
+        if let Some(last) = self.last_heartbeat {
+            let gap = sequence.wrapping_sub(last);
+            // Duplicate or reordered heartbeat
+            if gap as i32 <= 0 {
+                return;
+            }
+            let missed = (gap - 1).min(MAX_COUNTED_GAP);
+            for _ in self.charged_misses.min(missed)..missed {
+                self.reverse_ratio *= LINK_ALPHA;
+            }
+        }
+        self.reverse_ratio = LINK_ALPHA * self.reverse_ratio + (1.0 - LINK_ALPHA);
+        self.last_heartbeat = Some(sequence);
+        self.heard_in_interval = true;
+        self.charged_misses = 0;
+    }
+
+    /// Close a heartbeat interval, charging a miss if the neighbor was silent
+    ///
+    /// Links that never delivered a heartbeat keep their initial estimate.
+    pub fn end_interval(&mut self) {
+        if self.last_heartbeat.is_some()
+            && !self.heard_in_interval
+            && self.charged_misses < MAX_COUNTED_GAP
+        {
+            self.reverse_ratio *= LINK_ALPHA;
+            self.charged_misses += 1;
+        }
+        self.heard_in_interval = false;
+    }
+
+    /// Record whether a frame sent to the neighbor got through
+    pub fn record_delivery(&mut self, success: bool) {
+        let measurement = if success { 1.0 } else { 0.0 };
+        self.forward_ratio = LINK_ALPHA * self.forward_ratio + (1.0 - LINK_ALPHA) * measurement;
+    }
+
+    /// Record a received signal strength sample
+    pub fn record_rssi(&mut self, rssi: i8) {
+        let sample = rssi as f32;
+        match self.rssi_dbm {
+            None => self.rssi_dbm = Some(sample),
+            Some(average) => {
+                let delta = sample - average;
+                self.rssi_dbm = Some(LINK_ALPHA * average + (1.0 - LINK_ALPHA) * sample);
+                self.rssi_trend = LINK_ALPHA * self.rssi_trend + (1.0 - LINK_ALPHA) * delta;
+            }
+        }
+    }
+
+    /// Record a probe round-trip time
+    pub fn record_rtt(&mut self, rtt_ms: u32) {
+        let sample = rtt_ms as f32;
+        self.rtt_ms = Some(match self.rtt_ms {
+            None => sample,
+            Some(average) => LINK_ALPHA * average + (1.0 - LINK_ALPHA) * sample,
+        });
+    }
+
+    /// Expected transmissions per delivered frame, ACK included
+    pub fn etx(&self) -> f32 {
+        let forward = self.forward_ratio.clamp(MIN_DELIVERY_RATIO, 1.0);
+        let reverse = self.reverse_ratio.clamp(MIN_DELIVERY_RATIO, 1.0);
+        1.0 / (forward * reverse)
+    }
+
+    /// Route cost of the link: 1.0 for a perfect link
+    ///
+    /// ETX plus one for every 10 dB below [`RSSI_GOOD_DBM`], one per dB of
+    /// fading per sample, and one per [`RTT_COST_MS`] of round trip.
+    pub fn cost(&self) -> f32 {
+        let mut cost = self.etx();
+        if let Some(rssi) = self.rssi_dbm {
+            cost += ((RSSI_GOOD_DBM - rssi) / 10.0).max(0.0);
+            cost += (-self.rssi_trend).max(0.0);
+        }
+        if let Some(rtt) = self.rtt_ms {
+            cost += rtt / RTT_COST_MS;
+        }
+        cost
+    }
+
+    /// Link quality (0.0 - 1.0), the inverse of [`LinkEstimator::cost`]
+    pub fn quality(&self) -> f32 {
+        1.0 / self.cost()
+    }
 }
 
 /// Neighbor information
@@ -69,12 +390,14 @@ pub struct Neighbor {
     pub address: NetworkAddress,
     /// Last known position
     pub position: Position,
-    /// Link quality (0.0 - 1.0)
+    /// Link quality (0.0 - 1.0), kept in step with `link`
     pub link_quality: f32,
     /// Last heard timestamp
     pub last_seen: u64,
-    /// Round-trip time (ms)
+    /// Round-trip time (ms), 0 until probed
     pub rtt_ms: u32,
+    /// Link estimator
+    pub link: LinkEstimator,
 }
 
 impl Neighbor {
@@ -83,11 +406,16 @@ impl Neighbor {
         current_time - self.last_seen < timeout_ms as u64
     }
 
-    /// Update link quality based on RTT and packet loss
+    /// Record a delivery outcome for a frame sent to this neighbor
     pub fn update_quality(&mut self, success: bool) {
-        const ALPHA: f32 = 0.8; // Exponential smoothing factor
-        let measurement = if success { 1.0 } else { 0.0 };
-        self.link_quality = ALPHA * self.link_quality + (1.0 - ALPHA) * measurement;
+        self.link.record_delivery(success);
+        self.refresh_quality();
+    }
+
+    /// Recompute `link_quality` and `rtt_ms` from the link estimator
+    pub fn refresh_quality(&mut self) {
+        self.link_quality = self.link.quality();
+        self.rtt_ms = self.link.rtt_ms.map_or(0, |rtt| rtt as u32);
     }
 }
 
@@ -108,10 +436,34 @@ pub struct Route {
     pub last_updated: u64,
 }
 
+/// Location service entry
+#[derive(Debug, Clone, Copy)]
+pub struct LocationEntry {
+    /// Last reported position
+    pub position: Position,
+    /// Time the position was learned
+    pub updated: u64,
+}
+
+/// Topology database entry: the links last advertised by one drone
+#[derive(Debug, Clone)]
+pub struct TopologyEntry {
+    /// Advertised neighbors with link quality
+    pub neighbors: Vec<(DroneId, f32), MAX_NEIGHBORS>,
+    /// Sequence number of the update this entry came from
+    pub sequence: u32,
+    /// Time the update was received
+    pub received_at: u64,
+    /// Whether this node already relayed the update
+    pub retransmitted: bool,
+}
+
 /// Mesh network manager
-pub struct MeshNetwork {
+pub struct MeshNetwork<T: Transport = NullTransport> {
     /// This drone's ID
     local_id: DroneId,
+    /// Link layer used to send and receive frames
+    transport: T,
     /// Neighbor table
     neighbors: FnvIndexMap<u64, Neighbor, MAX_NEIGHBORS>,
     /// Routing table
@@ -120,17 +472,53 @@ pub struct MeshNetwork {
     sequence_number: u32,
     /// Message queue
     message_queue: Vec<QueuedMessage, 100>,
+    /// Recently seen flooded messages (route requests, flood data) as (source, sequence)
+    seen_broadcasts: Deque<(u64, u32), SEEN_BROADCAST_CACHE>,
+    /// Active routing protocol
+    routing_protocol: RoutingProtocol,
+    /// Link-state topology database, keyed by advertising drone
+    topology: FnvIndexMap<u64, TopologyEntry, MAX_TOPOLOGY_ENTRIES>,
+    /// Neighbors selected as multipoint relays
+    mprs: Vec<DroneId, MAX_NEIGHBORS>,
+    /// Neighbors that selected us as their multipoint relay
+    mpr_selectors: Vec<DroneId, MAX_NEIGHBORS>,
+    /// Whether this drone is a data sink in gradient mode
+    is_sink: bool,
+    /// This drone's own position, if known
+    position: Option<Position>,
+    /// Location service table, keyed by drone
+    locations: FnvIndexMap<u64, LocationEntry, MAX_LOCATIONS>,
+    /// Time of the last periodic advertisement (link state update or sink beacon)
+    last_advertisement: Option<u64>,
+    /// Heartbeat counter for delivery ratio measurement
+    heartbeat_sequence: u32,
+    /// Drones not trusted to relay traffic
+    untrusted: Vec<DroneId, MAX_ROUTES>,
+    /// Fragmentation and reassembly of large messages
+    fragmentation: FragmentationLayer,
+    /// Reassembled message not yet taken, with its source
+    reassembled: Option<(DroneId, Vec<u8, MAX_MESSAGE_PAYLOAD>)>,
     /// Network statistics
     stats: NetworkStats,
 }
 
+/// Dijkstra working entry
+#[derive(Debug, Clone, Copy)]
+struct PathNode {
+    id: DroneId,
+    cost: f32,
+    first_hop: DroneId,
+    hop_count: u8,
+    settled: bool,
+}
+
 #[derive(Debug, Clone)]
 struct QueuedMessage {
     destination: DroneId,
     payload: Vec<u8, 1024>,
-    #[allow(dead_code)] // Reserved for retry logic
+    /// Route requests already retried for this message
     retry_count: u8,
-    #[allow(dead_code)] // Reserved for timeout handling
+    /// Time of the last route request (ms)
     timestamp: u64,
 }
 
@@ -146,19 +534,53 @@ pub struct NetworkStats {
     pub avg_rtt_ms: u32,
 }
 
-impl MeshNetwork {
-    /// Create a new mesh network instance
+impl MeshNetwork<NullTransport> {
+    /// Create a new mesh network instance without a radio attached
+    ///
+    /// Frames are discarded; use [`MeshNetwork::with_transport`] to talk to other nodes.
     pub fn new(local_id: DroneId) -> Self {
+        Self::with_transport(local_id, NullTransport)
+    }
+}
+
+impl<T: Transport> MeshNetwork<T> {
+    /// Create a mesh network instance that sends frames through `transport`
+    ///
+    /// Uses on-demand [`RoutingProtocol::AdaptiveMesh`] routing.
+    pub fn with_transport(local_id: DroneId, transport: T) -> Self {
         Self {
             local_id,
+            transport,
             neighbors: FnvIndexMap::new(),
             routes: FnvIndexMap::new(),
             sequence_number: 0,
             message_queue: Vec::new(),
+            seen_broadcasts: Deque::new(),
+            routing_protocol: RoutingProtocol::AdaptiveMesh,
+            topology: FnvIndexMap::new(),
+            mprs: Vec::new(),
+            mpr_selectors: Vec::new(),
+            is_sink: false,
+            position: None,
+            locations: FnvIndexMap::new(),
+            last_advertisement: None,
+            heartbeat_sequence: 0,
+            untrusted: Vec::new(),
+            fragmentation: FragmentationLayer::new(local_id, fragment_config(MAX_FRAME_SIZE)),
+            reassembled: None,
             stats: NetworkStats::default(),
         }
     }
 
+    /// Create a mesh network instance using the routing protocol and MTU from `config`
+    pub fn with_config(local_id: DroneId, transport: T, config: &NetworkConfig) -> Self {
+        let mut network = Self::with_transport(local_id, transport);
+        network.routing_protocol = config.routing_protocol;
+        network.fragmentation =
+            FragmentationLayer::new(local_id, fragment_config(config.mtu as usize));
+        network
+    }
+
     /// Process incoming network message
     pub fn process_message(
         &mut self,
@@ -174,8 +596,27 @@ impl MeshNetwork {
                 self.handle_hello(sender, position, sender_addr, sequence)?;
                 Ok(None)
             }
-            NetworkMessage::Heartbeat { sender, timestamp } => {
-                self.handle_heartbeat(sender, timestamp)?;
+            NetworkMessage::Heartbeat {
+                sender,
+                timestamp,
+                sequence,
+                reception,
+            } => {
+                self.handle_heartbeat(sender, timestamp, sequence, &reception)?;
+                Ok(None)
+            }
+            NetworkMessage::Probe { sender, timestamp } => {
+                if sender != self.local_id {
+                    let echo = NetworkMessage::Echo {
+                        sender: self.local_id,
+                        timestamp,
+                    };
+                    self.transmit(Some(sender_addr), &echo)?;
+                }
+                Ok(None)
+            }
+            NetworkMessage::Echo { sender, timestamp } => {
+                self.handle_echo(sender, timestamp);
                 Ok(None)
             }
             NetworkMessage::Data {
@@ -190,7 +631,14 @@ impl MeshNetwork {
                     Ok(Some(payload))
                 } else {
                     // Forward message
-                    self.forward_message(source, destination, payload, hop_count)?;
+                    self.forward_message(destination, hop_count, |hop_count| {
+                        NetworkMessage::Data {
+                            source,
+                            destination,
+                            payload,
+                            hop_count,
+                        }
+                    })?;
                     Ok(None)
                 }
             }
@@ -198,8 +646,17 @@ impl MeshNetwork {
                 source,
                 destination,
                 sequence,
+                hop_count,
+                metric,
             } => {
-                self.handle_route_request(source, destination, sequence)?;
+                self.handle_route_request(
+                    source,
+                    destination,
+                    sequence,
+                    hop_count,
+                    metric,
+                    sender_addr,
+                )?;
                 Ok(None)
             }
             NetworkMessage::RouteReply {
@@ -207,47 +664,333 @@ impl MeshNetwork {
                 destination,
                 next_hop,
                 hop_count,
+                sequence,
+                metric,
             } => {
-                self.handle_route_reply(source, destination, next_hop, hop_count)?;
+                self.handle_route_reply(
+                    source,
+                    destination,
+                    next_hop,
+                    hop_count,
+                    sequence,
+                    metric,
+                )?;
                 Ok(None)
             }
             NetworkMessage::LinkStateUpdate {
                 sender,
                 neighbors,
                 sequence,
+                mprs,
+                hop_count,
+            } => {
+                self.handle_link_state_update(
+                    sender,
+                    neighbors,
+                    sequence,
+                    mprs,
+                    hop_count,
+                    sender_addr,
+                )?;
+                Ok(None)
+            }
+            NetworkMessage::RouteError {
+                sender,
+                unreachable,
+            } => {
+                self.handle_route_error(sender, &unreachable)?;
+                Ok(None)
+            }
+            NetworkMessage::Flood {
+                source,
+                destination,
+                sequence,
+                payload,
+                hop_count,
+            } => self.handle_flood(source, destination, sequence, payload, hop_count),
+            NetworkMessage::SinkBeacon {
+                sink,
+                sequence,
+                hop_count,
+                metric,
+            } => {
+                self.handle_sink_beacon(sink, sequence, hop_count, metric, sender_addr)?;
+                Ok(None)
+            }
+            NetworkMessage::GeoData {
+                source,
+                destination,
+                target,
+                payload,
+                hop_count,
+                perimeter,
+            } => {
+                if destination == self.local_id {
+                    self.stats.messages_received += 1;
+                    return Ok(Some(payload));
+                }
+
+                // Same hop limit as routed data
+                let new_hop_count = hop_count.saturating_add(1);
+                if new_hop_count > MAX_NETWORK_HOPS {
+                    self.stats.messages_dropped += 1;
+                    return Err(SwarmError::NetworkError);
+                }
+                let previous_hop = self.neighbor_at(sender_addr);
+                let packet = NetworkMessage::GeoData {
+                    source,
+                    destination,
+                    target,
+                    payload,
+                    hop_count: new_hop_count,
+                    perimeter,
+                };
+                self.forward_geographic(packet, previous_hop)?;
+                Ok(None)
+            }
+            NetworkMessage::LocationUpdate {
+                drone,
+                position,
+                sequence,
+                hop_count,
             } => {
-                self.handle_link_state_update(sender, neighbors, sequence)?;
+                self.handle_location_update(drone, position, sequence, hop_count)?;
+                Ok(None)
+            }
+            NetworkMessage::Fragment {
+                source,
+                destination,
+                fragment,
+                hop_count,
+                delivery,
+            } => {
+                if let FragmentDelivery::Flood { sequence } = delivery {
+                    if source == self.local_id || self.seen_before(source, sequence) {
+                        return Ok(None);
+                    }
+                }
+                if destination == self.local_id {
+                    self.receive_fragment(fragment)?;
+                } else {
+                    let previous_hop = self.neighbor_at(sender_addr);
+                    let packet = NetworkMessage::Fragment {
+                        source,
+                        destination,
+                        fragment,
+                        hop_count,
+                        delivery,
+                    };
+                    self.forward_fragment(packet, previous_hop)?;
+                }
                 Ok(None)
             }
         }
     }
 
+    /// Receive and process pending frames from the transport
+    ///
+    /// Handles control traffic internally and returns the first data payload
+    /// addressed to this node together with its source, or `None` once the
+    /// transport has no more frames. Malformed frames are counted as dropped.
+    /// Also returns `None` as soon as a reassembled message is waiting in
+    /// [`take_reassembled`](Self::take_reassembled).
+    pub fn poll(&mut self) -> Result<Option<(DroneId, Vec<u8, 1024>)>> {
+        let mut buf = [0u8; MAX_FRAME_SIZE];
+
+        while self.reassembled.is_none() {
+            let Some((len, sender_addr)) = self.transport.poll_recv(&mut buf)? else {
+                break;
+            };
+            let message = match postcard::from_bytes::<NetworkMessage>(&buf[..len]) {
+                Ok(message) => message,
+                Err(_) => {
+                    self.stats.messages_dropped += 1;
+                    continue;
+                }
+            };
+
+            let source = match &message {
+                NetworkMessage::Data { source, .. }
+                | NetworkMessage::Flood { source, .. }
+                | NetworkMessage::GeoData { source, .. } => Some(*source),
+                _ => None,
+            };
+
+            // A frame that cannot be handled (no route, hop limit) must not stall the rest
+            if let Ok(Some(payload)) = self.process_message(message, sender_addr) {
+                if let Some(source) = source {
+                    return Ok(Some((source, payload)));
+                }
+            }
+        }
+
+        Ok(None)
+    }
+
     /// Send a message to a destination drone
+    ///
+    /// Without a route the message is queued and route discovery starts;
+    /// queued messages are sent as soon as a route is learned. In flooding
+    /// mode the message is broadcast straight away.
     pub fn send_message(&mut self, destination: DroneId, payload: Vec<u8, 1024>) -> Result<()> {
-        // Check if we have a route
-        if let Some(_route) = self.routes.get(&destination.as_u64()) {
-            // Send directly via route
-            let _msg = NetworkMessage::Data {
+        if self.routing_protocol == RoutingProtocol::Flooding {
+            self.sequence_number += 1;
+            let msg = NetworkMessage::Flood {
                 source: self.local_id,
                 destination,
+                sequence: self.sequence_number,
                 payload,
                 hop_count: 0,
             };
-            self.stats.messages_sent += 1;
+            return self.broadcast(&msg);
+        }
+
+        if self.routing_protocol == RoutingProtocol::Geographic {
+            // Without a known position the message waits for the location service
+            return match self.location(destination) {
+                Some(target) => {
+                    let msg = NetworkMessage::GeoData {
+                        source: self.local_id,
+                        destination,
+                        target,
+                        payload,
+                        hop_count: 0,
+                        perimeter: None,
+                    };
+                    self.forward_geographic(msg, None)
+                }
+                None => self.queue_message(destination, payload),
+            };
+        }
 
-            // SIMULATION MODE: In production, this would transmit via hardware radio interface
-            // For testing/simulation, we track the message but don't perform actual I/O
-            #[cfg(feature = "hardware")]
-            unimplemented!("Hardware radio transmission not yet implemented");
+        if let Some(route) = self.active_route(destination) {
+            if self.neighbors.contains_key(&route.next_hop.as_u64()) {
+                let msg = NetworkMessage::Data {
+                    source: self.local_id,
+                    destination,
+                    payload,
+                    hop_count: 0,
+                };
+                return self.unicast(route.next_hop, &msg);
+            }
+            // Next hop has vanished - tear down its routes and rediscover
+            self.handle_link_break(route.next_hop)?;
+        }
 
-            #[cfg(not(feature = "hardware"))]
-            Ok(())
-        } else {
-            // No route - queue and initiate route discovery
-            self.queue_message(destination, payload)?;
+        // No route - queue and initiate route discovery unless one is in progress
+        let discovery_pending = self
+            .message_queue
+            .iter()
+            .any(|queued| queued.destination == destination);
+        self.queue_message(destination, payload)?;
+        if !discovery_pending {
             self.initiate_route_discovery(destination)?;
-            Ok(())
         }
+        Ok(())
+    }
+
+    /// Send a message of up to [`MAX_MESSAGE_PAYLOAD`] bytes, returning its message ID
+    ///
+    /// The message is fragmented to the link MTU and each fragment travels
+    /// like data in the current routing mode: routed, flooded or forwarded on
+    /// positions. Fragments are not queued while a route is being discovered:
+    /// the destination acknowledges the whole message, and
+    /// [`update`](Self::update) resends whatever it has not received.
+    /// Fails with `BufferFull` while too many messages await acknowledgment.
+    pub fn send_large(&mut self, destination: DroneId, payload: &[u8]) -> Result<u16> {
+        let message_id = self.fragmentation.send(destination, payload)?;
+        self.flush_fragments()?;
+        Ok(message_id)
+    }
+
+    /// Serialize `message` with postcard and send it like [`send_large`](Self::send_large)
+    ///
+    /// Carries consensus traffic such as `AppendEntries` batches that exceed
+    /// a data payload.
+    pub fn send_serialized<M: Serialize>(
+        &mut self,
+        destination: DroneId,
+        message: &M,
+    ) -> Result<u16> {
+        let message_id = self.fragmentation.send_serialized(destination, message)?;
+        self.flush_fragments()?;
+        Ok(message_id)
+    }
+
+    /// Take the last message reassembled from fragments, with its source
+    ///
+    /// Until it is taken, further messages cannot complete: their fragments
+    /// are refused and resent later by the sender.
+    pub fn take_reassembled(&mut self) -> Option<(DroneId, Vec<u8, MAX_MESSAGE_PAYLOAD>)> {
+        self.reassembled.take()
+    }
+
+    /// Get the fragmentation layer carrying large messages
+    pub fn fragmentation(&self) -> &FragmentationLayer {
+        &self.fragmentation
+    }
+
+    /// Handle a fragment, ACK or NACK addressed to this node
+    fn receive_fragment(&mut self, fragment: FragmentMessage) -> Result<()> {
+        if self.reassembled.is_some() {
+            self.stats.messages_dropped += 1;
+            return Err(SwarmError::BufferFull);
+        }
+        if let Some(message) = self.fragmentation.receive(fragment)? {
+            self.stats.messages_received += 1;
+            self.reassembled = Some(message);
+        }
+        self.flush_fragments()
+    }
+
+    /// Send everything the fragmentation layer has to send
+    ///
+    /// Fragments without a route (or, in geographic mode, without a known
+    /// destination position) are dropped and a route discovery starts once
+    /// per destination.
+    fn flush_fragments(&mut self) -> Result<()> {
+        let mut unroutable: Vec<DroneId, MAX_UNREACHABLE> = Vec::new();
+        while let Some((destination, fragment)) = self.fragmentation.poll_transmit() {
+            let source = self.local_id;
+            let packet = |delivery| NetworkMessage::Fragment {
+                source,
+                destination,
+                fragment,
+                hop_count: 0,
+                delivery,
+            };
+            let sent = match self.routing_protocol {
+                RoutingProtocol::Flooding => {
+                    self.sequence_number += 1;
+                    let sequence = self.sequence_number;
+                    Some(self.broadcast(&packet(FragmentDelivery::Flood { sequence })))
+                }
+                RoutingProtocol::Geographic => self.location(destination).map(|target| {
+                    let delivery = FragmentDelivery::Geographic {
+                        target,
+                        perimeter: None,
+                    };
+                    self.forward_geographic(packet(delivery), None)
+                }),
+                _ => self
+                    .active_route(destination)
+                    .map(|route| route.next_hop)
+                    .filter(|next_hop| self.neighbors.contains_key(&next_hop.as_u64()))
+                    .map(|next_hop| self.unicast(next_hop, &packet(FragmentDelivery::Routed))),
+            };
+            // A failed fragment is resent after the destination's NACK
+            if sent.is_none() {
+                self.stats.messages_dropped += 1;
+                if !unroutable.contains(&destination) {
+                    unroutable.push(destination).ok();
+                }
+            }
+        }
+
+        for destination in unroutable {
+            self.initiate_route_discovery(destination)?;
+        }
+        Ok(())
     }
 
     /// Handle hello message (neighbor discovery)
@@ -258,13 +1001,19 @@ impl MeshNetwork {
         address: NetworkAddress,
         sequence: u32,
     ) -> Result<()> {
+        // A known neighbor keeps its link estimate
+        let link = self
+            .neighbors
+            .get(&sender.as_u64())
+            .map_or_else(LinkEstimator::default, |neighbor| neighbor.link);
         let neighbor = Neighbor {
             id: sender,
             address,
             position,
-            link_quality: 1.0,
+            link_quality: link.quality(),
             last_seen: Self::get_time(),
-            rtt_ms: 0,
+            rtt_ms: link.rtt_ms.map_or(0, |rtt| rtt as u32),
+            link,
         };
 
         self.neighbors
@@ -276,7 +1025,7 @@ impl MeshNetwork {
             destination: sender,
             next_hop: sender,
             hop_count: 1,
-            metric: 1.0,
+            metric: link.cost(),
             sequence,
             last_updated: Self::get_time(),
         };
@@ -285,25 +1034,93 @@ impl MeshNetwork {
             .insert(sender.as_u64(), route)
             .map_err(|_| SwarmError::ResourceExhausted)?;
 
-        Ok(())
+        if self.routing_protocol == RoutingProtocol::LinkState {
+            self.compute_routes()?;
+        }
+        self.record_location(sender, position)?;
+        self.flush_message_queue(sender)
     }
 
     /// Handle heartbeat message
-    fn handle_heartbeat(&mut self, sender: DroneId, _timestamp: u64) -> Result<()> {
+    ///
+    /// The sequence gap gives the reverse delivery ratio; the sender's
+    /// reception report for us gives the forward one.
+    fn handle_heartbeat(
+        &mut self,
+        sender: DroneId,
+        _timestamp: u64,
+        sequence: u32,
+        reception: &[(DroneId, u8)],
+    ) -> Result<()> {
+        let local_id = self.local_id;
         if let Some(neighbor) = self.neighbors.get_mut(&sender.as_u64()) {
             neighbor.last_seen = Self::get_time();
-            neighbor.update_quality(true);
+            neighbor.link.record_heartbeat(sequence);
+            if let Some((_, ratio)) = reception.iter().find(|(id, _)| *id == local_id) {
+                neighbor.link.forward_ratio = *ratio as f32 / 255.0;
+            }
+            self.refresh_link(sender);
         }
         Ok(())
     }
 
-    /// Forward message to next hop
+    /// Handle a probe echo: one round-trip time sample
+    fn handle_echo(&mut self, sender: DroneId, timestamp: u64) {
+        let rtt = Self::get_time().saturating_sub(timestamp) as u32;
+        if let Some(neighbor) = self.neighbors.get_mut(&sender.as_u64()) {
+            neighbor.link.record_rtt(rtt);
+            self.stats.avg_rtt_ms = if self.stats.avg_rtt_ms == 0 {
+                rtt
+            } else {
+                (LINK_ALPHA * self.stats.avg_rtt_ms as f32 + (1.0 - LINK_ALPHA) * rtt as f32) as u32
+            };
+            self.refresh_link(sender);
+        }
+    }
+
+    /// Record a received signal strength sample for the neighbor at `address`
+    ///
+    /// Call with the radio's RSSI for each received frame; samples from
+    /// addresses that are not (yet) neighbors are ignored.
+    pub fn record_rssi(&mut self, address: NetworkAddress, rssi: i8) {
+        let Some(id) = self.neighbor_at(address) else {
+            return;
+        };
+        if let Some(neighbor) = self.neighbors.get_mut(&id.as_u64()) {
+            neighbor.link.record_rssi(rssi);
+        }
+        self.refresh_link(id);
+    }
+
+    /// Propagate a neighbor's link estimate to its quality and direct route
+    fn refresh_link(&mut self, id: DroneId) {
+        let Some(neighbor) = self.neighbors.get_mut(&id.as_u64()) else {
+            return;
+        };
+        neighbor.refresh_quality();
+        let cost = neighbor.link.cost();
+        if let Some(route) = self.routes.get_mut(&id.as_u64()) {
+            if route.next_hop == id && route.hop_count == 1 {
+                route.metric = cost;
+            }
+        }
+    }
+
+    /// Route cost of the link to a neighbor (perfect link for unknown ones)
+    fn neighbor_cost(&self, id: DroneId) -> f32 {
+        self.neighbors
+            .get(&id.as_u64())
+            .map_or(1.0, |neighbor| neighbor.link.cost())
+    }
+
+    /// Forward a routed message to the next hop
+    ///
+    /// `packet` rebuilds the message with the incremented hop count.
     fn forward_message(
         &mut self,
-        source: DroneId,
         destination: DroneId,
-        payload: Vec<u8, 1024>,
         hop_count: u8,
+        packet: impl FnOnce(u8) -> NetworkMessage,
     ) -> Result<()> {
         // BUG-003 FIX: Strict hop limit validation to prevent loops and overflow
         if hop_count >= MAX_NETWORK_HOPS {
@@ -319,78 +1136,171 @@ impl MeshNetwork {
             return Err(SwarmError::NetworkError);
         }
 
-        // Find route
-        if let Some(_route) = self.routes.get(&destination.as_u64()) {
-            let _msg = NetworkMessage::Data {
-                source,
-                destination,
-                payload,
-                hop_count: new_hop_count,
-            };
-
-            // SIMULATION MODE: In production, forward to next hop via hardware interface
-            #[cfg(feature = "hardware")]
-            unimplemented!("Hardware radio forwarding not yet implemented");
-
-            #[cfg(not(feature = "hardware"))]
-            {
-                self.stats.messages_sent += 1;
-                Ok(())
+        match self.active_route(destination) {
+            Some(route) if self.neighbors.contains_key(&route.next_hop.as_u64()) => {
+                self.unicast(route.next_hop, &packet(new_hop_count))
+            }
+            Some(route) => {
+                self.stats.messages_dropped += 1;
+                self.handle_link_break(route.next_hop)?;
+                Err(SwarmError::NetworkError)
+            }
+            None => {
+                // Tell upstream nodes to stop using us for this destination
+                self.stats.messages_dropped += 1;
+                let mut unreachable = Vec::new();
+                unreachable.push(destination).ok();
+                self.send_route_error(unreachable)?;
+                Err(SwarmError::NetworkError)
             }
-        } else {
-            // No route - drop or queue
-            self.stats.messages_dropped += 1;
-            Err(SwarmError::NetworkError)
         }
     }
 
-    /// Initiate route discovery
+    /// Pass on a fragment for another drone the way its delivery mode
+    /// forwards data, with the same hop limits
+    fn forward_fragment(
+        &mut self,
+        packet: NetworkMessage,
+        previous_hop: Option<DroneId>,
+    ) -> Result<()> {
+        let NetworkMessage::Fragment {
+            source,
+            destination,
+            fragment,
+            hop_count,
+            delivery,
+        } = packet
+        else {
+            return Err(SwarmError::InvalidMessage);
+        };
+
+        let rebuild = |hop_count| NetworkMessage::Fragment {
+            source,
+            destination,
+            fragment,
+            hop_count,
+            delivery,
+        };
+        match delivery {
+            FragmentDelivery::Routed => self.forward_message(destination, hop_count, rebuild),
+            FragmentDelivery::Flood { .. } => {
+                let forward_hops = hop_count.saturating_add(1);
+                if forward_hops >= MAX_NETWORK_HOPS {
+                    self.stats.messages_dropped += 1;
+                    return Ok(());
+                }
+                self.broadcast(&rebuild(forward_hops))
+            }
+            FragmentDelivery::Geographic { .. } => {
+                let new_hop_count = hop_count.saturating_add(1);
+                if new_hop_count > MAX_NETWORK_HOPS {
+                    self.stats.messages_dropped += 1;
+                    return Err(SwarmError::NetworkError);
+                }
+                self.forward_geographic(rebuild(new_hop_count), previous_hop)
+            }
+        }
+    }
+
+    /// Initiate route discovery
     fn initiate_route_discovery(&mut self, destination: DroneId) -> Result<()> {
+        // Only on-demand routing discovers; other modes wait for proactive state
+        if self.routing_protocol != RoutingProtocol::AdaptiveMesh {
+            return Ok(());
+        }
+
         self.sequence_number += 1;
-        let _msg = NetworkMessage::RouteRequest {
+
+        let msg = NetworkMessage::RouteRequest {
             source: self.local_id,
             destination,
             sequence: self.sequence_number,
+            hop_count: 0,
+            metric: 0.0,
         };
-        // Broadcast to all neighbors
-        Ok(())
+        self.broadcast(&msg)
     }
 
     /// Handle route request
+    ///
+    /// Each request is processed once, however many neighbors rebroadcast it,
+    /// unless a later copy took a cheaper path: that copy is processed again
+    /// so the reverse route and the reply follow stable links.
     fn handle_route_request(
         &mut self,
         source: DroneId,
         destination: DroneId,
-        _sequence: u32,
+        sequence: u32,
+        hop_count: u8,
+        metric: f32,
+        sender_addr: NetworkAddress,
     ) -> Result<()> {
+        if source == self.local_id {
+            return Ok(());
+        }
+
+        // The reverse path runs through the neighbor we heard the request from
+        let previous_hop = match self.neighbor_at(sender_addr) {
+            Some(id) => id,
+            None => {
+                self.seen_before(source, sequence);
+                self.stats.messages_dropped += 1;
+                return Ok(());
+            }
+        };
+        let reverse_metric = metric + self.neighbor_cost(previous_hop);
+        if self.seen_before(source, sequence) {
+            let cheaper = self
+                .routes
+                .get(&source.as_u64())
+                .is_some_and(|route| route.sequence == sequence && reverse_metric < route.metric);
+            if !cheaper {
+                return Ok(());
+            }
+        }
+
+        let reverse_hops = hop_count.saturating_add(1);
+        self.update_route(source, previous_hop, reverse_hops, sequence, reverse_metric)?;
+
         if destination == self.local_id {
-            // We are the destination - send route reply
-            let _reply = NetworkMessage::RouteReply {
+            // We are the destination - answer with a fresh sequence number
+            self.sequence_number += 1;
+            let reply = NetworkMessage::RouteReply {
                 source: destination,
                 destination: source,
                 next_hop: self.local_id,
                 hop_count: 0,
+                sequence: self.sequence_number,
+                metric: 0.0,
             };
-            // SIMULATION MODE: In production, send reply via hardware interface
-            #[cfg(feature = "hardware")]
-            unimplemented!("Hardware radio transmission not yet implemented");
-        } else if let Some(route) = self.routes.get(&destination.as_u64()) {
-            // We have a route - send reply
-            let _reply = NetworkMessage::RouteReply {
-                source: destination,
-                destination: source,
-                next_hop: route.next_hop,
-                hop_count: route.hop_count,
+            return self.unicast(previous_hop, &reply);
+        }
+
+        if let Some(route) = self.active_route(destination) {
+            // Intermediate reply, unless the route leads back where the request came from
+            if route.next_hop != previous_hop {
+                let reply = NetworkMessage::RouteReply {
+                    source: destination,
+                    destination: source,
+                    next_hop: self.local_id,
+                    hop_count: route.hop_count,
+                    sequence: route.sequence,
+                    metric: route.metric,
+                };
+                return self.unicast(previous_hop, &reply);
+            }
+        }
+
+        if reverse_hops < MAX_NETWORK_HOPS {
+            let msg = NetworkMessage::RouteRequest {
+                source,
+                destination,
+                sequence,
+                hop_count: reverse_hops,
+                metric: reverse_metric,
             };
-            // SIMULATION MODE: In production, send reply via hardware interface
-            #[cfg(feature = "hardware")]
-            unimplemented!("Hardware radio transmission not yet implemented");
-        } else {
-            // SIMULATION MODE: In production, forward request to neighbors
-            #[cfg(feature = "hardware")]
-            unimplemented!("Hardware radio forwarding not yet implemented");
+            self.broadcast(&msg)?;
         }
-        #[cfg(not(feature = "hardware"))]
         Ok(())
     }
 
@@ -401,44 +1311,845 @@ impl MeshNetwork {
         destination: DroneId,
         next_hop: DroneId,
         hop_count: u8,
+        sequence: u32,
+        metric: f32,
     ) -> Result<()> {
+        if source == self.local_id {
+            return Ok(());
+        }
+
+        // Forward route via the drone that transmitted the reply
+        let forward_hops = hop_count.saturating_add(1);
+        let forward_metric = metric + self.neighbor_cost(next_hop);
+        self.update_route(source, next_hop, forward_hops, sequence, forward_metric)?;
+
         if destination == self.local_id {
-            // Route reply is for us - install route
+            // Route is ours - queued messages were flushed when it was installed
+            return Ok(());
+        }
+
+        // Relay the reply along the reverse path
+        match self.active_route(destination) {
+            Some(route) => {
+                let reply = NetworkMessage::RouteReply {
+                    source,
+                    destination,
+                    next_hop: self.local_id,
+                    hop_count: forward_hops,
+                    sequence,
+                    metric: forward_metric,
+                };
+                self.unicast(route.next_hop, &reply)
+            }
+            None => {
+                self.stats.messages_dropped += 1;
+                Ok(())
+            }
+        }
+    }
+
+    /// Handle route error
+    fn handle_route_error(&mut self, sender: DroneId, unreachable: &[DroneId]) -> Result<()> {
+        let mut lost = Vec::new();
+        for destination in unreachable {
+            let through_sender = self
+                .routes
+                .get(&destination.as_u64())
+                .is_some_and(|route| route.next_hop == sender);
+            if through_sender {
+                self.routes.remove(&destination.as_u64());
+                lost.push(*destination).ok();
+            }
+        }
+
+        // Propagate only if we actually lost routes, which bounds the flood
+        if lost.is_empty() {
+            Ok(())
+        } else {
+            self.send_route_error(lost)
+        }
+    }
+
+    /// Drop a neighbor and every route through it, and report them
+    fn handle_link_break(&mut self, neighbor: DroneId) -> Result<()> {
+        self.neighbors.remove(&neighbor.as_u64());
+
+        let mut broken: Vec<u64, MAX_ROUTES> = Vec::new();
+        for (key, route) in self.routes.iter() {
+            if route.next_hop == neighbor {
+                broken.push(*key).ok();
+            }
+        }
+
+        let mut lost = Vec::new();
+        for key in broken {
+            if let Some(route) = self.routes.remove(&key) {
+                lost.push(route.destination).ok();
+            }
+        }
+
+        if lost.is_empty() {
+            Ok(())
+        } else {
+            self.send_route_error(lost)
+        }
+    }
+
+    /// Broadcast a route error for destinations we can no longer reach
+    fn send_route_error(&mut self, unreachable: Vec<DroneId, MAX_UNREACHABLE>) -> Result<()> {
+        let msg = NetworkMessage::RouteError {
+            sender: self.local_id,
+            unreachable,
+        };
+        self.broadcast(&msg)
+    }
+
+    /// Install or refresh a route if it is new, fresher, or cheaper
+    ///
+    /// Returns whether the route was installed.
+    fn update_route(
+        &mut self,
+        destination: DroneId,
+        next_hop: DroneId,
+        hop_count: u8,
+        sequence: u32,
+        metric: f32,
+    ) -> Result<bool> {
+        if destination == self.local_id || !self.may_relay(next_hop, destination) {
+            return Ok(false);
+        }
+
+        let now = Self::get_time();
+        let replace = match self.routes.get(&destination.as_u64()) {
+            None => true,
+            Some(route) => {
+                // Sequence numbers compare with wrap-around (RFC 3561 6.1)
+                let freshness = sequence.wrapping_sub(route.sequence) as i32;
+                now.saturating_sub(route.last_updated) > ACTIVE_ROUTE_TIMEOUT_MS
+                    || freshness > 0
+                    || (freshness == 0 && metric < route.metric)
+            }
+        };
+
+        if replace {
             let route = Route {
-                destination: source,
+                destination,
                 next_hop,
-                hop_count: hop_count + 1,
-                metric: (hop_count + 1) as f32,
-                sequence: 0,
-                last_updated: Self::get_time(),
+                hop_count,
+                metric,
+                sequence,
+                last_updated: now,
             };
-
             self.routes
-                .insert(source.as_u64(), route)
+                .insert(destination.as_u64(), route)
                 .map_err(|_| SwarmError::ResourceExhausted)?;
+        }
+
+        self.flush_message_queue(destination)?;
+        Ok(replace)
+    }
 
-            // Send queued messages
-            self.flush_message_queue(source)?;
+    /// Check whether `next_hop` may carry traffic for `destination`
+    fn may_relay(&self, next_hop: DroneId, destination: DroneId) -> bool {
+        next_hop == destination || !self.untrusted.contains(&next_hop)
+    }
+
+    /// Look up a live route, refreshing its lifetime or expiring it
+    fn active_route(&mut self, destination: DroneId) -> Option<Route> {
+        let now = Self::get_time();
+        let route = self.routes.get_mut(&destination.as_u64())?;
+
+        if now.saturating_sub(route.last_updated) > ACTIVE_ROUTE_TIMEOUT_MS {
+            self.routes.remove(&destination.as_u64());
+            return None;
+        }
+
+        route.last_updated = now;
+        Some(*route)
+    }
+
+    /// Find the neighbor bound to a link-layer address
+    fn neighbor_at(&self, address: NetworkAddress) -> Option<DroneId> {
+        self.neighbors
+            .values()
+            .find(|neighbor| neighbor.address == address)
+            .map(|neighbor| neighbor.id)
+    }
+
+    /// Check a flooded message against the duplicate cache, recording it if new
+    fn seen_before(&mut self, source: DroneId, sequence: u32) -> bool {
+        let key = (source.as_u64(), sequence);
+        if self.seen_broadcasts.iter().any(|seen| *seen == key) {
+            return true;
+        }
+
+        if self.seen_broadcasts.is_full() {
+            self.seen_broadcasts.pop_front();
+        }
+        self.seen_broadcasts.push_back(key).ok();
+        false
+    }
+
+    /// Handle flooded data
+    fn handle_flood(
+        &mut self,
+        source: DroneId,
+        destination: DroneId,
+        sequence: u32,
+        payload: Vec<u8, 1024>,
+        hop_count: u8,
+    ) -> Result<Option<Vec<u8, 1024>>> {
+        if source == self.local_id || self.seen_before(source, sequence) {
+            return Ok(None);
+        }
+
+        if destination == self.local_id {
+            self.stats.messages_received += 1;
+            return Ok(Some(payload));
+        }
+
+        // TTL: the last hop allowed by MAX_NETWORK_HOPS does not rebroadcast
+        let forward_hops = hop_count.saturating_add(1);
+        if forward_hops >= MAX_NETWORK_HOPS {
+            self.stats.messages_dropped += 1;
+            return Ok(None);
+        }
+
+        let msg = NetworkMessage::Flood {
+            source,
+            destination,
+            sequence,
+            payload,
+            hop_count: forward_hops,
+        };
+        self.broadcast(&msg)?;
+        Ok(None)
+    }
+
+    /// Handle sink beacon (gradient routing)
+    ///
+    /// The gradient toward a sink is an ordinary route through the neighbor
+    /// that offered the cheapest path in the freshest beacon round; it is only
+    /// rebroadcast when it improves, which bounds the flood.
+    fn handle_sink_beacon(
+        &mut self,
+        sink: DroneId,
+        sequence: u32,
+        hop_count: u8,
+        metric: f32,
+        sender_addr: NetworkAddress,
+    ) -> Result<()> {
+        if sink == self.local_id {
+            return Ok(());
+        }
+
+        let previous_hop = match self.neighbor_at(sender_addr) {
+            Some(id) => id,
+            None => {
+                self.stats.messages_dropped += 1;
+                return Ok(());
+            }
+        };
+
+        let hops = hop_count.saturating_add(1);
+        let cost = metric + self.neighbor_cost(previous_hop);
+        if self.update_route(sink, previous_hop, hops, sequence, cost)? && hops < MAX_NETWORK_HOPS {
+            let msg = NetworkMessage::SinkBeacon {
+                sink,
+                sequence,
+                hop_count: hops,
+                metric: cost,
+            };
+            self.broadcast(&msg)?;
+        }
+        Ok(())
+    }
+
+    /// Announce this drone as a sink, refreshing the gradient toward it
+    ///
+    /// Called periodically by [`MeshNetwork::update`] when this drone is a
+    /// sink in gradient mode.
+    pub fn broadcast_sink_beacon(&mut self) -> Result<()> {
+        self.sequence_number += 1;
+        self.last_advertisement = Some(Self::get_time());
+        let msg = NetworkMessage::SinkBeacon {
+            sink: self.local_id,
+            sequence: self.sequence_number,
+            hop_count: 0,
+            metric: 0.0,
+        };
+        self.broadcast(&msg)
+    }
+
+    /// Forward position-addressed data or a geographic fragment one hop (GPSR)
+    ///
+    /// Greedy mode hands the packet to the neighbor closest to the target.
+    /// At a local minimum the packet switches to perimeter mode and walks the
+    /// faces of the planarized neighbor graph by the right-hand rule until it
+    /// reaches a drone closer to the target than where it got stuck.
+    fn forward_geographic(
+        &mut self,
+        mut packet: NetworkMessage,
+        previous_hop: Option<DroneId>,
+    ) -> Result<()> {
+        let (destination, target, header) = match &mut packet {
+            NetworkMessage::GeoData {
+                destination,
+                target,
+                perimeter,
+                ..
+            }
+            | NetworkMessage::Fragment {
+                destination,
+                delivery: FragmentDelivery::Geographic { target, perimeter },
+                ..
+            } => (*destination, *target, perimeter),
+            _ => return Err(SwarmError::InvalidMessage),
+        };
+        let perimeter = *header;
+
+        let here = match self.position {
+            Some(position) => position,
+            None => {
+                self.stats.messages_dropped += 1;
+                return Err(SwarmError::NetworkError);
+            }
+        };
+
+        let mut neighbors: Vec<(DroneId, Position), MAX_NEIGHBORS> = Vec::new();
+        for neighbor in self.neighbors.values() {
+            if !self.untrusted.contains(&neighbor.id) {
+                neighbors.push((neighbor.id, neighbor.position)).ok();
+            }
+        }
+
+        // Perimeter mode ends once we are closer than where it began
+        let perimeter = perimeter.filter(|state| {
+            planar_distance(&here, &target) >= planar_distance(&state.entered_at, &target)
+        });
+
+        let (next_hop, perimeter) = if self.neighbors.contains_key(&destination.as_u64()) {
+            (Some(destination), None)
+        } else if let Some(state) = perimeter {
+            let planar = gabriel_neighbors(&here, &neighbors);
+            let arrived_from = previous_hop
+                .and_then(|id| planar.iter().find(|(n, _)| *n == id))
+                .map_or(bearing(&here, &target), |(_, position)| {
+                    bearing(&here, position)
+                });
+            match self.perimeter_next_hop(here, target, state, arrived_from, &planar) {
+                Some((next, state)) => (Some(next), Some(state)),
+                None => (None, None),
+            }
         } else {
-            // Forward route reply
+            match greedy_next_hop(&here, &target, &neighbors) {
+                Some(next) => (Some(next), None),
+                None => {
+                    // Local minimum: start on the face crossed by the line to the target
+                    let planar = gabriel_neighbors(&here, &neighbors);
+                    match next_counterclockwise(&here, bearing(&here, &target), &planar) {
+                        Some(next) => (
+                            Some(next),
+                            Some(PerimeterState {
+                                entered_at: here,
+                                face_entry: here,
+                                first_edge: (self.local_id, next),
+                            }),
+                        ),
+                        None => (None, None),
+                    }
+                }
+            }
+        };
+
+        let Some(next_hop) = next_hop else {
+            self.stats.messages_dropped += 1;
+            return Err(SwarmError::NetworkError);
+        };
+
+        *header = perimeter;
+        self.unicast(next_hop, &packet)
+    }
+
+    /// Pick the next perimeter hop, changing faces where the edge crosses the
+    /// line from the perimeter entry point to the target
+    ///
+    /// Returns `None` when the walk is back on its first edge: the target is
+    /// unreachable.
+    fn perimeter_next_hop(
+        &self,
+        here: Position,
+        target: Position,
+        mut state: PerimeterState,
+        arrived_from: f32,
+        planar: &[(DroneId, Position)],
+    ) -> Option<(DroneId, PerimeterState)> {
+        let mut next = next_counterclockwise(&here, arrived_from, planar)?;
+        let mut changed_face = false;
+
+        for _ in 0..planar.len() {
+            let position = planar.iter().find(|(id, _)| *id == next)?.1;
+            let crossing = segment_intersection(&here, &position, &state.entered_at, &target);
+            match crossing {
+                Some(point)
+                    if planar_distance(&point, &target)
+                        < planar_distance(&state.face_entry, &target) =>
+                {
+                    state.face_entry = point;
+                    next = next_counterclockwise(&here, bearing(&here, &position), planar)?;
+                    state.first_edge = (self.local_id, next);
+                    changed_face = true;
+                }
+                _ => break,
+            }
+        }
+
+        // Traversing the face's first edge again means we went all the way round
+        if !changed_face && state.first_edge == (self.local_id, next) {
+            return None;
+        }
+        Some((next, state))
+    }
+
+    /// Handle location service update
+    fn handle_location_update(
+        &mut self,
+        drone: DroneId,
+        position: Position,
+        sequence: u32,
+        hop_count: u8,
+    ) -> Result<()> {
+        if drone == self.local_id || self.seen_before(drone, sequence) {
+            return Ok(());
+        }
+
+        self.record_location(drone, position)?;
+
+        let forward_hops = hop_count.saturating_add(1);
+        if forward_hops < MAX_NETWORK_HOPS {
+            let msg = NetworkMessage::LocationUpdate {
+                drone,
+                position,
+                sequence,
+                hop_count: forward_hops,
+            };
+            self.broadcast(&msg)?;
+        }
+        Ok(())
+    }
+
+    /// Publish our position through the location service
+    ///
+    /// Called periodically by [`MeshNetwork::update`] in geographic mode once
+    /// the position is known.
+    pub fn broadcast_location(&mut self) -> Result<()> {
+        let position = self.position.ok_or(SwarmError::InvalidParameter)?;
+        self.sequence_number += 1;
+        self.last_advertisement = Some(Self::get_time());
+        let msg = NetworkMessage::LocationUpdate {
+            drone: self.local_id,
+            position,
+            sequence: self.sequence_number,
+            hop_count: 0,
+        };
+        self.broadcast(&msg)
+    }
+
+    /// Store a drone's position, evicting the stalest entry when full
+    fn record_location(&mut self, drone: DroneId, position: Position) -> Result<()> {
+        let key = drone.as_u64();
+        if !self.locations.contains_key(&key) && self.locations.len() == MAX_LOCATIONS {
+            let stalest = self
+                .locations
+                .iter()
+                .min_by_key(|(_, entry)| entry.updated)
+                .map(|(key, _)| *key);
+            if let Some(stalest) = stalest {
+                self.locations.remove(&stalest);
+            }
+        }
+
+        let entry = LocationEntry {
+            position,
+            updated: Self::get_time(),
+        };
+        self.locations
+            .insert(key, entry)
+            .map_err(|_| SwarmError::ResourceExhausted)?;
+
+        if self.routing_protocol == RoutingProtocol::Geographic {
+            self.flush_message_queue(drone)?;
         }
         Ok(())
     }
 
+    /// Whether a periodic advertisement is due
+    fn advertisement_due(&self, now: u64, interval_ms: u64) -> bool {
+        self.last_advertisement
+            .is_none_or(|last| now.saturating_sub(last) >= interval_ms)
+    }
+
     /// Handle link state update
     fn handle_link_state_update(
         &mut self,
-        _sender: DroneId,
-        _neighbors: Vec<(DroneId, f32), MAX_NEIGHBORS>,
-        _sequence: u32,
+        sender: DroneId,
+        neighbors: Vec<(DroneId, f32), MAX_NEIGHBORS>,
+        sequence: u32,
+        mprs: Vec<DroneId, MAX_NEIGHBORS>,
+        hop_count: u8,
+        sender_addr: NetworkAddress,
+    ) -> Result<()> {
+        if sender == self.local_id {
+            return Ok(());
+        }
+
+        // Older updates are dropped. A repeat is not processed again, but a copy
+        // from an MPR selector is still relayed if no earlier copy was
+        // (RFC 3626 section 3.4.1)
+        if let Some(entry) = self.topology.get(&sender.as_u64()) {
+            let age = sequence.wrapping_sub(entry.sequence) as i32;
+            if age < 0 || (age == 0 && entry.retransmitted) {
+                return Ok(());
+            }
+            if age == 0 {
+                return self.relay_link_state(sender, sequence, mprs, hop_count, sender_addr);
+            }
+        }
+
+        // Heard straight from the originator: track whether it relays through us
+        if hop_count == 0 {
+            let selected = mprs.contains(&self.local_id);
+            let known = self.mpr_selectors.iter().position(|id| *id == sender);
+            match (selected, known) {
+                (true, None) => {
+                    self.mpr_selectors.push(sender).ok();
+                }
+                (false, Some(index)) => {
+                    self.mpr_selectors.swap_remove(index);
+                }
+                _ => {}
+            }
+        }
+
+        let entry = TopologyEntry {
+            neighbors,
+            sequence,
+            received_at: Self::get_time(),
+            retransmitted: false,
+        };
+        self.topology
+            .insert(sender.as_u64(), entry)
+            .map_err(|_| SwarmError::ResourceExhausted)?;
+
+        self.relay_link_state(sender, sequence, mprs, hop_count, sender_addr)?;
+
+        if self.routing_protocol == RoutingProtocol::LinkState {
+            self.compute_routes()?;
+        }
+        Ok(())
+    }
+
+    /// Retransmit a stored link state update if the neighbor that sent this
+    /// copy selected us as a multipoint relay
+    fn relay_link_state(
+        &mut self,
+        sender: DroneId,
+        sequence: u32,
+        mprs: Vec<DroneId, MAX_NEIGHBORS>,
+        hop_count: u8,
+        sender_addr: NetworkAddress,
     ) -> Result<()> {
-        // SIMULATION MODE: In production, update routing table based on link state
-        // This would implement a distance vector or link state routing protocol
-        // Currently stubbed for simulation/testing
-        #[cfg(feature = "hardware")]
-        unimplemented!("Link state routing table updates not yet implemented");
+        let transmitter = self.neighbor_at(sender_addr);
+        let relay = transmitter.is_some_and(|id| self.mpr_selectors.contains(&id));
+        let forward_hops = hop_count.saturating_add(1);
+        if !relay || forward_hops >= MAX_NETWORK_HOPS {
+            return Ok(());
+        }
+        let Some(entry) = self.topology.get_mut(&sender.as_u64()) else {
+            return Ok(());
+        };
+        entry.retransmitted = true;
+        let msg = NetworkMessage::LinkStateUpdate {
+            sender,
+            neighbors: entry.neighbors.clone(),
+            sequence,
+            mprs,
+            hop_count: forward_hops,
+        };
+        self.broadcast(&msg)
+    }
+
+    /// Advertise our links and multipoint relays to the swarm
+    ///
+    /// Called periodically by [`MeshNetwork::update`] in link-state mode.
+    pub fn broadcast_link_state(&mut self) -> Result<()> {
+        self.select_mprs();
+
+        let mut neighbors = Vec::new();
+        for neighbor in self.neighbors.values() {
+            neighbors.push((neighbor.id, neighbor.link_quality)).ok();
+        }
+
+        self.sequence_number += 1;
+        self.last_advertisement = Some(Self::get_time());
+        let msg = NetworkMessage::LinkStateUpdate {
+            sender: self.local_id,
+            neighbors,
+            sequence: self.sequence_number,
+            mprs: self.mprs.clone(),
+            hop_count: 0,
+        };
+        self.broadcast(&msg)
+    }
+
+    /// Select multipoint relays covering every two-hop neighbor
+    ///
+    /// Greedy heuristic from RFC 3626 8.3.1: first take neighbors that are the
+    /// only path to some two-hop neighbor, then repeatedly the neighbor covering
+    /// the most uncovered ones, preferring the better link on ties.
+    fn select_mprs(&mut self) {
+        self.mprs.clear();
+
+        // Two-hop neighbors, learned from our neighbors' own updates
+        let mut uncovered: Vec<DroneId, MAX_TOPOLOGY_ENTRIES> = Vec::new();
+        for neighbor in self.neighbors.values() {
+            if let Some(entry) = self.topology.get(&neighbor.id.as_u64()) {
+                for (id, _) in entry.neighbors.iter() {
+                    let two_hop = *id != self.local_id
+                        && !self.neighbors.contains_key(&id.as_u64())
+                        && !uncovered.contains(id);
+                    if two_hop {
+                        uncovered.push(*id).ok();
+                    }
+                }
+            }
+        }
+
+        let reaches = |topology: &FnvIndexMap<u64, TopologyEntry, MAX_TOPOLOGY_ENTRIES>,
+                       relay: DroneId,
+                       target: DroneId| {
+            topology
+                .get(&relay.as_u64())
+                .is_some_and(|entry| entry.neighbors.iter().any(|(id, _)| *id == target))
+        };
+
+        // Sole providers are mandatory
+        for target in uncovered.iter() {
+            let mut providers = self
+                .neighbors
+                .values()
+                .filter(|n| !self.untrusted.contains(&n.id))
+                .filter(|n| reaches(&self.topology, n.id, *target));
+            if let (Some(only), None) = (providers.next(), providers.next()) {
+                if !self.mprs.contains(&only.id) {
+                    self.mprs.push(only.id).ok();
+                }
+            }
+        }
+        let mprs = &self.mprs;
+        let topology = &self.topology;
+        uncovered.retain(|target| !mprs.iter().any(|relay| reaches(topology, *relay, *target)));
+
+        while !uncovered.is_empty() {
+            let mut best: Option<(usize, f32, DroneId)> = None;
+            for neighbor in self.neighbors.values() {
+                if self.mprs.contains(&neighbor.id) || self.untrusted.contains(&neighbor.id) {
+                    continue;
+                }
+                let covered = uncovered
+                    .iter()
+                    .filter(|target| reaches(&self.topology, neighbor.id, **target))
+                    .count();
+                let better = best.is_none_or(|(count, quality, _)| {
+                    covered > count || (covered == count && neighbor.link_quality > quality)
+                });
+                if covered > 0 && better {
+                    best = Some((covered, neighbor.link_quality, neighbor.id));
+                }
+            }
+
+            let Some((_, _, relay)) = best else { break };
+            self.mprs.push(relay).ok();
+            let topology = &self.topology;
+            uncovered.retain(|target| !reaches(topology, relay, *target));
+        }
+    }
+
+    /// Rebuild the routing table from the topology database (Dijkstra)
+    ///
+    /// Link cost is the inverse of link quality, so two good hops beat one
+    /// poor one.
+    fn compute_routes(&mut self) -> Result<()> {
+        let mut nodes: Vec<PathNode, MAX_ROUTES> = Vec::new();
+        for neighbor in self.neighbors.values() {
+            nodes
+                .push(PathNode {
+                    id: neighbor.id,
+                    cost: Self::link_cost(neighbor.link_quality),
+                    first_hop: neighbor.id,
+                    hop_count: 1,
+                    settled: false,
+                })
+                .ok();
+        }
+
+        loop {
+            let next = nodes
+                .iter()
+                .enumerate()
+                .filter(|(_, node)| !node.settled)
+                .min_by(|(_, a), (_, b)| a.cost.total_cmp(&b.cost))
+                .map(|(index, _)| index);
+            let Some(index) = next else { break };
+            nodes[index].settled = true;
+            let current = nodes[index];
+
+            // Untrusted drones are reachable but never relay
+            if current.hop_count >= MAX_NETWORK_HOPS || self.untrusted.contains(&current.id) {
+                continue;
+            }
+            let Some(entry) = self.topology.get(&current.id.as_u64()) else {
+                continue;
+            };
+
+            for (id, quality) in entry.neighbors.iter() {
+                if *id == self.local_id {
+                    continue;
+                }
+                let cost = current.cost + Self::link_cost(*quality);
+                match nodes.iter_mut().find(|node| node.id == *id) {
+                    Some(node) => {
+                        if !node.settled && cost < node.cost {
+                            node.cost = cost;
+                            node.first_hop = current.first_hop;
+                            node.hop_count = current.hop_count + 1;
+                        }
+                    }
+                    None => {
+                        // Table full: farther drones stay unreachable
+                        nodes
+                            .push(PathNode {
+                                id: *id,
+                                cost,
+                                first_hop: current.first_hop,
+                                hop_count: current.hop_count + 1,
+                                settled: false,
+                            })
+                            .ok();
+                    }
+                }
+            }
+        }
+
+        let now = Self::get_time();
+        self.routes.clear();
+        for node in nodes.iter() {
+            let sequence = self
+                .topology
+                .get(&node.id.as_u64())
+                .map_or(0, |entry| entry.sequence);
+            let route = Route {
+                destination: node.id,
+                next_hop: node.first_hop,
+                hop_count: node.hop_count,
+                metric: node.cost,
+                sequence,
+                last_updated: now,
+            };
+            self.routes
+                .insert(node.id.as_u64(), route)
+                .map_err(|_| SwarmError::ResourceExhausted)?;
+        }
+
+        for node in nodes.iter() {
+            if self.message_queue.iter().any(|m| m.destination == node.id) {
+                self.flush_message_queue(node.id)?;
+            }
+        }
+        Ok(())
+    }
+
+    /// Route cost of a link: 1 for a perfect link, growing as quality drops
+    ///
+    /// Inverse of [`LinkEstimator::quality`], so advertised qualities map
+    /// back to the estimator's cost.
+    fn link_cost(quality: f32) -> f32 {
+        1.0 / quality.clamp(0.01, 1.0)
+    }
+
+    /// Run periodic route maintenance (call in main loop)
+    ///
+    /// Expires idle routes and retries route discovery for queued messages with
+    /// exponential backoff, dropping them after [`MAX_ROUTE_REQUEST_RETRIES`]
+    /// unanswered retries. In link-state and gradient mode it also sends the
+    /// periodic link state updates or sink beacons.
+    pub fn update(&mut self) -> Result<()> {
+        let now = Self::get_time();
+        self.routes
+            .retain(|_, route| now.saturating_sub(route.last_updated) <= ACTIVE_ROUTE_TIMEOUT_MS);
+
+        if self.routing_protocol == RoutingProtocol::LinkState {
+            self.topology
+                .retain(|_, entry| now.saturating_sub(entry.received_at) <= TOPOLOGY_HOLD_TIME_MS);
+            let neighbors = &self.neighbors;
+            self.mpr_selectors
+                .retain(|id| neighbors.contains_key(&id.as_u64()));
+
+            if self.advertisement_due(now, LINK_STATE_INTERVAL_MS) {
+                self.broadcast_link_state()?;
+            }
+            self.compute_routes()?;
+        }
+
+        if self.routing_protocol == RoutingProtocol::GradientBased
+            && self.is_sink
+            && self.advertisement_due(now, SINK_BEACON_INTERVAL_MS)
+        {
+            self.broadcast_sink_beacon()?;
+        }
+
+        if self.routing_protocol == RoutingProtocol::Geographic {
+            self.locations
+                .retain(|_, entry| now.saturating_sub(entry.updated) <= LOCATION_TIMEOUT_MS);
+            if self.position.is_some() && self.advertisement_due(now, LOCATION_UPDATE_INTERVAL_MS) {
+                self.broadcast_location()?;
+            }
+        }
+
+        self.fragmentation.tick()?;
+        self.flush_fragments()?;
+
+        // Destinations whose discovery timed out, each handled once
+        let mut timed_out: Vec<(DroneId, u8), 16> = Vec::new();
+        for queued in &self.message_queue {
+            let timeout = ROUTE_DISCOVERY_TIMEOUT_MS << queued.retry_count;
+            let already_listed = timed_out.iter().any(|(d, _)| *d == queued.destination);
+            if now.saturating_sub(queued.timestamp) > timeout && !already_listed {
+                timed_out
+                    .push((queued.destination, queued.retry_count))
+                    .ok();
+            }
+        }
+
+        for (destination, retries) in timed_out {
+            if retries >= MAX_ROUTE_REQUEST_RETRIES {
+                let before = self.message_queue.len();
+                self.message_queue
+                    .retain(|queued| queued.destination != destination);
+                self.stats.messages_dropped += (before - self.message_queue.len()) as u64;
+            } else {
+                for queued in self
+                    .message_queue
+                    .iter_mut()
+                    .filter(|queued| queued.destination == destination)
+                {
+                    queued.retry_count = retries + 1;
+                    queued.timestamp = now;
+                }
+                self.initiate_route_discovery(destination)?;
+            }
+        }
 
-        #[cfg(not(feature = "hardware"))]
         Ok(())
     }
 
@@ -486,39 +2197,133 @@ impl MeshNetwork {
 
     /// Broadcast hello message
     pub fn broadcast_hello(&mut self, position: Position) -> Result<()> {
+        self.position = Some(position);
         self.sequence_number += 1;
-        let _msg = NetworkMessage::Hello {
+        let msg = NetworkMessage::Hello {
             sender: self.local_id,
             position,
             sequence: self.sequence_number,
         };
-        // SIMULATION MODE: In production, broadcast to all neighbors via hardware
-        #[cfg(feature = "hardware")]
-        unimplemented!("Hardware broadcast not yet implemented");
-
-        #[cfg(not(feature = "hardware"))]
-        Ok(())
+        self.broadcast(&msg)
     }
 
     /// Send heartbeat to maintain connections
+    ///
+    /// Carries our reception ratio from each neighbor, which they use as
+    /// their forward delivery ratio. Call once per heartbeat interval: each
+    /// call also charges silent neighbors a missed heartbeat.
     pub fn send_heartbeat(&mut self) -> Result<()> {
-        let _msg = NetworkMessage::Heartbeat {
+        let mut reception = Vec::new();
+        for neighbor in self.neighbors.values_mut() {
+            neighbor.link.end_interval();
+            let ratio = (neighbor.link.reverse_ratio * 255.0) as u8;
+            reception.push((neighbor.id, ratio)).ok();
+        }
+        for (id, _) in reception.iter() {
+            self.refresh_link(*id);
+        }
+
+        self.heartbeat_sequence = self.heartbeat_sequence.wrapping_add(1);
+        let msg = NetworkMessage::Heartbeat {
+            sender: self.local_id,
+            timestamp: Self::get_time(),
+            sequence: self.heartbeat_sequence,
+            reception,
+        };
+        self.broadcast(&msg)
+    }
+
+    /// Probe neighbors for round-trip time; each answers with an echo
+    pub fn send_probe(&mut self) -> Result<()> {
+        let msg = NetworkMessage::Probe {
             sender: self.local_id,
             timestamp: Self::get_time(),
         };
-        // SIMULATION MODE: In production, send heartbeat to all neighbors
-        #[cfg(feature = "hardware")]
-        unimplemented!("Hardware heartbeat transmission not yet implemented");
+        self.broadcast(&msg)
+    }
 
-        #[cfg(not(feature = "hardware"))]
-        Ok(())
+    /// Unicast a message to a direct neighbor
+    fn unicast(&mut self, neighbor: DroneId, msg: &NetworkMessage) -> Result<()> {
+        let address = match self.neighbors.get(&neighbor.as_u64()) {
+            Some(n) => n.address,
+            None => {
+                self.stats.messages_dropped += 1;
+                return Err(SwarmError::NetworkError);
+            }
+        };
+        self.transmit(Some(address), msg)
+    }
+
+    /// Broadcast a message to all nodes in radio range
+    fn broadcast(&mut self, msg: &NetworkMessage) -> Result<()> {
+        self.transmit(None, msg)
+    }
+
+    /// Encode a message into a frame and hand it to the transport
+    fn transmit(&mut self, address: Option<NetworkAddress>, msg: &NetworkMessage) -> Result<()> {
+        let mut buf = [0u8; MAX_FRAME_SIZE];
+        let frame =
+            postcard::to_slice(msg, &mut buf).map_err(|_| SwarmError::SerializationError)?;
+
+        let result = match address {
+            Some(address) => self.transport.send_to(address, frame),
+            None => self.transport.broadcast(frame),
+        };
+
+        match result {
+            Ok(()) => {
+                self.stats.messages_sent += 1;
+                Ok(())
+            }
+            Err(e) => {
+                self.stats.messages_dropped += 1;
+                Err(e)
+            }
+        }
     }
 
     /// Prune dead neighbors
+    ///
+    /// Routes through a pruned neighbor are removed and reported with a route error.
     pub fn prune_neighbors(&mut self, timeout_ms: u32) {
         let current_time = Self::get_time();
-        self.neighbors
-            .retain(|_, neighbor| neighbor.is_alive(current_time, timeout_ms));
+        let mut dead: Vec<DroneId, MAX_NEIGHBORS> = Vec::new();
+        for neighbor in self.neighbors.values() {
+            if !neighbor.is_alive(current_time, timeout_ms) {
+                dead.push(neighbor.id).ok();
+            }
+        }
+
+        for neighbor in dead {
+            // Best effort: the neighbor is gone even if the route error cannot be sent
+            self.handle_link_break(neighbor).ok();
+        }
+    }
+
+    /// Stop relaying through drones the policy does not trust
+    ///
+    /// Untrusted drones stay reachable as destinations, but routes through
+    /// them are dropped and they are no longer chosen as next hop, multipoint
+    /// relay or greedy forwarder. Call again whenever trust scores change.
+    pub fn exclude_untrusted(&mut self, policy: &impl TrustPolicy) {
+        self.untrusted.clear();
+        let known = self
+            .neighbors
+            .values()
+            .map(|neighbor| neighbor.id)
+            .chain(self.topology.keys().map(|id| DroneId::new(*id)))
+            .chain(self.routes.values().map(|route| route.next_hop));
+        for drone in known {
+            if !self.untrusted.contains(&drone) && !policy.is_trusted(drone) {
+                self.untrusted.push(drone).ok();
+            }
+        }
+
+        let untrusted = &self.untrusted;
+        self.routes.retain(|_, route| {
+            route.next_hop == route.destination || !untrusted.contains(&route.next_hop)
+        });
+        self.mprs.retain(|relay| !untrusted.contains(relay));
     }
 
     /// Get neighbor count
@@ -531,17 +2336,211 @@ impl MeshNetwork {
         self.neighbors.values()
     }
 
+    /// Get the route to a destination, if one is known
+    pub fn route(&self, destination: DroneId) -> Option<&Route> {
+        self.routes.get(&destination.as_u64())
+    }
+
+    /// Get all known routes
+    pub fn routes(&self) -> impl Iterator<Item = &Route> {
+        self.routes.values()
+    }
+
+    /// Get the active routing protocol
+    pub fn routing_protocol(&self) -> RoutingProtocol {
+        self.routing_protocol
+    }
+
+    /// Set this drone's own position (used by geographic routing)
+    pub fn set_position(&mut self, position: Position) {
+        self.position = Some(position);
+    }
+
+    /// Get this drone's own position, if known
+    pub fn position(&self) -> Option<Position> {
+        self.position
+    }
+
+    /// Look up a drone's position in the location service
+    pub fn location(&self, drone: DroneId) -> Option<Position> {
+        self.locations
+            .get(&drone.as_u64())
+            .map(|entry| entry.position)
+    }
+
+    /// Tell the location service where a drone is (e.g. from a mission plan)
+    pub fn set_location(&mut self, drone: DroneId, position: Position) -> Result<()> {
+        self.record_location(drone, position)
+    }
+
+    /// Make this drone a data sink (ground station or leader) for gradient routing
+    pub fn set_sink(&mut self, is_sink: bool) {
+        self.is_sink = is_sink;
+    }
+
+    /// Check whether this drone is a data sink
+    pub fn is_sink(&self) -> bool {
+        self.is_sink
+    }
+
+    /// Get the neighbors selected as multipoint relays
+    pub fn mprs(&self) -> &[DroneId] {
+        &self.mprs
+    }
+
+    /// Get the neighbors that selected us as their multipoint relay
+    pub fn mpr_selectors(&self) -> &[DroneId] {
+        &self.mpr_selectors
+    }
+
+    /// Get the topology entry advertised by a drone
+    pub fn topology_entry(&self, drone: DroneId) -> Option<&TopologyEntry> {
+        self.topology.get(&drone.as_u64())
+    }
+
+    /// Number of messages waiting for a route
+    pub fn queued_message_count(&self) -> usize {
+        self.message_queue.len()
+    }
+
     /// Get network statistics
     pub fn statistics(&self) -> &NetworkStats {
         &self.stats
     }
 
+    /// Get the underlying transport
+    pub fn transport(&self) -> &T {
+        &self.transport
+    }
+
+    /// Get the underlying transport mutably
+    pub fn transport_mut(&mut self) -> &mut T {
+        &mut self.transport
+    }
+
     /// Get current time (uses centralized time abstraction)
     fn get_time() -> u64 {
         crate::get_time_ms()
     }
 }
 
+/// Fragmentation settings for a link MTU, leaving room for the routing header
+fn fragment_config(mtu: usize) -> FragmentConfig {
+    FragmentConfig {
+        mtu: mtu
+            .min(MAX_FRAME_SIZE)
+            .saturating_sub(ROUTED_FRAGMENT_OVERHEAD),
+        ..FragmentConfig::default()
+    }
+}
+
+/// Distance in the horizontal plane, where GPSR's planar geometry lives
+fn planar_distance(a: &Position, b: &Position) -> f32 {
+    let dx = a.x - b.x;
+    let dy = a.y - b.y;
+    libm::sqrtf(dx * dx + dy * dy)
+}
+
+/// Bearing from `from` to `to` in radians
+fn bearing(from: &Position, to: &Position) -> f32 {
+    libm::atan2f(to.y - from.y, to.x - from.x)
+}
+
+/// Neighbor strictly closer to the target than we are, closest first
+fn greedy_next_hop(
+    here: &Position,
+    target: &Position,
+    neighbors: &[(DroneId, Position)],
+) -> Option<DroneId> {
+    let own = planar_distance(here, target);
+    neighbors
+        .iter()
+        .map(|(id, position)| (*id, planar_distance(position, target)))
+        .filter(|(_, distance)| *distance < own)
+        .min_by(|a, b| a.1.total_cmp(&b.1))
+        .map(|(id, _)| id)
+}
+
+/// Planarize the neighbor graph with the Gabriel graph rule
+///
+/// An edge to `v` is kept unless some other neighbor lies inside the circle
+/// whose diameter is that edge.
+fn gabriel_neighbors(
+    here: &Position,
+    neighbors: &[(DroneId, Position)],
+) -> Vec<(DroneId, Position), MAX_NEIGHBORS> {
+    let squared = |a: &Position, b: &Position| {
+        let d = planar_distance(a, b);
+        d * d
+    };
+
+    let mut planar = Vec::new();
+    for (id, position) in neighbors {
+        let edge = squared(here, position);
+        let witnessed = neighbors.iter().any(|(other, witness)| {
+            other != id && squared(here, witness) + squared(position, witness) < edge
+        });
+        if !witnessed {
+            planar.push((*id, *position)).ok();
+        }
+    }
+    planar
+}
+
+/// First neighbor counterclockwise from `reference` (right-hand rule)
+///
+/// A neighbor exactly on the reference bearing comes last, so a packet only
+/// goes back where it came from on a dead end.
+fn next_counterclockwise(
+    here: &Position,
+    reference: f32,
+    neighbors: &[(DroneId, Position)],
+) -> Option<DroneId> {
+    const TAU: f32 = 2.0 * core::f32::consts::PI;
+    neighbors
+        .iter()
+        .map(|(id, position)| {
+            let mut delta = bearing(here, position) - reference;
+            while delta <= 1e-6 {
+                delta += TAU;
+            }
+            while delta > TAU + 1e-6 {
+                delta -= TAU;
+            }
+            (*id, delta)
+        })
+        .min_by(|a, b| a.1.total_cmp(&b.1))
+        .map(|(id, _)| id)
+}
+
+/// Crossing point of segments `a`-`b` and `c`-`d`, if they cross
+fn segment_intersection(
+    a: &Position,
+    b: &Position,
+    c: &Position,
+    d: &Position,
+) -> Option<Position> {
+    let (rx, ry) = (b.x - a.x, b.y - a.y);
+    let (sx, sy) = (d.x - c.x, d.y - c.y);
+    let denominator = rx * sy - ry * sx;
+    if denominator.abs() < f32::EPSILON {
+        return None;
+    }
+
+    let (qx, qy) = (c.x - a.x, c.y - a.y);
+    let t = (qx * sy - qy * sx) / denominator;
+    let u = (qx * ry - qy * rx) / denominator;
+    if (0.0..=1.0).contains(&t) && (0.0..=1.0).contains(&u) {
+        Some(Position {
+            x: a.x + t * rx,
+            y: a.y + t * ry,
+            z: a.z,
+        })
+    } else {
+        None
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -571,4 +2570,66 @@ mod tests {
 
         assert_eq!(network.neighbor_count(), 1);
     }
+
+    fn at(x: f32, y: f32) -> Position {
+        Position { x, y, z: 0.0 }
+    }
+
+    #[test]
+    fn test_greedy_picks_closest_to_target() {
+        let neighbors = [
+            (DroneId::new(2), at(10.0, 0.0)),
+            (DroneId::new(3), at(20.0, 5.0)),
+            (DroneId::new(4), at(-10.0, 0.0)),
+        ];
+        let next = greedy_next_hop(&at(0.0, 0.0), &at(100.0, 0.0), &neighbors);
+        assert_eq!(next, Some(DroneId::new(3)));
+
+        // Nobody closer than us: local minimum
+        let next = greedy_next_hop(&at(0.0, 0.0), &at(-5.0, 50.0), &neighbors[..2]);
+        assert_eq!(next, None);
+    }
+
+    #[test]
+    fn test_gabriel_graph_drops_witnessed_edge() {
+        // Drone 3 sits inside the circle on the edge to drone 2
+        let neighbors = [
+            (DroneId::new(2), at(10.0, 0.0)),
+            (DroneId::new(3), at(5.0, 1.0)),
+        ];
+        let planar = gabriel_neighbors(&at(0.0, 0.0), &neighbors);
+        assert_eq!(planar.len(), 1);
+        assert_eq!(planar[0].0, DroneId::new(3));
+    }
+
+    #[test]
+    fn test_right_hand_rule_order() {
+        let neighbors = [
+            (DroneId::new(2), at(0.0, 10.0)),  // north
+            (DroneId::new(3), at(-10.0, 0.0)), // west
+            (DroneId::new(4), at(10.0, 0.0)),  // east
+        ];
+        let here = at(0.0, 0.0);
+
+        // Counterclockwise from east is north; the reference edge itself comes last
+        assert_eq!(
+            next_counterclockwise(&here, 0.0, &neighbors),
+            Some(DroneId::new(2))
+        );
+        assert_eq!(
+            next_counterclockwise(&here, 0.0, &neighbors[2..]),
+            Some(DroneId::new(4))
+        );
+    }
+
+    #[test]
+    fn test_segment_intersection() {
+        let crossing =
+            segment_intersection(&at(0.0, -1.0), &at(0.0, 1.0), &at(-1.0, 0.0), &at(1.0, 0.0));
+        assert_eq!(crossing, Some(at(0.0, 0.0)));
+
+        let parallel =
+            segment_intersection(&at(0.0, 0.0), &at(1.0, 0.0), &at(0.0, 1.0), &at(1.0, 1.0));
+        assert_eq!(parallel, None);
+    }
 }
@@ -0,0 +1,220 @@
+//! Post-quantum hybrid key exchange and dual signatures
+//!
+//! A recorded X25519 exchange can be broken later by a large quantum
+//! computer, and Ed25519 signatures forged. With the `post-quantum` feature
+//! both are paired with their lattice-based counterparts, so an attacker has
+//! to break the classical *and* the post-quantum scheme:
+//! - [`HybridSecretKey`]/[`encapsulate`]: ML-KEM-768 plus X25519. The shared
+//!   secret hashes both component secrets together with the X25519 ciphertext
+//!   and public key, following the X-Wing combiner
+//! - [`CryptoContext::enable_dual_signatures`]: messages sealed with
+//!   [`CryptoContext::encrypt_and_sign_dual`] carry an ML-DSA-65 signature
+//!   next to the Ed25519 one
+//!
+//! The lattice schemes cost kilobytes on the air (see [`HYBRID_CIPHERTEXT_SIZE`]
+//! and [`DUAL_SIGNATURE_SIZE`]); messages carrying them go through
+//! [`crate::fragmentation`] on small-MTU links.
+
+/// ML-DSA-65 signatures (FIPS 204)
+pub mod ml_dsa;
+/// ML-KEM-768 key encapsulation (FIPS 203)
+pub mod ml_kem;
+
+use crate::crypto::{CryptoContext, SIGNATURE_SIZE};
+use crate::rng::SecureRng;
+use crate::types::*;
+use serde::{Deserialize, Serialize};
+use serde_big_array::BigArray;
+use sha3::{Digest, Sha3_256};
+
+/// Hybrid public key: ML-KEM encapsulation key and X25519 public key (bytes)
+pub const HYBRID_PUBLIC_KEY_SIZE: usize = ml_kem::ENCAPSULATION_KEY_SIZE + 32;
+
+/// Hybrid ciphertext: ML-KEM ciphertext and X25519 ephemeral key (bytes)
+pub const HYBRID_CIPHERTEXT_SIZE: usize = ml_kem::CIPHERTEXT_SIZE + 32;
+
+/// Ed25519 plus ML-DSA-65 signature (bytes)
+pub const DUAL_SIGNATURE_SIZE: usize = SIGNATURE_SIZE + ml_dsa::SIGNATURE_SIZE;
+
+/// Context string of the ML-DSA signatures on sealed messages
+pub const DUAL_SIGNATURE_CONTEXT: &[u8] = b"droneswarm-v1 sealed message";
+
+/// Domain separation of the combined shared secret
+const COMBINER_LABEL: &[u8] = b"droneswarm-v1 hybrid kem";
+
+/// Domain separation when expanding a key seed
+const KEM_SEED_CONTEXT: &str = "droneswarm-v1 hybrid kem seed";
+
+/// Recipient's hybrid public key
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct HybridPublicKey {
+    /// ML-KEM-768 encapsulation key
+    #[serde(with = "BigArray")]
+    pub kem: ml_kem::EncapsulationKey,
+    /// X25519 public key
+    pub x25519: [u8; 32],
+}
+
+/// Encapsulated shared secret
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct HybridCiphertext {
+    /// ML-KEM-768 ciphertext
+    #[serde(with = "BigArray")]
+    pub kem: ml_kem::Ciphertext,
+    /// Sender's ephemeral X25519 public key
+    pub x25519: [u8; 32],
+}
+
+/// Hybrid decapsulation key
+pub struct HybridSecretKey {
+    kem: ml_kem::DecapsulationKey,
+    x25519: [u8; 32],
+    public: HybridPublicKey,
+}
+
+impl HybridSecretKey {
+    /// Derive a key pair from a 32-byte seed
+    ///
+    /// # Security Note
+    /// Draw the seed from a hardware TRNG, or use [`Self::generate`].
+    pub fn from_seed(seed: &[u8; 32]) -> Self {
+        let mut expanded = [0u8; 96];
+        let mut xof = blake3::Hasher::new_derive_key(KEM_SEED_CONTEXT);
+        xof.update(seed);
+        xof.finalize_xof().fill(&mut expanded);
+
+        let (mut d, mut z, mut x25519) = ([0u8; 32], [0u8; 32], [0u8; 32]);
+        d.copy_from_slice(&expanded[..32]);
+        z.copy_from_slice(&expanded[32..64]);
+        x25519.copy_from_slice(&expanded[64..]);
+
+        let (ek, kem) = ml_kem::key_gen(&d, &z);
+        Self {
+            kem,
+            x25519,
+            public: HybridPublicKey {
+                kem: ek,
+                x25519: CryptoContext::exchange_public_key(&x25519),
+            },
+        }
+    }
+
+    /// Generate a key pair from the system RNG
+    pub fn generate() -> Result<Self> {
+        let mut seed = [0u8; 32];
+        SecureRng::new()?.fill_bytes(&mut seed)?;
+        Ok(Self::from_seed(&seed))
+    }
+
+    /// Public key to hand to senders
+    pub fn public_key(&self) -> &HybridPublicKey {
+        &self.public
+    }
+
+    /// Recover the shared secret of a ciphertext
+    ///
+    /// A tampered ML-KEM ciphertext does not fail here but yields a different
+    /// secret, so the first message under it fails authentication.
+    pub fn decapsulate(&self, ciphertext: &HybridCiphertext) -> Result<[u8; 32]> {
+        let kem_secret = ml_kem::decapsulate(&self.kem, &ciphertext.kem);
+        let x25519_secret = CryptoContext::key_exchange(&self.x25519, &ciphertext.x25519)?;
+        Ok(combine(
+            &kem_secret,
+            &x25519_secret,
+            &ciphertext.x25519,
+            &self.public.x25519,
+        ))
+    }
+}
+
+/// Encapsulate a fresh shared secret for `public_key`
+///
+/// `randomness` is the ML-KEM message followed by the X25519 ephemeral key.
+pub fn encapsulate(
+    public_key: &HybridPublicKey,
+    randomness: &[u8; 64],
+) -> Result<(HybridCiphertext, [u8; 32])> {
+    let mut m = [0u8; 32];
+    let mut ephemeral = [0u8; 32];
+    m.copy_from_slice(&randomness[..32]);
+    ephemeral.copy_from_slice(&randomness[32..]);
+
+    let (kem_secret, kem_ciphertext) =
+        ml_kem::encapsulate(&public_key.kem, &m).ok_or(SwarmError::InvalidParameter)?;
+    let x25519_ciphertext = CryptoContext::exchange_public_key(&ephemeral);
+    let x25519_secret = CryptoContext::key_exchange(&ephemeral, &public_key.x25519)?;
+
+    let shared = combine(
+        &kem_secret,
+        &x25519_secret,
+        &x25519_ciphertext,
+        &public_key.x25519,
+    );
+    Ok((
+        HybridCiphertext {
+            kem: kem_ciphertext,
+            x25519: x25519_ciphertext,
+        },
+        shared,
+    ))
+}
+
+/// SHA3-256 over both component secrets and the X25519 transcript
+fn combine(
+    kem_secret: &[u8; 32],
+    x25519_secret: &[u8; 32],
+    x25519_ciphertext: &[u8; 32],
+    x25519_public: &[u8; 32],
+) -> [u8; 32] {
+    let mut hasher = Sha3_256::new();
+    hasher.update(COMBINER_LABEL);
+    hasher.update(kem_secret);
+    hasher.update(x25519_secret);
+    hasher.update(x25519_ciphertext);
+    hasher.update(x25519_public);
+    hasher.finalize().into()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// 32 bytes from 64 hex digits, for the reference vectors of both schemes
+    pub fn hex(digits: &str) -> [u8; 32] {
+        let mut out = [0u8; 32];
+        for (i, byte) in out.iter_mut().enumerate() {
+            *byte = u8::from_str_radix(&digits[2 * i..2 * i + 2], 16).unwrap();
+        }
+        out
+    }
+
+    #[test]
+    fn test_hybrid_roundtrip() {
+        let recipient = HybridSecretKey::from_seed(&[4; 32]);
+        let (ciphertext, shared) = encapsulate(recipient.public_key(), &[9; 64]).unwrap();
+        assert_eq!(recipient.decapsulate(&ciphertext).unwrap(), shared);
+    }
+
+    #[test]
+    fn test_either_component_changes_secret() {
+        let recipient = HybridSecretKey::from_seed(&[4; 32]);
+        let (ciphertext, shared) = encapsulate(recipient.public_key(), &[9; 64]).unwrap();
+
+        let mut kem_tampered = ciphertext.clone();
+        kem_tampered.kem[0] ^= 1;
+        assert_ne!(recipient.decapsulate(&kem_tampered).unwrap(), shared);
+
+        let mut x25519_tampered = ciphertext;
+        x25519_tampered.x25519[0] ^= 1;
+        assert_ne!(recipient.decapsulate(&x25519_tampered).unwrap(), shared);
+    }
+
+    #[test]
+    fn test_public_key_encoding() {
+        let recipient = HybridSecretKey::from_seed(&[1; 32]);
+        let mut buf = [0u8; HYBRID_PUBLIC_KEY_SIZE + 8];
+        let encoded = postcard::to_slice(recipient.public_key(), &mut buf).unwrap();
+        let decoded: HybridPublicKey = postcard::from_bytes(encoded).unwrap();
+        assert_eq!(&decoded, recipient.public_key());
+    }
+}
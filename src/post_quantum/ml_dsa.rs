@@ -0,0 +1,796 @@
+//! ML-DSA-65 signatures (FIPS 204)
+//!
+//! Straightforward, allocation-free transcription of the standard in the
+//! same style as [`super::ml_kem`]: branch-free modular arithmetic and the
+//! matrix `Â` sampled entry by entry whenever it is needed. Signing is hedged with
+//! caller-supplied randomness and uses the pure (non-prehashed) variant with
+//! a context string.
+
+use sha3::digest::{ExtendableOutput, Update, XofReader};
+use sha3::{Shake128, Shake256};
+
+/// Coefficients per polynomial
+const N: usize = 256;
+
+/// Modulus
+const Q: i32 = 8380417;
+
+/// Bits dropped from `t`
+const D: usize = 13;
+
+/// Nonzero coefficients of the challenge
+const TAU: usize = 49;
+
+/// Commitment hash size, λ/4 (bytes)
+const C_TILDE_SIZE: usize = 48;
+
+/// Coefficient range of the masking vector
+const GAMMA1: i32 = 1 << 19;
+
+/// Low-order rounding range
+const GAMMA2: i32 = (Q - 1) / 32;
+
+/// Rows of `A`
+const K: usize = 6;
+
+/// Columns of `A`
+const L: usize = 5;
+
+/// Coefficient range of the secrets
+const ETA: i32 = 4;
+
+/// τ · η
+const BETA: i32 = TAU as i32 * ETA;
+
+/// Maximum hint ones
+const OMEGA: usize = 55;
+
+/// Bits per coefficient of `t1`
+const T1_BITS: usize = 10;
+
+/// Bits per coefficient of `s1` and `s2`
+const ETA_BITS: usize = 4;
+
+/// Bits per coefficient of `z`
+const Z_BITS: usize = 20;
+
+/// Bits per coefficient of `w1`
+const W1_BITS: usize = 4;
+
+/// Public key size (bytes)
+pub const PUBLIC_KEY_SIZE: usize = 32 + 32 * K * T1_BITS;
+
+/// Secret key size (bytes)
+pub const SECRET_KEY_SIZE: usize = 128 + 32 * ((K + L) * ETA_BITS + D * K);
+
+/// Signature size (bytes)
+pub const SIGNATURE_SIZE: usize = C_TILDE_SIZE + 32 * L * Z_BITS + OMEGA + K;
+
+/// Maximum context string length (bytes)
+pub const MAX_CONTEXT_SIZE: usize = 255;
+
+/// Encoded public key: `ρ` and `t1`
+pub type PublicKey = [u8; PUBLIC_KEY_SIZE];
+
+/// Encoded secret key: `ρ`, `K`, `tr`, `s1`, `s2` and `t0`
+pub type SecretKey = [u8; SECRET_KEY_SIZE];
+
+/// Encoded signature: `c̃`, `z` and the hint
+pub type Signature = [u8; SIGNATURE_SIZE];
+
+type Poly = [i32; N];
+
+const fn pow_mod(base: i64, mut exp: u32) -> i64 {
+    let q = Q as i64;
+    let mut result = 1;
+    let mut base = base % q;
+    while exp > 0 {
+        if exp & 1 == 1 {
+            result = result * base % q;
+        }
+        base = base * base % q;
+        exp >>= 1;
+    }
+    result
+}
+
+const fn bit_rev8(i: u32) -> u32 {
+    let mut reversed = 0;
+    let mut bit = 0;
+    while bit < 8 {
+        reversed |= ((i >> bit) & 1) << (7 - bit);
+        bit += 1;
+    }
+    reversed
+}
+
+/// `ζ^BitRev8(m)` for the NTT, with ζ = 1753
+const ZETAS: [i32; N] = {
+    let mut table = [0i32; N];
+    let mut m = 0;
+    while m < N {
+        table[m] = pow_mod(1753, bit_rev8(m as u32)) as i32;
+        m += 1;
+    }
+    table
+};
+
+/// 256⁻¹ mod q
+const INV_256: i64 = 8347681;
+
+/// Multiple of q that lifts every input of [`reduce`] above zero
+const REDUCE_OFFSET: i64 = (Q as i64) << 26;
+
+/// `x mod q` for `|x| < 2^48`, without dividing or branching
+fn reduce(x: i64) -> i32 {
+    // 2^23 ≡ 2^13 - 1 (mod q): three folds bring the value below 2q
+    let mut u = (x + REDUCE_OFFSET) as u64;
+    for _ in 0..3 {
+        u = (u >> 23) * 8191 + (u & 0x7F_FFFF);
+    }
+    let r = u as i64 - Q as i64;
+    (r + ((r >> 63) & Q as i64)) as i32
+}
+
+/// Centered representative of `x mod q`, in `[-(q-1)/2, (q-1)/2]`
+fn centered(x: i32) -> i32 {
+    x - ((((Q - 1) / 2 - x) >> 31) & Q)
+}
+
+/// Absolute value of the centered representative of `x mod q`
+fn centered_abs(x: i32) -> i32 {
+    let c = centered(x);
+    let sign = c >> 31;
+    (c ^ sign) - sign
+}
+
+/// SHAKE256 over the concatenation of `parts`
+fn shake256(parts: &[&[u8]], out: &mut [u8]) {
+    let mut xof = Shake256::default();
+    for part in parts {
+        xof.update(part);
+    }
+    xof.finalize_xof().read(out);
+}
+
+/// Number-theoretic transform (Algorithm 41)
+fn ntt(w: &mut Poly) {
+    let mut m = 0;
+    let mut len = 128;
+    while len >= 1 {
+        for start in (0..N).step_by(2 * len) {
+            m += 1;
+            let zeta = ZETAS[m] as i64;
+            for j in start..start + len {
+                let t = reduce(zeta * w[j + len] as i64);
+                w[j + len] = reduce(w[j] as i64 - t as i64);
+                w[j] = reduce(w[j] as i64 + t as i64);
+            }
+        }
+        len /= 2;
+    }
+}
+
+/// Inverse number-theoretic transform (Algorithm 42)
+fn inv_ntt(w: &mut Poly) {
+    let mut m = N;
+    let mut len = 1;
+    while len < N {
+        for start in (0..N).step_by(2 * len) {
+            m -= 1;
+            let zeta = -(ZETAS[m] as i64);
+            for j in start..start + len {
+                let t = w[j] as i64;
+                w[j] = reduce(t + w[j + len] as i64);
+                w[j + len] = reduce(zeta * (t - w[j + len] as i64));
+            }
+        }
+        len *= 2;
+    }
+    for coefficient in w.iter_mut() {
+        *coefficient = reduce(*coefficient as i64 * INV_256);
+    }
+}
+
+/// Accumulate `f ∘ g` in the NTT domain into `acc`
+fn multiply_add_ntt(acc: &mut Poly, f: &Poly, g: &Poly) {
+    for ((a, &x), &y) in acc.iter_mut().zip(f).zip(g) {
+        *a = reduce(*a as i64 + x as i64 * y as i64);
+    }
+}
+
+fn add_assign(f: &mut Poly, g: &Poly) {
+    for (a, &b) in f.iter_mut().zip(g) {
+        *a = reduce(*a as i64 + b as i64);
+    }
+}
+
+fn sub_assign(f: &mut Poly, g: &Poly) {
+    for (a, &b) in f.iter_mut().zip(g) {
+        *a = reduce(*a as i64 - b as i64);
+    }
+}
+
+/// Entry `Â[r][s]` of the public matrix (RejNTTPoly on `ρ ‖ s ‖ r`)
+fn matrix_entry(rho: &[u8; 32], r: usize, s: usize) -> Poly {
+    let mut xof = Shake128::default();
+    xof.update(rho);
+    xof.update(&[s as u8, r as u8]);
+    let mut reader = xof.finalize_xof();
+
+    let mut poly = [0i32; N];
+    let mut filled = 0;
+    let mut bytes = [0u8; 3];
+    while filled < N {
+        reader.read(&mut bytes);
+        let z = bytes[0] as i32 | (bytes[1] as i32) << 8 | ((bytes[2] & 0x7F) as i32) << 16;
+        if z < Q {
+            poly[filled] = z;
+            filled += 1;
+        }
+    }
+    poly
+}
+
+/// `Â · v̂`, left in the NTT domain
+fn matrix_mul(rho: &[u8; 32], v_hat: &[Poly; L]) -> [Poly; K] {
+    let mut product = [[0i32; N]; K];
+    for (r, row) in product.iter_mut().enumerate() {
+        for (s, v) in v_hat.iter().enumerate() {
+            multiply_add_ntt(row, &matrix_entry(rho, r, s), v);
+        }
+    }
+    product
+}
+
+/// Secret polynomial with coefficients in `[-η, η]` (RejBoundedPoly)
+fn sample_bounded(rho_prime: &[u8; 64], index: u16) -> Poly {
+    let mut xof = Shake256::default();
+    xof.update(rho_prime);
+    xof.update(&index.to_le_bytes());
+    let mut reader = xof.finalize_xof();
+
+    let mut poly = [0i32; N];
+    let mut filled = 0;
+    let mut byte = [0u8; 1];
+    while filled < N {
+        reader.read(&mut byte);
+        for half in [byte[0] & 0x0F, byte[0] >> 4] {
+            if half < 9 && filled < N {
+                poly[filled] = reduce((ETA - half as i32) as i64);
+                filled += 1;
+            }
+        }
+    }
+    poly
+}
+
+/// Masking polynomial with coefficients in `(-γ1, γ1]` (ExpandMask)
+fn sample_mask(rho_double_prime: &[u8; 64], index: u16) -> Poly {
+    let mut bytes = [0u8; 32 * Z_BITS];
+    shake256(&[rho_double_prime, &index.to_le_bytes()], &mut bytes);
+    let raw = unpack(Z_BITS, &bytes);
+    let mut poly = [0i32; N];
+    for (coefficient, &value) in poly.iter_mut().zip(&raw) {
+        *coefficient = reduce((GAMMA1 - value as i32) as i64);
+    }
+    poly
+}
+
+/// Challenge with τ coefficients of ±1 (Algorithm 29)
+fn sample_in_ball(c_tilde: &[u8; C_TILDE_SIZE]) -> Poly {
+    let mut xof = Shake256::default();
+    xof.update(c_tilde);
+    let mut reader = xof.finalize_xof();
+    let mut signs = [0u8; 8];
+    reader.read(&mut signs);
+    let signs = u64::from_le_bytes(signs);
+
+    let mut c = [0i32; N];
+    let mut byte = [0u8; 1];
+    for i in N - TAU..N {
+        let j = loop {
+            reader.read(&mut byte);
+            if byte[0] as usize <= i {
+                break byte[0] as usize;
+            }
+        };
+        c[i] = c[j];
+        c[j] = if (signs >> (i + TAU - N)) & 1 == 1 {
+            Q - 1
+        } else {
+            1
+        };
+    }
+    c
+}
+
+/// Pack `bits`-bit values little-endian
+fn pack(bits: usize, values: impl Iterator<Item = u32>, out: &mut [u8]) {
+    let (mut acc, mut held, mut pos) = (0u64, 0usize, 0usize);
+    for value in values {
+        acc |= (value as u64) << held;
+        held += bits;
+        while held >= 8 {
+            out[pos] = acc as u8;
+            pos += 1;
+            acc >>= 8;
+            held -= 8;
+        }
+    }
+}
+
+/// Unpack 256 `bits`-bit values
+fn unpack(bits: usize, bytes: &[u8]) -> [u32; N] {
+    let (mut acc, mut held, mut pos) = (0u64, 0usize, 0usize);
+    let mut values = [0u32; N];
+    for value in values.iter_mut() {
+        while held < bits {
+            acc |= (bytes[pos] as u64) << held;
+            pos += 1;
+            held += 8;
+        }
+        *value = (acc & ((1 << bits) - 1)) as u32;
+        acc >>= bits;
+        held -= bits;
+    }
+    values
+}
+
+/// Pack `b - w` for coefficients in `[b - 2^bits + 1, b]` (BitPack)
+fn pack_offset(bits: usize, b: i32, poly: &Poly, out: &mut [u8]) {
+    pack(bits, poly.iter().map(|&c| (b - centered(c)) as u32), out);
+}
+
+/// Inverse of [`pack_offset`]
+fn unpack_offset(bits: usize, b: i32, bytes: &[u8]) -> Poly {
+    let mut poly = [0i32; N];
+    for (coefficient, &raw) in poly.iter_mut().zip(&unpack(bits, bytes)) {
+        *coefficient = reduce((b - raw as i32) as i64);
+    }
+    poly
+}
+
+/// Split `r` into `r1 · 2^d + r0` (Algorithm 35)
+fn power2round(r: i32) -> (i32, i32) {
+    let mut r0 = r & ((1 << D) - 1);
+    r0 -= (((1 << (D - 1)) - r0) >> 31) & (1 << D);
+    ((r - r0) >> D, r0)
+}
+
+/// High and low bits of `r` (Algorithm 36)
+///
+/// Computes `⌈r / 2^7⌉ · 1025 / 2^22`, which equals `round(r / 2γ2)` on
+/// `[0, q)`; the mask folds the `r - r0 = q - 1` case into `r1 = 0`.
+fn decompose(r: i32) -> (i32, i32) {
+    let mut r1 = (r + 127) >> 7;
+    r1 = ((r1 * 1025 + (1 << 21)) >> 22) & 15;
+    let mut r0 = r - r1 * 2 * GAMMA2;
+    r0 -= (((Q - 1) / 2 - r0) >> 31) & Q;
+    (r1, r0)
+}
+
+fn high_bits(r: i32) -> i32 {
+    decompose(r).0
+}
+
+/// Correct the high bits of `r` with a hint (Algorithm 40)
+fn use_hint(hint: bool, r: i32) -> i32 {
+    let m = (Q - 1) / (2 * GAMMA2);
+    let (r1, r0) = decompose(r);
+    match (hint, r0 > 0) {
+        (false, _) => r1,
+        (true, true) => (r1 + 1) % m,
+        (true, false) => (r1 + m - 1) % m,
+    }
+}
+
+/// Commitment hash `c̃ = H(μ ‖ w1Encode(w1))`
+fn commitment_hash(mu: &[u8; 64], w1: &[Poly; K]) -> [u8; C_TILDE_SIZE] {
+    let mut encoded = [0u8; 32 * W1_BITS * K];
+    for (poly, chunk) in w1.iter().zip(encoded.chunks_mut(32 * W1_BITS)) {
+        pack(W1_BITS, poly.iter().map(|&c| c as u32), chunk);
+    }
+    let mut c_tilde = [0u8; C_TILDE_SIZE];
+    shake256(&[mu, &encoded], &mut c_tilde);
+    c_tilde
+}
+
+/// Message representative `μ = H(tr ‖ M')` with `M' = 0 ‖ |ctx| ‖ ctx ‖ M`
+fn message_representative(tr: &[u8; 64], message: &[u8], context: &[u8]) -> [u8; 64] {
+    let mut mu = [0u8; 64];
+    shake256(&[tr, &[0, context.len() as u8], context, message], &mut mu);
+    mu
+}
+
+/// ML-DSA.KeyGen_internal (Algorithm 6) from the seed `ξ`
+pub fn key_gen(xi: &[u8; 32]) -> (PublicKey, SecretKey) {
+    let mut seeds = [0u8; 128];
+    shake256(&[xi, &[K as u8, L as u8]], &mut seeds);
+    let mut rho = [0u8; 32];
+    let mut rho_prime = [0u8; 64];
+    rho.copy_from_slice(&seeds[..32]);
+    rho_prime.copy_from_slice(&seeds[32..96]);
+    let key = &seeds[96..];
+
+    let mut s1 = [[0i32; N]; L];
+    for (r, poly) in s1.iter_mut().enumerate() {
+        *poly = sample_bounded(&rho_prime, r as u16);
+    }
+    let mut s2 = [[0i32; N]; K];
+    for (r, poly) in s2.iter_mut().enumerate() {
+        *poly = sample_bounded(&rho_prime, (L + r) as u16);
+    }
+
+    let mut s1_hat = s1;
+    s1_hat.iter_mut().for_each(ntt);
+    let mut t = matrix_mul(&rho, &s1_hat);
+    let mut t1 = [[0i32; N]; K];
+    let mut t0 = [[0i32; N]; K];
+    for r in 0..K {
+        inv_ntt(&mut t[r]);
+        add_assign(&mut t[r], &s2[r]);
+        for i in 0..N {
+            let (high, low) = power2round(t[r][i]);
+            t1[r][i] = high;
+            t0[r][i] = reduce(low as i64);
+        }
+    }
+
+    let mut pk = [0u8; PUBLIC_KEY_SIZE];
+    pk[..32].copy_from_slice(&rho);
+    for (poly, chunk) in t1.iter().zip(pk[32..].chunks_mut(32 * T1_BITS)) {
+        pack(T1_BITS, poly.iter().map(|&c| c as u32), chunk);
+    }
+    let mut tr = [0u8; 64];
+    shake256(&[&pk], &mut tr);
+
+    let mut sk = [0u8; SECRET_KEY_SIZE];
+    sk[..32].copy_from_slice(&rho);
+    sk[32..64].copy_from_slice(key);
+    sk[64..128].copy_from_slice(&tr);
+    let mut chunks = sk[128..].chunks_mut(32 * ETA_BITS);
+    for poly in s1.iter().chain(&s2) {
+        pack_offset(
+            ETA_BITS,
+            ETA,
+            poly,
+            chunks.next().expect("sized for s1 and s2"),
+        );
+    }
+    let t0_start = 128 + 32 * ETA_BITS * (K + L);
+    for (poly, chunk) in t0.iter().zip(sk[t0_start..].chunks_mut(32 * D)) {
+        pack_offset(D, 1 << (D - 1), poly, chunk);
+    }
+    (pk, sk)
+}
+
+/// ML-DSA.Sign (Algorithms 2 and 7) with the hedging randomness `rnd`
+///
+/// Returns `None` if the context is longer than [`MAX_CONTEXT_SIZE`].
+pub fn sign(sk: &SecretKey, message: &[u8], context: &[u8], rnd: &[u8; 32]) -> Option<Signature> {
+    if context.len() > MAX_CONTEXT_SIZE {
+        return None;
+    }
+    let mut rho = [0u8; 32];
+    let mut tr = [0u8; 64];
+    rho.copy_from_slice(&sk[..32]);
+    let key = &sk[32..64];
+    tr.copy_from_slice(&sk[64..128]);
+
+    let mut chunks = sk[128..].chunks(32 * ETA_BITS);
+    let mut s1_hat = [[0i32; N]; L];
+    for poly in s1_hat.iter_mut() {
+        *poly = unpack_offset(ETA_BITS, ETA, chunks.next()?);
+        ntt(poly);
+    }
+    let mut s2_hat = [[0i32; N]; K];
+    for poly in s2_hat.iter_mut() {
+        *poly = unpack_offset(ETA_BITS, ETA, chunks.next()?);
+        ntt(poly);
+    }
+    let t0_start = 128 + 32 * ETA_BITS * (K + L);
+    let mut t0_hat = [[0i32; N]; K];
+    for (poly, chunk) in t0_hat.iter_mut().zip(sk[t0_start..].chunks(32 * D)) {
+        *poly = unpack_offset(D, 1 << (D - 1), chunk);
+        ntt(poly);
+    }
+
+    let mu = message_representative(&tr, message, context);
+    let mut rho_double_prime = [0u8; 64];
+    shake256(&[key, rnd, &mu], &mut rho_double_prime);
+
+    let mut kappa: u16 = 0;
+    loop {
+        let mut y = [[0i32; N]; L];
+        for (r, poly) in y.iter_mut().enumerate() {
+            *poly = sample_mask(&rho_double_prime, kappa.checked_add(r as u16)?);
+        }
+        kappa = kappa.checked_add(L as u16)?;
+
+        let mut y_hat = y;
+        y_hat.iter_mut().for_each(ntt);
+        let mut w = matrix_mul(&rho, &y_hat);
+        let mut w1 = [[0i32; N]; K];
+        for (poly, high) in w.iter_mut().zip(w1.iter_mut()) {
+            inv_ntt(poly);
+            for (h, &c) in high.iter_mut().zip(poly.iter()) {
+                *h = high_bits(c);
+            }
+        }
+
+        let c_tilde = commitment_hash(&mu, &w1);
+        let mut c_hat = sample_in_ball(&c_tilde);
+        ntt(&mut c_hat);
+
+        // z = y + c·s1
+        let mut z = y;
+        let mut z_ok = true;
+        for (poly, s) in z.iter_mut().zip(&s1_hat) {
+            let mut cs1 = [0i32; N];
+            multiply_add_ntt(&mut cs1, &c_hat, s);
+            inv_ntt(&mut cs1);
+            add_assign(poly, &cs1);
+            z_ok &= poly.iter().all(|&c| centered_abs(c) < GAMMA1 - BETA);
+        }
+        if !z_ok {
+            continue;
+        }
+
+        // w - c·s2 must keep its high bits with margin β
+        let mut r0_ok = true;
+        for (poly, s) in w.iter_mut().zip(&s2_hat) {
+            let mut cs2 = [0i32; N];
+            multiply_add_ntt(&mut cs2, &c_hat, s);
+            inv_ntt(&mut cs2);
+            sub_assign(poly, &cs2);
+            r0_ok &= poly.iter().all(|&c| decompose(c).1.abs() < GAMMA2 - BETA);
+        }
+        if !r0_ok {
+            continue;
+        }
+
+        // Hint recovering HighBits(w - c·s2) from w - c·s2 + c·t0
+        let mut hint = [[false; N]; K];
+        let mut ones = 0;
+        let mut ct0_ok = true;
+        for ((row, poly), t0) in hint.iter_mut().zip(&w).zip(&t0_hat) {
+            let mut ct0 = [0i32; N];
+            multiply_add_ntt(&mut ct0, &c_hat, t0);
+            inv_ntt(&mut ct0);
+            ct0_ok &= ct0.iter().all(|&c| centered_abs(c) < GAMMA2);
+            for i in 0..N {
+                let shifted = reduce(poly[i] as i64 + ct0[i] as i64);
+                row[i] = high_bits(shifted) != high_bits(poly[i]);
+                ones += row[i] as usize;
+            }
+        }
+        if !ct0_ok || ones > OMEGA {
+            continue;
+        }
+
+        let mut signature = [0u8; SIGNATURE_SIZE];
+        signature[..C_TILDE_SIZE].copy_from_slice(&c_tilde);
+        let z_end = C_TILDE_SIZE + 32 * Z_BITS * L;
+        for (poly, chunk) in z
+            .iter()
+            .zip(signature[C_TILDE_SIZE..z_end].chunks_mut(32 * Z_BITS))
+        {
+            pack_offset(Z_BITS, GAMMA1, poly, chunk);
+        }
+        let hints = &mut signature[z_end..];
+        let mut index = 0;
+        for (r, row) in hint.iter().enumerate() {
+            for (i, &set) in row.iter().enumerate() {
+                if set {
+                    hints[index] = i as u8;
+                    index += 1;
+                }
+            }
+            hints[OMEGA + r] = index as u8;
+        }
+        return Some(signature);
+    }
+}
+
+/// Decode the hint, rejecting non-canonical encodings (Algorithm 21)
+fn unpack_hint(bytes: &[u8]) -> Option<[[bool; N]; K]> {
+    let mut hint = [[false; N]; K];
+    let mut index = 0;
+    for (r, row) in hint.iter_mut().enumerate() {
+        let end = bytes[OMEGA + r] as usize;
+        if end < index || end > OMEGA {
+            return None;
+        }
+        let first = index;
+        while index < end {
+            if index > first && bytes[index - 1] >= bytes[index] {
+                return None;
+            }
+            row[bytes[index] as usize] = true;
+            index += 1;
+        }
+    }
+    bytes[index..OMEGA].iter().all(|&b| b == 0).then_some(hint)
+}
+
+/// ML-DSA.Verify (Algorithms 3 and 8)
+pub fn verify(pk: &PublicKey, message: &[u8], context: &[u8], signature: &Signature) -> bool {
+    if context.len() > MAX_CONTEXT_SIZE {
+        return false;
+    }
+    let z_end = C_TILDE_SIZE + 32 * Z_BITS * L;
+    let Some(hint) = unpack_hint(&signature[z_end..]) else {
+        return false;
+    };
+    let mut c_tilde = [0u8; C_TILDE_SIZE];
+    c_tilde.copy_from_slice(&signature[..C_TILDE_SIZE]);
+
+    let mut z_hat = [[0i32; N]; L];
+    for (poly, chunk) in z_hat
+        .iter_mut()
+        .zip(signature[C_TILDE_SIZE..z_end].chunks(32 * Z_BITS))
+    {
+        *poly = unpack_offset(Z_BITS, GAMMA1, chunk);
+        if poly.iter().any(|&c| centered_abs(c) >= GAMMA1 - BETA) {
+            return false;
+        }
+        ntt(poly);
+    }
+
+    let mut rho = [0u8; 32];
+    rho.copy_from_slice(&pk[..32]);
+    let mut tr = [0u8; 64];
+    shake256(&[pk], &mut tr);
+    let mu = message_representative(&tr, message, context);
+    let mut c_hat = sample_in_ball(&c_tilde);
+    ntt(&mut c_hat);
+
+    // w'approx = A·z - c·t1·2^d
+    let mut w = matrix_mul(&rho, &z_hat);
+    let mut w1 = [[0i32; N]; K];
+    for (r, chunk) in pk[32..].chunks(32 * T1_BITS).enumerate() {
+        let mut t1 = [0i32; N];
+        for (coefficient, &raw) in t1.iter_mut().zip(&unpack(T1_BITS, chunk)) {
+            *coefficient = (raw as i32) << D;
+        }
+        ntt(&mut t1);
+        let mut ct1 = [0i32; N];
+        multiply_add_ntt(&mut ct1, &c_hat, &t1);
+        sub_assign(&mut w[r], &ct1);
+        inv_ntt(&mut w[r]);
+        for i in 0..N {
+            w1[r][i] = use_hint(hint[r][i], w[r][i]);
+        }
+    }
+
+    commitment_hash(&mu, &w1) == c_tilde
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::post_quantum::tests::hex;
+
+    #[test]
+    fn test_sizes_match_fips_204() {
+        assert_eq!(PUBLIC_KEY_SIZE, 1952);
+        assert_eq!(SECRET_KEY_SIZE, 4032);
+        assert_eq!(SIGNATURE_SIZE, 3309);
+    }
+
+    /// Outputs for fixed seeds, cross-checked against OpenSSL 3.5
+    #[test]
+    fn test_reference_vectors() {
+        use sha3::{Digest, Sha3_256};
+        let digest = |bytes: &[u8]| -> [u8; 32] { Sha3_256::digest(bytes).into() };
+
+        let (pk, sk) = key_gen(&[7; 32]);
+        assert_eq!(
+            digest(&pk),
+            hex("fc24452a6a4cd77f74643b2cb0a7dfd658b3874c37e7a23fae9fc7e2ec0978fc")
+        );
+        let signature = sign(&sk, b"waypoint", b"ctx", &[5; 32]).unwrap();
+        assert_eq!(
+            digest(&signature),
+            hex("1cd7582c4739ffa7ab43860a278cf6a08a4329703e1c006580fe7563ad1299f1")
+        );
+
+        // Deterministic variant (zero randomness) with an empty context
+        let signature = sign(&sk, b"land", b"", &[0; 32]).unwrap();
+        assert_eq!(
+            digest(&signature),
+            hex("91c9d3a53d9674c9a848579e97a18fb1f134ff0f9c8c995c0265336458117140")
+        );
+    }
+
+    #[test]
+    fn test_ntt_multiplication_matches_schoolbook() {
+        // x · x^255 = x^256 = -1 in Z_q[X]/(X^256 + 1)
+        let (mut f, mut g) = ([0i32; N], [0i32; N]);
+        f[1] = 1;
+        g[255] = 1;
+        ntt(&mut f);
+        ntt(&mut g);
+        let mut product = [0i32; N];
+        multiply_add_ntt(&mut product, &f, &g);
+        inv_ntt(&mut product);
+
+        let mut expected = [0i32; N];
+        expected[0] = Q - 1;
+        assert_eq!(product, expected);
+    }
+
+    #[test]
+    fn test_decompose_recombines() {
+        for r in (0..Q).step_by(9973).chain([Q - 1, Q - GAMMA2, GAMMA2]) {
+            let (r1, r0) = decompose(r);
+            assert_eq!(reduce(r1 as i64 * 2 * GAMMA2 as i64 + r0 as i64), r);
+            assert!((0..16).contains(&r1));
+            let (t1, t0) = power2round(r);
+            assert_eq!((t1 << D) + t0, r);
+        }
+    }
+
+    #[test]
+    fn test_branch_free_arithmetic_matches_spec() {
+        let bound = (Q as i64) * (Q as i64) * 2;
+        for x in (-bound..=bound)
+            .step_by(99_991)
+            .chain([-1, 0, Q as i64, bound])
+        {
+            assert_eq!(reduce(x) as i64, x.rem_euclid(Q as i64));
+        }
+        for r in 0..Q {
+            // Algorithm 36 as written in FIPS 204
+            let mut r0 = r % (2 * GAMMA2);
+            if r0 > GAMMA2 {
+                r0 -= 2 * GAMMA2;
+            }
+            let expected = if r - r0 == Q - 1 {
+                (0, r0 - 1)
+            } else {
+                ((r - r0) / (2 * GAMMA2), r0)
+            };
+            assert_eq!(decompose(r), expected);
+            let c = if r > (Q - 1) / 2 { r - Q } else { r };
+            assert_eq!((centered(r), centered_abs(r)), (c, c.abs()));
+        }
+    }
+
+    #[test]
+    fn test_sign_verify_roundtrip() {
+        let (pk, sk) = key_gen(&[7; 32]);
+        let signature = sign(&sk, b"waypoint 3", b"ctx", &[1; 32]).unwrap();
+        assert!(verify(&pk, b"waypoint 3", b"ctx", &signature));
+
+        // Hedged: fresh randomness gives a different, equally valid signature
+        let other = sign(&sk, b"waypoint 3", b"ctx", &[2; 32]).unwrap();
+        assert_ne!(signature[..], other[..]);
+        assert!(verify(&pk, b"waypoint 3", b"ctx", &other));
+    }
+
+    #[test]
+    fn test_wrong_message_context_or_key_rejected() {
+        let (pk, sk) = key_gen(&[7; 32]);
+        let (other_pk, _) = key_gen(&[8; 32]);
+        let signature = sign(&sk, b"land", b"ctx", &[0; 32]).unwrap();
+
+        assert!(!verify(&pk, b"lane", b"ctx", &signature));
+        assert!(!verify(&pk, b"land", b"other", &signature));
+        assert!(!verify(&other_pk, b"land", b"ctx", &signature));
+
+        let mut tampered = signature;
+        tampered[C_TILDE_SIZE + 10] ^= 0x04;
+        assert!(!verify(&pk, b"land", b"ctx", &tampered));
+    }
+
+    #[test]
+    fn test_malformed_hint_rejected() {
+        let (pk, sk) = key_gen(&[3; 32]);
+        let mut signature = sign(&sk, b"m", b"", &[0; 32]).unwrap();
+        // Hint counts may not exceed ω
+        signature[SIGNATURE_SIZE - K] = OMEGA as u8 + 1;
+        assert!(!verify(&pk, b"m", b"", &signature));
+    }
+}
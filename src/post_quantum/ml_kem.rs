@@ -0,0 +1,561 @@
+//! ML-KEM-768 key encapsulation (FIPS 203)
+//!
+//! Straightforward, allocation-free transcription of the standard: the
+//! matrix `Â` is sampled entry by entry instead of being kept in memory, and
+//! randomness is passed in, so callers decide where it comes from. Arithmetic
+//! on secret values never divides or branches: reductions mod q use a Barrett
+//! quotient with a branch-free correction.
+
+use sha3::digest::{ExtendableOutput, Update, XofReader};
+use sha3::{Digest, Sha3_256, Sha3_512, Shake128, Shake256};
+
+/// Coefficients per polynomial
+const N: usize = 256;
+
+/// Modulus
+const Q: u32 = 3329;
+
+/// Module rank of ML-KEM-768
+const K: usize = 3;
+
+/// Noise parameter of secrets and errors (η1 = η2 for ML-KEM-768)
+const ETA: usize = 2;
+
+/// Compression of `u`
+const DU: usize = 10;
+
+/// Compression of `v`
+const DV: usize = 4;
+
+/// Encoded polynomial with 12-bit coefficients (bytes)
+const POLY_BYTES: usize = 384;
+
+/// Encapsulation (public) key size (bytes)
+pub const ENCAPSULATION_KEY_SIZE: usize = POLY_BYTES * K + 32;
+
+/// Decapsulation (secret) key size (bytes)
+pub const DECAPSULATION_KEY_SIZE: usize = 2 * POLY_BYTES * K + 96;
+
+/// Ciphertext size (bytes)
+pub const CIPHERTEXT_SIZE: usize = 32 * (DU * K + DV);
+
+/// Shared secret size (bytes)
+pub const SHARED_SECRET_SIZE: usize = 32;
+
+/// Encapsulation key: `t̂` and `ρ`
+pub type EncapsulationKey = [u8; ENCAPSULATION_KEY_SIZE];
+
+/// Decapsulation key: `ŝ`, encapsulation key, its hash and the rejection seed
+pub type DecapsulationKey = [u8; DECAPSULATION_KEY_SIZE];
+
+/// Ciphertext: compressed `u` and `v`
+pub type Ciphertext = [u8; CIPHERTEXT_SIZE];
+
+type Poly = [u16; N];
+
+const fn pow_mod(base: u32, mut exp: u32) -> u32 {
+    let mut result = 1;
+    let mut base = base % Q;
+    while exp > 0 {
+        if exp & 1 == 1 {
+            result = result * base % Q;
+        }
+        base = base * base % Q;
+        exp >>= 1;
+    }
+    result
+}
+
+const fn bit_rev7(i: u32) -> u32 {
+    let mut reversed = 0;
+    let mut bit = 0;
+    while bit < 7 {
+        reversed |= ((i >> bit) & 1) << (6 - bit);
+        bit += 1;
+    }
+    reversed
+}
+
+/// `ζ^BitRev7(i)` for the NTT, with ζ = 17
+const ZETAS: [u16; 128] = {
+    let mut table = [0u16; 128];
+    let mut i = 0;
+    while i < 128 {
+        table[i] = pow_mod(17, bit_rev7(i as u32)) as u16;
+        i += 1;
+    }
+    table
+};
+
+/// `ζ^(2·BitRev7(i)+1)` for base case multiplication
+const GAMMAS: [u16; 128] = {
+    let mut table = [0u16; 128];
+    let mut i = 0;
+    while i < 128 {
+        table[i] = pow_mod(17, 2 * bit_rev7(i as u32) + 1) as u16;
+        i += 1;
+    }
+    table
+};
+
+/// 128⁻¹ mod q
+const INV_128: u32 = 3303;
+
+/// ⌊2³² / q⌋, the Barrett multiplier
+const BARRETT: u64 = (1 << 32) / Q as u64;
+
+/// `⌊a / q⌋` without dividing or branching
+fn divide_q(a: u32) -> u32 {
+    // One less than the quotient at most
+    let quotient = ((a as u64 * BARRETT) >> 32) as u32;
+    let remainder = a - quotient * Q;
+    quotient + ((Q - 1).wrapping_sub(remainder) >> 31)
+}
+
+/// `a mod q` without dividing or branching
+fn reduce(a: u32) -> u16 {
+    (a - divide_q(a) * Q) as u16
+}
+
+/// SHA3-256
+fn hash_h(data: &[u8]) -> [u8; 32] {
+    Sha3_256::digest(data).into()
+}
+
+/// SHA3-512, split into two 32-byte halves
+fn hash_g(parts: &[&[u8]]) -> ([u8; 32], [u8; 32]) {
+    let mut hasher = Sha3_512::new();
+    for part in parts {
+        Digest::update(&mut hasher, part);
+    }
+    let digest = hasher.finalize();
+    let (mut first, mut second) = ([0u8; 32], [0u8; 32]);
+    first.copy_from_slice(&digest[..32]);
+    second.copy_from_slice(&digest[32..]);
+    (first, second)
+}
+
+/// SHAKE256 with a 32-byte output
+fn hash_j(parts: &[&[u8]]) -> [u8; 32] {
+    let mut xof = Shake256::default();
+    for part in parts {
+        xof.update(part);
+    }
+    let mut out = [0u8; 32];
+    xof.finalize_xof().read(&mut out);
+    out
+}
+
+/// PRF_η(s, b) = SHAKE256(s ‖ b)
+fn prf(seed: &[u8; 32], nonce: u8) -> [u8; 64 * ETA] {
+    let mut xof = Shake256::default();
+    xof.update(seed);
+    xof.update(&[nonce]);
+    let mut out = [0u8; 64 * ETA];
+    xof.finalize_xof().read(&mut out);
+    out
+}
+
+/// Entry `Â[i][j]` of the public matrix (Algorithm 7, SampleNTT on `ρ ‖ j ‖ i`)
+fn matrix_entry(rho: &[u8; 32], i: usize, j: usize) -> Poly {
+    let mut xof = Shake128::default();
+    xof.update(rho);
+    xof.update(&[j as u8, i as u8]);
+    let mut reader = xof.finalize_xof();
+
+    let mut poly = [0u16; N];
+    let mut filled = 0;
+    let mut bytes = [0u8; 3];
+    while filled < N {
+        reader.read(&mut bytes);
+        let d1 = bytes[0] as u16 | ((bytes[1] as u16 & 0x0F) << 8);
+        let d2 = (bytes[1] as u16 >> 4) | ((bytes[2] as u16) << 4);
+        if (d1 as u32) < Q {
+            poly[filled] = d1;
+            filled += 1;
+        }
+        if (d2 as u32) < Q && filled < N {
+            poly[filled] = d2;
+            filled += 1;
+        }
+    }
+    poly
+}
+
+/// Centered binomial sample (Algorithm 8)
+fn sample_cbd(bytes: &[u8; 64 * ETA]) -> Poly {
+    let bit = |index: usize| (bytes[index / 8] >> (index % 8)) as u32 & 1;
+    let mut poly = [0u16; N];
+    for (i, coefficient) in poly.iter_mut().enumerate() {
+        let base = 2 * i * ETA;
+        let x: u32 = (0..ETA).map(|j| bit(base + j)).sum();
+        let y: u32 = (0..ETA).map(|j| bit(base + ETA + j)).sum();
+        *coefficient = reduce(x + Q - y);
+    }
+    poly
+}
+
+/// Number-theoretic transform (Algorithm 9)
+fn ntt(f: &mut Poly) {
+    let mut k = 1;
+    let mut len = 128;
+    while len >= 2 {
+        for start in (0..N).step_by(2 * len) {
+            let zeta = ZETAS[k] as u32;
+            k += 1;
+            for j in start..start + len {
+                let t = reduce(zeta * f[j + len] as u32) as u32;
+                f[j + len] = reduce(f[j] as u32 + Q - t);
+                f[j] = reduce(f[j] as u32 + t);
+            }
+        }
+        len /= 2;
+    }
+}
+
+/// Inverse number-theoretic transform (Algorithm 10)
+fn inv_ntt(f: &mut Poly) {
+    let mut k = 127;
+    let mut len = 2;
+    while len <= 128 {
+        for start in (0..N).step_by(2 * len) {
+            let zeta = ZETAS[k] as u32;
+            k -= 1;
+            for j in start..start + len {
+                let t = f[j] as u32;
+                f[j] = reduce(t + f[j + len] as u32);
+                f[j + len] = reduce(zeta * reduce(f[j + len] as u32 + Q - t) as u32);
+            }
+        }
+        len *= 2;
+    }
+    for coefficient in f.iter_mut() {
+        *coefficient = reduce(*coefficient as u32 * INV_128);
+    }
+}
+
+/// Accumulate `f ∘ g` in the NTT domain into `acc` (Algorithms 11 and 12)
+fn multiply_add_ntt(acc: &mut Poly, f: &Poly, g: &Poly) {
+    for i in 0..N / 2 {
+        let (a0, a1) = (f[2 * i] as u32, f[2 * i + 1] as u32);
+        let (b0, b1) = (g[2 * i] as u32, g[2 * i + 1] as u32);
+        let c0 = reduce(a0 * b0 + reduce(a1 * b1) as u32 * GAMMAS[i] as u32) as u32;
+        let c1 = reduce(a0 * b1 + a1 * b0) as u32;
+        acc[2 * i] = reduce(acc[2 * i] as u32 + c0);
+        acc[2 * i + 1] = reduce(acc[2 * i + 1] as u32 + c1);
+    }
+}
+
+fn add_assign(f: &mut Poly, g: &Poly) {
+    for (a, b) in f.iter_mut().zip(g) {
+        *a = reduce(*a as u32 + *b as u32);
+    }
+}
+
+fn sub_assign(f: &mut Poly, g: &Poly) {
+    for (a, b) in f.iter_mut().zip(g) {
+        *a = reduce(*a as u32 + Q - *b as u32);
+    }
+}
+
+/// Pack `d`-bit coefficients little-endian (Algorithm 5)
+fn byte_encode(d: usize, f: &Poly, out: &mut [u8]) {
+    let (mut acc, mut bits, mut pos) = (0u32, 0usize, 0usize);
+    for &coefficient in f {
+        acc |= (coefficient as u32) << bits;
+        bits += d;
+        while bits >= 8 {
+            out[pos] = acc as u8;
+            pos += 1;
+            acc >>= 8;
+            bits -= 8;
+        }
+    }
+}
+
+/// Unpack `d`-bit coefficients (Algorithm 6, without the reduction)
+fn byte_decode(d: usize, bytes: &[u8]) -> Poly {
+    let (mut acc, mut bits, mut pos) = (0u32, 0usize, 0usize);
+    let mut poly = [0u16; N];
+    for coefficient in poly.iter_mut() {
+        while bits < d {
+            acc |= (bytes[pos] as u32) << bits;
+            pos += 1;
+            bits += 8;
+        }
+        *coefficient = (acc & ((1 << d) - 1)) as u16;
+        acc >>= d;
+        bits -= d;
+    }
+    poly
+}
+
+/// Decode 12-bit coefficients, rejecting any not reduced mod q
+fn decode_reduced(bytes: &[u8]) -> Option<Poly> {
+    let poly = byte_decode(12, bytes);
+    poly.iter().all(|&c| (c as u32) < Q).then_some(poly)
+}
+
+fn compress(d: usize, f: &mut Poly) {
+    for coefficient in f.iter_mut() {
+        *coefficient = (divide_q(((*coefficient as u32) << d) + Q / 2) & ((1 << d) - 1)) as u16;
+    }
+}
+
+fn decompress(d: usize, f: &mut Poly) {
+    for coefficient in f.iter_mut() {
+        *coefficient = ((*coefficient as u32 * Q + (1 << (d - 1))) >> d) as u16;
+    }
+}
+
+/// K-PKE.KeyGen (Algorithm 13): encryption key and encoded `ŝ`
+fn pke_key_gen(d: &[u8; 32]) -> (EncapsulationKey, [u8; POLY_BYTES * K]) {
+    let (rho, sigma) = hash_g(&[d, &[K as u8]]);
+
+    let mut s_hat = [[0u16; N]; K];
+    let mut e_hat = [[0u16; N]; K];
+    for i in 0..K {
+        s_hat[i] = sample_cbd(&prf(&sigma, i as u8));
+        e_hat[i] = sample_cbd(&prf(&sigma, (K + i) as u8));
+        ntt(&mut s_hat[i]);
+        ntt(&mut e_hat[i]);
+    }
+
+    let mut ek = [0u8; ENCAPSULATION_KEY_SIZE];
+    let mut dk = [0u8; POLY_BYTES * K];
+    for i in 0..K {
+        let mut t_hat = e_hat[i];
+        for (j, s) in s_hat.iter().enumerate() {
+            multiply_add_ntt(&mut t_hat, &matrix_entry(&rho, i, j), s);
+        }
+        byte_encode(12, &t_hat, &mut ek[POLY_BYTES * i..POLY_BYTES * (i + 1)]);
+        byte_encode(12, &s_hat[i], &mut dk[POLY_BYTES * i..POLY_BYTES * (i + 1)]);
+    }
+    ek[POLY_BYTES * K..].copy_from_slice(&rho);
+    (ek, dk)
+}
+
+/// K-PKE.Encrypt (Algorithm 14); `None` if the key is not reduced mod q
+fn pke_encrypt(ek: &EncapsulationKey, message: &[u8; 32], r: &[u8; 32]) -> Option<Ciphertext> {
+    let mut t_hat = [[0u16; N]; K];
+    for (i, t) in t_hat.iter_mut().enumerate() {
+        *t = decode_reduced(&ek[POLY_BYTES * i..POLY_BYTES * (i + 1)])?;
+    }
+    let mut rho = [0u8; 32];
+    rho.copy_from_slice(&ek[POLY_BYTES * K..]);
+
+    let mut y_hat = [[0u16; N]; K];
+    for (i, y) in y_hat.iter_mut().enumerate() {
+        *y = sample_cbd(&prf(r, i as u8));
+        ntt(y);
+    }
+
+    let mut ciphertext = [0u8; CIPHERTEXT_SIZE];
+    for i in 0..K {
+        let mut u = [0u16; N];
+        for (j, y) in y_hat.iter().enumerate() {
+            multiply_add_ntt(&mut u, &matrix_entry(&rho, j, i), y);
+        }
+        inv_ntt(&mut u);
+        add_assign(&mut u, &sample_cbd(&prf(r, (K + i) as u8)));
+        compress(DU, &mut u);
+        byte_encode(DU, &u, &mut ciphertext[32 * DU * i..32 * DU * (i + 1)]);
+    }
+
+    let mut v = [0u16; N];
+    for (t, y) in t_hat.iter().zip(&y_hat) {
+        multiply_add_ntt(&mut v, t, y);
+    }
+    inv_ntt(&mut v);
+    add_assign(&mut v, &sample_cbd(&prf(r, (2 * K) as u8)));
+    let mut mu = byte_decode(1, message);
+    decompress(1, &mut mu);
+    add_assign(&mut v, &mu);
+    compress(DV, &mut v);
+    byte_encode(DV, &v, &mut ciphertext[32 * DU * K..]);
+    Some(ciphertext)
+}
+
+/// K-PKE.Decrypt (Algorithm 15)
+fn pke_decrypt(dk: &[u8], ciphertext: &Ciphertext) -> [u8; 32] {
+    let mut w = [0u16; N];
+    for i in 0..K {
+        let mut u = byte_decode(DU, &ciphertext[32 * DU * i..32 * DU * (i + 1)]);
+        decompress(DU, &mut u);
+        ntt(&mut u);
+        let s_hat = byte_decode(12, &dk[POLY_BYTES * i..POLY_BYTES * (i + 1)]);
+        multiply_add_ntt(&mut w, &s_hat, &u);
+    }
+    inv_ntt(&mut w);
+
+    let mut v = byte_decode(DV, &ciphertext[32 * DU * K..]);
+    decompress(DV, &mut v);
+    sub_assign(&mut v, &w);
+    compress(1, &mut v);
+    let mut message = [0u8; 32];
+    byte_encode(1, &v, &mut message);
+    message
+}
+
+/// ML-KEM.KeyGen_internal (Algorithm 16) from the seeds `d` and `z`
+pub fn key_gen(d: &[u8; 32], z: &[u8; 32]) -> (EncapsulationKey, DecapsulationKey) {
+    let (ek, dk_pke) = pke_key_gen(d);
+    let mut dk = [0u8; DECAPSULATION_KEY_SIZE];
+    dk[..POLY_BYTES * K].copy_from_slice(&dk_pke);
+    dk[POLY_BYTES * K..2 * POLY_BYTES * K + 32].copy_from_slice(&ek);
+    dk[2 * POLY_BYTES * K + 32..2 * POLY_BYTES * K + 64].copy_from_slice(&hash_h(&ek));
+    dk[2 * POLY_BYTES * K + 64..].copy_from_slice(z);
+    (ek, dk)
+}
+
+/// ML-KEM.Encaps_internal (Algorithm 17) with the random message `m`
+///
+/// Returns `None` if the encapsulation key fails the modulus check.
+pub fn encapsulate(
+    ek: &EncapsulationKey,
+    m: &[u8; 32],
+) -> Option<([u8; SHARED_SECRET_SIZE], Ciphertext)> {
+    let (shared, r) = hash_g(&[m, &hash_h(ek)]);
+    Some((shared, pke_encrypt(ek, m, &r)?))
+}
+
+/// ML-KEM.Decaps_internal (Algorithm 18)
+///
+/// Never fails: a tampered ciphertext yields an unrelated pseudorandom
+/// secret (implicit rejection), so the mismatch only shows once the secret
+/// is used.
+pub fn decapsulate(dk: &DecapsulationKey, ciphertext: &Ciphertext) -> [u8; SHARED_SECRET_SIZE] {
+    let dk_pke = &dk[..POLY_BYTES * K];
+    let mut ek = [0u8; ENCAPSULATION_KEY_SIZE];
+    ek.copy_from_slice(&dk[POLY_BYTES * K..2 * POLY_BYTES * K + 32]);
+    let h = &dk[2 * POLY_BYTES * K + 32..2 * POLY_BYTES * K + 64];
+    let z = &dk[2 * POLY_BYTES * K + 64..];
+
+    let message = pke_decrypt(dk_pke, ciphertext);
+    let (shared, r) = hash_g(&[&message, h]);
+    let rejected = hash_j(&[z, ciphertext]);
+
+    // Constant-time select on re-encryption mismatch
+    let reencrypted = pke_encrypt(&ek, &message, &r).unwrap_or([0u8; CIPHERTEXT_SIZE]);
+    let differ = reencrypted
+        .iter()
+        .zip(ciphertext.iter())
+        .fold(0u8, |acc, (a, b)| acc | (a ^ b));
+    let mask = ((differ as u16).wrapping_neg() >> 8) as u8;
+    let mut out = [0u8; SHARED_SECRET_SIZE];
+    for i in 0..SHARED_SECRET_SIZE {
+        out[i] = (shared[i] & !mask) | (rejected[i] & mask);
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::post_quantum::tests::hex;
+
+    #[test]
+    fn test_sizes_match_fips_203() {
+        assert_eq!(ENCAPSULATION_KEY_SIZE, 1184);
+        assert_eq!(DECAPSULATION_KEY_SIZE, 2400);
+        assert_eq!(CIPHERTEXT_SIZE, 1088);
+    }
+
+    /// Outputs for fixed seeds, cross-checked against OpenSSL 3.5
+    #[test]
+    fn test_reference_vectors() {
+        let (ek, dk) = key_gen(&[1; 32], &[2; 32]);
+        assert_eq!(
+            hash_h(&ek),
+            hex("605a1583f2f42c2622d4bb3714033272ba2528b8257fe30aeca1f7d2d88d4d8b")
+        );
+        let (shared, ciphertext) = encapsulate(&ek, &[9; 32]).unwrap();
+        assert_eq!(
+            hash_h(&ciphertext),
+            hex("59f08ad375657d0a4c32368dd891d7dd40c27b9573da7a8cb1261138c594f030")
+        );
+        assert_eq!(
+            shared,
+            hex("f98878c2d4961d2a0fa42ece5ab176daa5614e52ac1f6e6d90b06ed3723c519c")
+        );
+
+        // Implicit rejection derives the secret from z and the ciphertext
+        let mut tampered = ciphertext;
+        tampered[0] ^= 1;
+        assert_eq!(
+            decapsulate(&dk, &tampered),
+            hex("b4a7bfcc04f2550d23098f67a9460bb6e6dd8f887bc75fa4b86065782f48de97")
+        );
+    }
+
+    #[test]
+    fn test_barrett_reduction_matches_division() {
+        // Covers every value the NTT, products and compression produce
+        for a in (0..2 * Q * Q)
+            .step_by(7)
+            .chain([Q - 1, Q, 2 * Q * Q, u32::MAX])
+        {
+            assert_eq!(divide_q(a), a / Q);
+            assert_eq!(reduce(a) as u32, a % Q);
+        }
+    }
+
+    #[test]
+    fn test_ntt_roundtrip() {
+        let mut f = [0u16; N];
+        for (i, c) in f.iter_mut().enumerate() {
+            *c = (i as u32 * 13 % Q) as u16;
+        }
+        let original = f;
+        ntt(&mut f);
+        assert_ne!(f, original);
+        inv_ntt(&mut f);
+        assert_eq!(f, original);
+    }
+
+    #[test]
+    fn test_ntt_multiplication_matches_schoolbook() {
+        // x · x^255 = x^256 = -1 in Z_q[X]/(X^256 + 1)
+        let (mut f, mut g) = ([0u16; N], [0u16; N]);
+        f[1] = 1;
+        g[255] = 1;
+        ntt(&mut f);
+        ntt(&mut g);
+        let mut product = [0u16; N];
+        multiply_add_ntt(&mut product, &f, &g);
+        inv_ntt(&mut product);
+
+        let mut expected = [0u16; N];
+        expected[0] = (Q - 1) as u16;
+        assert_eq!(product, expected);
+    }
+
+    #[test]
+    fn test_encapsulation_roundtrip() {
+        for seed in 0..4u8 {
+            let (ek, dk) = key_gen(&[seed; 32], &[seed ^ 0xFF; 32]);
+            let (shared, ciphertext) = encapsulate(&ek, &[seed.wrapping_add(7); 32]).unwrap();
+            assert_eq!(decapsulate(&dk, &ciphertext), shared);
+        }
+    }
+
+    #[test]
+    fn test_tampered_ciphertext_rejected_implicitly() {
+        let (ek, dk) = key_gen(&[1; 32], &[2; 32]);
+        let (shared, mut ciphertext) = encapsulate(&ek, &[3; 32]).unwrap();
+        ciphertext[100] ^= 0x01;
+
+        let rejected = decapsulate(&dk, &ciphertext);
+        assert_ne!(rejected, shared);
+        assert_eq!(rejected, hash_j(&[&[2; 32], &ciphertext]));
+    }
+
+    #[test]
+    fn test_unreduced_key_rejected() {
+        let (mut ek, _) = key_gen(&[1; 32], &[2; 32]);
+        // First coefficient = 0xFFF ≥ q
+        ek[0] = 0xFF;
+        ek[1] |= 0x0F;
+        assert!(encapsulate(&ek, &[3; 32]).is_none());
+    }
+}
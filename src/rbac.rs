@@ -0,0 +1,633 @@
+//! Role-based authorization of swarm commands
+//!
+//! Every command a drone accepts passes through one [`CommandPolicy`]:
+//! - Each [`CommandAction`] and [`SwarmCommand`] variant maps to the
+//!   [`Roles`] allowed to issue it, separately for commands aimed at a single
+//!   drone and commands aimed at a group or the whole swarm
+//! - Drones hold roles by assignment, by being the current Raft leader, or
+//!   through a signed [`Delegation`] that expires or is revoked
+//!
+//! The default policy lets the ground station do anything and the leader
+//! fly the swarm, while members may only emergency-stop a single drone
+//! (e.g. to avoid a collision), never the whole swarm.
+
+use crate::consensus::SwarmCommand;
+use crate::crypto::{CryptoContext, KeyStore, SIGNATURE_SIZE};
+use crate::mesh_protocol::{CommandAction, CommandTarget};
+use crate::types::*;
+use ed25519_dalek::{Signature, Verifier};
+use heapless::{FnvIndexMap, Vec};
+use serde::{Deserialize, Serialize};
+
+/// Drones with assigned roles (power of 2)
+pub const MAX_ROLE_ASSIGNMENTS: usize = 128;
+
+/// Delegations held at once
+pub const MAX_DELEGATIONS: usize = 32;
+
+/// Delegator/delegate pairs whose newest issue number is remembered
+/// (power of 2)
+pub const MAX_DELEGATION_PAIRS: usize = 128;
+
+/// Domain separation of delegation signatures
+const DELEGATION_CONTEXT: &str = "droneswarm-v1 role delegation";
+
+/// Roles a drone holds, as a bit set
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Roles(pub u8);
+
+impl Roles {
+    /// No roles
+    pub const NONE: Self = Self(0);
+    /// May watch swarm traffic but not command
+    pub const OBSERVER: Self = Self(1 << 0);
+    /// Regular swarm member
+    pub const MEMBER: Self = Self(1 << 1);
+    /// Current Raft leader
+    pub const LEADER: Self = Self(1 << 2);
+    /// Ground control station
+    pub const GROUND_STATION: Self = Self(1 << 3);
+
+    /// Check that every role in `other` is present
+    pub fn contains(self, other: Self) -> bool {
+        self.0 & other.0 == other.0
+    }
+
+    /// Check that at least one role is shared with `other`
+    pub fn intersects(self, other: Self) -> bool {
+        self.0 & other.0 != 0
+    }
+
+    /// Union of two sets
+    pub fn with(self, other: Self) -> Self {
+        Self(self.0 | other.0)
+    }
+}
+
+/// Command variants, across mesh commands and consensus commands
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum CommandKind {
+    /// Arm motors
+    Arm,
+    /// Disarm motors
+    Disarm,
+    /// Take off to altitude
+    Takeoff,
+    /// Land at current position
+    Land,
+    /// Return to launch
+    ReturnToLaunch,
+    /// Go to waypoint
+    GoTo,
+    /// Set or change formation
+    SetFormation,
+    /// Emergency stop
+    EmergencyStop,
+    /// Set parameter
+    SetParam,
+    /// Assign task to drone
+    AssignTask,
+    /// Update mission parameters
+    UpdateMission,
+    /// Add drone to swarm
+    AddDrone,
+    /// Remove drone from swarm
+    RemoveDrone,
+    /// Ratchet the shared network key
+    RotateNetworkKey,
+}
+
+impl CommandKind {
+    /// Every command kind, in declaration order
+    pub const ALL: [Self; 14] = [
+        Self::Arm,
+        Self::Disarm,
+        Self::Takeoff,
+        Self::Land,
+        Self::ReturnToLaunch,
+        Self::GoTo,
+        Self::SetFormation,
+        Self::EmergencyStop,
+        Self::SetParam,
+        Self::AssignTask,
+        Self::UpdateMission,
+        Self::AddDrone,
+        Self::RemoveDrone,
+        Self::RotateNetworkKey,
+    ];
+
+    /// Number of command kinds
+    pub const COUNT: usize = Self::ALL.len();
+}
+
+impl From<&CommandAction> for CommandKind {
+    fn from(action: &CommandAction) -> Self {
+        match action {
+            CommandAction::Arm => Self::Arm,
+            CommandAction::Disarm => Self::Disarm,
+            CommandAction::Takeoff { .. } => Self::Takeoff,
+            CommandAction::Land => Self::Land,
+            CommandAction::ReturnToLaunch => Self::ReturnToLaunch,
+            CommandAction::GoTo { .. } => Self::GoTo,
+            CommandAction::SetFormation { .. } => Self::SetFormation,
+            CommandAction::EmergencyStop => Self::EmergencyStop,
+            CommandAction::SetParam { .. } => Self::SetParam,
+        }
+    }
+}
+
+impl From<&SwarmCommand> for CommandKind {
+    fn from(command: &SwarmCommand) -> Self {
+        match command {
+            SwarmCommand::AssignTask { .. } => Self::AssignTask,
+            SwarmCommand::UpdateMission { .. } => Self::UpdateMission,
+            SwarmCommand::AddDrone { .. } => Self::AddDrone,
+            SwarmCommand::RemoveDrone { .. } => Self::RemoveDrone,
+            SwarmCommand::EmergencyStop => Self::EmergencyStop,
+            SwarmCommand::ChangeFormation { .. } => Self::SetFormation,
+            SwarmCommand::RotateNetworkKey { .. } => Self::RotateNetworkKey,
+        }
+    }
+}
+
+/// How many drones a command reaches
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CommandScope {
+    /// One drone
+    Node,
+    /// A group or the whole swarm
+    Swarm,
+}
+
+impl From<&CommandTarget> for CommandScope {
+    fn from(target: &CommandTarget) -> Self {
+        match target {
+            CommandTarget::Node(_) => Self::Node,
+            CommandTarget::Group(_) | CommandTarget::Broadcast => Self::Swarm,
+        }
+    }
+}
+
+/// Roles allowed to issue one kind of command
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct CommandRule {
+    /// Roles allowed to command a single drone
+    pub node: Roles,
+    /// Roles allowed to command a group or the whole swarm
+    pub swarm: Roles,
+}
+
+impl CommandRule {
+    /// Same roles for either scope
+    pub const fn uniform(roles: Roles) -> Self {
+        Self {
+            node: roles,
+            swarm: roles,
+        }
+    }
+}
+
+/// Time-limited grant of roles from one drone to another
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Delegation {
+    /// Drone granting the roles; must hold them by assignment
+    pub delegator: DroneId,
+    /// Drone receiving the roles
+    pub delegate: DroneId,
+    /// Roles granted
+    pub roles: Roles,
+    /// Increases with every delegation the delegator grants the delegate
+    pub issue: u32,
+    /// End of validity (swarm time, ms)
+    pub expires_ms: u64,
+    /// Delegator's signature over all fields above
+    pub signature: Vec<u8, SIGNATURE_SIZE>,
+}
+
+impl Delegation {
+    /// Create a delegation signed by the delegator
+    pub fn new(
+        crypto: &CryptoContext,
+        delegator: DroneId,
+        delegate: DroneId,
+        roles: Roles,
+        issue: u32,
+        expires_ms: u64,
+    ) -> Result<Self> {
+        let mut delegation = Self {
+            delegator,
+            delegate,
+            roles,
+            issue,
+            expires_ms,
+            signature: Vec::new(),
+        };
+        delegation.signature = Vec::from_slice(&crypto.sign(&delegation.digest()))
+            .map_err(|_| SwarmError::BufferFull)?;
+        Ok(delegation)
+    }
+
+    /// Digest covered by the signature
+    fn digest(&self) -> [u8; 32] {
+        let mut hasher = blake3::Hasher::new_derive_key(DELEGATION_CONTEXT);
+        hasher.update(&self.delegator.as_u64().to_le_bytes());
+        hasher.update(&self.delegate.as_u64().to_le_bytes());
+        hasher.update(&[self.roles.0]);
+        hasher.update(&self.issue.to_le_bytes());
+        hasher.update(&self.expires_ms.to_le_bytes());
+        *hasher.finalize().as_bytes()
+    }
+
+    /// Check whether the delegation is in force at `now_ms`
+    pub fn is_active_at(&self, now_ms: u64) -> bool {
+        now_ms < self.expires_ms
+    }
+}
+
+/// Policy engine deciding who may issue which command
+pub struct CommandPolicy {
+    /// Allowed roles per [`CommandKind`]
+    rules: [CommandRule; CommandKind::COUNT],
+    /// Roles held by assignment
+    assigned: FnvIndexMap<u64, Roles, MAX_ROLE_ASSIGNMENTS>,
+    /// Current Raft leader
+    leader: Option<DroneId>,
+    /// Accepted delegations, possibly expired
+    delegations: Vec<Delegation, MAX_DELEGATIONS>,
+    /// Newest issue number accepted or revoked per (delegator, delegate)
+    issued: FnvIndexMap<(u64, u64), u32, MAX_DELEGATION_PAIRS>,
+}
+
+impl CommandPolicy {
+    /// Create a policy with the default rules and no assignments
+    pub fn new() -> Self {
+        let gcs = Roles::GROUND_STATION;
+        let command = gcs.with(Roles::LEADER);
+        let mut rules = [CommandRule::uniform(command); CommandKind::COUNT];
+        for kind in [CommandKind::Arm, CommandKind::Disarm, CommandKind::SetParam] {
+            rules[kind as usize] = CommandRule::uniform(gcs);
+        }
+        rules[CommandKind::EmergencyStop as usize] = CommandRule {
+            node: command.with(Roles::MEMBER),
+            swarm: command,
+        };
+
+        Self {
+            rules,
+            assigned: FnvIndexMap::new(),
+            leader: None,
+            delegations: Vec::new(),
+            issued: FnvIndexMap::new(),
+        }
+    }
+
+    /// Roles allowed to issue `kind`
+    pub fn rule(&self, kind: CommandKind) -> CommandRule {
+        self.rules[kind as usize]
+    }
+
+    /// Change the roles allowed to issue `kind`
+    pub fn set_rule(&mut self, kind: CommandKind, rule: CommandRule) {
+        self.rules[kind as usize] = rule;
+    }
+
+    /// Assign roles to a drone, replacing any it held by assignment
+    pub fn assign(&mut self, drone: DroneId, roles: Roles) -> Result<()> {
+        self.assigned
+            .insert(drone.as_u64(), roles)
+            .map_err(|_| SwarmError::ResourceExhausted)?;
+        Ok(())
+    }
+
+    /// Remove a drone's assigned roles and every delegation it granted
+    pub fn unassign(&mut self, drone: DroneId) {
+        self.assigned.remove(&drone.as_u64());
+        self.delegations.retain(|d| d.delegator != drone);
+    }
+
+    /// Record the current Raft leader, which holds [`Roles::LEADER`]
+    pub fn set_leader(&mut self, leader: Option<DroneId>) {
+        self.leader = leader;
+    }
+
+    /// Verify and accept a delegation
+    ///
+    /// The delegator must hold every delegated role by assignment. A
+    /// delegation replaces the one held between the same pair, and must
+    /// carry a higher issue number than any accepted or revoked before.
+    pub fn delegate(&mut self, delegation: Delegation, keys: &KeyStore, now_ms: u64) -> Result<()> {
+        let public_key = keys.get_key(delegation.delegator)?;
+        let signature = Signature::from_slice(&delegation.signature)
+            .map_err(|_| SwarmError::AuthenticationFailed)?;
+        public_key
+            .verify(&delegation.digest(), &signature)
+            .map_err(|_| SwarmError::AuthenticationFailed)?;
+
+        if !delegation.is_active_at(now_ms)
+            || !self
+                .assigned_roles(delegation.delegator)
+                .contains(delegation.roles)
+        {
+            return Err(SwarmError::PermissionDenied);
+        }
+
+        let pair = (delegation.delegator.as_u64(), delegation.delegate.as_u64());
+        if self
+            .issued
+            .get(&pair)
+            .is_some_and(|issue| delegation.issue <= *issue)
+        {
+            return Err(SwarmError::InvalidMessage);
+        }
+        self.issued
+            .insert(pair, delegation.issue)
+            .map_err(|_| SwarmError::ResourceExhausted)?;
+
+        self.delegations.retain(|d| {
+            d.is_active_at(now_ms)
+                && !(d.delegator == delegation.delegator && d.delegate == delegation.delegate)
+        });
+        self.delegations
+            .push(delegation)
+            .map_err(|_| SwarmError::ResourceExhausted)
+    }
+
+    /// Withdraw every delegation from `delegator` to `delegate` issued up to
+    /// `through_issue`
+    ///
+    /// Those delegations are refused from then on; a later issue re-grants.
+    pub fn revoke_delegation(
+        &mut self,
+        delegator: DroneId,
+        delegate: DroneId,
+        through_issue: u32,
+    ) -> Result<()> {
+        let pair = (delegator.as_u64(), delegate.as_u64());
+        let issue = self
+            .issued
+            .get(&pair)
+            .copied()
+            .unwrap_or(0)
+            .max(through_issue);
+        self.issued
+            .insert(pair, issue)
+            .map_err(|_| SwarmError::ResourceExhausted)?;
+        self.delegations.retain(|d| {
+            !(d.delegator == delegator && d.delegate == delegate && d.issue <= through_issue)
+        });
+        Ok(())
+    }
+
+    /// Roles a drone holds at `now_ms`
+    pub fn roles(&self, drone: DroneId, now_ms: u64) -> Roles {
+        let mut roles = self.assigned_roles(drone);
+        if self.leader == Some(drone) {
+            roles = roles.with(Roles::LEADER);
+        }
+        for delegation in &self.delegations {
+            // Delegated roles lapse when the delegator loses them
+            if delegation.delegate == drone && delegation.is_active_at(now_ms) {
+                let held = self.assigned_roles(delegation.delegator);
+                roles = roles.with(Roles(delegation.roles.0 & held.0));
+            }
+        }
+        roles
+    }
+
+    /// Check that `issuer` may issue a `kind` command reaching `scope`
+    pub fn authorize(
+        &self,
+        issuer: DroneId,
+        kind: CommandKind,
+        scope: CommandScope,
+        now_ms: u64,
+    ) -> Result<()> {
+        let rule = self.rule(kind);
+        let allowed = match scope {
+            CommandScope::Node => rule.node,
+            CommandScope::Swarm => rule.swarm,
+        };
+        if self.roles(issuer, now_ms).intersects(allowed) {
+            Ok(())
+        } else {
+            Err(SwarmError::PermissionDenied)
+        }
+    }
+
+    /// Roles held by assignment alone
+    fn assigned_roles(&self, drone: DroneId) -> Roles {
+        self.assigned
+            .get(&drone.as_u64())
+            .copied()
+            .unwrap_or(Roles::NONE)
+    }
+}
+
+impl Default for CommandPolicy {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const GCS: DroneId = DroneId::new(100);
+    const MEMBER: DroneId = DroneId::new(3);
+
+    fn policy() -> CommandPolicy {
+        let mut policy = CommandPolicy::new();
+        policy.assign(GCS, Roles::GROUND_STATION).unwrap();
+        for id in 1..=5 {
+            policy.assign(DroneId::new(id), Roles::MEMBER).unwrap();
+        }
+        policy.set_leader(Some(DroneId::new(1)));
+        policy
+    }
+
+    #[test]
+    fn test_command_kinds_complete() {
+        for (index, kind) in CommandKind::ALL.iter().enumerate() {
+            assert_eq!(*kind as usize, index);
+        }
+        // A new variant fails to compile here until it is listed in `ALL`
+        let listed = |kind: CommandKind| match kind {
+            CommandKind::Arm
+            | CommandKind::Disarm
+            | CommandKind::Takeoff
+            | CommandKind::Land
+            | CommandKind::ReturnToLaunch
+            | CommandKind::GoTo
+            | CommandKind::SetFormation
+            | CommandKind::EmergencyStop
+            | CommandKind::SetParam
+            | CommandKind::AssignTask
+            | CommandKind::UpdateMission
+            | CommandKind::AddDrone
+            | CommandKind::RemoveDrone
+            | CommandKind::RotateNetworkKey => CommandKind::ALL.contains(&kind),
+        };
+        assert!(CommandKind::ALL.iter().all(|kind| listed(*kind)));
+        assert_eq!(
+            CommandKind::COUNT,
+            CommandKind::RotateNetworkKey as usize + 1
+        );
+    }
+
+    #[test]
+    fn test_default_rules() {
+        let policy = policy();
+        let stop = CommandKind::from(&CommandAction::EmergencyStop);
+
+        assert!(policy.authorize(GCS, stop, CommandScope::Swarm, 0).is_ok());
+        assert!(policy
+            .authorize(DroneId::new(1), stop, CommandScope::Swarm, 0)
+            .is_ok());
+        assert!(policy
+            .authorize(MEMBER, stop, CommandScope::Node, 0)
+            .is_ok());
+        assert_eq!(
+            policy.authorize(MEMBER, stop, CommandScope::Swarm, 0),
+            Err(SwarmError::PermissionDenied)
+        );
+
+        // Only the ground station arms motors, even the leader may not
+        assert!(policy
+            .authorize(DroneId::new(1), CommandKind::Arm, CommandScope::Node, 0)
+            .is_err());
+        assert!(policy
+            .authorize(GCS, CommandKind::Arm, CommandScope::Swarm, 0)
+            .is_ok());
+
+        // Unknown drones and observers may not command at all
+        let mut policy = policy;
+        policy.assign(DroneId::new(9), Roles::OBSERVER).unwrap();
+        for drone in [DroneId::new(9), DroneId::new(50)] {
+            assert!(policy
+                .authorize(drone, stop, CommandScope::Node, 0)
+                .is_err());
+        }
+    }
+
+    #[test]
+    fn test_leader_role_follows_election() {
+        let mut policy = policy();
+        let add = CommandKind::from(&SwarmCommand::AddDrone {
+            drone: DroneId::new(8),
+        });
+        assert!(policy
+            .authorize(DroneId::new(1), add, CommandScope::Swarm, 0)
+            .is_ok());
+
+        policy.set_leader(Some(DroneId::new(2)));
+        assert!(policy
+            .authorize(DroneId::new(1), add, CommandScope::Swarm, 0)
+            .is_err());
+        assert!(policy
+            .authorize(DroneId::new(2), add, CommandScope::Swarm, 0)
+            .is_ok());
+    }
+
+    #[test]
+    fn test_delegation_expires() {
+        let gcs_crypto = CryptoContext::with_keys([0; 32], [1; 32]);
+        let mut keys = KeyStore::new();
+        keys.add_key(GCS, *gcs_crypto.public_key()).unwrap();
+        let mut policy = policy();
+
+        let grant =
+            Delegation::new(&gcs_crypto, GCS, MEMBER, Roles::GROUND_STATION, 1, 5000).unwrap();
+        policy.delegate(grant, &keys, 1000).unwrap();
+        assert!(policy
+            .authorize(MEMBER, CommandKind::Arm, CommandScope::Swarm, 4999)
+            .is_ok());
+        assert!(policy
+            .authorize(MEMBER, CommandKind::Arm, CommandScope::Swarm, 5000)
+            .is_err());
+
+        // Delegations lapse with the delegator's own roles
+        let grant =
+            Delegation::new(&gcs_crypto, GCS, MEMBER, Roles::GROUND_STATION, 2, 9000).unwrap();
+        policy.delegate(grant, &keys, 6000).unwrap();
+        policy.unassign(GCS);
+        assert!(policy
+            .authorize(MEMBER, CommandKind::Arm, CommandScope::Swarm, 6000)
+            .is_err());
+    }
+
+    #[test]
+    fn test_invalid_delegations_rejected() {
+        let gcs_crypto = CryptoContext::with_keys([0; 32], [1; 32]);
+        let member_crypto = CryptoContext::with_keys([0; 32], [3; 32]);
+        let mut keys = KeyStore::new();
+        keys.add_key(GCS, *gcs_crypto.public_key()).unwrap();
+        keys.add_key(MEMBER, *member_crypto.public_key()).unwrap();
+        let mut policy = policy();
+
+        // A member cannot hand out roles it does not hold
+        let escalation = Delegation::new(
+            &member_crypto,
+            MEMBER,
+            DroneId::new(4),
+            Roles::GROUND_STATION,
+            1,
+            5000,
+        )
+        .unwrap();
+        assert_eq!(
+            policy.delegate(escalation, &keys, 0),
+            Err(SwarmError::PermissionDenied)
+        );
+
+        // Nor forge one in the ground station's name
+        let mut forged = Delegation::new(
+            &member_crypto,
+            MEMBER,
+            MEMBER,
+            Roles::GROUND_STATION,
+            1,
+            5000,
+        )
+        .unwrap();
+        forged.delegator = GCS;
+        assert_eq!(
+            policy.delegate(forged, &keys, 0),
+            Err(SwarmError::AuthenticationFailed)
+        );
+
+        let expired =
+            Delegation::new(&gcs_crypto, GCS, MEMBER, Roles::GROUND_STATION, 1, 100).unwrap();
+        assert!(policy.delegate(expired, &keys, 100).is_err());
+        assert_eq!(policy.roles(MEMBER, 0), Roles::MEMBER);
+    }
+
+    #[test]
+    fn test_revoked_and_older_delegations_refused() {
+        let gcs_crypto = CryptoContext::with_keys([0; 32], [1; 32]);
+        let mut keys = KeyStore::new();
+        keys.add_key(GCS, *gcs_crypto.public_key()).unwrap();
+        let mut policy = policy();
+        let grant = |issue| {
+            Delegation::new(&gcs_crypto, GCS, MEMBER, Roles::GROUND_STATION, issue, 5000).unwrap()
+        };
+
+        policy.delegate(grant(2), &keys, 0).unwrap();
+        assert_eq!(
+            policy.delegate(grant(1), &keys, 0),
+            Err(SwarmError::InvalidMessage)
+        );
+        assert!(policy.roles(MEMBER, 0).contains(Roles::GROUND_STATION));
+
+        // A revoked delegation cannot be replayed
+        policy.revoke_delegation(GCS, MEMBER, 2).unwrap();
+        assert_eq!(policy.roles(MEMBER, 0), Roles::MEMBER);
+        assert!(policy.delegate(grant(2), &keys, 0).is_err());
+
+        // Revoking ahead also covers delegations not yet seen
+        policy.revoke_delegation(GCS, MEMBER, 4).unwrap();
+        assert!(policy.delegate(grant(3), &keys, 0).is_err());
+        policy.delegate(grant(5), &keys, 0).unwrap();
+        assert!(policy.roles(MEMBER, 0).contains(Roles::GROUND_STATION));
+    }
+}
@@ -0,0 +1,529 @@
+//! Distributed reputation and trust scores
+//!
+//! The [`IntrusionDetectionSystem`] only sees what this drone observes. Each
+//! node also gossips its direct opinions as signed [`TrustReport`]s, and the
+//! [`ReputationSystem`] aggregates them into one trust score per peer:
+//! - Direct trust is a beta reputation over this drone's own evidence (good
+//!   and bad observations), e.g. synced from the IDS
+//! - Second-hand opinions are discounted by how much the reporter itself is
+//!   trusted; reporters below [`ReputationConfig::min_trust`] are ignored,
+//!   and nobody's opinion of themselves counts
+//! - Outlier filtering: opinions whose expected trust lies further than
+//!   [`ReputationConfig::outlier_deviation`] from the median opinion are
+//!   dropped, so a minority of colluding liars can neither frame an honest
+//!   drone nor whitewash a malicious one
+//! - Replay protection: reports carry a signed issue time and sequence
+//!   number; stale reports, and reports no newer than one already accepted
+//!   from the reporter, are refused
+//!
+//! Routing, consensus voting and federated aggregation query scores through
+//! the [`TrustPolicy`] trait and exclude peers below the threshold.
+
+use crate::crypto::{CryptoContext, KeyStore, SIGNATURE_SIZE};
+use crate::security::IntrusionDetectionSystem;
+use crate::types::*;
+use ed25519_dalek::{Signature, Verifier};
+use heapless::{FnvIndexMap, Vec};
+use serde::{Deserialize, Serialize};
+
+/// Peers with direct evidence (power of 2)
+pub const MAX_TRUST_SUBJECTS: usize = 64;
+
+/// Opinions carried by one [`TrustReport`]
+pub const MAX_REPORT_ENTRIES: usize = 16;
+
+/// Reporters whose latest report is kept (power of 2)
+pub const MAX_REPORTERS: usize = 32;
+
+/// Reporters whose newest sequence number is remembered after their report
+/// expired or was evicted (power of 2)
+pub const MAX_TRACKED_REPORTERS: usize = 128;
+
+/// Domain separation of trust report signatures
+const REPORT_CONTEXT: &str = "droneswarm-v1 trust report";
+
+/// Decides whether a peer may take part
+pub trait TrustPolicy {
+    /// Check whether `drone` is trusted enough to route through, vote or
+    /// contribute model updates
+    fn is_trusted(&self, drone: DroneId) -> bool;
+}
+
+/// How second-hand opinions are weighed
+#[derive(Debug, Clone, Copy)]
+pub struct ReputationConfig {
+    /// Weight of second-hand evidence relative to direct evidence
+    pub indirect_weight: f32,
+    /// Opinions further than this from the median opinion are discarded
+    pub outlier_deviation: f32,
+    /// Peers below this trust are excluded, and their reports ignored
+    pub min_trust: f32,
+    /// Reports issued longer ago than this are dropped or refused (ms)
+    pub report_lifetime_ms: u64,
+}
+
+impl Default for ReputationConfig {
+    fn default() -> Self {
+        Self {
+            indirect_weight: 0.5,
+            outlier_deviation: 0.3,
+            min_trust: 0.3,
+            report_lifetime_ms: 60_000,
+        }
+    }
+}
+
+/// Good and bad observations of one peer
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct TrustOpinion {
+    /// Peer the evidence is about
+    pub subject: DroneId,
+    /// Weight of good observations
+    pub good: f32,
+    /// Weight of bad observations
+    pub bad: f32,
+}
+
+impl TrustOpinion {
+    /// Expected trust of a beta(good + 1, bad + 1) reputation
+    pub fn expected_trust(&self) -> f32 {
+        (self.good + 1.0) / (self.good + self.bad + 2.0)
+    }
+}
+
+/// Signed gossip of a node's direct opinions
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct TrustReport {
+    /// Node whose opinions these are
+    pub reporter: DroneId,
+    /// Increases with every report from the reporter
+    pub sequence: u32,
+    /// When the reporter issued the report (ms)
+    pub issued_ms: u64,
+    /// Direct opinions, most evidence first
+    pub opinions: Vec<TrustOpinion, MAX_REPORT_ENTRIES>,
+    /// Reporter's signature over all fields above
+    pub signature: Vec<u8, SIGNATURE_SIZE>,
+}
+
+impl TrustReport {
+    /// Digest covered by the signature
+    fn digest(&self) -> [u8; 32] {
+        let mut hasher = blake3::Hasher::new_derive_key(REPORT_CONTEXT);
+        hasher.update(&self.reporter.as_u64().to_le_bytes());
+        hasher.update(&self.sequence.to_le_bytes());
+        hasher.update(&self.issued_ms.to_le_bytes());
+        for opinion in &self.opinions {
+            hasher.update(&opinion.subject.as_u64().to_le_bytes());
+            hasher.update(&opinion.good.to_le_bytes());
+            hasher.update(&opinion.bad.to_le_bytes());
+        }
+        *hasher.finalize().as_bytes()
+    }
+}
+
+/// Direct and gossiped trust in swarm peers
+pub struct ReputationSystem {
+    /// This drone's ID
+    local_id: DroneId,
+    /// Aggregation parameters
+    config: ReputationConfig,
+    /// Own evidence per peer
+    direct: FnvIndexMap<u64, TrustOpinion, MAX_TRUST_SUBJECTS>,
+    /// IDS evidence (good, bad) per peer as of the last sync
+    synced: FnvIndexMap<u64, (f32, f32), MAX_TRUST_SUBJECTS>,
+    /// Latest report per reporter
+    reports: FnvIndexMap<u64, TrustReport, MAX_REPORTERS>,
+    /// Newest sequence number and issue time accepted per reporter
+    newest: FnvIndexMap<u64, (u32, u64), MAX_TRACKED_REPORTERS>,
+    /// Sequence number of the next own report
+    sequence: u32,
+}
+
+impl ReputationSystem {
+    /// Create a reputation system for `local_id`
+    pub fn new(local_id: DroneId, config: ReputationConfig) -> Self {
+        Self {
+            local_id,
+            config,
+            direct: FnvIndexMap::new(),
+            synced: FnvIndexMap::new(),
+            reports: FnvIndexMap::new(),
+            newest: FnvIndexMap::new(),
+            sequence: 0,
+        }
+    }
+
+    /// Add direct evidence about a peer
+    pub fn record(&mut self, subject: DroneId, good: f32, bad: f32) -> Result<()> {
+        use heapless::Entry;
+        let opinion = match self.direct.entry(subject.as_u64()) {
+            Entry::Occupied(o) => o.into_mut(),
+            Entry::Vacant(v) => v
+                .insert(TrustOpinion {
+                    subject,
+                    good: 0.0,
+                    bad: 0.0,
+                })
+                .map_err(|_| SwarmError::ResourceExhausted)?,
+        };
+        opinion.good += good;
+        opinion.bad += bad;
+        Ok(())
+    }
+
+    /// Add the intrusion detection system's evidence to direct evidence
+    ///
+    /// Only evidence gathered since the last sync is added, so repeated
+    /// syncs do not count it twice and evidence from [`record`](Self::record)
+    /// is kept.
+    pub fn sync_from_ids(&mut self, ids: &IntrusionDetectionSystem) -> Result<()> {
+        for (subject, good, bad) in ids.observations() {
+            if subject == self.local_id {
+                continue;
+            }
+            let (last_good, last_bad) = self
+                .synced
+                .get(&subject.as_u64())
+                .copied()
+                .unwrap_or((0.0, 0.0));
+            // The IDS halves old evidence, so totals may shrink
+            self.record(
+                subject,
+                (good - last_good).max(0.0),
+                (bad - last_bad).max(0.0),
+            )?;
+            self.synced
+                .insert(subject.as_u64(), (good, bad))
+                .map_err(|_| SwarmError::ResourceExhausted)?;
+        }
+        Ok(())
+    }
+
+    /// Trust from this drone's own evidence only (0.5 for an unknown peer)
+    pub fn direct_trust(&self, subject: DroneId) -> f32 {
+        self.direct
+            .get(&subject.as_u64())
+            .map_or(0.5, TrustOpinion::expected_trust)
+    }
+
+    /// Sign this drone's direct opinions for gossip
+    ///
+    /// Carries the [`MAX_REPORT_ENTRIES`] opinions backed by the most
+    /// evidence.
+    pub fn create_report(&mut self, crypto: &CryptoContext, now_ms: u64) -> Result<TrustReport> {
+        let mut ranked: Vec<TrustOpinion, MAX_TRUST_SUBJECTS> =
+            self.direct.values().copied().collect();
+        ranked.sort_unstable_by(|a, b| (b.good + b.bad).total_cmp(&(a.good + a.bad)));
+        ranked.truncate(MAX_REPORT_ENTRIES);
+
+        self.sequence = self.sequence.wrapping_add(1);
+        let mut report = TrustReport {
+            reporter: self.local_id,
+            sequence: self.sequence,
+            issued_ms: now_ms,
+            opinions: ranked.iter().copied().collect(),
+            signature: Vec::new(),
+        };
+        report.signature =
+            Vec::from_slice(&crypto.sign(&report.digest())).map_err(|_| SwarmError::BufferFull)?;
+        Ok(report)
+    }
+
+    /// Verify and store a gossiped report
+    ///
+    /// Returns `false` for own reports, for reports issued further from now
+    /// than the report lifetime and for reports no newer than the newest accepted
+    /// from the reporter, even if that one has since expired or been
+    /// evicted. When full, the report issued longest ago is evicted.
+    pub fn receive_report(
+        &mut self,
+        report: TrustReport,
+        keys: &KeyStore,
+        now_ms: u64,
+    ) -> Result<bool> {
+        if report.reporter == self.local_id || self.is_stale(report.issued_ms, now_ms) {
+            return Ok(false);
+        }
+        let public_key = keys.get_key(report.reporter)?;
+        let signature = Signature::from_slice(&report.signature)
+            .map_err(|_| SwarmError::AuthenticationFailed)?;
+        public_key
+            .verify(&report.digest(), &signature)
+            .map_err(|_| SwarmError::AuthenticationFailed)?;
+
+        let reporter = report.reporter.as_u64();
+        if let Some((sequence, _)) = self.newest.get(&reporter) {
+            if report.sequence.wrapping_sub(*sequence) as i32 <= 0 {
+                return Ok(false);
+            }
+        } else if self.newest.len() == MAX_TRACKED_REPORTERS {
+            // Replays of a stale reporter are refused as stale anyway
+            let lifetime = self.config.report_lifetime_ms;
+            self.newest
+                .retain(|_, (_, issued_ms)| now_ms.saturating_sub(*issued_ms) <= lifetime);
+        }
+        self.newest
+            .insert(reporter, (report.sequence, report.issued_ms))
+            .map_err(|_| SwarmError::ResourceExhausted)?;
+
+        if !self.reports.contains_key(&reporter) && self.reports.len() == MAX_REPORTERS {
+            let oldest = self
+                .reports
+                .iter()
+                .min_by_key(|(_, stored)| stored.issued_ms)
+                .map(|(id, _)| *id);
+            if let Some(id) = oldest {
+                self.reports.remove(&id);
+            }
+        }
+        self.reports
+            .insert(reporter, report)
+            .map_err(|_| SwarmError::ResourceExhausted)?;
+        Ok(true)
+    }
+
+    /// Drop reports issued longer ago than the configured lifetime
+    pub fn expire_reports(&mut self, now_ms: u64) {
+        let lifetime = self.config.report_lifetime_ms;
+        self.reports
+            .retain(|_, stored| now_ms.saturating_sub(stored.issued_ms) <= lifetime);
+    }
+
+    /// Check whether a report issued at `issued_ms` is outside its lifetime
+    fn is_stale(&self, issued_ms: u64, now_ms: u64) -> bool {
+        issued_ms.abs_diff(now_ms) > self.config.report_lifetime_ms
+    }
+
+    /// Aggregate trust in a peer from direct evidence and gossip
+    pub fn trust(&self, subject: DroneId) -> f32 {
+        let direct = self.direct.get(&subject.as_u64()).copied();
+
+        // Second-hand opinions from reporters we trust, weighted by that trust
+        let mut opinions: Vec<(TrustOpinion, f32), MAX_REPORTERS> = Vec::new();
+        for stored in self.reports.values() {
+            let reporter = stored.reporter;
+            let weight = self.direct_trust(reporter);
+            if reporter == subject || weight < self.config.min_trust {
+                continue;
+            }
+            if let Some(opinion) = stored.opinions.iter().find(|o| o.subject == subject) {
+                opinions.push((*opinion, weight)).ok();
+            }
+        }
+
+        // Median of all opinions, own included
+        let mut expectations: Vec<f32, { MAX_REPORTERS + 1 }> = opinions
+            .iter()
+            .map(|(opinion, _)| opinion.expected_trust())
+            .collect();
+        if let Some(direct) = direct {
+            expectations.push(direct.expected_trust()).ok();
+        }
+        expectations.sort_unstable_by(f32::total_cmp);
+        let median = match expectations.len() {
+            0 => return 0.5,
+            n if n % 2 == 1 => expectations[n / 2],
+            n => (expectations[n / 2 - 1] + expectations[n / 2]) / 2.0,
+        };
+
+        let (mut good, mut bad) = direct.map_or((0.0, 0.0), |d| (d.good, d.bad));
+        for (opinion, weight) in &opinions {
+            if libm::fabsf(opinion.expected_trust() - median) > self.config.outlier_deviation {
+                continue;
+            }
+            let discount = self.config.indirect_weight * weight;
+            good += discount * opinion.good;
+            bad += discount * opinion.bad;
+        }
+        (good + 1.0) / (good + bad + 2.0)
+    }
+
+    /// Number of reporters whose reports are held
+    pub fn reporter_count(&self) -> usize {
+        self.reports.len()
+    }
+
+    /// Aggregation parameters
+    pub fn config(&self) -> &ReputationConfig {
+        &self.config
+    }
+}
+
+impl TrustPolicy for ReputationSystem {
+    fn is_trusted(&self, drone: DroneId) -> bool {
+        drone == self.local_id || self.trust(drone) >= self.config.min_trust
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const ME: DroneId = DroneId::new(0);
+    const SUSPECT: DroneId = DroneId::new(9);
+
+    /// Reporters 1..=n with keys registered in `keys`
+    fn reporters(n: u8, keys: &mut KeyStore) -> std::vec::Vec<(DroneId, CryptoContext)> {
+        (1..=n)
+            .map(|i| {
+                let crypto = CryptoContext::with_keys([0; 32], [i; 32]);
+                keys.add_key(DroneId::new(i as u64), *crypto.public_key())
+                    .unwrap();
+                (DroneId::new(i as u64), crypto)
+            })
+            .collect()
+    }
+
+    fn report_on(
+        reporter: DroneId,
+        crypto: &CryptoContext,
+        subject: DroneId,
+        good: f32,
+        bad: f32,
+    ) -> TrustReport {
+        let mut system = ReputationSystem::new(reporter, ReputationConfig::default());
+        system.record(subject, good, bad).unwrap();
+        system.create_report(crypto, 0).unwrap()
+    }
+
+    #[test]
+    fn test_direct_trust() {
+        let mut system = ReputationSystem::new(ME, ReputationConfig::default());
+        assert_eq!(system.trust(SUSPECT), 0.5);
+
+        system.record(SUSPECT, 8.0, 0.0).unwrap();
+        assert!((system.trust(SUSPECT) - 0.9).abs() < 1e-6);
+        system.record(SUSPECT, 0.0, 30.0).unwrap();
+        assert!(!system.is_trusted(SUSPECT));
+        assert!(system.is_trusted(ME));
+    }
+
+    #[test]
+    fn test_gossip_lowers_trust() {
+        let mut keys = KeyStore::new();
+        let mut system = ReputationSystem::new(ME, ReputationConfig::default());
+        for (id, crypto) in reporters(3, &mut keys) {
+            let report = report_on(id, &crypto, SUSPECT, 0.0, 20.0);
+            assert!(system.receive_report(report, &keys, 0).unwrap());
+        }
+        assert_eq!(system.reporter_count(), 3);
+        assert!(!system.is_trusted(SUSPECT));
+    }
+
+    #[test]
+    fn test_forged_and_stale_reports() {
+        let mut keys = KeyStore::new();
+        let mut system = ReputationSystem::new(ME, ReputationConfig::default());
+        let (id, crypto) = reporters(1, &mut keys).pop().unwrap();
+
+        let report = report_on(id, &crypto, SUSPECT, 0.0, 20.0);
+        let mut forged = report.clone();
+        forged.opinions[0].bad = 0.0;
+        forged.opinions[0].good = 50.0;
+        assert_eq!(
+            system.receive_report(forged, &keys, 0).unwrap_err(),
+            SwarmError::AuthenticationFailed
+        );
+
+        assert!(system.receive_report(report.clone(), &keys, 0).unwrap());
+        assert!(!system.receive_report(report, &keys, 10).unwrap());
+
+        // Unknown reporters cannot sign
+        let stranger = CryptoContext::with_keys([0; 32], [77; 32]);
+        let report = report_on(DroneId::new(77), &stranger, SUSPECT, 0.0, 20.0);
+        assert!(system.receive_report(report, &keys, 0).is_err());
+
+        let lifetime = ReputationConfig::default().report_lifetime_ms;
+        system.expire_reports(lifetime + 1);
+        assert_eq!(system.reporter_count(), 0);
+
+        // An old capture is not stored as fresh
+        let report = report_on(id, &crypto, SUSPECT, 0.0, 20.0);
+        assert!(!system.receive_report(report, &keys, lifetime + 1).unwrap());
+    }
+
+    #[test]
+    fn test_replay_after_eviction_refused() {
+        let mut keys = KeyStore::new();
+        let mut system = ReputationSystem::new(ME, ReputationConfig::default());
+        let all = reporters(MAX_REPORTERS as u8 + 1, &mut keys);
+
+        let (first, crypto) = &all[0];
+        let mut reporter = ReputationSystem::new(*first, ReputationConfig::default());
+        reporter.record(SUSPECT, 20.0, 0.0).unwrap();
+        let old = reporter.create_report(crypto, 0).unwrap();
+        let new = reporter.create_report(crypto, 10).unwrap();
+        assert!(system.receive_report(new, &keys, 10).unwrap());
+
+        // Newer reports of every other reporter push the first one out
+        for (id, crypto) in &all[1..] {
+            let mut other = ReputationSystem::new(*id, ReputationConfig::default());
+            other.record(SUSPECT, 0.0, 1.0).unwrap();
+            let report = other.create_report(crypto, 20).unwrap();
+            assert!(system.receive_report(report, &keys, 20).unwrap());
+        }
+        assert_eq!(system.reporter_count(), MAX_REPORTERS);
+
+        assert!(!system.receive_report(old, &keys, 30).unwrap());
+        assert_eq!(system.reporter_count(), MAX_REPORTERS);
+    }
+
+    #[test]
+    fn test_liars_filtered_as_outliers() {
+        let mut keys = KeyStore::new();
+        let mut system = ReputationSystem::new(ME, ReputationConfig::default());
+        let all = reporters(5, &mut keys);
+        system.record(SUSPECT, 10.0, 0.0).unwrap();
+
+        // Three honest peers agree with us; two try to frame the drone
+        for (index, (id, crypto)) in all.iter().enumerate() {
+            let (good, bad) = if index < 3 { (10.0, 0.0) } else { (0.0, 100.0) };
+            let report = report_on(*id, crypto, SUSPECT, good, bad);
+            system.receive_report(report, &keys, 0).unwrap();
+        }
+        assert!(system.trust(SUSPECT) > 0.9);
+    }
+
+    #[test]
+    fn test_distrusted_reporters_ignored() {
+        let mut keys = KeyStore::new();
+        let mut system = ReputationSystem::new(ME, ReputationConfig::default());
+        let (liar, crypto) = reporters(1, &mut keys).pop().unwrap();
+        system.record(liar, 0.0, 20.0).unwrap();
+
+        let report = report_on(liar, &crypto, SUSPECT, 0.0, 100.0);
+        system.receive_report(report, &keys, 0).unwrap();
+        assert_eq!(system.trust(SUSPECT), 0.5);
+
+        // Nor can a drone vouch for itself
+        let mut boast = report_on(liar, &crypto, liar, 100.0, 0.0);
+        boast.sequence = 2;
+        boast.signature = Vec::from_slice(&crypto.sign(&boast.digest())).unwrap();
+        system.receive_report(boast, &keys, 0).unwrap();
+        assert!(!system.is_trusted(liar));
+    }
+
+    #[test]
+    fn test_sync_from_ids() {
+        let mut ids = IntrusionDetectionSystem::new();
+        ids.observe_position(SUSPECT, [0.0; 3], 0).unwrap();
+        ids.observe_position(SUSPECT, [9000.0, 0.0, 0.0], 1000).ok();
+
+        let mut system = ReputationSystem::new(ME, ReputationConfig::default());
+        system.sync_from_ids(&ids).unwrap();
+        assert!(system.direct_trust(SUSPECT) < 0.5);
+        assert_eq!(system.direct_trust(SUSPECT), ids.trust(SUSPECT));
+
+        // Syncing again adds nothing new
+        system.sync_from_ids(&ids).unwrap();
+        assert_eq!(system.direct_trust(SUSPECT), ids.trust(SUSPECT));
+
+        // Recorded evidence survives a sync
+        system.record(SUSPECT, 0.0, 10.0).unwrap();
+        let recorded = system.direct_trust(SUSPECT);
+        system.sync_from_ids(&ids).unwrap();
+        assert_eq!(system.direct_trust(SUSPECT), recorded);
+        assert!(recorded < ids.trust(SUSPECT));
+    }
+}
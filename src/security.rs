@@ -1,8 +1,13 @@
 //! Advanced security features and intrusion detection
 
 // Crypto types available for future enhancements
+use crate::consensus::ConsensusMessage;
+use crate::crypto::{CryptoContext, SIGNATURE_SIZE};
+use crate::merkle::{MerkleProof, MerkleTree};
 use crate::types::*;
-use heapless::{FnvIndexMap, Vec};
+use ed25519_dalek::{Signature, Verifier, VerifyingKey};
+use heapless::{Deque, FnvIndexMap, Vec};
+use serde::{Deserialize, Serialize};
 
 /// Security monitor for detecting and preventing attacks
 pub struct SecurityMonitor {
@@ -69,6 +74,35 @@ impl SecurityMonitor {
     pub fn clear_failures(&mut self, drone_id: DroneId) {
         self.failed_auth_attempts.remove(&drone_id.as_u64());
     }
+
+    /// Intrusion detection system, to query trust
+    pub fn ids(&self) -> &IntrusionDetectionSystem {
+        &self.ids
+    }
+
+    /// Intrusion detection system, to feed behavioral observations
+    pub fn ids_mut(&mut self) -> &mut IntrusionDetectionSystem {
+        &mut self.ids
+    }
+
+    /// Move pending detections into the audit log
+    ///
+    /// Detections the log has no room for stay pending. Returns the number of
+    /// detections logged.
+    pub fn audit_detections(&mut self, log: &mut AuditLog) -> Result<usize> {
+        let mut logged = 0;
+        while log.has_room() {
+            let Some(report) = self.ids.poll_detection() else {
+                break;
+            };
+            log.log(
+                report.drone,
+                AuditEvent::IntrusionDetected(report.detection),
+            )?;
+            logged += 1;
+        }
+        Ok(logged)
+    }
 }
 
 impl Default for SecurityMonitor {
@@ -140,20 +174,245 @@ impl RateLimiter {
     }
 }
 
-/// Intrusion Detection System
+/// Messages larger than this are flagged by [`IntrusionDetectionSystem::analyze`]
+pub const MAX_MESSAGE_SIZE: usize = 2048;
+
+/// Drones whose behavior the IDS tracks (power of 2)
+pub const MAX_MONITORED_DRONES: usize = 128;
+
+/// Radio fingerprints remembered for Sybil detection (power of 2)
+pub const MAX_FINGERPRINTS: usize = 128;
+
+/// Detections queued until polled
+pub const MAX_PENDING_DETECTIONS: usize = 32;
+
+/// Evidence weight kept per drone before older evidence is halved
+const MAX_EVIDENCE: f32 = 100.0;
+
+/// Thresholds of the behavioral detection rules
+#[derive(Debug, Clone, Copy)]
+pub struct DetectionConfig {
+    /// Fastest a drone can fly (m/s)
+    pub max_speed_mps: f32,
+    /// Position error tolerated between two reports (m)
+    pub position_noise_m: f32,
+    /// Two drones reporting positions this close count as co-located (m)
+    pub colocation_radius_m: f32,
+    /// Consecutive co-located reports before flagging a Sybil
+    pub colocation_strikes: u8,
+    /// Position reports this far apart in time are not compared (ms)
+    pub colocation_window_ms: u64,
+    /// Fingerprints not seen for this long are forgotten (ms)
+    pub fingerprint_lifetime_ms: u64,
+    /// Forwarding ratio below which a relay is a black hole
+    pub black_hole_ratio: f32,
+    /// Forwarding ratio below which a relay is a grey hole
+    pub grey_hole_ratio: f32,
+    /// Packets handed to a relay before its forwarding ratio is judged
+    pub min_forwarding_samples: u32,
+    /// Window over which consensus traffic is counted (ms)
+    pub flood_window_ms: u64,
+    /// Raft heartbeats allowed per window
+    pub max_heartbeats_per_window: u16,
+    /// Vote requests allowed per window
+    pub max_votes_per_window: u16,
+    /// Largest plausible Raft term increase between two messages
+    pub max_term_jump: u64,
+    /// Drones whose trust falls below this are banned
+    pub ban_trust: f32,
+}
+
+impl Default for DetectionConfig {
+    fn default() -> Self {
+        Self {
+            max_speed_mps: 25.0,
+            position_noise_m: 5.0,
+            colocation_radius_m: 0.5,
+            colocation_strikes: 3,
+            colocation_window_ms: 500,
+            fingerprint_lifetime_ms: 300_000,
+            black_hole_ratio: 0.1,
+            grey_hole_ratio: 0.6,
+            min_forwarding_samples: 20,
+            flood_window_ms: 1000,
+            max_heartbeats_per_window: 20,
+            max_votes_per_window: 3,
+            max_term_jump: 3,
+            ban_trust: 0.1,
+        }
+    }
+}
+
+/// Misbehavior recognized by a detection rule
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub enum Detection {
+    /// Message larger than [`MAX_MESSAGE_SIZE`]
+    OversizedMessage { len: usize },
+    /// Position report implying a speed the airframe cannot fly
+    ImpossibleMovement { speed_mps: f32 },
+    /// Radio fingerprint already seen under another identity
+    SybilFingerprint { fingerprint: u32, other: DroneId },
+    /// Repeatedly reporting the same position as another identity
+    SybilColocation { other: DroneId },
+    /// Relay dropping (nearly) everything it should forward
+    BlackHole { forwarding_ratio: f32 },
+    /// Relay selectively dropping traffic it should forward
+    GreyHole { forwarding_ratio: f32 },
+    /// Raft heartbeats above the allowed rate
+    HeartbeatFlood { count: u16 },
+    /// Vote requests above the allowed rate
+    VoteFlood { count: u16 },
+    /// Raft term raised implausibly far, to force elections
+    TermInflation { jump: u64 },
+}
+
+impl Detection {
+    /// Evidence weight against the drone's trust
+    pub fn severity(&self) -> f32 {
+        match self {
+            Self::OversizedMessage { .. } => 0.5,
+            Self::HeartbeatFlood { .. } | Self::VoteFlood { .. } => 3.0,
+            Self::ImpossibleMovement { .. }
+            | Self::GreyHole { .. }
+            | Self::TermInflation { .. } => 5.0,
+            Self::BlackHole { .. } => 15.0,
+            Self::SybilFingerprint { .. } | Self::SybilColocation { .. } => 25.0,
+        }
+    }
+
+    /// Feed a fixed little-endian encoding into `hasher`
+    fn hash_into(&self, hasher: &mut blake3::Hasher) {
+        match *self {
+            Self::OversizedMessage { len } => {
+                hasher.update(&[0]);
+                hasher.update(&(len as u64).to_le_bytes());
+            }
+            Self::ImpossibleMovement { speed_mps } => {
+                hasher.update(&[1]);
+                hasher.update(&speed_mps.to_le_bytes());
+            }
+            Self::SybilFingerprint { fingerprint, other } => {
+                hasher.update(&[2]);
+                hasher.update(&fingerprint.to_le_bytes());
+                hasher.update(&other.as_u64().to_le_bytes());
+            }
+            Self::SybilColocation { other } => {
+                hasher.update(&[3]);
+                hasher.update(&other.as_u64().to_le_bytes());
+            }
+            Self::BlackHole { forwarding_ratio } => {
+                hasher.update(&[4]);
+                hasher.update(&forwarding_ratio.to_le_bytes());
+            }
+            Self::GreyHole { forwarding_ratio } => {
+                hasher.update(&[5]);
+                hasher.update(&forwarding_ratio.to_le_bytes());
+            }
+            Self::HeartbeatFlood { count } => {
+                hasher.update(&[6]);
+                hasher.update(&count.to_le_bytes());
+            }
+            Self::VoteFlood { count } => {
+                hasher.update(&[7]);
+                hasher.update(&count.to_le_bytes());
+            }
+            Self::TermInflation { jump } => {
+                hasher.update(&[8]);
+                hasher.update(&jump.to_le_bytes());
+            }
+        }
+    }
+}
+
+/// Detection attributed to a drone
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct DetectionReport {
+    /// Drone that misbehaved
+    pub drone: DroneId,
+    /// What it did
+    pub detection: Detection,
+    /// When it was observed (ms)
+    pub timestamp_ms: u64,
+}
+
+/// Observed behavior of one drone
+#[derive(Debug, Clone, Copy, Default)]
+struct DroneBehavior {
+    /// Last reported position and its timestamp
+    position: Option<([f32; 3], u64)>,
+    /// Drone it was last co-located with and for how many reports
+    colocated: Option<(DroneId, u8)>,
+    /// Packets handed to it for forwarding, and seen forwarded
+    handed: u32,
+    forwarded: u32,
+    /// Consensus traffic in the current window
+    window_start_ms: u64,
+    heartbeats: u16,
+    votes: u16,
+    /// Highest Raft term seen from it
+    term: u64,
+    /// Beta reputation evidence
+    good: f32,
+    bad: f32,
+}
+
+impl DroneBehavior {
+    /// Expected trust of a beta(good + 1, bad + 1) reputation
+    fn trust(&self) -> f32 {
+        (self.good + 1.0) / (self.good + self.bad + 2.0)
+    }
+
+    fn add_evidence(&mut self, good: f32, bad: f32) {
+        self.good += good;
+        self.bad += bad;
+        // Let recent behavior outweigh old behavior
+        if self.good + self.bad > MAX_EVIDENCE {
+            self.good /= 2.0;
+            self.bad /= 2.0;
+        }
+    }
+}
+
+/// Behavioral Intrusion Detection System
+///
+/// Rules look at swarm-level semantics rather than message bytes:
+/// - Kinematics: position reports the airframe cannot physically fly
+/// - Sybil identities: one radio fingerprint, or one position, claimed by
+///   several drone IDs
+/// - Routing: relays whose forwarding ratio marks them as black or grey holes
+/// - Consensus: heartbeat and vote floods, and inflated Raft terms
+///
+/// Every observation is evidence for a per-drone beta reputation; drones
+/// whose trust drops below [`DetectionConfig::ban_trust`] are banned.
+/// Detections queue up for [`poll_detection`](Self::poll_detection), e.g. to
+/// go into the [`AuditLog`].
 pub struct IntrusionDetectionSystem {
+    /// Rule thresholds
+    config: DetectionConfig,
     /// Banned drones
     banned: Vec<DroneId, 100>,
-    /// Suspicious activity counter
-    suspicious_activity: FnvIndexMap<u64, u32, 128>,
+    /// Behavior per drone
+    behavior: FnvIndexMap<u64, DroneBehavior, MAX_MONITORED_DRONES>,
+    /// Drone seen with each radio fingerprint, and when it was last seen
+    fingerprints: FnvIndexMap<u32, (DroneId, u64), MAX_FINGERPRINTS>,
+    /// Detections not yet polled
+    detections: Deque<DetectionReport, MAX_PENDING_DETECTIONS>,
 }
 
 impl IntrusionDetectionSystem {
     /// Create a new IDS
     pub fn new() -> Self {
+        Self::with_config(DetectionConfig::default())
+    }
+
+    /// Create an IDS with custom rule thresholds
+    pub fn with_config(config: DetectionConfig) -> Self {
         Self {
+            config,
             banned: Vec::new(),
-            suspicious_activity: FnvIndexMap::new(),
+            behavior: FnvIndexMap::new(),
+            fingerprints: FnvIndexMap::new(),
+            detections: Deque::new(),
         }
     }
 
@@ -172,60 +431,288 @@ impl IntrusionDetectionSystem {
         self.banned.contains(&drone_id)
     }
 
-    /// Analyze message for suspicious patterns
+    /// Trust in a drone from local observations (0.5 for an unknown drone)
+    pub fn trust(&self, drone_id: DroneId) -> f32 {
+        self.behavior
+            .get(&drone_id.as_u64())
+            .map_or(0.5, DroneBehavior::trust)
+    }
+
+    /// Beta reputation evidence (drone, good, bad) of every observed drone
+    pub fn observations(&self) -> impl Iterator<Item = (DroneId, f32, f32)> + '_ {
+        self.behavior
+            .iter()
+            .map(|(id, state)| (DroneId::new(*id), state.good, state.bad))
+    }
+
+    /// Next detection not yet polled
+    pub fn poll_detection(&mut self) -> Option<DetectionReport> {
+        self.detections.pop_front()
+    }
+
+    /// Check a raw message before decoding
     pub fn analyze(&mut self, drone_id: DroneId, message: &[u8]) -> Result<()> {
-        let mut suspicious = false;
+        if message.len() > MAX_MESSAGE_SIZE {
+            let detection = Detection::OversizedMessage { len: message.len() };
+            self.report(drone_id, detection, crate::get_time_ms())?;
+        }
+        self.admit(drone_id)
+    }
 
-        // Check for abnormal message size
-        if message.len() > 2048 {
-            suspicious = true;
+    /// Check a position report for physically impossible movement and for
+    /// identities sharing one position
+    ///
+    /// Reports no newer than the last one from the drone (reordered or
+    /// duplicated in transit) are ignored.
+    pub fn observe_position(
+        &mut self,
+        drone_id: DroneId,
+        position: [f32; 3],
+        timestamp_ms: u64,
+    ) -> Result<()> {
+        let config = self.config;
+        let mut state = self.state(drone_id)?;
+
+        if let Some((last, last_ms)) = state.position {
+            if timestamp_ms <= last_ms {
+                return self.admit(drone_id);
+            }
+            let dt = (timestamp_ms - last_ms) as f32 / 1000.0;
+            let moved = (distance(&position, &last) - config.position_noise_m).max(0.0);
+            let speed_mps = moved / dt;
+            if speed_mps > config.max_speed_mps {
+                self.report(
+                    drone_id,
+                    Detection::ImpossibleMovement { speed_mps },
+                    timestamp_ms,
+                )?;
+            } else {
+                self.evidence(drone_id, 1.0, 0.0)?;
+            }
         }
 
-        // Check for repeated patterns (possible attack)
-        if Self::has_repetitive_pattern(message) {
-            suspicious = true;
+        // Another identity at the same spot at the same time
+        let twin = self
+            .behavior
+            .iter()
+            .filter(|(id, _)| **id != drone_id.as_u64())
+            .find(|(_, other)| {
+                other.position.is_some_and(|(at, at_ms)| {
+                    timestamp_ms.abs_diff(at_ms) <= config.colocation_window_ms
+                        && distance(&position, &at) < config.colocation_radius_m
+                })
+            })
+            .map(|(id, _)| DroneId::new(*id));
+        state.colocated = match (twin, state.colocated) {
+            (Some(other), Some((same, strikes))) if same == other => Some((other, strikes + 1)),
+            (Some(other), _) => Some((other, 1)),
+            (None, _) => None,
+        };
+        state.position = Some((position, timestamp_ms));
+        if let Some(behavior) = self.behavior.get_mut(&drone_id.as_u64()) {
+            behavior.position = state.position;
+            behavior.colocated = state.colocated;
         }
 
-        if suspicious {
-            use heapless::Entry;
-            let count = match self.suspicious_activity.entry(drone_id.as_u64()) {
-                Entry::Occupied(mut o) => {
-                    *o.get_mut() += 1;
-                    *o.get()
-                }
-                Entry::Vacant(v) => {
-                    v.insert(1).ok();
-                    1
+        if let Some((other, strikes)) = state.colocated {
+            if strikes == config.colocation_strikes {
+                self.report(drone_id, Detection::SybilColocation { other }, timestamp_ms)?;
+            }
+        }
+        self.admit(drone_id)
+    }
+
+    /// Check the radio fingerprint (e.g. hashed MAC and channel state) a
+    /// drone's frames arrived with
+    ///
+    /// Fingerprints unseen for [`DetectionConfig::fingerprint_lifetime_ms`]
+    /// are forgotten, so a radio handed to another drone is not held against
+    /// it. When the table is full, the fingerprint seen longest ago makes
+    /// room.
+    pub fn observe_fingerprint(
+        &mut self,
+        drone_id: DroneId,
+        fingerprint: u32,
+        now_ms: u64,
+    ) -> Result<()> {
+        let lifetime = self.config.fingerprint_lifetime_ms;
+        let owner = self
+            .fingerprints
+            .get(&fingerprint)
+            .filter(|(_, seen_ms)| now_ms.saturating_sub(*seen_ms) <= lifetime)
+            .map(|(owner, _)| *owner);
+        match owner {
+            Some(owner) if owner != drone_id => {
+                let detection = Detection::SybilFingerprint {
+                    fingerprint,
+                    other: owner,
+                };
+                self.report(drone_id, detection, now_ms)?;
+                let detection = Detection::SybilFingerprint {
+                    fingerprint,
+                    other: drone_id,
+                };
+                self.report(owner, detection, now_ms)?;
+                // The earlier claimant is banned too, but this call is about `drone_id`
+                self.admit(owner).ok();
+            }
+            _ => {
+                if self.fingerprints.len() == MAX_FINGERPRINTS
+                    && !self.fingerprints.contains_key(&fingerprint)
+                {
+                    let oldest = self
+                        .fingerprints
+                        .iter()
+                        .min_by_key(|(_, (_, seen_ms))| *seen_ms)
+                        .map(|(fingerprint, _)| *fingerprint);
+                    if let Some(oldest) = oldest {
+                        self.fingerprints.remove(&oldest);
+                    }
                 }
+                self.fingerprints
+                    .insert(fingerprint, (drone_id, now_ms))
+                    .map_err(|_| SwarmError::ResourceExhausted)?;
+            }
+        }
+        self.admit(drone_id)
+    }
+
+    /// Record packets handed to a relay and how many it was overheard
+    /// forwarding
+    pub fn observe_forwarding(
+        &mut self,
+        relay: DroneId,
+        handed: u32,
+        forwarded: u32,
+        now_ms: u64,
+    ) -> Result<()> {
+        let config = self.config;
+        let mut state = self.state(relay)?;
+        state.handed = state.handed.saturating_add(handed);
+        state.forwarded = state.forwarded.saturating_add(forwarded.min(handed));
+
+        let judged = state.handed >= config.min_forwarding_samples;
+        if judged {
+            let forwarding_ratio = state.forwarded as f32 / state.handed as f32;
+            if forwarding_ratio < config.black_hole_ratio {
+                self.report(relay, Detection::BlackHole { forwarding_ratio }, now_ms)?;
+            } else if forwarding_ratio < config.grey_hole_ratio {
+                self.report(relay, Detection::GreyHole { forwarding_ratio }, now_ms)?;
+            } else {
+                self.evidence(relay, 1.0, 0.0)?;
+            }
+        }
+        if let Some(behavior) = self.behavior.get_mut(&relay.as_u64()) {
+            (behavior.handed, behavior.forwarded) = if judged {
+                (0, 0)
+            } else {
+                (state.handed, state.forwarded)
             };
+        }
+        self.admit(relay)
+    }
 
-            // Ban after multiple suspicious activities
-            if count > 10 {
-                self.ban_drone(drone_id)?;
-                return Err(SwarmError::PermissionDenied);
+    /// Check consensus traffic from `sender` for floods and term inflation
+    pub fn observe_consensus(
+        &mut self,
+        sender: DroneId,
+        message: &ConsensusMessage,
+        now_ms: u64,
+    ) -> Result<()> {
+        let config = self.config;
+        let mut state = self.state(sender)?;
+        if now_ms.saturating_sub(state.window_start_ms) >= config.flood_window_ms {
+            state.window_start_ms = now_ms;
+            state.heartbeats = 0;
+            state.votes = 0;
+        }
+
+        let term = match message {
+            ConsensusMessage::RequestVote { term, .. } => {
+                state.votes = state.votes.saturating_add(1);
+                *term
+            }
+            ConsensusMessage::AppendEntries { term, entries, .. } => {
+                if entries.is_empty() {
+                    state.heartbeats = state.heartbeats.saturating_add(1);
+                }
+                *term
             }
+            ConsensusMessage::VoteReply { term, .. }
+            | ConsensusMessage::AppendEntriesReply { term, .. } => *term,
+        };
+        let jump = term.saturating_sub(state.term);
+        let inflated = state.term > 0 && jump > config.max_term_jump;
+        let previous_term = state.term;
+        state.term = state.term.max(term);
+
+        if let Some(behavior) = self.behavior.get_mut(&sender.as_u64()) {
+            behavior.window_start_ms = state.window_start_ms;
+            behavior.heartbeats = state.heartbeats;
+            behavior.votes = state.votes;
+            behavior.term = state.term;
         }
 
-        Ok(())
+        // Flag each flood once per window
+        if state.heartbeats == config.max_heartbeats_per_window + 1 {
+            let count = state.heartbeats;
+            self.report(sender, Detection::HeartbeatFlood { count }, now_ms)?;
+        }
+        if state.votes == config.max_votes_per_window + 1 {
+            let count = state.votes;
+            self.report(sender, Detection::VoteFlood { count }, now_ms)?;
+        }
+        if inflated {
+            let jump = term - previous_term;
+            self.report(sender, Detection::TermInflation { jump }, now_ms)?;
+        }
+        self.admit(sender)
     }
 
-    /// Detect repetitive patterns (simple heuristic)
-    fn has_repetitive_pattern(data: &[u8]) -> bool {
-        if data.len() < 16 {
-            return false;
+    /// Behavior of a drone, tracked from now on if new
+    fn state(&mut self, drone_id: DroneId) -> Result<DroneBehavior> {
+        if let Some(state) = self.behavior.get(&drone_id.as_u64()) {
+            return Ok(*state);
         }
+        self.behavior
+            .insert(drone_id.as_u64(), DroneBehavior::default())
+            .map_err(|_| SwarmError::ResourceExhausted)?;
+        Ok(DroneBehavior::default())
+    }
 
-        // Check if first 8 bytes repeat
-        let pattern = &data[..8];
-        let mut matches = 0;
+    fn evidence(&mut self, drone_id: DroneId, good: f32, bad: f32) -> Result<()> {
+        self.state(drone_id)?;
+        if let Some(behavior) = self.behavior.get_mut(&drone_id.as_u64()) {
+            behavior.add_evidence(good, bad);
+        }
+        Ok(())
+    }
 
-        for chunk in data.chunks(8) {
-            if chunk == pattern {
-                matches += 1;
-            }
+    /// Count a detection against a drone and queue it for polling
+    fn report(&mut self, drone: DroneId, detection: Detection, timestamp_ms: u64) -> Result<()> {
+        self.evidence(drone, 0.0, detection.severity())?;
+        if self.detections.is_full() {
+            self.detections.pop_front();
         }
+        self.detections
+            .push_back(DetectionReport {
+                drone,
+                detection,
+                timestamp_ms,
+            })
+            .map_err(|_| SwarmError::BufferFull)
+    }
 
-        matches > data.len() / 16
+    /// Ban a drone whose trust has fallen too low
+    fn admit(&mut self, drone_id: DroneId) -> Result<()> {
+        if self.is_banned(drone_id) {
+            return Err(SwarmError::PermissionDenied);
+        }
+        if self.trust(drone_id) < self.config.ban_trust {
+            self.ban_drone(drone_id)?;
+            return Err(SwarmError::PermissionDenied);
+        }
+        Ok(())
     }
 
     /// Unban a drone (for testing or after verification)
@@ -237,6 +724,12 @@ impl IntrusionDetectionSystem {
     }
 }
 
+/// Euclidean distance between two positions (m)
+fn distance(a: &[f32; 3], b: &[f32; 3]) -> f32 {
+    let (dx, dy, dz) = (a[0] - b[0], a[1] - b[1], a[2] - b[2]);
+    libm::sqrtf(dx * dx + dy * dy + dz * dz)
+}
+
 impl Default for IntrusionDetectionSystem {
     fn default() -> Self {
         Self::new()
@@ -280,6 +773,10 @@ impl AccessControl {
     }
 
     /// Revoke authorization
+    ///
+    /// The drone keeps any key it holds; remove it from the
+    /// [`GroupKeyServer`](crate::group_key::GroupKeyServer) to lock it out of
+    /// swarm traffic.
     pub fn revoke(&mut self, drone_id: DroneId) -> Result<()> {
         self.authorized
             .remove(&drone_id.as_u64())
@@ -294,23 +791,88 @@ impl Default for AccessControl {
     }
 }
 
-/// Secure audit log for forensics
+/// Entries held in memory until sealed and exported
+pub const AUDIT_LOG_CAPACITY: usize = 1000;
+
+/// Entries sealed by one [`AuditCheckpoint`]
+pub const AUDIT_BATCH_SIZE: usize = 64;
+
+/// Checkpoints held in memory until exported
+pub const MAX_AUDIT_CHECKPOINTS: usize = 32;
+
+/// Domain separation of audit entry hashes
+const AUDIT_ENTRY_CONTEXT: &str = "droneswarm-v1 audit entry";
+
+/// Domain separation of checkpoint signatures
+const AUDIT_CHECKPOINT_CONTEXT: &str = "droneswarm-v1 audit checkpoint";
+
+/// Tamper-evident audit log for forensics
+///
+/// Each entry carries the hash of its predecessor, so editing, dropping or
+/// reordering entries breaks the chain. [`commit`](Self::commit) seals
+/// batches of entries into Merkle roots signed by this node, which back
+/// inclusion proofs for single entries.
+///
+/// Entries that are both sealed and exported leave memory first. What
+/// happens when the log is full of entries that are not is set by its
+/// [`AuditOverflow`] policy.
 pub struct AuditLog {
-    /// Log entries
-    entries: Vec<AuditEntry, 1000>,
+    /// Retained entries, oldest first
+    entries: Vec<AuditEntry, AUDIT_LOG_CAPACITY>,
+    /// Retained checkpoints, oldest first
+    checkpoints: Vec<AuditCheckpoint, MAX_AUDIT_CHECKPOINTS>,
+    /// Sequence number of the next entry
+    next_sequence: u64,
+    /// Hash of the newest entry
+    head: [u8; 32],
+    /// Entries below this sequence number are sealed
+    sealed_through: u64,
+    /// Entries below this sequence number are exported
+    exported_through: u64,
+    /// Leading retained checkpoints already exported
+    exported_checkpoints: usize,
+    /// Behavior when full
+    overflow: AuditOverflow,
 }
 
-#[derive(Debug, Clone, Copy)]
+/// What a full [`AuditLog`] does with new records
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum AuditOverflow {
+    /// Drop the oldest record, even if not yet sealed or exported
+    #[default]
+    Rotate,
+    /// Fail with [`SwarmError::BufferFull`] rather than lose evidence
+    Refuse,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
 pub struct AuditEntry {
+    /// Position in the log, starting at 0
+    pub sequence: u64,
     /// Timestamp
     pub timestamp: u64,
     /// Source drone
     pub source: DroneId,
     /// Event type
     pub event: AuditEvent,
+    /// Hash of the previous entry (zero for the first)
+    pub prev_hash: [u8; 32],
 }
 
-#[derive(Debug, Clone, Copy)]
+impl AuditEntry {
+    /// Hash the next entry links to
+    pub fn hash(&self) -> [u8; 32] {
+        let mut hasher = blake3::Hasher::new_derive_key(AUDIT_ENTRY_CONTEXT);
+        hasher.update(&self.sequence.to_le_bytes());
+        hasher.update(&self.timestamp.to_le_bytes());
+        hasher.update(&self.source.as_u64().to_le_bytes());
+        self.event.hash_into(&mut hasher);
+        hasher.update(&self.prev_hash);
+        *hasher.finalize().as_bytes()
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
 pub enum AuditEvent {
     /// Authentication success
     AuthSuccess,
@@ -328,34 +890,177 @@ pub enum AuditEvent {
     DroneLeft,
     /// Security violation detected
     SecurityViolation,
+    /// Intrusion detection rule fired
+    IntrusionDetected(Detection),
     /// System error
     SystemError,
 }
 
+impl AuditEvent {
+    /// Feed a fixed little-endian encoding into `hasher`
+    fn hash_into(&self, hasher: &mut blake3::Hasher) {
+        let tag: u8 = match self {
+            Self::AuthSuccess => 0,
+            Self::AuthFailure => 1,
+            Self::MessageSent => 2,
+            Self::MessageReceived => 3,
+            Self::ConsensusReached => 4,
+            Self::DroneJoined => 5,
+            Self::DroneLeft => 6,
+            Self::SecurityViolation => 7,
+            Self::IntrusionDetected(_) => 8,
+            Self::SystemError => 9,
+        };
+        hasher.update(&[tag]);
+        if let Self::IntrusionDetected(detection) = self {
+            detection.hash_into(hasher);
+        }
+    }
+}
+
+/// Signed Merkle root over a batch of consecutive entries
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct AuditCheckpoint {
+    /// Sequence number of the first sealed entry
+    pub first_sequence: u64,
+    /// Entries sealed
+    pub count: u16,
+    /// Merkle root over the hashes of the sealed entries
+    pub root: [u8; 32],
+    /// When the batch was sealed (ms)
+    pub timestamp_ms: u64,
+    /// Node's signature over all fields above
+    pub signature: Vec<u8, SIGNATURE_SIZE>,
+}
+
+impl AuditCheckpoint {
+    /// Digest covered by the signature
+    fn digest(&self) -> [u8; 32] {
+        let mut hasher = blake3::Hasher::new_derive_key(AUDIT_CHECKPOINT_CONTEXT);
+        hasher.update(&self.first_sequence.to_le_bytes());
+        hasher.update(&self.count.to_le_bytes());
+        hasher.update(&self.root);
+        hasher.update(&self.timestamp_ms.to_le_bytes());
+        *hasher.finalize().as_bytes()
+    }
+
+    /// Check whether the entry with `sequence` is sealed by this checkpoint
+    pub fn covers(&self, sequence: u64) -> bool {
+        sequence >= self.first_sequence && sequence - self.first_sequence < u64::from(self.count)
+    }
+
+    /// Check the node's signature
+    pub fn verify(&self, public_key: &VerifyingKey) -> Result<()> {
+        let signature =
+            Signature::from_slice(&self.signature).map_err(|_| SwarmError::AuthenticationFailed)?;
+        public_key
+            .verify(&self.digest(), &signature)
+            .map_err(|_| SwarmError::AuthenticationFailed)
+    }
+
+    /// Check that `entry` is one of the sealed entries
+    ///
+    /// Only meaningful once [`verify`](Self::verify) succeeded.
+    pub fn verify_inclusion(&self, entry: &AuditEntry, proof: &MerkleProof) -> bool {
+        self.covers(entry.sequence)
+            && u64::from(proof.leaf_index) == entry.sequence - self.first_sequence
+            && proof.verify(&entry.hash(), &self.root)
+    }
+}
+
+/// One line of an exported audit log
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub enum AuditRecord {
+    /// Hash-chained log entry
+    Entry(AuditEntry),
+    /// Signed seal over earlier entries
+    Checkpoint(AuditCheckpoint),
+}
+
+/// Outcome of verifying an exported audit log
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct AuditSummary {
+    /// Entries with an intact hash chain
+    pub entries: usize,
+    /// Entries also sealed by a valid checkpoint
+    pub sealed: usize,
+    /// Valid checkpoints
+    pub checkpoints: usize,
+}
+
+/// Merkle tree over a batch of entries, and its root
+fn seal_tree(batch: &[AuditEntry]) -> Result<(MerkleTree<{ 2 * AUDIT_BATCH_SIZE }>, [u8; 32])> {
+    if batch.is_empty() || batch.len() > AUDIT_BATCH_SIZE {
+        return Err(SwarmError::InvalidParameter);
+    }
+    let hashes: Vec<[u8; 32], AUDIT_BATCH_SIZE> = batch.iter().map(AuditEntry::hash).collect();
+    seal_hashes(hashes.iter())
+}
+
+/// Merkle tree over a batch of entry hashes, and its root
+fn seal_hashes<'a>(
+    hashes: impl Iterator<Item = &'a [u8; 32]>,
+) -> Result<(MerkleTree<{ 2 * AUDIT_BATCH_SIZE }>, [u8; 32])> {
+    let mut leaves: Vec<&[u8], AUDIT_BATCH_SIZE> = Vec::new();
+    for hash in hashes {
+        leaves
+            .push(hash.as_slice())
+            .map_err(|_| SwarmError::InvalidParameter)?;
+    }
+    if leaves.is_empty() {
+        return Err(SwarmError::InvalidParameter);
+    }
+    let mut tree = MerkleTree::new();
+    let root = tree.compute_root(&leaves)?;
+    Ok((tree, root))
+}
+
 impl AuditLog {
-    /// Create a new audit log
+    /// Create a new audit log that rotates when full
     pub fn new() -> Self {
+        Self::with_overflow(AuditOverflow::Rotate)
+    }
+
+    /// Create a new audit log with the given overflow policy
+    pub fn with_overflow(overflow: AuditOverflow) -> Self {
         Self {
             entries: Vec::new(),
+            checkpoints: Vec::new(),
+            next_sequence: 0,
+            head: [0; 32],
+            sealed_through: 0,
+            exported_through: 0,
+            exported_checkpoints: 0,
+            overflow,
         }
     }
 
     /// Log an event
+    ///
+    /// Under [`AuditOverflow::Refuse`], fails with [`SwarmError::BufferFull`]
+    /// while the log is full of entries not yet both sealed and exported.
     pub fn log(&mut self, source: DroneId, event: AuditEvent) -> Result<()> {
+        if !self.has_room() {
+            return Err(SwarmError::BufferFull);
+        }
+        if self.entries.is_full() && !self.evict_entries() {
+            // Rotate log (remove oldest entry), giving up on sealing it
+            self.entries.remove(0);
+            self.sealed_through = self.sealed_through.max(self.entries[0].sequence);
+        }
+
         let entry = AuditEntry {
+            sequence: self.next_sequence,
             timestamp: Self::get_time(),
             source,
             event,
+            prev_hash: self.head,
         };
-
-        if self.entries.len() >= self.entries.capacity() {
-            // Rotate log (remove oldest entry)
-            self.entries.remove(0);
-        }
-
         self.entries
             .push(entry)
-            .map_err(|_| SwarmError::ResourceExhausted)?;
+            .map_err(|_| SwarmError::BufferFull)?;
+        self.head = entry.hash();
+        self.next_sequence += 1;
 
         Ok(())
     }
@@ -366,6 +1071,163 @@ impl AuditLog {
         &self.entries[start..]
     }
 
+    /// Entries not yet sealed by a checkpoint
+    pub fn unsealed_count(&self) -> usize {
+        (self.next_sequence - self.sealed_through) as usize
+    }
+
+    /// Retained checkpoints, oldest first
+    pub fn checkpoints(&self) -> &[AuditCheckpoint] {
+        &self.checkpoints
+    }
+
+    /// Seal all unsealed entries into signed checkpoints
+    ///
+    /// Call periodically; each checkpoint covers up to [`AUDIT_BATCH_SIZE`]
+    /// entries. Returns the number of checkpoints created.
+    pub fn commit(&mut self, crypto: &CryptoContext) -> Result<usize> {
+        let mut created = 0;
+        while self.sealed_through < self.next_sequence {
+            // Unsealed entries are never evicted
+            let start = (self.sealed_through - self.entries[0].sequence) as usize;
+            let end = self.entries.len().min(start + AUDIT_BATCH_SIZE);
+            let (_, root) = seal_tree(&self.entries[start..end])?;
+
+            let mut checkpoint = AuditCheckpoint {
+                first_sequence: self.sealed_through,
+                count: (end - start) as u16,
+                root,
+                timestamp_ms: Self::get_time(),
+                signature: Vec::new(),
+            };
+            checkpoint.signature = Vec::from_slice(&crypto.sign(&checkpoint.digest()))
+                .map_err(|_| SwarmError::BufferFull)?;
+
+            if self.checkpoints.is_full() && !self.evict_checkpoints() {
+                if self.overflow == AuditOverflow::Refuse {
+                    return Err(SwarmError::BufferFull);
+                }
+                self.checkpoints.remove(0);
+                self.exported_checkpoints = self.exported_checkpoints.saturating_sub(1);
+            }
+            self.checkpoints
+                .push(checkpoint)
+                .map_err(|_| SwarmError::BufferFull)?;
+            self.sealed_through += (end - start) as u64;
+            created += 1;
+        }
+        Ok(created)
+    }
+
+    /// Inclusion proof for a sealed, retained entry
+    ///
+    /// Returns the proof and the index of the checkpoint it verifies against.
+    pub fn prove(&self, sequence: u64) -> Result<(MerkleProof, usize)> {
+        let index = self
+            .checkpoints
+            .iter()
+            .position(|checkpoint| checkpoint.covers(sequence))
+            .ok_or(SwarmError::InvalidParameter)?;
+        let checkpoint = &self.checkpoints[index];
+
+        let oldest = self
+            .entries
+            .first()
+            .map_or(u64::MAX, |entry| entry.sequence);
+        if checkpoint.first_sequence < oldest {
+            return Err(SwarmError::InvalidParameter);
+        }
+        let start = (checkpoint.first_sequence - oldest) as usize;
+        let batch = &self.entries[start..start + checkpoint.count as usize];
+        let (tree, _) = seal_tree(batch)?;
+        let proof = tree.proof((sequence - checkpoint.first_sequence) as usize)?;
+        Ok((proof, index))
+    }
+
+    /// Write entries and checkpoints not yet exported as JSON lines
+    ///
+    /// Exported records may later be evicted. Returns the number of records
+    /// written.
+    #[cfg(feature = "std")]
+    pub fn export_jsonl<W: std::io::Write>(&mut self, out: &mut W) -> Result<usize> {
+        let mut written = 0;
+        for entry in self
+            .entries
+            .iter()
+            .filter(|entry| entry.sequence >= self.exported_through)
+        {
+            Self::write_record(out, &AuditRecord::Entry(*entry))?;
+            written += 1;
+        }
+        self.exported_through = self.next_sequence;
+
+        for checkpoint in &self.checkpoints[self.exported_checkpoints..] {
+            Self::write_record(out, &AuditRecord::Checkpoint(checkpoint.clone()))?;
+            written += 1;
+        }
+        self.exported_checkpoints = self.checkpoints.len();
+
+        Ok(written)
+    }
+
+    /// Verify an exported log offline against the node's public key
+    ///
+    /// Shorthand for a fresh [`AuditVerifier`] fed a single chunk.
+    #[cfg(feature = "std")]
+    pub fn verify_export<R: std::io::BufRead>(
+        input: R,
+        public_key: &VerifyingKey,
+    ) -> Result<AuditSummary> {
+        AuditVerifier::new(public_key).verify_chunk(input)
+    }
+
+    /// Write one JSON line
+    #[cfg(feature = "std")]
+    fn write_record<W: std::io::Write>(out: &mut W, record: &AuditRecord) -> Result<()> {
+        serde_json::to_writer(&mut *out, record).map_err(|_| SwarmError::SerializationError)?;
+        out.write_all(b"\n")
+            .map_err(|_| SwarmError::SerializationError)
+    }
+
+    /// Check whether [`log`](Self::log) would accept another entry
+    fn has_room(&self) -> bool {
+        !self.entries.is_full()
+            || self.overflow == AuditOverflow::Rotate
+            || self.evictable_entries() > 0
+    }
+
+    /// Leading entries that are sealed and exported
+    fn evictable_entries(&self) -> usize {
+        let keep_from = self.sealed_through.min(self.exported_through);
+        self.entries
+            .iter()
+            .take_while(|entry| entry.sequence < keep_from)
+            .count()
+    }
+
+    /// Drop entries that are sealed and exported
+    ///
+    /// Returns whether any were dropped.
+    fn evict_entries(&mut self) -> bool {
+        let evictable = self.evictable_entries();
+        for _ in 0..evictable {
+            self.entries.remove(0);
+        }
+        evictable > 0
+    }
+
+    /// Drop checkpoints that are exported
+    ///
+    /// Returns whether any were dropped.
+    fn evict_checkpoints(&mut self) -> bool {
+        let evictable = self.exported_checkpoints;
+        for _ in 0..evictable {
+            self.checkpoints.remove(0);
+        }
+        self.exported_checkpoints = 0;
+        evictable > 0
+    }
+
     /// Get timestamp (uses centralized time abstraction)
     fn get_time() -> u64 {
         crate::get_time_ms()
@@ -378,6 +1240,118 @@ impl Default for AuditLog {
     }
 }
 
+/// Offline verifier of an exported audit log
+///
+/// Checks that entries are consecutive and hash-chained, that every
+/// checkpoint is signed by the node and that its root matches the entries
+/// it seals. The chain head and the entries not yet sealed carry over from
+/// one [`verify_chunk`](Self::verify_chunk) call to the next, so a log
+/// exported in increments verifies as a whole. Entries after the last
+/// checkpoint are only linked by the chain, so the newest of them can be
+/// altered undetected.
+#[cfg(feature = "std")]
+pub struct AuditVerifier {
+    /// Node's public key
+    public_key: VerifyingKey,
+    /// Sequence number and hash of the newest entry
+    head: Option<(u64, [u8; 32])>,
+    /// Sequence number and hash of entries not yet sealed, oldest first
+    unsealed: std::collections::VecDeque<(u64, [u8; 32])>,
+    /// Totals so far
+    summary: AuditSummary,
+}
+
+#[cfg(feature = "std")]
+impl AuditVerifier {
+    /// Create a verifier for logs of the node with `public_key`
+    pub fn new(public_key: &VerifyingKey) -> Self {
+        Self {
+            public_key: *public_key,
+            head: None,
+            unsealed: std::collections::VecDeque::new(),
+            summary: AuditSummary {
+                entries: 0,
+                sealed: 0,
+                checkpoints: 0,
+            },
+        }
+    }
+
+    /// Verify the next exported chunk
+    ///
+    /// Returns the totals over all chunks so far. After an error the
+    /// verifier's state is unspecified.
+    pub fn verify_chunk<R: std::io::BufRead>(&mut self, input: R) -> Result<AuditSummary> {
+        for line in input.lines() {
+            let line = line.map_err(|_| SwarmError::SerializationError)?;
+            if line.trim().is_empty() {
+                continue;
+            }
+            match serde_json::from_str(&line).map_err(|_| SwarmError::SerializationError)? {
+                AuditRecord::Entry(entry) => self.verify_entry(&entry)?,
+                AuditRecord::Checkpoint(checkpoint) => self.verify_checkpoint(&checkpoint)?,
+            }
+        }
+        Ok(self.summary)
+    }
+
+    /// Totals over all chunks so far
+    pub fn summary(&self) -> AuditSummary {
+        self.summary
+    }
+
+    /// Check that `entry` extends the chain
+    fn verify_entry(&mut self, entry: &AuditEntry) -> Result<()> {
+        match self.head {
+            Some((sequence, _)) if entry.sequence != sequence + 1 => {
+                return Err(SwarmError::InvalidMessage);
+            }
+            Some((_, hash)) if entry.prev_hash != hash => {
+                return Err(SwarmError::AuthenticationFailed);
+            }
+            None if entry.sequence == 0 && entry.prev_hash != [0; 32] => {
+                return Err(SwarmError::AuthenticationFailed);
+            }
+            _ => {}
+        }
+
+        let hash = entry.hash();
+        self.head = Some((entry.sequence, hash));
+        self.unsealed.push_back((entry.sequence, hash));
+        self.summary.entries += 1;
+        Ok(())
+    }
+
+    /// Check a checkpoint against the entries it seals
+    fn verify_checkpoint(&mut self, checkpoint: &AuditCheckpoint) -> Result<()> {
+        checkpoint.verify(&self.public_key)?;
+
+        // Entries whose checkpoint was never exported stay unsealed
+        while self
+            .unsealed
+            .front()
+            .is_some_and(|(sequence, _)| *sequence < checkpoint.first_sequence)
+        {
+            self.unsealed.pop_front();
+        }
+        let count = checkpoint.count as usize;
+        if self.unsealed.front().map(|(sequence, _)| *sequence) != Some(checkpoint.first_sequence)
+            || self.unsealed.len() < count
+        {
+            return Err(SwarmError::InvalidMessage);
+        }
+
+        let (_, root) = seal_hashes(self.unsealed.iter().take(count).map(|(_, hash)| hash))?;
+        if root != checkpoint.root {
+            return Err(SwarmError::AuthenticationFailed);
+        }
+        self.unsealed.drain(..count);
+        self.summary.sealed += count;
+        self.summary.checkpoints += 1;
+        Ok(())
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
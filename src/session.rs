@@ -0,0 +1,981 @@
+//! Authenticated session key establishment
+//!
+//! [`CryptoContext::key_exchange`] gives two drones a shared secret, but on its
+//! own anyone on the channel can sit in the middle of it. The handshake here
+//! follows the Noise XX pattern, with the Ed25519 identity keys registered in
+//! the [`KeyStore`] taking the place of Noise's static DH keys:
+//!
+//! ```text
+//! -> e
+//! <- e, ee, {id, sig}
+//! -> {id, sig}
+//! ```
+//!
+//! - Each side contributes a fresh X25519 ephemeral key and the session keys
+//!   derive only from their exchange, so recorded traffic stays safe if an
+//!   identity key leaks later (forward secrecy)
+//! - Each side signs the handshake hash, which covers the prologue and both
+//!   ephemeral keys, proving it holds the identity key in the peer's
+//!   [`KeyStore`] and binding that proof to this run
+//! - Identities travel encrypted; the initiator's is only revealed to an
+//!   authenticated responder
+//!
+//! The same handshake runs between drone pairs and between a drone and the
+//! ground station, which takes part with its own [`DroneId`] and identity key.
+//! [`SessionManager`] runs the handshakes per peer and keeps the resulting
+//! [`Session`]s for the network layer.
+
+use crate::crypto::{CryptoContext, KeyStore, REPLAY_WINDOW, SIGNATURE_SIZE, TAG_SIZE};
+use crate::rng::SecureRng;
+use crate::types::*;
+use crate::KEY_SIZE;
+use aead::{AeadInPlace, KeyInit};
+use chacha20poly1305::{ChaCha20Poly1305, Nonce, Tag};
+use ed25519_dalek::{Signature, Verifier};
+use heapless::{FnvIndexMap, Vec};
+use serde::{Deserialize, Serialize};
+use sha3::{Digest, Sha3_256};
+
+/// Noise protocol name, hashed into the initial handshake state
+const PROTOCOL_NAME: &[u8] = b"Noise_XXsig_25519_ChaChaPoly_SHA3/256_BLAKE3";
+
+/// Identity payload: drone ID and signature over the handshake hash
+pub const IDENTITY_PAYLOAD_SIZE: usize = 8 + SIGNATURE_SIZE;
+
+/// Encrypted identity payload
+pub const SEALED_IDENTITY_SIZE: usize = IDENTITY_PAYLOAD_SIZE + TAG_SIZE;
+
+/// Session message overhead: counter and tag
+pub const SESSION_OVERHEAD: usize = 8 + TAG_SIZE;
+
+/// Maximum prologue length (bytes)
+pub const MAX_PROLOGUE_SIZE: usize = 32;
+
+/// Maximum concurrent sessions (power of 2)
+pub const MAX_SESSIONS: usize = 32;
+
+/// Maximum handshakes in progress (power of 2)
+pub const MAX_PENDING_HANDSHAKES: usize = 8;
+
+/// Time after which an unfinished handshake is abandoned (ms)
+pub const HANDSHAKE_TIMEOUT_MS: u64 = 5000;
+
+/// Handshake message
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub enum HandshakeMessage {
+    /// Initiator's ephemeral key
+    Init { ephemeral: [u8; 32] },
+    /// Responder's ephemeral key and encrypted identity
+    Response {
+        ephemeral: [u8; 32],
+        sealed: Vec<u8, SEALED_IDENTITY_SIZE>,
+    },
+    /// Initiator's encrypted identity
+    Finish {
+        sealed: Vec<u8, SEALED_IDENTITY_SIZE>,
+    },
+}
+
+/// Side of a handshake
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum HandshakeRole {
+    /// Sends the first message
+    Initiator,
+    /// Answers an [`HandshakeMessage::Init`]
+    Responder,
+}
+
+/// AEAD nonce for a message counter
+fn aead_nonce(counter: u64) -> [u8; 12] {
+    let mut nonce = [0u8; 12];
+    nonce[..8].copy_from_slice(&counter.to_le_bytes());
+    nonce
+}
+
+/// HKDF with keyed BLAKE3 as the PRF, producing two keys
+fn kdf(chaining_key: &[u8; 32], input: &[u8]) -> ([u8; 32], [u8; 32]) {
+    let temp = blake3::keyed_hash(chaining_key, input);
+    let first = blake3::keyed_hash(temp.as_bytes(), &[1]);
+    let mut second_input = [0u8; 33];
+    second_input[..32].copy_from_slice(first.as_bytes());
+    second_input[32] = 2;
+    let second = blake3::keyed_hash(temp.as_bytes(), &second_input);
+    (*first.as_bytes(), *second.as_bytes())
+}
+
+/// Noise symmetric state: chaining key, handshake hash and current key
+#[derive(Clone)]
+struct SymmetricState {
+    chaining_key: [u8; 32],
+    hash: [u8; 32],
+    key: Option<[u8; KEY_SIZE]>,
+    nonce: u64,
+}
+
+impl SymmetricState {
+    fn new(prologue: &[u8]) -> Self {
+        let hash = CryptoContext::secure_hash(PROTOCOL_NAME);
+        let mut state = Self {
+            chaining_key: hash,
+            hash,
+            key: None,
+            nonce: 0,
+        };
+        state.mix_hash(prologue);
+        state
+    }
+
+    fn mix_hash(&mut self, data: &[u8]) {
+        let mut hasher = Sha3_256::new();
+        hasher.update(self.hash);
+        hasher.update(data);
+        self.hash = hasher.finalize().into();
+    }
+
+    fn mix_key(&mut self, input: &[u8]) {
+        let (chaining_key, key) = kdf(&self.chaining_key, input);
+        self.chaining_key = chaining_key;
+        self.key = Some(key);
+        self.nonce = 0;
+    }
+
+    fn encrypt_and_hash(&mut self, plaintext: &[u8]) -> Result<Vec<u8, SEALED_IDENTITY_SIZE>> {
+        let key = self.key.ok_or(SwarmError::CryptoError)?;
+        let cipher = ChaCha20Poly1305::new_from_slice(&key).map_err(|_| SwarmError::CryptoError)?;
+        let mut buffer: Vec<u8, SEALED_IDENTITY_SIZE> =
+            Vec::from_slice(plaintext).map_err(|_| SwarmError::BufferFull)?;
+        let tag = cipher
+            .encrypt_in_place_detached(
+                Nonce::from_slice(&aead_nonce(self.nonce)),
+                &self.hash,
+                &mut buffer,
+            )
+            .map_err(|_| SwarmError::CryptoError)?;
+        buffer
+            .extend_from_slice(&tag)
+            .map_err(|_| SwarmError::BufferFull)?;
+        self.nonce += 1;
+        self.mix_hash(&buffer);
+        Ok(buffer)
+    }
+
+    fn decrypt_and_hash(&mut self, ciphertext: &[u8]) -> Result<Vec<u8, IDENTITY_PAYLOAD_SIZE>> {
+        let key = self.key.ok_or(SwarmError::CryptoError)?;
+        if ciphertext.len() < TAG_SIZE {
+            return Err(SwarmError::InvalidMessage);
+        }
+        let cipher = ChaCha20Poly1305::new_from_slice(&key).map_err(|_| SwarmError::CryptoError)?;
+        let (body, tag) = ciphertext.split_at(ciphertext.len() - TAG_SIZE);
+        let mut buffer: Vec<u8, IDENTITY_PAYLOAD_SIZE> =
+            Vec::from_slice(body).map_err(|_| SwarmError::InvalidMessage)?;
+        cipher
+            .decrypt_in_place_detached(
+                Nonce::from_slice(&aead_nonce(self.nonce)),
+                &self.hash,
+                &mut buffer,
+                Tag::from_slice(tag),
+            )
+            .map_err(|_| SwarmError::AuthenticationFailed)?;
+        self.nonce += 1;
+        self.mix_hash(ciphertext);
+        Ok(buffer)
+    }
+
+    /// Initiator-to-responder and responder-to-initiator transport keys
+    fn split(&self) -> ([u8; KEY_SIZE], [u8; KEY_SIZE]) {
+        kdf(&self.chaining_key, &[])
+    }
+}
+
+/// Handshake progress
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Stage {
+    AwaitInit,
+    AwaitResponse,
+    AwaitFinish,
+    Complete,
+    Failed,
+}
+
+/// One run of the handshake with a single peer
+pub struct Handshake {
+    role: HandshakeRole,
+    stage: Stage,
+    local_id: DroneId,
+    /// Identity the peer must prove, if known in advance
+    expected_peer: Option<DroneId>,
+    /// Identity the peer proved
+    peer: Option<DroneId>,
+    /// Ephemeral X25519 private key (wiped once the handshake ends)
+    ephemeral: [u8; 32],
+    remote_ephemeral: [u8; 32],
+    state: SymmetricState,
+}
+
+impl Handshake {
+    /// Start a handshake as initiator
+    ///
+    /// `ephemeral_seed` must be fresh randomness (TRNG) for every handshake.
+    /// Both sides must use the same `prologue`, e.g. the mesh ID.
+    pub fn initiate(
+        local_id: DroneId,
+        ephemeral_seed: [u8; 32],
+        prologue: &[u8],
+    ) -> (Self, HandshakeMessage) {
+        let mut handshake = Self::new(
+            HandshakeRole::Initiator,
+            Stage::AwaitResponse,
+            local_id,
+            ephemeral_seed,
+            prologue,
+        );
+        let ephemeral = CryptoContext::exchange_public_key(&handshake.ephemeral);
+        handshake.state.mix_hash(&ephemeral);
+        (handshake, HandshakeMessage::Init { ephemeral })
+    }
+
+    /// Prepare to answer a handshake as responder
+    pub fn respond(local_id: DroneId, ephemeral_seed: [u8; 32], prologue: &[u8]) -> Self {
+        Self::new(
+            HandshakeRole::Responder,
+            Stage::AwaitInit,
+            local_id,
+            ephemeral_seed,
+            prologue,
+        )
+    }
+
+    fn new(
+        role: HandshakeRole,
+        stage: Stage,
+        local_id: DroneId,
+        ephemeral_seed: [u8; 32],
+        prologue: &[u8],
+    ) -> Self {
+        Self {
+            role,
+            stage,
+            local_id,
+            expected_peer: None,
+            peer: None,
+            ephemeral: ephemeral_seed,
+            remote_ephemeral: [0; 32],
+            state: SymmetricState::new(prologue),
+        }
+    }
+
+    /// Require the peer to authenticate as `peer`
+    pub fn with_peer(mut self, peer: DroneId) -> Self {
+        self.expected_peer = Some(peer);
+        self
+    }
+
+    /// Side of the handshake
+    pub fn role(&self) -> HandshakeRole {
+        self.role
+    }
+
+    /// Authenticated peer identity, once proven
+    pub fn peer(&self) -> Option<DroneId> {
+        self.peer
+    }
+
+    /// Check if both sides are authenticated and the session keys are ready
+    pub fn is_complete(&self) -> bool {
+        self.stage == Stage::Complete
+    }
+
+    /// Process the peer's next message
+    ///
+    /// Returns the reply to send, if any. Any failure aborts the handshake.
+    pub fn read_message(
+        &mut self,
+        msg: &HandshakeMessage,
+        identity: &CryptoContext,
+        keys: &KeyStore,
+    ) -> Result<Option<HandshakeMessage>> {
+        let result = self.step(msg, identity, keys);
+        if result.is_err() {
+            self.stage = Stage::Failed;
+        }
+        // Each side uses its ephemeral key exactly once
+        self.ephemeral = [0; 32];
+        result
+    }
+
+    fn step(
+        &mut self,
+        msg: &HandshakeMessage,
+        identity: &CryptoContext,
+        keys: &KeyStore,
+    ) -> Result<Option<HandshakeMessage>> {
+        match (self.stage, msg) {
+            (Stage::AwaitInit, HandshakeMessage::Init { ephemeral: remote }) => {
+                self.remote_ephemeral = *remote;
+                self.state.mix_hash(remote);
+                let ephemeral = CryptoContext::exchange_public_key(&self.ephemeral);
+                self.state.mix_hash(&ephemeral);
+                self.mix_exchange()?;
+                let sealed = self.seal_identity(identity)?;
+                self.stage = Stage::AwaitFinish;
+                Ok(Some(HandshakeMessage::Response { ephemeral, sealed }))
+            }
+            (
+                Stage::AwaitResponse,
+                HandshakeMessage::Response {
+                    ephemeral: remote,
+                    sealed,
+                },
+            ) => {
+                self.remote_ephemeral = *remote;
+                self.state.mix_hash(remote);
+                self.mix_exchange()?;
+                self.open_identity(sealed, keys)?;
+                let sealed = self.seal_identity(identity)?;
+                self.stage = Stage::Complete;
+                Ok(Some(HandshakeMessage::Finish { sealed }))
+            }
+            (Stage::AwaitFinish, HandshakeMessage::Finish { sealed }) => {
+                self.open_identity(sealed, keys)?;
+                self.stage = Stage::Complete;
+                Ok(None)
+            }
+            _ => Err(SwarmError::InvalidMessage),
+        }
+    }
+
+    /// Mix the ephemeral-ephemeral exchange into the key schedule
+    fn mix_exchange(&mut self) -> Result<()> {
+        let shared = CryptoContext::key_exchange(&self.ephemeral, &self.remote_ephemeral)?;
+        // A low-order public key forces an all-zero secret
+        if shared == [0; 32] {
+            return Err(SwarmError::AuthenticationFailed);
+        }
+        self.state.mix_key(&shared);
+        Ok(())
+    }
+
+    /// Encrypt our ID and a signature over the transcript so far
+    fn seal_identity(&mut self, identity: &CryptoContext) -> Result<Vec<u8, SEALED_IDENTITY_SIZE>> {
+        let mut payload = [0u8; IDENTITY_PAYLOAD_SIZE];
+        payload[..8].copy_from_slice(&self.local_id.as_u64().to_le_bytes());
+        payload[8..].copy_from_slice(&identity.sign(&self.state.hash));
+        self.state.encrypt_and_hash(&payload)
+    }
+
+    /// Decrypt the peer's ID and check its signature against the key store
+    fn open_identity(&mut self, sealed: &[u8], keys: &KeyStore) -> Result<()> {
+        let transcript = self.state.hash;
+        let payload = self.state.decrypt_and_hash(sealed)?;
+        if payload.len() != IDENTITY_PAYLOAD_SIZE {
+            return Err(SwarmError::InvalidMessage);
+        }
+
+        let mut id = [0u8; 8];
+        id.copy_from_slice(&payload[..8]);
+        let peer = DroneId::new(u64::from_le_bytes(id));
+        if self.expected_peer.is_some_and(|expected| expected != peer) {
+            return Err(SwarmError::AuthenticationFailed);
+        }
+
+        let key = keys
+            .get_key(peer)
+            .map_err(|_| SwarmError::AuthenticationFailed)?;
+        let signature = Signature::from_bytes(
+            payload[8..]
+                .try_into()
+                .map_err(|_| SwarmError::InvalidMessage)?,
+        );
+        key.verify(&transcript, &signature)
+            .map_err(|_| SwarmError::AuthenticationFailed)?;
+
+        self.peer = Some(peer);
+        Ok(())
+    }
+
+    /// Turn a completed handshake into a session
+    pub fn into_session(self) -> Result<Session> {
+        let peer = self.peer.ok_or(SwarmError::InvalidMessage)?;
+        if self.stage != Stage::Complete {
+            return Err(SwarmError::InvalidMessage);
+        }
+        let (initiator_key, responder_key) = self.state.split();
+        let (send_key, recv_key) = match self.role {
+            HandshakeRole::Initiator => (initiator_key, responder_key),
+            HandshakeRole::Responder => (responder_key, initiator_key),
+        };
+        Ok(Session::new(peer, send_key, recv_key, self.state.hash))
+    }
+}
+
+/// Established session with one peer
+///
+/// Messages are `counter || ciphertext || tag` under a per-direction key.
+/// Received counters are checked against a sliding window, as in
+/// [`NonceTracker`](crate::crypto::NonceTracker): each is accepted once and
+/// may arrive out of order by up to [`REPLAY_WINDOW`] messages.
+pub struct Session {
+    peer: DroneId,
+    send_cipher: ChaCha20Poly1305,
+    recv_cipher: ChaCha20Poly1305,
+    send_counter: u64,
+    /// Highest counter received
+    recv_counter: u64,
+    /// Bit `i` set = counter `recv_counter - i` received
+    recv_seen: u128,
+    handshake_hash: [u8; 32],
+}
+
+impl Session {
+    fn new(
+        peer: DroneId,
+        send_key: [u8; KEY_SIZE],
+        recv_key: [u8; KEY_SIZE],
+        handshake_hash: [u8; 32],
+    ) -> Self {
+        Self {
+            peer,
+            send_cipher: ChaCha20Poly1305::new_from_slice(&send_key)
+                .expect("32-byte key is always valid"),
+            recv_cipher: ChaCha20Poly1305::new_from_slice(&recv_key)
+                .expect("32-byte key is always valid"),
+            send_counter: 0,
+            recv_counter: 0,
+            recv_seen: 0,
+            handshake_hash,
+        }
+    }
+
+    /// Authenticated peer
+    pub fn peer(&self) -> DroneId {
+        self.peer
+    }
+
+    /// Hash of the handshake transcript, identical on both sides
+    ///
+    /// Usable as channel binding, e.g. to tie higher-level authentication to
+    /// this session.
+    pub fn handshake_hash(&self) -> &[u8; 32] {
+        &self.handshake_hash
+    }
+
+    /// Messages encrypted so far
+    pub fn messages_sent(&self) -> u64 {
+        self.send_counter
+    }
+
+    /// Encrypt a message for the peer
+    ///
+    /// Returns: [counter || ciphertext || tag]
+    pub fn encrypt(&mut self, plaintext: &[u8], associated_data: &[u8]) -> Result<Vec<u8, 2048>> {
+        self.send_counter = self
+            .send_counter
+            .checked_add(1)
+            .ok_or(SwarmError::CryptoError)?;
+
+        let mut message = Vec::<u8, 2048>::new();
+        message
+            .extend_from_slice(&self.send_counter.to_le_bytes())
+            .map_err(|_| SwarmError::BufferFull)?;
+        let mut buffer = Vec::<u8, 2048>::new();
+        buffer
+            .extend_from_slice(plaintext)
+            .map_err(|_| SwarmError::BufferFull)?;
+        let tag = self
+            .send_cipher
+            .encrypt_in_place_detached(
+                Nonce::from_slice(&aead_nonce(self.send_counter)),
+                associated_data,
+                &mut buffer,
+            )
+            .map_err(|_| SwarmError::CryptoError)?;
+        message
+            .extend_from_slice(&buffer)
+            .map_err(|_| SwarmError::BufferFull)?;
+        message
+            .extend_from_slice(&tag)
+            .map_err(|_| SwarmError::BufferFull)?;
+        Ok(message)
+    }
+
+    /// Decrypt a message from the peer, rejecting replays
+    pub fn decrypt(&mut self, message: &[u8], associated_data: &[u8]) -> Result<Vec<u8, 2048>> {
+        if message.len() < SESSION_OVERHEAD {
+            return Err(SwarmError::InvalidMessage);
+        }
+        let mut counter = [0u8; 8];
+        counter.copy_from_slice(&message[..8]);
+        let counter = u64::from_le_bytes(counter);
+        if !self.admits(counter) {
+            return Err(SwarmError::AuthenticationFailed);
+        }
+
+        let (body, tag) = message[8..].split_at(message.len() - SESSION_OVERHEAD);
+        let mut buffer = Vec::<u8, 2048>::new();
+        buffer
+            .extend_from_slice(body)
+            .map_err(|_| SwarmError::BufferFull)?;
+        self.recv_cipher
+            .decrypt_in_place_detached(
+                Nonce::from_slice(&aead_nonce(counter)),
+                associated_data,
+                &mut buffer,
+                Tag::from_slice(tag),
+            )
+            .map_err(|_| SwarmError::AuthenticationFailed)?;
+
+        self.accept(counter);
+        Ok(buffer)
+    }
+
+    /// Check whether `counter` is new and inside the replay window
+    fn admits(&self, counter: u64) -> bool {
+        if counter > self.recv_counter {
+            return true;
+        }
+        // Counters start at 1
+        let age = self.recv_counter - counter;
+        counter != 0 && age < REPLAY_WINDOW && self.recv_seen & (1 << age) == 0
+    }
+
+    /// Record `counter` as received, sliding the window if it is the highest
+    fn accept(&mut self, counter: u64) {
+        if counter > self.recv_counter {
+            let shift = counter - self.recv_counter;
+            self.recv_seen = if shift < REPLAY_WINDOW {
+                self.recv_seen << shift
+            } else {
+                0
+            };
+            self.recv_counter = counter;
+        }
+        self.recv_seen |= 1 << (self.recv_counter - counter);
+    }
+}
+
+/// Per-peer handshakes and sessions of one drone or ground station
+pub struct SessionManager {
+    local_id: DroneId,
+    prologue: Vec<u8, MAX_PROLOGUE_SIZE>,
+    /// Handshakes in progress with their start time (ms)
+    pending: FnvIndexMap<u64, (Handshake, u64), MAX_PENDING_HANDSHAKES>,
+    sessions: FnvIndexMap<u64, Session, MAX_SESSIONS>,
+}
+
+impl SessionManager {
+    /// Create a session manager
+    ///
+    /// Every participant must use the same `prologue` (up to
+    /// [`MAX_PROLOGUE_SIZE`] bytes, e.g. the mesh ID).
+    pub fn new(local_id: DroneId, prologue: &[u8]) -> Result<Self> {
+        Ok(Self {
+            local_id,
+            prologue: Vec::from_slice(prologue).map_err(|_| SwarmError::InvalidParameter)?,
+            pending: FnvIndexMap::new(),
+            sessions: FnvIndexMap::new(),
+        })
+    }
+
+    /// Start a handshake with `peer`
+    ///
+    /// Replaces any handshake already in progress with it.
+    pub fn connect(&mut self, peer: DroneId, now_ms: u64) -> Result<HandshakeMessage> {
+        let (handshake, init) =
+            Handshake::initiate(self.local_id, Self::ephemeral_seed()?, &self.prologue);
+        self.start(peer, handshake.with_peer(peer), now_ms)?;
+        Ok(init)
+    }
+
+    /// Process a handshake message from `from`
+    ///
+    /// `from` is the sender as seen by the transport; the peer has to
+    /// authenticate as that drone. Returns the reply to send, if any. Once a
+    /// handshake completes, its session replaces any previous one with the
+    /// peer.
+    pub fn handle(
+        &mut self,
+        from: DroneId,
+        msg: &HandshakeMessage,
+        identity: &CryptoContext,
+        keys: &KeyStore,
+        now_ms: u64,
+    ) -> Result<Option<HandshakeMessage>> {
+        self.expire_handshakes(now_ms);
+
+        if let HandshakeMessage::Init { .. } = msg {
+            let ours = self
+                .pending
+                .get(&from.as_u64())
+                .is_some_and(|(handshake, _)| handshake.role() == HandshakeRole::Initiator);
+            // Both sides connected at once: the lower ID stays initiator
+            if ours && self.local_id.as_u64() < from.as_u64() {
+                return Ok(None);
+            }
+            let handshake =
+                Handshake::respond(self.local_id, Self::ephemeral_seed()?, &self.prologue)
+                    .with_peer(from);
+            self.start(from, handshake, now_ms)?;
+        }
+
+        let (handshake, _) = self
+            .pending
+            .get_mut(&from.as_u64())
+            .ok_or(SwarmError::InvalidMessage)?;
+        let reply = match handshake.read_message(msg, identity, keys) {
+            Ok(reply) => reply,
+            Err(err) => {
+                self.pending.remove(&from.as_u64());
+                return Err(err);
+            }
+        };
+
+        if handshake.is_complete() {
+            if let Some((handshake, _)) = self.pending.remove(&from.as_u64()) {
+                let session = handshake.into_session()?;
+                self.sessions.remove(&from.as_u64());
+                self.sessions
+                    .insert(from.as_u64(), session)
+                    .map_err(|_| SwarmError::ResourceExhausted)?;
+            }
+        }
+        Ok(reply)
+    }
+
+    /// Drop handshakes older than [`HANDSHAKE_TIMEOUT_MS`]
+    pub fn expire_handshakes(&mut self, now_ms: u64) {
+        self.pending
+            .retain(|_, (_, started_ms)| now_ms.saturating_sub(*started_ms) < HANDSHAKE_TIMEOUT_MS);
+    }
+
+    /// Check if a handshake with `peer` is in progress
+    pub fn is_pending(&self, peer: DroneId) -> bool {
+        self.pending.contains_key(&peer.as_u64())
+    }
+
+    /// Session with `peer`
+    pub fn session(&self, peer: DroneId) -> Option<&Session> {
+        self.sessions.get(&peer.as_u64())
+    }
+
+    /// Mutable session with `peer`
+    pub fn session_mut(&mut self, peer: DroneId) -> Option<&mut Session> {
+        self.sessions.get_mut(&peer.as_u64())
+    }
+
+    /// Check if a session with `peer` is established
+    pub fn has_session(&self, peer: DroneId) -> bool {
+        self.sessions.contains_key(&peer.as_u64())
+    }
+
+    /// Number of established sessions
+    pub fn session_count(&self) -> usize {
+        self.sessions.len()
+    }
+
+    /// Close the session with `peer`
+    pub fn remove_session(&mut self, peer: DroneId) -> Result<()> {
+        self.sessions
+            .remove(&peer.as_u64())
+            .map(|_| ())
+            .ok_or(SwarmError::InvalidDroneId)
+    }
+
+    /// Encrypt a message for `peer` under its session
+    pub fn encrypt(
+        &mut self,
+        peer: DroneId,
+        plaintext: &[u8],
+        associated_data: &[u8],
+    ) -> Result<Vec<u8, 2048>> {
+        self.session_mut(peer)
+            .ok_or(SwarmError::InvalidDroneId)?
+            .encrypt(plaintext, associated_data)
+    }
+
+    /// Decrypt a message from `peer` under its session
+    pub fn decrypt(
+        &mut self,
+        peer: DroneId,
+        message: &[u8],
+        associated_data: &[u8],
+    ) -> Result<Vec<u8, 2048>> {
+        self.session_mut(peer)
+            .ok_or(SwarmError::InvalidDroneId)?
+            .decrypt(message, associated_data)
+    }
+
+    fn start(&mut self, peer: DroneId, handshake: Handshake, now_ms: u64) -> Result<()> {
+        self.pending.remove(&peer.as_u64());
+        self.pending
+            .insert(peer.as_u64(), (handshake, now_ms))
+            .map(|_| ())
+            .map_err(|_| SwarmError::ResourceExhausted)
+    }
+
+    /// Fresh randomness for an ephemeral key
+    fn ephemeral_seed() -> Result<[u8; 32]> {
+        let mut seed = [0u8; 32];
+        SecureRng::new()?.fill_bytes(&mut seed)?;
+        Ok(seed)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const PROLOGUE: &[u8] = b"DRONES";
+
+    struct Party {
+        id: DroneId,
+        identity: CryptoContext,
+    }
+
+    fn party(id: u64) -> Party {
+        Party {
+            id: DroneId::new(id),
+            identity: CryptoContext::new([id as u8; 32]),
+        }
+    }
+
+    /// Key store knowing every party
+    fn keys(parties: &[&Party]) -> KeyStore {
+        let mut keys = KeyStore::new();
+        for party in parties {
+            keys.add_key(party.id, *party.identity.public_key())
+                .unwrap();
+        }
+        keys
+    }
+
+    fn run(
+        a: &Party,
+        b: &Party,
+        a_keys: &KeyStore,
+        b_keys: &KeyStore,
+    ) -> Result<(Session, Session)> {
+        let (mut initiator, init) = Handshake::initiate(a.id, [0xA1; 32], PROLOGUE);
+        let mut responder = Handshake::respond(b.id, [0xB2; 32], PROLOGUE);
+
+        let response = responder.read_message(&init, &b.identity, b_keys)?.unwrap();
+        let finish = initiator
+            .read_message(&response, &a.identity, a_keys)?
+            .unwrap();
+        assert!(responder
+            .read_message(&finish, &b.identity, b_keys)?
+            .is_none());
+
+        Ok((initiator.into_session()?, responder.into_session()?))
+    }
+
+    #[test]
+    fn test_handshake_establishes_matching_sessions() {
+        let (drone, ground) = (party(1), party(100));
+        let store = keys(&[&drone, &ground]);
+        let (mut a, mut b) = run(&drone, &ground, &store, &store).unwrap();
+
+        assert_eq!(a.peer(), ground.id);
+        assert_eq!(b.peer(), drone.id);
+        assert_eq!(a.handshake_hash(), b.handshake_hash());
+
+        let sealed = a.encrypt(b"telemetry", b"ad").unwrap();
+        assert_eq!(&b.decrypt(&sealed, b"ad").unwrap()[..], b"telemetry");
+        let sealed = b.encrypt(b"waypoint", b"ad").unwrap();
+        assert_eq!(&a.decrypt(&sealed, b"ad").unwrap()[..], b"waypoint");
+
+        // Each direction has its own key
+        let sealed = a.encrypt(b"loopback", b"ad").unwrap();
+        assert!(a.decrypt(&sealed, b"ad").is_err());
+    }
+
+    #[test]
+    fn test_session_rejects_replay_and_tampering() {
+        let (a, b) = (party(1), party(2));
+        let store = keys(&[&a, &b]);
+        let (mut alice, mut bob) = run(&a, &b, &store, &store).unwrap();
+
+        let first = alice.encrypt(b"one", b"").unwrap();
+        let second = alice.encrypt(b"two", b"").unwrap();
+        assert!(bob.decrypt(&second, b"").is_ok());
+        // Replayed messages are refused, reordered ones accepted once
+        assert_eq!(
+            bob.decrypt(&second, b"").unwrap_err(),
+            SwarmError::AuthenticationFailed
+        );
+        assert_eq!(&bob.decrypt(&first, b"").unwrap()[..], b"one");
+        assert!(bob.decrypt(&first, b"").is_err());
+
+        // Until they fall behind the window
+        let late = alice.encrypt(b"late", b"").unwrap();
+        for _ in 0..REPLAY_WINDOW {
+            let sealed = alice.encrypt(b"", b"").unwrap();
+            bob.decrypt(&sealed, b"").unwrap();
+        }
+        assert!(bob.decrypt(&late, b"").is_err());
+
+        let mut tampered = alice.encrypt(b"three", b"").unwrap();
+        tampered[9] ^= 1;
+        assert_eq!(
+            bob.decrypt(&tampered, b"").unwrap_err(),
+            SwarmError::AuthenticationFailed
+        );
+    }
+
+    #[test]
+    fn test_unknown_identity_rejected() {
+        let (a, b) = (party(1), party(2));
+        // The responder has never registered drone 1
+        let a_keys = keys(&[&a, &b]);
+        let b_keys = keys(&[&b]);
+
+        assert_eq!(
+            run(&a, &b, &a_keys, &b_keys).err(),
+            Some(SwarmError::AuthenticationFailed)
+        );
+    }
+
+    #[test]
+    fn test_swapped_ephemeral_key_detected() {
+        let (a, b) = (party(1), party(2));
+        let store = keys(&[&a, &b]);
+        let (mut initiator, _) = Handshake::initiate(a.id, [0xA1; 32], PROLOGUE);
+        let mut responder = Handshake::respond(b.id, [0xB2; 32], PROLOGUE);
+
+        // A man in the middle substitutes his own ephemeral key
+        let forged = HandshakeMessage::Init {
+            ephemeral: CryptoContext::exchange_public_key(&[0xEE; 32]),
+        };
+        let response = responder
+            .read_message(&forged, &b.identity, &store)
+            .unwrap()
+            .unwrap();
+        assert!(initiator
+            .read_message(&response, &a.identity, &store)
+            .is_err());
+        assert!(initiator.into_session().is_err());
+    }
+
+    #[test]
+    fn test_expected_peer_and_prologue_enforced() {
+        let (a, b, c) = (party(1), party(2), party(3));
+        let store = keys(&[&a, &b, &c]);
+
+        // Drone 1 meant to reach drone 3 but drone 2 answered
+        let (initiator, init) = Handshake::initiate(a.id, [0xA1; 32], PROLOGUE);
+        let mut initiator = initiator.with_peer(c.id);
+        let mut responder = Handshake::respond(b.id, [0xB2; 32], PROLOGUE);
+        let response = responder
+            .read_message(&init, &b.identity, &store)
+            .unwrap()
+            .unwrap();
+        assert_eq!(
+            initiator.read_message(&response, &a.identity, &store),
+            Err(SwarmError::AuthenticationFailed)
+        );
+
+        // Another swarm's prologue
+        let (mut initiator, init) = Handshake::initiate(a.id, [0xA1; 32], PROLOGUE);
+        let mut responder = Handshake::respond(b.id, [0xB2; 32], b"OTHER");
+        let response = responder
+            .read_message(&init, &b.identity, &store)
+            .unwrap()
+            .unwrap();
+        assert!(initiator
+            .read_message(&response, &a.identity, &store)
+            .is_err());
+    }
+
+    #[test]
+    fn test_fresh_ephemerals_give_fresh_keys() {
+        let (a, b) = (party(1), party(2));
+        let store = keys(&[&a, &b]);
+        let (mut first, _) = run(&a, &b, &store, &store).unwrap();
+
+        let (mut initiator, init) = Handshake::initiate(a.id, [0xC3; 32], PROLOGUE);
+        let mut responder = Handshake::respond(b.id, [0xD4; 32], PROLOGUE);
+        let response = responder
+            .read_message(&init, &b.identity, &store)
+            .unwrap()
+            .unwrap();
+        let finish = initiator
+            .read_message(&response, &a.identity, &store)
+            .unwrap()
+            .unwrap();
+        responder
+            .read_message(&finish, &b.identity, &store)
+            .unwrap();
+        let mut second = responder.into_session().unwrap();
+
+        let sealed = first.encrypt(b"old", b"").unwrap();
+        assert!(second.decrypt(&sealed, b"").is_err());
+    }
+
+    #[test]
+    fn test_session_manager_simultaneous_connect() {
+        let (a, b) = (party(1), party(2));
+        let store = keys(&[&a, &b]);
+        let mut alice = SessionManager::new(a.id, PROLOGUE).unwrap();
+        let mut bob = SessionManager::new(b.id, PROLOGUE).unwrap();
+
+        let from_alice = alice.connect(b.id, 0).unwrap();
+        let from_bob = bob.connect(a.id, 0).unwrap();
+
+        // Drone 1 keeps its own attempt and ignores drone 2's
+        assert!(alice
+            .handle(b.id, &from_bob, &a.identity, &store, 1)
+            .unwrap()
+            .is_none());
+        let response = bob
+            .handle(a.id, &from_alice, &b.identity, &store, 1)
+            .unwrap()
+            .unwrap();
+        let finish = alice
+            .handle(b.id, &response, &a.identity, &store, 2)
+            .unwrap()
+            .unwrap();
+        assert!(bob
+            .handle(a.id, &finish, &b.identity, &store, 3)
+            .unwrap()
+            .is_none());
+
+        assert!(alice.has_session(b.id) && bob.has_session(a.id));
+        assert!(!alice.is_pending(b.id) && !bob.is_pending(a.id));
+        let sealed = alice.encrypt(b.id, b"formation", b"").unwrap();
+        assert_eq!(&bob.decrypt(a.id, &sealed, b"").unwrap()[..], b"formation");
+    }
+
+    #[test]
+    fn test_session_manager_binds_identity_to_sender() {
+        let (a, b) = (party(1), party(2));
+        let store = keys(&[&a, &b]);
+        let mut alice = SessionManager::new(a.id, PROLOGUE).unwrap();
+        let mut bob = SessionManager::new(b.id, PROLOGUE).unwrap();
+
+        // Drone 1 claims to be drone 5 on the transport
+        let init = alice.connect(b.id, 0).unwrap();
+        let spoofed = DroneId::new(5);
+        let response = bob
+            .handle(spoofed, &init, &b.identity, &store, 0)
+            .unwrap()
+            .unwrap();
+        let finish = alice
+            .handle(b.id, &response, &a.identity, &store, 1)
+            .unwrap()
+            .unwrap();
+        assert_eq!(
+            bob.handle(spoofed, &finish, &b.identity, &store, 2),
+            Err(SwarmError::AuthenticationFailed)
+        );
+        assert!(!bob.has_session(spoofed) && !bob.is_pending(spoofed));
+    }
+
+    #[test]
+    fn test_unfinished_handshakes_expire() {
+        let mut manager = SessionManager::new(DroneId::new(1), PROLOGUE).unwrap();
+        manager.connect(DroneId::new(2), 0).unwrap();
+        assert!(manager.is_pending(DroneId::new(2)));
+
+        manager.expire_handshakes(HANDSHAKE_TIMEOUT_MS);
+        assert!(!manager.is_pending(DroneId::new(2)));
+    }
+}
@@ -10,6 +10,8 @@
 // Consensus, federated, and network types available for integration
 use crate::types::*;
 use crate::collision_avoidance::{CollisionAvoidance, AvoidanceConfig};
+use crate::consensus::SwarmCommand;
+use crate::rbac::{CommandPolicy, CommandScope};
 use heapless::{FnvIndexMap, Vec};
 
 /// Swarm formation types
@@ -339,6 +341,34 @@ impl SwarmController {
         self.target_position = target;
     }
 
+    /// Apply a committed swarm command issued by `issuer`
+    ///
+    /// Fails with [`SwarmError::PermissionDenied`] unless `policy` lets
+    /// `issuer` order the command swarm-wide. Commands that do not concern
+    /// the controller are accepted without effect.
+    pub fn apply_command(
+        &mut self,
+        issuer: DroneId,
+        command: &SwarmCommand,
+        policy: &CommandPolicy,
+        now_ms: u64,
+    ) -> Result<()> {
+        policy.authorize(issuer, command.into(), CommandScope::Swarm, now_ms)?;
+        match command {
+            SwarmCommand::EmergencyStop => {
+                self.target_position = None;
+                self.tasks.clear();
+                self.behavior = BehaviorMode::Emergency;
+                self.local_state.status = MissionStatus::Emergency;
+            }
+            SwarmCommand::RemoveDrone { drone } => {
+                self.swarm_states.remove(&drone.as_u64());
+            }
+            _ => {}
+        }
+        Ok(())
+    }
+
     /// Get swarm size
     pub fn swarm_size(&self) -> usize {
         self.swarm_states.len() + 1 // +1 for self
@@ -430,4 +460,32 @@ mod tests {
         // Should be zero with no nearby drones
         assert_eq!(avoidance.vx, 0.0);
     }
+
+    #[test]
+    fn test_apply_command_checks_policy() {
+        use crate::rbac::Roles;
+
+        let pos = Position {
+            x: 0.0,
+            y: 0.0,
+            z: 10.0,
+        };
+        let mut controller = SwarmController::new(DroneId::new(1), pos);
+        controller.set_target(Some(pos));
+        let mut policy = CommandPolicy::new();
+        policy.assign(DroneId::new(2), Roles::MEMBER).unwrap();
+        policy.set_leader(Some(DroneId::new(3)));
+
+        let stop = SwarmCommand::EmergencyStop;
+        assert_eq!(
+            controller.apply_command(DroneId::new(2), &stop, &policy, 0),
+            Err(SwarmError::PermissionDenied)
+        );
+        assert_eq!(controller.local_state().status, MissionStatus::Idle);
+
+        controller
+            .apply_command(DroneId::new(3), &stop, &policy, 0)
+            .unwrap();
+        assert_eq!(controller.local_state().status, MissionStatus::Emergency);
+    }
 }
\ No newline at end of file
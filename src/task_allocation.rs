@@ -30,7 +30,7 @@ impl TaskAllocator {
     /// Allocate tasks to drones (greedy nearest-neighbor)
     pub fn allocate_tasks(&mut self, drone_states: &[DroneState]) -> Result<()> {
         // Sort tasks by priority
-        self.tasks.sort_by(|a, b| b.priority.cmp(&a.priority));
+        self.tasks.sort_by_key(|t| core::cmp::Reverse(t.priority));
 
         // Allocate each task to nearest available drone
         // Use index-based iteration to avoid borrow checker issues
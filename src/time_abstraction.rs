@@ -28,6 +28,8 @@
 //!
 //! ## Standard Library (std)
 //! Uses SystemTime for development and testing on desktop platforms.
+//! A thread can pin its clock to a virtual time with [`set_virtual_time_us`]
+//! so discrete-event simulations (see `netsim`) run deterministically.
 
 use core::sync::atomic::{AtomicU64, Ordering};
 
@@ -35,6 +37,12 @@ use core::sync::atomic::{AtomicU64, Ordering};
 /// Updated by platform-specific interrupt handlers or direct reads
 static GLOBAL_TIME_MS: AtomicU64 = AtomicU64::new(0);
 
+#[cfg(feature = "std")]
+std::thread_local! {
+    /// Per-thread virtual clock (microseconds) overriding the system clock when set
+    static VIRTUAL_TIME_US: core::cell::Cell<Option<u64>> = const { core::cell::Cell::new(None) };
+}
+
 /// Global cycles per microsecond (for high precision timing)
 #[cfg(all(target_arch = "arm", not(feature = "std")))]
 static CYCLES_PER_US: core::sync::atomic::AtomicU32 = core::sync::atomic::AtomicU32::new(0);
@@ -367,6 +375,22 @@ pub fn init_time_source(_: u32) {
     GLOBAL_TIME_MS.store(0, Ordering::Relaxed);
 }
 
+/// Pin the current thread's clock to a virtual time, or release it with `None`
+///
+/// While set, [`get_time_ms`] and [`get_time_us`] on this thread return the
+/// virtual time instead of the system clock. Only the calling thread is
+/// affected, so parallel tests do not interfere with each other.
+#[cfg(feature = "std")]
+pub fn set_virtual_time_us(time_us: Option<u64>) {
+    VIRTUAL_TIME_US.with(|t| t.set(time_us));
+}
+
+/// Current virtual time of this thread, if one is set
+#[cfg(feature = "std")]
+pub fn virtual_time_us() -> Option<u64> {
+    VIRTUAL_TIME_US.with(|t| t.get())
+}
+
 /// Get current time in milliseconds (platform-independent)
 ///
 /// This is the main function used throughout the drone swarm system.
@@ -387,6 +411,10 @@ pub fn init_time_source(_: u32) {
 pub fn get_time_ms() -> u64 {
     #[cfg(feature = "std")]
     {
+        if let Some(time_us) = virtual_time_us() {
+            return time_us / 1000;
+        }
+
         // For testing/development
         use std::time::{SystemTime, UNIX_EPOCH};
         SystemTime::now()
@@ -427,6 +455,10 @@ pub fn get_time_ms() -> u64 {
 pub fn get_time_us() -> u64 {
     #[cfg(feature = "std")]
     {
+        if let Some(time_us) = virtual_time_us() {
+            return time_us;
+        }
+
         use std::time::{SystemTime, UNIX_EPOCH};
         SystemTime::now()
             .duration_since(UNIX_EPOCH)
@@ -522,4 +554,16 @@ mod tests {
         assert!(elapsed >= 50, "Delay should wait at least 50ms");
         assert!(elapsed < 100, "Delay should not wait too long");
     }
+
+    #[test]
+    #[cfg(feature = "std")]
+    fn test_virtual_time_override() {
+        set_virtual_time_us(Some(1_500_000));
+        assert_eq!(get_time_ms(), 1500);
+        assert_eq!(get_time_us(), 1_500_000);
+
+        set_virtual_time_us(None);
+        assert!(virtual_time_us().is_none());
+        assert!(get_time_ms() > 1500);
+    }
 }
@@ -0,0 +1,495 @@
+//! Mesh time synchronization (FTSP-style)
+//!
+//! Heartbeat timestamps, Raft timers and telemetry staleness checks compare
+//! times taken on different drones, but each drone only has its own
+//! free-running clock. This module agrees on a swarm-wide time base:
+//! - One root drone defines swarm time: the Raft leader when pinned with
+//!   [`TimeSyncNode::set_leader`], otherwise the lowest drone ID heard
+//! - Synchronized drones flood beacons carrying their estimate of swarm time
+//! - Each drone fits offset and skew to every neighbor by linear regression
+//!   and follows the neighbor closest to the root
+//! - [`TimeSyncNode::status`] reports the estimated accuracy so consumers can
+//!   decide whether clocks are good enough for what they are doing
+//!
+//! Timestamps are passed in as local microseconds so radios with MAC-layer
+//! receive timestamps can supply them; [`TimeSyncNode::swarm_time_ms`] reads
+//! the local clock itself. Transport-agnostic like [`crate::dtn`]: broadcast
+//! the beacon returned by [`TimeSyncNode::tick`] and hand received beacons to
+//! [`TimeSyncNode::process_beacon`].
+
+use crate::types::*;
+use heapless::{Deque, Vec};
+use serde::{Deserialize, Serialize};
+
+/// Maximum neighbors tracked
+pub const MAX_SYNC_NEIGHBORS: usize = 16;
+
+/// Regression points kept per neighbor
+pub const REGRESSION_ENTRIES: usize = 8;
+
+/// Time sync beacon
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SyncBeacon {
+    /// Transmitting drone
+    pub sender: DroneId,
+    /// Root whose time the sender follows
+    pub root: DroneId,
+    /// Root beacon sequence, relayed unchanged so drones can tell the root is alive
+    pub root_seq: u32,
+    /// Sender's distance from the root (root = 0)
+    pub hops: u8,
+    /// Sender's swarm time when the beacon was sent (us)
+    pub global_time_us: u64,
+    /// Sender's estimated accuracy (us)
+    pub accuracy_us: u32,
+}
+
+/// Time sync configuration
+#[derive(Debug, Clone, Copy)]
+pub struct TimeSyncConfig {
+    /// Interval between beacons (ms)
+    pub beacon_interval_ms: u64,
+    /// Time without news from the root before electing a new one (ms)
+    pub root_timeout_ms: u64,
+    /// Regression points needed before a neighbor can be followed
+    pub min_entries: usize,
+    /// Expected delay from sender timestamp to receiver timestamp (us)
+    pub delay_compensation_us: u32,
+    /// Deviation from the fitted line that resets a neighbor's table (us)
+    pub outlier_threshold_us: u32,
+}
+
+impl Default for TimeSyncConfig {
+    fn default() -> Self {
+        Self {
+            beacon_interval_ms: 1000,
+            root_timeout_ms: 5000,
+            min_entries: 3,
+            delay_compensation_us: 0,
+            outlier_threshold_us: 10_000,
+        }
+    }
+}
+
+/// Synchronization state reported to consumers
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct SyncStatus {
+    /// Root defining swarm time
+    pub root: DroneId,
+    /// This drone is the root
+    pub is_root: bool,
+    /// Swarm time is tracked (root, or following a neighbor)
+    pub synchronized: bool,
+    /// Neighbor followed for swarm time
+    pub parent: Option<DroneId>,
+    /// Distance from the root
+    pub hops: u8,
+    /// Estimated worst-case error of [`TimeSyncNode::swarm_time_ms`] (us)
+    pub accuracy_us: u32,
+    /// Local clock rate relative to swarm time (parts per million)
+    pub skew_ppm: f32,
+}
+
+/// Time sync statistics
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct TimeSyncStats {
+    /// Beacons sent
+    pub beacons_sent: u64,
+    /// Beacons used for synchronization
+    pub beacons_received: u64,
+    /// Beacons ignored (other or dead root, own time echoed back)
+    pub beacons_ignored: u64,
+    /// Root changes, including electing this drone
+    pub root_changes: u64,
+    /// Neighbor tables reset after an outlier
+    pub outliers: u64,
+}
+
+/// Clock estimate for one neighbor
+#[derive(Debug, Clone)]
+pub struct NeighborClock {
+    /// Neighbor drone
+    pub id: DroneId,
+    /// Root the neighbor follows
+    pub root: DroneId,
+    /// Latest root sequence the neighbor relayed
+    pub root_seq: u32,
+    /// Neighbor's distance from the root
+    pub hops: u8,
+    /// Accuracy the neighbor reported (us)
+    pub accuracy_us: u32,
+    /// Local time of the last beacon (us)
+    pub last_heard_us: u64,
+    /// (local receive time, neighbor time - local time) pairs
+    entries: Deque<(u64, i64), REGRESSION_ENTRIES>,
+    /// Mean local time of the entries
+    local_mean: u64,
+    /// Mean offset of the entries
+    offset_mean: i64,
+    /// Offset change per local microsecond
+    skew: f64,
+    /// Largest deviation of an entry from the fitted line (us)
+    residual_us: u32,
+}
+
+impl NeighborClock {
+    fn new(id: DroneId, root: DroneId) -> Self {
+        Self {
+            id,
+            root,
+            root_seq: 0,
+            hops: u8::MAX,
+            accuracy_us: u32::MAX,
+            last_heard_us: 0,
+            entries: Deque::new(),
+            local_mean: 0,
+            offset_mean: 0,
+            skew: 0.0,
+            residual_us: 0,
+        }
+    }
+
+    /// Estimated neighbor time minus local time at `local_us`
+    pub fn offset_us(&self, local_us: u64) -> i64 {
+        let dx = local_us as f64 - self.local_mean as f64;
+        self.offset_mean + (self.skew * dx) as i64
+    }
+
+    /// Local clock rate relative to the neighbor (parts per million)
+    pub fn skew_ppm(&self) -> f32 {
+        (self.skew * 1e6) as f32
+    }
+
+    /// Largest deviation of a regression point from the fitted line (us)
+    pub fn residual_us(&self) -> u32 {
+        self.residual_us
+    }
+
+    /// Regression points held
+    pub fn entries(&self) -> usize {
+        self.entries.len()
+    }
+
+    fn clear(&mut self) {
+        self.entries.clear();
+        self.local_mean = 0;
+        self.offset_mean = 0;
+        self.skew = 0.0;
+        self.residual_us = 0;
+    }
+
+    fn add_entry(&mut self, local_us: u64, offset: i64) {
+        if self.entries.is_full() {
+            self.entries.pop_front();
+        }
+        self.entries.push_back((local_us, offset)).ok();
+        self.regress();
+    }
+
+    /// Least-squares fit of offset against local time
+    fn regress(&mut self) {
+        let n = self.entries.len() as i64;
+        let Some(&(x0, y0)) = self.entries.front() else {
+            return;
+        };
+        // Fit relative to the first entry to keep the sums small
+        let (mut sum_x, mut sum_y) = (0i64, 0i64);
+        for &(x, y) in self.entries.iter() {
+            sum_x += (x - x0) as i64;
+            sum_y += y - y0;
+        }
+        let mean_x = sum_x as f64 / n as f64;
+        let mean_y = sum_y as f64 / n as f64;
+
+        let (mut sxy, mut sxx) = (0.0f64, 0.0f64);
+        for &(x, y) in self.entries.iter() {
+            let dx = (x - x0) as f64 - mean_x;
+            sxy += dx * ((y - y0) as f64 - mean_y);
+            sxx += dx * dx;
+        }
+
+        self.skew = if sxx > 0.0 { sxy / sxx } else { 0.0 };
+        self.local_mean = x0 + mean_x as u64;
+        self.offset_mean = y0 + mean_y as i64;
+        self.residual_us = self
+            .entries
+            .iter()
+            .map(|&(x, y)| (y - self.offset_us(x)).unsigned_abs())
+            .max()
+            .unwrap_or(0)
+            .min(u32::MAX as u64) as u32;
+    }
+}
+
+/// Time synchronization service for one drone
+pub struct TimeSyncNode {
+    /// This drone's ID
+    local_id: DroneId,
+    /// Configuration
+    config: TimeSyncConfig,
+    /// Pinned root (e.g. the Raft leader)
+    leader: Option<DroneId>,
+    /// Root currently defining swarm time
+    root: DroneId,
+    /// Latest root sequence seen (or sent, when root)
+    root_seq: u32,
+    /// Local time the root sequence last advanced (us)
+    root_progress_us: u64,
+    /// Swarm time minus local time while root, so taking over keeps time continuous
+    root_offset_us: i64,
+    /// Root given up on and its last sequence; stale relays of it are ignored
+    abandoned: Option<(DroneId, u32)>,
+    /// Per-neighbor clock estimates
+    neighbors: Vec<NeighborClock, MAX_SYNC_NEIGHBORS>,
+    /// Neighbor followed for swarm time
+    parent: Option<DroneId>,
+    /// Local time of the last beacon sent (us)
+    last_beacon_us: Option<u64>,
+    /// Statistics
+    stats: TimeSyncStats,
+}
+
+impl TimeSyncNode {
+    /// Create a time sync node; it is its own root until it hears a better one
+    pub fn new(local_id: DroneId, config: TimeSyncConfig) -> Self {
+        Self {
+            local_id,
+            config,
+            leader: None,
+            root: local_id,
+            root_seq: 0,
+            root_progress_us: 0,
+            root_offset_us: 0,
+            abandoned: None,
+            neighbors: Vec::new(),
+            parent: None,
+            last_beacon_us: None,
+            stats: TimeSyncStats::default(),
+        }
+    }
+
+    /// Pin the root to the Raft leader, or go back to lowest-ID election
+    pub fn set_leader(&mut self, leader: Option<DroneId>, local_us: u64) {
+        self.leader = leader;
+        if let Some(leader) = leader {
+            if leader != self.root {
+                self.change_root(leader, 0, local_us);
+            }
+        }
+    }
+
+    /// Periodic processing; returns a beacon to broadcast when one is due
+    pub fn tick(&mut self, local_us: u64) -> Option<SyncBeacon> {
+        let timeout_us = self.config.root_timeout_ms * 1000;
+        let root_silent = local_us.saturating_sub(self.root_progress_us) > timeout_us;
+        let may_lead = self.leader.is_none_or(|leader| leader == self.local_id);
+        if !self.is_root() && root_silent && may_lead {
+            // Take over before dropping the parent, carrying its time estimate
+            self.abandoned = Some((self.root, self.root_seq));
+            self.change_root(self.local_id, 0, local_us);
+        }
+        self.neighbors
+            .retain(|n| local_us.saturating_sub(n.last_heard_us) <= timeout_us);
+        self.select_parent();
+
+        let due = self.last_beacon_us.is_none_or(|last| {
+            local_us.saturating_sub(last) >= self.config.beacon_interval_ms * 1000
+        });
+        if !due || !self.status().synchronized {
+            return None;
+        }
+        self.last_beacon_us = Some(local_us);
+
+        if self.is_root() {
+            self.root_seq = self.root_seq.wrapping_add(1);
+            self.root_progress_us = local_us;
+        }
+        let status = self.status();
+        self.stats.beacons_sent += 1;
+        Some(SyncBeacon {
+            sender: self.local_id,
+            root: self.root,
+            root_seq: self.root_seq,
+            hops: status.hops,
+            global_time_us: self.swarm_time_us_at(local_us),
+            accuracy_us: status.accuracy_us,
+        })
+    }
+
+    /// Process a beacon received at local time `local_us`
+    pub fn process_beacon(&mut self, beacon: SyncBeacon, local_us: u64) {
+        let acceptable = match self.leader {
+            Some(leader) => beacon.root == leader,
+            None => beacon.root.as_u64() <= self.root.as_u64(),
+        };
+        // Neighbors that have not timed out yet keep relaying a dead root
+        let stale = self.abandoned.is_some_and(|(root, seq)| {
+            root == beacon.root && beacon.root_seq.wrapping_sub(seq) as i32 <= 0
+        });
+        let acceptable = acceptable && !stale;
+        if beacon.sender == self.local_id || !acceptable || beacon.root == self.local_id {
+            self.stats.beacons_ignored += 1;
+            return;
+        }
+
+        if beacon.root != self.root {
+            self.change_root(beacon.root, beacon.root_seq, local_us);
+        } else if beacon.root_seq.wrapping_sub(self.root_seq) as i32 > 0 {
+            self.root_seq = beacon.root_seq;
+            self.root_progress_us = local_us;
+        }
+
+        let index = match self.neighbors.iter().position(|n| n.id == beacon.sender) {
+            Some(index) => index,
+            None => {
+                let clock = NeighborClock::new(beacon.sender, beacon.root);
+                if self.neighbors.push(clock).is_err() {
+                    self.stats.beacons_ignored += 1;
+                    return;
+                }
+                self.neighbors.len() - 1
+            }
+        };
+
+        let min_entries = self.config.min_entries;
+        let threshold = self.config.outlier_threshold_us as u64;
+        let neighbor = &mut self.neighbors[index];
+        if neighbor.root != beacon.root {
+            neighbor.root = beacon.root;
+            neighbor.clear();
+        }
+        let global_us = beacon.global_time_us + self.config.delay_compensation_us as u64;
+        let offset = global_us as i64 - local_us as i64;
+        if neighbor.entries() >= min_entries
+            && (offset - neighbor.offset_us(local_us)).unsigned_abs() > threshold
+        {
+            neighbor.clear();
+            self.stats.outliers += 1;
+        }
+        neighbor.add_entry(local_us, offset);
+        neighbor.root_seq = beacon.root_seq;
+        neighbor.hops = beacon.hops;
+        neighbor.accuracy_us = beacon.accuracy_us;
+        neighbor.last_heard_us = local_us;
+
+        self.stats.beacons_received += 1;
+        self.select_parent();
+    }
+
+    /// Swarm time at local time `local_us` (local time while unsynchronized)
+    pub fn swarm_time_us_at(&self, local_us: u64) -> u64 {
+        match self.parent_clock() {
+            Some(parent) => local_us.saturating_add_signed(parent.offset_us(local_us)),
+            None if self.is_root() => local_us.saturating_add_signed(self.root_offset_us),
+            None => local_us,
+        }
+    }
+
+    /// Current swarm time (ms); check [`TimeSyncNode::status`] for accuracy
+    pub fn swarm_time_ms(&self) -> u64 {
+        self.swarm_time_us_at(crate::get_time_us()) / 1000
+    }
+
+    /// Current synchronization state
+    pub fn status(&self) -> SyncStatus {
+        let is_root = self.is_root();
+        let parent = self.parent_clock();
+        let (hops, accuracy_us, skew_ppm) = match parent {
+            Some(p) => (
+                p.hops.saturating_add(1),
+                p.accuracy_us.saturating_add(p.residual_us),
+                p.skew_ppm(),
+            ),
+            None if is_root => (0, 0, 0.0),
+            None => (u8::MAX, u32::MAX, 0.0),
+        };
+        SyncStatus {
+            root: self.root,
+            is_root,
+            synchronized: is_root || parent.is_some(),
+            parent: parent.map(|p| p.id),
+            hops,
+            accuracy_us,
+            skew_ppm,
+        }
+    }
+
+    /// Check if this drone defines swarm time
+    pub fn is_root(&self) -> bool {
+        self.root == self.local_id
+    }
+
+    /// Clock estimate for a neighbor
+    pub fn neighbor(&self, id: DroneId) -> Option<&NeighborClock> {
+        self.neighbors.iter().find(|n| n.id == id)
+    }
+
+    /// Get configuration
+    pub fn config(&self) -> &TimeSyncConfig {
+        &self.config
+    }
+
+    /// Get statistics
+    pub fn statistics(&self) -> &TimeSyncStats {
+        &self.stats
+    }
+
+    fn parent_clock(&self) -> Option<&NeighborClock> {
+        self.parent.and_then(|id| self.neighbor(id))
+    }
+
+    fn change_root(&mut self, root: DroneId, root_seq: u32, local_us: u64) {
+        if root == self.local_id {
+            self.root_offset_us = self.swarm_time_us_at(local_us) as i64 - local_us as i64;
+        }
+        self.root = root;
+        self.root_seq = root_seq;
+        self.root_progress_us = local_us;
+        self.parent = None;
+        self.stats.root_changes += 1;
+    }
+
+    /// Follow the usable neighbor closest to the root, then the most accurate
+    fn select_parent(&mut self) {
+        self.parent = if self.is_root() {
+            None
+        } else {
+            self.neighbors
+                .iter()
+                .filter(|n| n.root == self.root && n.entries() >= self.config.min_entries)
+                .min_by_key(|n| (n.hops, n.accuracy_us.saturating_add(n.residual_us)))
+                .map(|n| n.id)
+        };
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_regression_recovers_offset_and_skew() {
+        let mut clock = NeighborClock::new(DroneId::new(2), DroneId::new(1));
+        // Neighbor runs 50 ppm fast and 3 ms ahead
+        for i in 0..REGRESSION_ENTRIES as u64 {
+            let local = 1_000_000 + i * 1_000_000;
+            let offset = 3_000 + (local as f64 * 50e-6) as i64;
+            clock.add_entry(local, offset);
+        }
+
+        assert!((clock.skew_ppm() - 50.0).abs() < 0.5);
+        assert!(clock.residual_us() <= 1);
+        let expected = 3_000 + (20_000_000f64 * 50e-6) as i64;
+        assert!((clock.offset_us(20_000_000) - expected).abs() <= 2);
+    }
+
+    #[test]
+    fn test_regression_window_slides() {
+        let mut clock = NeighborClock::new(DroneId::new(2), DroneId::new(1));
+        for i in 0..20u64 {
+            clock.add_entry(i * 1000, 500);
+        }
+        assert_eq!(clock.entries(), REGRESSION_ENTRIES);
+        assert_eq!(clock.offset_us(50_000), 500);
+    }
+}
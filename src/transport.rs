@@ -0,0 +1,354 @@
+//! Link-layer transports for the mesh network
+//!
+//! [`MeshNetwork`](crate::network::MeshNetwork) serializes its protocol
+//! messages into frames and hands them to a [`Transport`]. Implementations:
+//! - [`NullTransport`] - discards all frames (default, single-node simulation;
+//!   refuses them with the `hardware` feature)
+//! - [`LoopbackHub`] / [`LoopbackTransport`] - in-process bus for multi-node tests (`std`)
+//! - [`UdpTransport`] - UDP sockets over IPv4/IPv6 (`std`)
+//!
+//! Hardware radios (ESP-NOW, LoRa, 802.15.4) plug in by implementing [`Transport`].
+
+use crate::types::*;
+
+/// Maximum frame size in bytes (IPv6 minimum MTU)
+pub const MAX_FRAME_SIZE: usize = 1280;
+
+/// Frame-oriented link layer used by the mesh network
+pub trait Transport {
+    /// Send a frame to a single neighbor
+    fn send_to(&mut self, destination: NetworkAddress, frame: &[u8]) -> Result<()>;
+
+    /// Send a frame to every node in radio range
+    fn broadcast(&mut self, frame: &[u8]) -> Result<()>;
+
+    /// Poll for a received frame without blocking
+    ///
+    /// Copies the frame into `buf` and returns its length and the sender's address,
+    /// or `None` if nothing is pending.
+    fn poll_recv(&mut self, buf: &mut [u8]) -> Result<Option<(usize, NetworkAddress)>>;
+}
+
+/// Transport that silently discards every frame
+///
+/// Used when no radio is attached (single-node simulation and unit tests).
+/// With the `hardware` feature sends fail with `HardwareFault` instead, so
+/// a build for real radios cannot drop its traffic unnoticed.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct NullTransport;
+
+impl NullTransport {
+    fn discard() -> Result<()> {
+        if cfg!(feature = "hardware") {
+            Err(SwarmError::HardwareFault)
+        } else {
+            Ok(())
+        }
+    }
+}
+
+impl Transport for NullTransport {
+    fn send_to(&mut self, _destination: NetworkAddress, _frame: &[u8]) -> Result<()> {
+        Self::discard()
+    }
+
+    fn broadcast(&mut self, _frame: &[u8]) -> Result<()> {
+        Self::discard()
+    }
+
+    fn poll_recv(&mut self, _buf: &mut [u8]) -> Result<Option<(usize, NetworkAddress)>> {
+        Ok(None)
+    }
+}
+
+#[cfg(feature = "std")]
+pub use self::std_transports::{
+    to_network_address, to_socket_addr, LoopbackHub, LoopbackTransport, UdpTransport,
+};
+
+#[cfg(feature = "std")]
+mod std_transports {
+    use super::*;
+    use std::collections::VecDeque;
+    use std::net::{Ipv6Addr, SocketAddr, SocketAddrV6, UdpSocket};
+    use std::sync::{Arc, Mutex};
+    use std::vec::Vec;
+
+    /// Maximum frames buffered per loopback endpoint before new frames are dropped
+    const LOOPBACK_QUEUE_DEPTH: usize = 256;
+
+    struct Endpoint {
+        address: NetworkAddress,
+        inbox: VecDeque<(NetworkAddress, Vec<u8>)>,
+    }
+
+    /// In-process frame bus connecting several [`LoopbackTransport`]s
+    ///
+    /// Every endpoint hears every broadcast; unicast frames reach only the
+    /// endpoint bound to the destination address.
+    #[derive(Clone, Default)]
+    pub struct LoopbackHub {
+        endpoints: Arc<Mutex<Vec<Endpoint>>>,
+    }
+
+    impl LoopbackHub {
+        /// Create an empty hub
+        pub fn new() -> Self {
+            Self::default()
+        }
+
+        /// Attach a new endpoint bound to `address`
+        pub fn connect(&self, address: NetworkAddress) -> LoopbackTransport {
+            let mut endpoints = self.endpoints.lock().expect("loopback hub poisoned");
+            if !endpoints.iter().any(|e| e.address == address) {
+                endpoints.push(Endpoint {
+                    address,
+                    inbox: VecDeque::new(),
+                });
+            }
+            LoopbackTransport {
+                hub: self.clone(),
+                address,
+            }
+        }
+
+        fn deliver(&self, from: NetworkAddress, to: Option<NetworkAddress>, frame: &[u8]) {
+            let mut endpoints = self.endpoints.lock().expect("loopback hub poisoned");
+            for endpoint in endpoints.iter_mut() {
+                let addressed = match to {
+                    Some(dest) => endpoint.address == dest,
+                    None => endpoint.address != from,
+                };
+                if addressed && endpoint.inbox.len() < LOOPBACK_QUEUE_DEPTH {
+                    endpoint.inbox.push_back((from, frame.to_vec()));
+                }
+            }
+        }
+
+        fn take(&self, address: NetworkAddress) -> Option<(NetworkAddress, Vec<u8>)> {
+            let mut endpoints = self.endpoints.lock().expect("loopback hub poisoned");
+            endpoints
+                .iter_mut()
+                .find(|e| e.address == address)
+                .and_then(|e| e.inbox.pop_front())
+        }
+    }
+
+    /// One node's attachment to a [`LoopbackHub`]
+    #[derive(Clone)]
+    pub struct LoopbackTransport {
+        hub: LoopbackHub,
+        address: NetworkAddress,
+    }
+
+    impl LoopbackTransport {
+        /// Address this endpoint is bound to
+        pub fn local_address(&self) -> NetworkAddress {
+            self.address
+        }
+    }
+
+    impl Transport for LoopbackTransport {
+        fn send_to(&mut self, destination: NetworkAddress, frame: &[u8]) -> Result<()> {
+            if frame.len() > MAX_FRAME_SIZE {
+                return Err(SwarmError::BufferFull);
+            }
+            self.hub.deliver(self.address, Some(destination), frame);
+            Ok(())
+        }
+
+        fn broadcast(&mut self, frame: &[u8]) -> Result<()> {
+            if frame.len() > MAX_FRAME_SIZE {
+                return Err(SwarmError::BufferFull);
+            }
+            self.hub.deliver(self.address, None, frame);
+            Ok(())
+        }
+
+        fn poll_recv(&mut self, buf: &mut [u8]) -> Result<Option<(usize, NetworkAddress)>> {
+            match self.hub.take(self.address) {
+                Some((from, frame)) => {
+                    let dest = buf.get_mut(..frame.len()).ok_or(SwarmError::BufferFull)?;
+                    dest.copy_from_slice(&frame);
+                    Ok(Some((frame.len(), from)))
+                }
+                None => Ok(None),
+            }
+        }
+    }
+
+    /// UDP socket transport
+    ///
+    /// Broadcast is emulated by unicasting to every registered peer, which works
+    /// on networks without IP broadcast/multicast (e.g. SITL over loopback).
+    pub struct UdpTransport {
+        socket: UdpSocket,
+        peers: Vec<SocketAddr>,
+    }
+
+    impl UdpTransport {
+        /// Bind a non-blocking UDP socket
+        pub fn bind(address: SocketAddr) -> Result<Self> {
+            let socket = UdpSocket::bind(address).map_err(|_| SwarmError::NetworkError)?;
+            socket
+                .set_nonblocking(true)
+                .map_err(|_| SwarmError::NetworkError)?;
+            Ok(Self {
+                socket,
+                peers: Vec::new(),
+            })
+        }
+
+        /// Register a peer that receives broadcast frames
+        pub fn add_peer(&mut self, peer: SocketAddr) {
+            if !self.peers.contains(&peer) {
+                self.peers.push(peer);
+            }
+        }
+
+        /// Local socket address
+        pub fn local_addr(&self) -> Result<SocketAddr> {
+            self.socket
+                .local_addr()
+                .map_err(|_| SwarmError::NetworkError)
+        }
+
+        /// Local address in mesh form
+        pub fn local_address(&self) -> Result<NetworkAddress> {
+            self.local_addr().map(to_network_address)
+        }
+    }
+
+    impl Transport for UdpTransport {
+        fn send_to(&mut self, destination: NetworkAddress, frame: &[u8]) -> Result<()> {
+            self.socket
+                .send_to(frame, to_socket_addr(destination))
+                .map_err(|_| SwarmError::NetworkError)?;
+            Ok(())
+        }
+
+        fn broadcast(&mut self, frame: &[u8]) -> Result<()> {
+            for peer in &self.peers {
+                self.socket
+                    .send_to(frame, peer)
+                    .map_err(|_| SwarmError::NetworkError)?;
+            }
+            Ok(())
+        }
+
+        /// Datagrams over [`MAX_FRAME_SIZE`] or larger than `buf` are dropped
+        /// with `BufferFull` rather than returned truncated.
+        fn poll_recv(&mut self, buf: &mut [u8]) -> Result<Option<(usize, NetworkAddress)>> {
+            // One spare byte tells an oversized datagram from one that fits exactly
+            let mut scratch = [0u8; MAX_FRAME_SIZE + 1];
+            match self.socket.recv_from(&mut scratch) {
+                Ok((len, from)) => {
+                    if len > MAX_FRAME_SIZE {
+                        return Err(SwarmError::BufferFull);
+                    }
+                    let dest = buf.get_mut(..len).ok_or(SwarmError::BufferFull)?;
+                    dest.copy_from_slice(&scratch[..len]);
+                    Ok(Some((len, to_network_address(from))))
+                }
+                Err(e) if e.kind() == std::io::ErrorKind::WouldBlock => Ok(None),
+                Err(_) => Err(SwarmError::NetworkError),
+            }
+        }
+    }
+
+    /// Convert a socket address to a mesh address (IPv4 becomes IPv4-mapped IPv6)
+    pub fn to_network_address(addr: SocketAddr) -> NetworkAddress {
+        let ip = match addr {
+            SocketAddr::V4(v4) => v4.ip().to_ipv6_mapped(),
+            SocketAddr::V6(v6) => *v6.ip(),
+        };
+        NetworkAddress::new(ip.octets(), addr.port())
+    }
+
+    /// Convert a mesh address to a socket address (IPv4-mapped addresses become IPv4)
+    pub fn to_socket_addr(addr: NetworkAddress) -> SocketAddr {
+        let ip = Ipv6Addr::from(addr.addr);
+        match ip.to_ipv4_mapped() {
+            Some(v4) => SocketAddr::new(v4.into(), addr.port),
+            None => SocketAddr::V6(SocketAddrV6::new(ip, addr.port, 0, 0)),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    #[cfg(not(feature = "hardware"))]
+    fn test_null_transport_discards() {
+        let mut transport = NullTransport;
+        let mut buf = [0u8; 16];
+        assert!(transport.broadcast(b"hello").is_ok());
+        assert!(transport.poll_recv(&mut buf).unwrap().is_none());
+    }
+
+    #[cfg(feature = "std")]
+    #[test]
+    fn test_loopback_broadcast_and_unicast() {
+        let hub = LoopbackHub::new();
+        let a_addr = NetworkAddress::new([0; 16], 1);
+        let b_addr = NetworkAddress::new([0; 16], 2);
+        let c_addr = NetworkAddress::new([0; 16], 3);
+        let mut a = hub.connect(a_addr);
+        let mut b = hub.connect(b_addr);
+        let mut c = hub.connect(c_addr);
+
+        a.broadcast(b"all").unwrap();
+        a.send_to(c_addr, b"c-only").unwrap();
+
+        let mut buf = [0u8; MAX_FRAME_SIZE];
+        assert_eq!(b.poll_recv(&mut buf).unwrap(), Some((3, a_addr)));
+        assert!(b.poll_recv(&mut buf).unwrap().is_none());
+
+        assert_eq!(c.poll_recv(&mut buf).unwrap(), Some((3, a_addr)));
+        assert_eq!(c.poll_recv(&mut buf).unwrap(), Some((6, a_addr)));
+        assert_eq!(&buf[..6], b"c-only");
+
+        // Sender does not hear its own broadcast
+        assert!(a.poll_recv(&mut buf).unwrap().is_none());
+    }
+
+    #[cfg(feature = "std")]
+    #[test]
+    fn test_udp_rejects_oversized_datagrams() {
+        let mut receiver = UdpTransport::bind("127.0.0.1:0".parse().unwrap()).unwrap();
+        let mut sender = UdpTransport::bind("127.0.0.1:0".parse().unwrap()).unwrap();
+        let to = receiver.local_address().unwrap();
+
+        // Wait out asynchronous loopback delivery
+        let mut recv = |buf: &mut [u8]| loop {
+            match receiver.poll_recv(buf) {
+                Ok(None) => std::thread::sleep(std::time::Duration::from_millis(1)),
+                other => break other,
+            }
+        };
+
+        let mut buf = [0u8; MAX_FRAME_SIZE];
+        sender.send_to(to, &[7u8; MAX_FRAME_SIZE + 1]).unwrap();
+        assert_eq!(recv(&mut buf), Err(SwarmError::BufferFull));
+
+        sender.send_to(to, &[7u8; 32]).unwrap();
+        assert_eq!(recv(&mut buf[..16]), Err(SwarmError::BufferFull));
+
+        sender.send_to(to, &[9u8; MAX_FRAME_SIZE]).unwrap();
+        let (len, _) = recv(&mut buf).unwrap().unwrap();
+        assert_eq!(len, MAX_FRAME_SIZE);
+        assert!(buf.iter().all(|&b| b == 9));
+    }
+
+    #[cfg(feature = "std")]
+    #[test]
+    fn test_socket_address_round_trip() {
+        let v4: std::net::SocketAddr = "127.0.0.1:14550".parse().unwrap();
+        assert_eq!(to_socket_addr(to_network_address(v4)), v4);
+
+        let v6: std::net::SocketAddr = "[fe80::1]:9000".parse().unwrap();
+        assert_eq!(to_socket_addr(to_network_address(v6)), v6);
+    }
+}
@@ -112,6 +112,7 @@ mod position3d_tests {
     }
 
     #[test]
+    #[allow(clippy::clone_on_copy)]
     fn test_position3d_clone() {
         let pos1 = Position3D::new(1.0, 2.0, 3.0);
         let pos2 = pos1.clone();
@@ -212,6 +213,7 @@ mod obstacle_tests {
     }
 
     #[test]
+    #[allow(clippy::clone_on_copy)]
     fn test_obstacle_clone() {
         let center = Position3D::new(5.0, 6.0, 7.0);
         let obstacle1 = Obstacle::new(center, 3.0);
@@ -430,6 +432,7 @@ mod aco_algorithm_tests {
     }
 
     #[test]
+    #[allow(clippy::clone_on_copy)]
     fn test_aco_algorithm_clone() {
         let algo1 = ACOAlgorithm::MMAS;
         let algo2 = algo1.clone();
@@ -513,6 +516,7 @@ mod aco_optimizer_tests {
     }
 
     #[test]
+    #[allow(clippy::field_reassign_with_default)]
     fn test_aco_optimizer_new_too_many_ants() {
         let mut config = ACOConfig::default();
         config.num_ants = MAX_ANTS + 1; // Exceed maximum
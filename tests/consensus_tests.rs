@@ -22,6 +22,7 @@ mod node_state_tests {
     }
 
     #[test]
+    #[allow(clippy::clone_on_copy)]
     fn test_node_state_clone() {
         let state1 = NodeState::Leader;
         let state2 = state1.clone();
@@ -151,6 +152,7 @@ mod log_entry_tests {
         let entry = LogEntry {
             term: 5,
             index: 10,
+            issuer: DroneId::new(1),
             command: SwarmCommand::EmergencyStop,
         };
 
@@ -163,6 +165,7 @@ mod log_entry_tests {
         let entry1 = LogEntry {
             term: 3,
             index: 7,
+            issuer: DroneId::new(1),
             command: SwarmCommand::AddDrone {
                 drone: DroneId::new(2),
             },
@@ -370,7 +373,7 @@ mod consensus_engine_tests {
         let mut engine = ConsensusEngine::new(DroneId::new(1), 150);
 
         let cmd = SwarmCommand::EmergencyStop;
-        let result = engine.propose_command(cmd);
+        let result = engine.propose_command(DroneId::new(1), cmd, 0);
 
         // Will fail because we're not leader
         assert!(result.is_err());
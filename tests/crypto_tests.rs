@@ -35,6 +35,7 @@ mod crypto_context_tests {
     }
 
     #[test]
+    #[allow(clippy::len_zero)]
     fn test_encrypt_empty_message() {
         let mut ctx = CryptoContext::new([2u8; 32]);
 
@@ -278,6 +279,17 @@ mod key_exchange_tests {
         assert_eq!(alice_shared, bob_shared);
     }
 
+    #[test]
+    fn test_exchange_public_key_matches_x25519() {
+        let private = [7u8; 32];
+        let expected = x25519_dalek::PublicKey::from(&x25519_dalek::StaticSecret::from(private));
+
+        assert_eq!(
+            CryptoContext::exchange_public_key(&private),
+            *expected.as_bytes()
+        );
+    }
+
     #[test]
     fn test_derive_session_key() {
         let shared_secret = [0x42u8; 32];
@@ -495,13 +507,110 @@ mod nonce_tracker_tests {
         let mut tracker = NonceTracker::new();
         let drone = DroneId::new(1);
 
-        tracker.check_nonce(drone, 100).unwrap();
+        tracker.check_nonce(drone, 1000).unwrap();
 
-        // Going backwards should fail
-        let result = tracker.check_nonce(drone, 50);
+        // Going back past the window should fail
+        let result = tracker.check_nonce(drone, 1000 - REPLAY_WINDOW);
         assert!(result.is_err());
     }
 
+    #[test]
+    fn test_nonce_out_of_order_within_window() {
+        let mut tracker = NonceTracker::new();
+        let drone = DroneId::new(1);
+
+        tracker.check_nonce(drone, 1000).unwrap();
+        assert!(tracker.check_nonce(drone, 999).is_ok());
+        assert!(tracker.check_nonce(drone, 1000 - REPLAY_WINDOW + 1).is_ok());
+        assert!(tracker.check_nonce(drone, 999).is_err());
+
+        // Window slides with the highest nonce
+        assert!(tracker.check_nonce(drone, 1100).is_ok());
+        assert!(tracker.check_nonce(drone, 990).is_ok());
+        assert!(tracker.check_nonce(drone, 999).is_err());
+        assert!(tracker.check_nonce(drone, 950).is_err());
+    }
+
+    #[test]
+    fn test_nonce_epoch_reset() {
+        let mut tracker = NonceTracker::new();
+        let drone = DroneId::new(1);
+
+        tracker.check(drone, 0, 500).unwrap();
+        // New epoch starts a fresh window; the previous one stays checked
+        assert!(tracker.check(drone, 1, 1).is_ok());
+        assert!(tracker.check(drone, 0, 499).is_ok());
+        assert!(tracker.check(drone, 0, 500).is_err());
+
+        tracker.retire_epochs_before(1);
+        assert!(tracker.check(drone, 0, 498).is_err());
+        assert!(tracker.check(drone, 1, 2).is_ok());
+    }
+
+    #[test]
+    fn test_full_nonce_tracker_keeps_windows() {
+        let mut tracker = NonceTracker::new();
+        for id in 0..MAX_TRACKED_PEERS as u64 {
+            tracker.check_nonce(DroneId::new(id), 1).unwrap();
+        }
+
+        // No window is evicted, so old nonces stay rejected
+        assert_eq!(
+            tracker.check_nonce(DroneId::new(1000), 1),
+            Err(SwarmError::ResourceExhausted)
+        );
+        assert_eq!(tracker.tracked_peers(), MAX_TRACKED_PEERS);
+        assert!(tracker.check_nonce(DroneId::new(0), 1).is_err());
+        assert!(tracker.check_nonce(DroneId::new(0), 2).is_ok());
+
+        // A peer that left frees its slot
+        tracker.forget(DroneId::new(1));
+        assert!(tracker.check_nonce(DroneId::new(1000), 1).is_ok());
+    }
+
+    #[test]
+    fn test_nonce_reservations_persisted() {
+        struct Store(std::vec::Vec<(DroneId, u32, u64)>, bool);
+        impl ReplayStore for Store {
+            fn reserve(&mut self, peer: DroneId, epoch: u32, reserved: u64) -> Result<()> {
+                if self.1 {
+                    return Err(SwarmError::ResourceExhausted);
+                }
+                self.0.push((peer, epoch, reserved));
+                Ok(())
+            }
+        }
+
+        let mut store = Store(std::vec::Vec::new(), false);
+        let mut tracker = NonceTracker::new();
+        let drone = DroneId::new(1);
+        for nonce in 1..=REPLAY_RESERVATION + 2 {
+            tracker.check_durable(drone, 3, nonce, &mut store).unwrap();
+        }
+        // One write per block of nonces
+        assert_eq!(
+            store.0,
+            [
+                (drone, 3, 1 + REPLAY_RESERVATION),
+                (drone, 3, 2 + 2 * REPLAY_RESERVATION)
+            ]
+        );
+
+        // After a reboot every reserved nonce counts as seen
+        let mut rebooted = NonceTracker::new();
+        let (_, epoch, reserved) = store.0[1];
+        rebooted.restore(drone, epoch, reserved).unwrap();
+        assert!(rebooted.check(drone, 3, REPLAY_RESERVATION).is_err());
+        assert!(rebooted.check(drone, 3, reserved + 1).is_ok());
+
+        // A failed write rejects the nonce
+        store.1 = true;
+        assert!(tracker
+            .check_durable(drone, 3, 10 * REPLAY_RESERVATION, &mut store)
+            .is_err());
+        assert!(tracker.check(drone, 3, 10 * REPLAY_RESERVATION).is_ok());
+    }
+
     #[test]
     fn test_nonce_zero_after_nonzero() {
         let mut tracker = NonceTracker::new();
@@ -579,6 +688,13 @@ mod constants_tests {
         assert_eq!(CRYPTO_OVERHEAD, 92);
     }
 
+    #[test]
+    #[cfg(feature = "post-quantum")]
+    fn test_crypto_overhead_with_dual_signatures() {
+        assert_eq!(DUAL_CRYPTO_OVERHEAD, CRYPTO_OVERHEAD + 3309);
+        assert_eq!(DUAL_SEALED_CAPACITY, 2048 + 3309);
+    }
+
     #[test]
     fn test_max_safe_encryptions() {
         assert_eq!(MAX_SAFE_ENCRYPTIONS, 1_000_000_000);
@@ -0,0 +1,342 @@
+//! Tests for the delay-tolerant bundle layer
+//!
+//! Tests the bundle store, forwarding strategies, custody transfer and expiry
+
+use drone_swarm_system::dtn::*;
+use drone_swarm_system::time_abstraction::set_virtual_time_us;
+use drone_swarm_system::types::*;
+use heapless::Vec;
+
+fn bundle(sequence: u32, priority: BundlePriority, custody: bool, created_ms: u64) -> Bundle {
+    Bundle {
+        id: BundleId {
+            source: DroneId::new(1),
+            sequence,
+        },
+        destination: DroneId::new(9),
+        priority,
+        created_ms,
+        lifetime_ms: 1000,
+        custody,
+        copies: 1,
+        payload: Vec::new(),
+    }
+}
+
+fn node(id: u64, strategy: DtnStrategy) -> DtnNode {
+    DtnNode::new(
+        DroneId::new(id),
+        DtnConfig {
+            strategy,
+            ..DtnConfig::default()
+        },
+    )
+}
+
+/// Summary of `node` as its peers would receive it
+fn summary(node: &DtnNode) -> DtnMessage {
+    node.summary()
+}
+
+#[cfg(test)]
+mod bundle_store_tests {
+    use super::*;
+
+    #[test]
+    fn test_insert_and_remove() {
+        let mut store = BundleStore::new();
+        let b = bundle(1, BundlePriority::Normal, false, 0);
+        let id = b.id;
+
+        assert!(store.insert(b).unwrap().is_none());
+        assert!(store.contains(&id));
+        assert_eq!(store.len(), 1);
+
+        assert!(store.remove(&id).is_some());
+        assert!(store.is_empty());
+    }
+
+    #[test]
+    fn test_eviction_prefers_lowest_priority() {
+        let mut store = BundleStore::new();
+        for sequence in 0..MAX_BUNDLES as u32 {
+            let priority = if sequence == 7 {
+                BundlePriority::Bulk
+            } else {
+                BundlePriority::Normal
+            };
+            store.insert(bundle(sequence, priority, false, 0)).unwrap();
+        }
+
+        let evicted = store
+            .insert(bundle(100, BundlePriority::Normal, false, 0))
+            .unwrap()
+            .unwrap();
+        assert_eq!(evicted.id.sequence, 7);
+        assert_eq!(store.len(), MAX_BUNDLES);
+    }
+
+    #[test]
+    fn test_eviction_spares_custody_then_prefers_soonest_expiry() {
+        let mut store = BundleStore::new();
+        for sequence in 0..MAX_BUNDLES as u32 {
+            // Bundle 3 has no custody; bundle 5 expires first among the rest
+            let custody = sequence != 3;
+            let created = if sequence == 5 { 0 } else { 100 };
+            store
+                .insert(bundle(sequence, BundlePriority::Normal, custody, created))
+                .unwrap();
+        }
+
+        let first = store
+            .insert(bundle(100, BundlePriority::Normal, true, 100))
+            .unwrap()
+            .unwrap();
+        assert_eq!(first.id.sequence, 3);
+
+        let second = store
+            .insert(bundle(101, BundlePriority::Normal, true, 100))
+            .unwrap()
+            .unwrap();
+        assert_eq!(second.id.sequence, 5);
+    }
+
+    #[test]
+    fn test_lower_priority_refused_when_full() {
+        let mut store = BundleStore::new();
+        for sequence in 0..MAX_BUNDLES as u32 {
+            store
+                .insert(bundle(sequence, BundlePriority::Expedited, false, 0))
+                .unwrap();
+        }
+
+        let result = store.insert(bundle(100, BundlePriority::Normal, false, 0));
+        assert_eq!(result.unwrap_err(), SwarmError::BufferFull);
+    }
+
+    #[test]
+    fn test_expire() {
+        let mut store = BundleStore::new();
+        store
+            .insert(bundle(1, BundlePriority::Normal, false, 0))
+            .unwrap();
+        store
+            .insert(bundle(2, BundlePriority::Normal, false, 500))
+            .unwrap();
+
+        assert_eq!(store.expire(1200), 1);
+        assert_eq!(store.len(), 1);
+    }
+}
+
+#[cfg(test)]
+mod forwarding_tests {
+    use super::*;
+
+    fn transfers(output: &DtnOutput) -> std::vec::Vec<&Bundle> {
+        output
+            .replies
+            .iter()
+            .filter_map(|reply| match reply {
+                DtnMessage::Transfer { bundle, .. } => Some(bundle),
+                _ => None,
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_send_rejects_self_and_oversized() {
+        let mut a = node(1, DtnStrategy::Prophet);
+        assert!(a
+            .send(DroneId::new(1), b"x", BundlePriority::Normal)
+            .is_err());
+        assert!(a
+            .send(
+                DroneId::new(2),
+                &[0; MAX_BUNDLE_PAYLOAD + 1],
+                BundlePriority::Normal
+            )
+            .is_err());
+        assert_eq!(a.statistics().bundles_created, 0);
+    }
+
+    #[test]
+    fn test_spray_halves_copies() {
+        let mut a = node(1, DtnStrategy::SprayAndWait { copies: 8 });
+        let b = node(2, DtnStrategy::SprayAndWait { copies: 8 });
+        let id = a
+            .send(DroneId::new(9), b"survey", BundlePriority::Normal)
+            .unwrap();
+
+        let output = a.process_message(summary(&b)).unwrap();
+
+        let handed = transfers(&output);
+        assert_eq!(handed.len(), 1);
+        assert_eq!(handed[0].copies, 4);
+        assert_eq!(a.store().get(&id).unwrap().copies, 4);
+    }
+
+    #[test]
+    fn test_spray_waits_with_last_copy() {
+        let mut a = node(1, DtnStrategy::SprayAndWait { copies: 1 });
+        a.send(DroneId::new(9), b"survey", BundlePriority::Normal)
+            .unwrap();
+
+        let relay = a
+            .process_message(summary(&node(2, DtnStrategy::Prophet)))
+            .unwrap();
+        assert!(relay.replies.is_empty());
+
+        // The destination itself always gets it
+        let direct = a
+            .process_message(summary(&node(9, DtnStrategy::Prophet)))
+            .unwrap();
+        assert_eq!(transfers(&direct).len(), 1);
+    }
+
+    #[test]
+    fn test_peer_holding_bundle_is_skipped() {
+        let mut a = node(1, DtnStrategy::SprayAndWait { copies: 8 });
+        let mut b = node(2, DtnStrategy::SprayAndWait { copies: 8 });
+        a.send(DroneId::new(9), b"survey", BundlePriority::Normal)
+            .unwrap();
+
+        let first = a.process_message(summary(&b)).unwrap();
+        for reply in first.replies {
+            b.process_message(reply).unwrap();
+        }
+        let second = a.process_message(summary(&b)).unwrap();
+
+        assert!(second.replies.is_empty());
+        assert_eq!(b.store().len(), 1);
+    }
+
+    #[test]
+    fn test_prophet_forwards_to_better_carrier() {
+        let mut a = node(1, DtnStrategy::Prophet);
+        let mut b = node(2, DtnStrategy::Prophet);
+        a.send(DroneId::new(9), b"survey", BundlePriority::Normal)
+            .unwrap();
+
+        // Drone 2 has not met drone 9 yet: keep the bundle
+        let output = a.process_message(summary(&b)).unwrap();
+        assert!(transfers(&output).is_empty());
+
+        // After drone 2 meets drone 9 it is the better carrier
+        b.process_message(summary(&node(9, DtnStrategy::Prophet)))
+            .unwrap();
+        let output = a.process_message(summary(&b)).unwrap();
+        assert_eq!(transfers(&output).len(), 1);
+    }
+
+    #[test]
+    fn test_priority_order_on_contact() {
+        let mut a = node(1, DtnStrategy::SprayAndWait { copies: 2 });
+        a.send(DroneId::new(9), b"bulk", BundlePriority::Bulk)
+            .unwrap();
+        a.send(DroneId::new(9), b"urgent", BundlePriority::Expedited)
+            .unwrap();
+
+        let output = a
+            .process_message(summary(&node(2, DtnStrategy::Prophet)))
+            .unwrap();
+
+        let handed = transfers(&output);
+        assert_eq!(handed[0].priority, BundlePriority::Expedited);
+        assert_eq!(handed[1].priority, BundlePriority::Bulk);
+    }
+}
+
+#[cfg(test)]
+mod custody_tests {
+    use super::*;
+
+    #[test]
+    fn test_custody_transfer_releases_sender() {
+        let mut a = node(1, DtnStrategy::Prophet);
+        let mut b = node(2, DtnStrategy::Prophet);
+        b.process_message(summary(&node(9, DtnStrategy::Prophet)))
+            .unwrap();
+        let id = a
+            .send(DroneId::new(9), b"survey", BundlePriority::Normal)
+            .unwrap();
+
+        let offer = a.process_message(summary(&b)).unwrap();
+        let mut signals = std::vec::Vec::new();
+        for reply in offer.replies {
+            signals.extend(b.process_message(reply).unwrap().replies);
+        }
+        assert!(matches!(
+            signals[0],
+            DtnMessage::Custody { accepted: true, .. }
+        ));
+        assert!(b.store().contains(&id));
+
+        for signal in signals {
+            a.process_message(signal).unwrap();
+        }
+        assert!(!a.store().contains(&id));
+        assert_eq!(b.statistics().custody_accepted, 1);
+    }
+
+    #[test]
+    fn test_delivery_is_reported_once() {
+        let mut a = node(1, DtnStrategy::Prophet);
+        let mut dest = node(9, DtnStrategy::Prophet);
+        a.send(DroneId::new(9), b"survey", BundlePriority::Normal)
+            .unwrap();
+
+        let offer = a.process_message(summary(&dest)).unwrap();
+        let transfer = offer.replies[0].clone();
+        let first = dest.process_message(transfer.clone()).unwrap();
+        let again = dest.process_message(transfer).unwrap();
+
+        assert_eq!(&first.delivered.unwrap().payload[..], b"survey");
+        assert!(again.delivered.is_none());
+        assert_eq!(dest.statistics().bundles_delivered, 1);
+    }
+
+    #[test]
+    fn test_carrier_drops_bundle_reported_delivered() {
+        let config = DtnConfig {
+            strategy: DtnStrategy::SprayAndWait { copies: 4 },
+            custody: false,
+            ..DtnConfig::default()
+        };
+        let mut a = DtnNode::new(DroneId::new(1), config);
+        let mut dest = DtnNode::new(DroneId::new(9), config);
+        let id = a
+            .send(DroneId::new(9), b"survey", BundlePriority::Normal)
+            .unwrap();
+
+        for reply in a.process_message(summary(&dest)).unwrap().replies {
+            dest.process_message(reply).unwrap();
+        }
+        // Without custody the carrier keeps its copy until it sees the receipt
+        assert!(a.store().contains(&id));
+
+        a.process_message(summary(&dest)).unwrap();
+        assert!(!a.store().contains(&id));
+    }
+
+    #[test]
+    fn test_expired_bundles_dropped() {
+        set_virtual_time_us(Some(0));
+        let mut a = DtnNode::new(
+            DroneId::new(1),
+            DtnConfig {
+                default_lifetime_ms: 1000,
+                ..DtnConfig::default()
+            },
+        );
+        a.send(DroneId::new(9), b"stale", BundlePriority::Normal)
+            .unwrap();
+
+        set_virtual_time_us(Some(1_500_000));
+        a.tick().unwrap();
+
+        assert!(a.store().is_empty());
+        assert_eq!(a.statistics().bundles_expired, 1);
+        set_virtual_time_us(None);
+    }
+}
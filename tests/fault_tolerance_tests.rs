@@ -27,6 +27,7 @@ mod fault_severity_tests {
     }
 
     #[test]
+    #[allow(clippy::clone_on_copy)]
     fn test_severity_clone() {
         let severity = FaultSeverity::Major;
         let cloned = severity.clone();
@@ -79,6 +80,7 @@ mod fault_type_tests {
     }
 
     #[test]
+    #[allow(clippy::clone_on_copy)]
     fn test_fault_type_clone() {
         let fault_type = FaultType::MotorFailure;
         let cloned = fault_type.clone();
@@ -123,6 +125,7 @@ mod fault_tests {
     }
 
     #[test]
+    #[allow(clippy::clone_on_copy)]
     fn test_fault_clone() {
         let fault = Fault {
             fault_type: FaultType::GpsFailure,
@@ -414,6 +417,7 @@ mod subsystem_tests {
     }
 
     #[test]
+    #[allow(clippy::clone_on_copy)]
     fn test_subsystem_clone() {
         let subsystem = Subsystem::Navigation;
         let cloned = subsystem.clone();
@@ -430,6 +434,7 @@ mod redundancy_manager_tests {
     use super::*;
 
     #[test]
+    #[allow(unused_variables, clippy::assertions_on_constants)]
     fn test_new() {
         let rm = RedundancyManager::new();
         // Just verify creation
@@ -437,6 +442,7 @@ mod redundancy_manager_tests {
     }
 
     #[test]
+    #[allow(unused_variables, clippy::assertions_on_constants)]
     fn test_default() {
         let rm = RedundancyManager::default();
         // Just verify creation
@@ -499,6 +505,7 @@ mod fault_statistics_tests {
     }
 
     #[test]
+    #[allow(clippy::clone_on_copy)]
     fn test_clone() {
         let stats = FaultStatistics {
             total_faults: 10,
@@ -523,6 +530,7 @@ mod health_monitor_tests {
     use super::*;
 
     #[test]
+    #[allow(unused_variables, clippy::assertions_on_constants)]
     fn test_new() {
         let monitor = HealthMonitor::new(1000);
         // Just verify creation
@@ -0,0 +1,329 @@
+//! Tests for message fragmentation and reassembly
+//!
+//! Tests fragment framing, reassembly, selective retransmission and timeouts
+
+use drone_swarm_system::consensus::{ConsensusMessage, LogEntry, SwarmCommand};
+use drone_swarm_system::fragmentation::*;
+use drone_swarm_system::time_abstraction::set_virtual_time_us;
+use drone_swarm_system::types::*;
+
+fn layer(id: u64, mtu: usize) -> FragmentationLayer {
+    FragmentationLayer::new(
+        DroneId::new(id),
+        FragmentConfig {
+            mtu,
+            ..FragmentConfig::default()
+        },
+    )
+}
+
+fn payload(len: usize) -> std::vec::Vec<u8> {
+    (0..len).map(|i| (i * 7 + 3) as u8).collect()
+}
+
+/// Drain every queued message from `from`
+fn drain(from: &mut FragmentationLayer) -> std::vec::Vec<FragmentMessage> {
+    core::iter::from_fn(|| from.poll_transmit().map(|(_, message)| message)).collect()
+}
+
+#[cfg(test)]
+mod framing_tests {
+    use super::*;
+
+    #[test]
+    fn test_fragment_count_follows_mtu() {
+        let mut a = layer(1, 256);
+        a.send(DroneId::new(2), &payload(1000)).unwrap();
+
+        let fragments = drain(&mut a);
+        // 224 data bytes per fragment at a 256-byte MTU
+        assert_eq!(fragments.len(), 5);
+        for (i, fragment) in fragments.iter().enumerate() {
+            let FragmentMessage::Fragment { header, data } = fragment else {
+                panic!("expected a fragment");
+            };
+            assert_eq!(header.index as usize, i);
+            assert_eq!(header.count, 5);
+            assert_eq!(header.offset as usize, i * 224);
+            assert_eq!(header.total_len, 1000);
+            assert!(data.len() <= a.fragment_size());
+        }
+    }
+
+    #[test]
+    fn test_mtu_is_clamped() {
+        assert_eq!(layer(1, 16).fragment_size(), MIN_MTU - FRAGMENT_OVERHEAD);
+        assert_eq!(layer(1, 9000).fragment_size(), MAX_FRAGMENT_DATA);
+    }
+
+    #[test]
+    fn test_send_validation() {
+        let mut a = layer(1, 1280);
+        assert!(a.send(DroneId::new(1), b"self").is_err());
+        assert!(a.send(DroneId::new(2), &[]).is_err());
+        assert!(a
+            .send(DroneId::new(2), &payload(MAX_MESSAGE_PAYLOAD + 1))
+            .is_err());
+
+        a.send(DroneId::new(2), b"one").unwrap();
+        a.send(DroneId::new(2), b"two").unwrap();
+        assert_eq!(
+            a.send(DroneId::new(2), b"three").unwrap_err(),
+            SwarmError::BufferFull
+        );
+    }
+
+    #[test]
+    fn test_malformed_fragment_rejected() {
+        let mut b = layer(2, 1280);
+        let fragment = FragmentMessage::Fragment {
+            header: FragmentHeader {
+                source: DroneId::new(1),
+                message_id: 0,
+                index: 3,
+                count: 3,
+                offset: 0,
+                total_len: 4,
+            },
+            data: heapless::Vec::from_slice(b"data").unwrap(),
+        };
+        assert_eq!(b.receive(fragment).unwrap_err(), SwarmError::InvalidMessage);
+    }
+}
+
+#[cfg(test)]
+mod reassembly_tests {
+    use super::*;
+
+    #[test]
+    fn test_out_of_order_reassembly() {
+        let mut a = layer(1, 200);
+        let mut b = layer(2, 200);
+        let data = payload(3000);
+        a.send(DroneId::new(2), &data).unwrap();
+
+        let mut delivered = None;
+        for fragment in drain(&mut a).into_iter().rev() {
+            if let Some(message) = b.receive(fragment).unwrap() {
+                delivered = Some(message);
+            }
+        }
+
+        let (source, message) = delivered.unwrap();
+        assert_eq!(source, DroneId::new(1));
+        assert_eq!(&message[..], &data[..]);
+        assert_eq!(b.reassembling(), 0);
+    }
+
+    #[test]
+    fn test_misplaced_fragment_rejected() {
+        let mut a = layer(1, 256);
+        let mut b = layer(2, 256);
+        let data = payload(1000);
+        a.send(DroneId::new(2), &data).unwrap();
+        let fragments = drain(&mut a);
+
+        let forge = |i: usize, offset: u16, len: usize| {
+            let FragmentMessage::Fragment { mut header, data } = fragments[i].clone() else {
+                panic!("expected a fragment");
+            };
+            header.offset = offset;
+            FragmentMessage::Fragment {
+                header,
+                data: heapless::Vec::from_slice(&data[..len]).unwrap(),
+            }
+        };
+        // Overlaps fragment 0
+        assert_eq!(
+            b.receive(forge(1, 100, 224)).unwrap_err(),
+            SwarmError::InvalidMessage
+        );
+        // Last fragment must end the message
+        assert_eq!(
+            b.receive(forge(4, 800, 100)).unwrap_err(),
+            SwarmError::InvalidMessage
+        );
+
+        // Self-consistent, but not the fragment size already seen
+        b.receive(fragments[0].clone()).unwrap();
+        assert_eq!(
+            b.receive(forge(1, 200, 200)).unwrap_err(),
+            SwarmError::InvalidMessage
+        );
+
+        let mut delivered = None;
+        for fragment in fragments.into_iter().skip(1) {
+            delivered = b.receive(fragment).unwrap().or(delivered);
+        }
+        assert_eq!(&delivered.unwrap().1[..], &data[..]);
+    }
+
+    #[test]
+    fn test_ack_releases_sender() {
+        let mut a = layer(1, 1280);
+        let mut b = layer(2, 1280);
+        a.send(DroneId::new(2), &payload(4000)).unwrap();
+
+        for fragment in drain(&mut a) {
+            b.receive(fragment).unwrap();
+        }
+        for reply in drain(&mut b) {
+            assert!(matches!(reply, FragmentMessage::Ack { .. }));
+            a.receive(reply).unwrap();
+        }
+
+        assert_eq!(a.pending_messages(), 0);
+        assert_eq!(a.statistics().messages_acked, 1);
+    }
+
+    #[test]
+    fn test_duplicate_delivery_suppressed() {
+        let mut a = layer(1, 1280);
+        let mut b = layer(2, 1280);
+        a.send(DroneId::new(2), b"formation").unwrap();
+
+        let fragment = drain(&mut a).remove(0);
+        assert!(b.receive(fragment.clone()).unwrap().is_some());
+        assert!(b.receive(fragment).unwrap().is_none());
+
+        // The duplicate is answered with a second ACK
+        assert_eq!(drain(&mut b).len(), 2);
+        assert_eq!(b.statistics().messages_delivered, 1);
+    }
+
+    #[test]
+    fn test_serialized_log_batch() {
+        let entries = (0..32)
+            .map(|index| LogEntry {
+                term: 3,
+                index,
+                issuer: DroneId::new(1),
+                command: SwarmCommand::UpdateMission {
+                    params: heapless::Vec::from_slice(&[index as u8; 256]).unwrap(),
+                },
+            })
+            .collect();
+        let batch = ConsensusMessage::AppendEntries {
+            term: 3,
+            leader_id: DroneId::new(1),
+            prev_log_index: 0,
+            prev_log_term: 0,
+            entries,
+            leader_commit: 0,
+        };
+
+        let mut a = layer(1, MIN_MTU);
+        let mut b = layer(2, MIN_MTU);
+        a.send_serialized(DroneId::new(2), &batch).unwrap();
+
+        let mut delivered = None;
+        for fragment in drain(&mut a) {
+            delivered = delivered.or(b.receive(fragment).unwrap());
+        }
+
+        let (_, bytes) = delivered.unwrap();
+        let decoded: ConsensusMessage = postcard::from_bytes(&bytes).unwrap();
+        let ConsensusMessage::AppendEntries { entries, .. } = decoded else {
+            panic!("expected AppendEntries");
+        };
+        assert_eq!(entries.len(), 32);
+        assert_eq!(entries[31].index, 31);
+    }
+}
+
+#[cfg(test)]
+mod retransmission_tests {
+    use super::*;
+
+    #[test]
+    fn test_nack_resends_only_missing_fragments() {
+        set_virtual_time_us(Some(0));
+        let mut a = layer(1, 256);
+        let mut b = layer(2, 256);
+        let data = payload(2000);
+        a.send(DroneId::new(2), &data).unwrap();
+
+        // Fragments 2 and 5 are lost
+        for (i, fragment) in drain(&mut a).into_iter().enumerate() {
+            if i != 2 && i != 5 {
+                b.receive(fragment).unwrap();
+            }
+        }
+
+        // The last fragment arrived with gaps, so the NACK is immediate
+        let nack = drain(&mut b);
+        assert_eq!(nack.len(), 1);
+        let FragmentMessage::Nack { received, .. } = &nack[0] else {
+            panic!("expected a NACK");
+        };
+        assert_eq!(received.count(), 7);
+
+        a.receive(nack[0].clone()).unwrap();
+        let resent = drain(&mut a);
+        let indices: std::vec::Vec<u16> = resent
+            .iter()
+            .map(|fragment| match fragment {
+                FragmentMessage::Fragment { header, .. } => header.index,
+                _ => panic!("expected a fragment"),
+            })
+            .collect();
+        assert_eq!(indices, [2, 5]);
+
+        let mut delivered = None;
+        for fragment in resent {
+            delivered = delivered.or(b.receive(fragment).unwrap());
+        }
+        assert_eq!(&delivered.unwrap().1[..], &data[..]);
+        set_virtual_time_us(None);
+    }
+
+    #[test]
+    fn test_stalled_reassembly_nacks_then_times_out() {
+        set_virtual_time_us(Some(0));
+        let mut a = layer(1, 256);
+        let mut b = layer(2, 256);
+        a.send(DroneId::new(2), &payload(2000)).unwrap();
+
+        // Only the first fragment gets through
+        let first = drain(&mut a).remove(0);
+        b.receive(first).unwrap();
+        assert!(drain(&mut b).is_empty());
+
+        set_virtual_time_us(Some(250_000));
+        b.tick().unwrap();
+        assert!(matches!(drain(&mut b)[..], [FragmentMessage::Nack { .. }]));
+
+        set_virtual_time_us(Some(6_000_000));
+        b.tick().unwrap();
+        assert_eq!(b.reassembling(), 0);
+        assert_eq!(b.statistics().reassembly_timeouts, 1);
+        set_virtual_time_us(None);
+    }
+
+    #[test]
+    fn test_sender_probes_then_gives_up() {
+        set_virtual_time_us(Some(0));
+        let mut a = layer(1, 256);
+        a.send(DroneId::new(2), &payload(2000)).unwrap();
+        drain(&mut a);
+
+        // Nothing ever answers: probe with the last fragment each timeout
+        let mut now_us = 0;
+        for _ in 0..5 {
+            now_us += 1_000_000;
+            set_virtual_time_us(Some(now_us));
+            a.tick().unwrap();
+            let probe = drain(&mut a);
+            assert!(matches!(
+                probe[..],
+                [FragmentMessage::Fragment { header, .. }] if header.index == header.count - 1
+            ));
+        }
+
+        set_virtual_time_us(Some(now_us + 1_000_000));
+        a.tick().unwrap();
+        assert_eq!(a.pending_messages(), 0);
+        assert_eq!(a.statistics().messages_failed, 1);
+        set_virtual_time_us(None);
+    }
+}
@@ -41,6 +41,7 @@ mod gwo_variant_tests {
     }
 
     #[test]
+    #[allow(clippy::clone_on_copy)]
     fn test_variant_clone() {
         let variant = GWOVariant::Hybrid;
         let cloned = variant.clone();
@@ -0,0 +1,1187 @@
+//! Multi-node scenarios on the deterministic network simulator
+//!
+//! Tests the virtual channel (loss, latency, bandwidth, range, partitions,
+//! churn) and whole-swarm behaviour of the mesh and consensus layers
+
+use drone_swarm_system::config::RoutingProtocol;
+use drone_swarm_system::consensus::NodeState;
+use drone_swarm_system::esp32_mesh::{MeshNode, ProcessResult};
+use drone_swarm_system::mesh_protocol::{CommandAction, CommandTarget, MeshNodeId};
+use drone_swarm_system::netsim::*;
+use drone_swarm_system::types::*;
+
+fn at(x: f32, y: f32) -> Position {
+    Position { x, y, z: 10.0 }
+}
+
+/// Minimal node that records when frames arrive
+struct Probe {
+    arrivals: Vec<(NodeIndex, u64)>,
+}
+
+impl SimNode for Probe {
+    type Message = Vec<u8>;
+
+    fn tick(&mut self, _now_ms: u64, _outbox: &mut Outbox<Vec<u8>>) {}
+
+    fn receive(
+        &mut self,
+        from: NodeIndex,
+        _message: Vec<u8>,
+        _rssi: i8,
+        now_ms: u64,
+        _outbox: &mut Outbox<Vec<u8>>,
+    ) {
+        self.arrivals.push((from, now_ms));
+    }
+
+    fn message_size(message: &Vec<u8>) -> usize {
+        message.len()
+    }
+}
+
+fn probe_pair(link: LinkModel) -> NetworkSimulator<Probe> {
+    probe_pair_seeded(link, 0)
+}
+
+fn probe_pair_seeded(link: LinkModel, seed: u64) -> NetworkSimulator<Probe> {
+    let mut sim = NetworkSimulator::new(SimConfig {
+        seed,
+        default_link: link,
+        ..SimConfig::default()
+    });
+    sim.add_node(
+        Probe {
+            arrivals: Vec::new(),
+        },
+        at(0.0, 0.0),
+    );
+    sim.add_node(
+        Probe {
+            arrivals: Vec::new(),
+        },
+        at(10.0, 0.0),
+    );
+    sim
+}
+
+#[cfg(test)]
+mod channel_tests {
+    use super::*;
+
+    #[test]
+    fn test_latency_and_bandwidth() {
+        // 8 kbit/s = 1 byte per ms
+        let mut sim = probe_pair(LinkModel {
+            latency_ms: 20,
+            bandwidth_bps: 8_000,
+            ..LinkModel::ideal()
+        });
+
+        sim.send(0, Destination::Unicast(1), vec![0; 100]);
+        sim.send(0, Destination::Unicast(1), vec![0; 100]);
+        sim.run_for(500);
+
+        // Second frame queues behind the first on the link
+        assert_eq!(sim.node(1).arrivals, vec![(0, 120), (0, 220)]);
+    }
+
+    #[test]
+    fn test_range_limits_delivery() {
+        let mut sim = probe_pair(LinkModel {
+            range_m: 5.0,
+            ..LinkModel::ideal()
+        });
+
+        sim.send(0, Destination::Broadcast, vec![1]);
+        sim.run_for(10);
+
+        assert!(sim.node(1).arrivals.is_empty());
+        assert_eq!(sim.stats().dropped_range, 1);
+
+        // Per-link override takes precedence over the default model
+        sim.set_link(0, 1, LinkModel::ideal());
+        sim.send(1, Destination::Broadcast, vec![1]);
+        sim.run_for(10);
+        assert_eq!(sim.node(0).arrivals.len(), 1);
+    }
+
+    #[test]
+    fn test_partition_and_heal() {
+        let mut sim = probe_pair(LinkModel::ideal());
+
+        sim.partition(&[0]);
+        sim.send(0, Destination::Unicast(1), vec![1]);
+        sim.run_for(10);
+        assert!(sim.node(1).arrivals.is_empty());
+        assert_eq!(sim.stats().dropped_partition, 1);
+
+        sim.heal();
+        sim.send(0, Destination::Unicast(1), vec![1]);
+        sim.run_for(10);
+        assert_eq!(sim.node(1).arrivals.len(), 1);
+    }
+
+    #[test]
+    fn test_offline_node_loses_frames() {
+        let mut sim = probe_pair(LinkModel {
+            latency_ms: 10,
+            ..LinkModel::ideal()
+        });
+
+        sim.send(0, Destination::Unicast(1), vec![1]);
+        sim.set_online(1, false);
+        sim.run_for(50);
+
+        assert!(sim.node(1).arrivals.is_empty());
+        assert_eq!(sim.stats().dropped_offline, 1);
+    }
+
+    #[test]
+    fn test_lossy_runs_are_reproducible() {
+        let run = |seed: u64| {
+            let link = LinkModel {
+                loss_rate: 0.3,
+                jitter_ms: 5,
+                ..LinkModel::ideal()
+            };
+            let mut sim = probe_pair_seeded(link, seed);
+            for _ in 0..200 {
+                sim.send(0, Destination::Unicast(1), vec![0; 16]);
+            }
+            sim.run_for(100);
+            (*sim.stats(), sim.node(1).arrivals.clone())
+        };
+
+        let (stats_a, arrivals_a) = run(1234);
+        let (stats_b, arrivals_b) = run(1234);
+        let (_, arrivals_c) = run(4321);
+
+        assert_eq!(stats_a, stats_b);
+        assert_eq!(arrivals_a, arrivals_b);
+        assert!(stats_a.dropped_loss > 30 && stats_a.dropped_loss < 90);
+        assert_ne!(arrivals_a, arrivals_c);
+    }
+
+    #[test]
+    fn test_run_until_stops_early() {
+        let mut sim = probe_pair(LinkModel {
+            latency_ms: 30,
+            ..LinkModel::ideal()
+        });
+
+        sim.send(0, Destination::Unicast(1), vec![1]);
+        assert!(sim.run_until(1000, |sim| !sim.node(1).arrivals.is_empty()));
+        assert_eq!(sim.now_ms(), 30);
+    }
+}
+
+#[cfg(test)]
+mod mesh_network_scenarios {
+    use super::*;
+
+    fn line(count: u64, spacing: f32, range_m: f32) -> NetworkSimulator<SimMeshNetwork> {
+        let mut sim = NetworkSimulator::new(SimConfig {
+            seed: 7,
+            default_link: LinkModel {
+                range_m,
+                ..LinkModel::default()
+            },
+            ..SimConfig::default()
+        });
+        for i in 0..count {
+            sim.add_node_with(at(i as f32 * spacing, 0.0), |index| {
+                SimMeshNetwork::new(DroneId::new(i + 1), index)
+            });
+        }
+        sim
+    }
+
+    #[test]
+    fn test_neighbor_discovery_respects_range() {
+        let mut sim = line(3, 200.0, 250.0);
+        sim.run_for(3000);
+
+        assert_eq!(sim.node(0).network.neighbor_count(), 1);
+        assert_eq!(sim.node(1).network.neighbor_count(), 2);
+        assert_eq!(sim.node(2).network.neighbor_count(), 1);
+    }
+
+    #[test]
+    fn test_data_between_neighbors() {
+        let mut sim = line(2, 50.0, 300.0);
+        sim.run_for(100);
+
+        let payload = heapless::Vec::from_slice(b"survey-grid-7").unwrap();
+        sim.node_mut(0)
+            .network
+            .send_message(DroneId::new(2), payload)
+            .unwrap();
+
+        assert!(sim.run_until(1000, |sim| !sim.node(1).delivered.is_empty()));
+        let (source, payload) = &sim.node(1).delivered[0];
+        assert_eq!(*source, DroneId::new(1));
+        assert_eq!(&payload[..], b"survey-grid-7");
+    }
+
+    #[test]
+    fn test_multi_hop_route_discovery() {
+        let mut sim = line(4, 200.0, 250.0);
+        sim.run_for(1500);
+
+        let payload = heapless::Vec::from_slice(b"relay-me").unwrap();
+        sim.node_mut(0)
+            .network
+            .send_message(DroneId::new(4), payload)
+            .unwrap();
+
+        assert!(sim.run_until(2000, |sim| !sim.node(3).delivered.is_empty()));
+        let (source, payload) = &sim.node(3).delivered[0];
+        assert_eq!(*source, DroneId::new(1));
+        assert_eq!(&payload[..], b"relay-me");
+
+        let forward = sim.node(0).network.route(DroneId::new(4)).unwrap();
+        assert_eq!(forward.next_hop, DroneId::new(2));
+        assert_eq!(forward.hop_count, 3);
+        // Drone 3 already knew drone 4 and answered on its behalf
+        let reverse = sim.node(2).network.route(DroneId::new(1)).unwrap();
+        assert_eq!(reverse.next_hop, DroneId::new(2));
+        assert_eq!(reverse.hop_count, 2);
+    }
+
+    #[test]
+    fn test_log_batch_fragmented_across_relays() {
+        use drone_swarm_system::consensus::{ConsensusMessage, LogEntry, SwarmCommand};
+
+        let mut sim = line(3, 200.0, 250.0);
+        sim.run_for(1500);
+
+        let entries = (0..16)
+            .map(|index| LogEntry {
+                term: 2,
+                index,
+                issuer: DroneId::new(1),
+                command: SwarmCommand::UpdateMission {
+                    params: heapless::Vec::from_slice(&[index as u8; 256]).unwrap(),
+                },
+            })
+            .collect();
+        let batch = ConsensusMessage::AppendEntries {
+            term: 2,
+            leader_id: DroneId::new(1),
+            prev_log_index: 0,
+            prev_log_term: 0,
+            entries,
+            leader_commit: 0,
+        };
+        // No route to drone 3 yet: discovery runs while the sender resends
+        sim.node_mut(0)
+            .network
+            .send_serialized(DroneId::new(3), &batch)
+            .unwrap();
+
+        assert!(sim.run_until(6000, |sim| !sim.node(2).reassembled.is_empty()));
+        let (source, bytes) = &sim.node(2).reassembled[0];
+        assert_eq!(*source, DroneId::new(1));
+        let decoded: ConsensusMessage = postcard::from_bytes(bytes).unwrap();
+        let ConsensusMessage::AppendEntries { entries, .. } = decoded else {
+            panic!("expected AppendEntries");
+        };
+        assert_eq!(entries.len(), 16);
+
+        // The end-to-end ACK travels back through the relay
+        assert!(sim.run_until(2000, |sim| sim
+            .node(0)
+            .network
+            .fragmentation()
+            .pending_messages()
+            == 0));
+        assert_eq!(sim.node(2).reassembled.len(), 1);
+    }
+
+    #[test]
+    fn test_relay_failure_tears_down_routes() {
+        let mut sim = line(4, 200.0, 250.0);
+        sim.run_for(1500);
+        let payload = heapless::Vec::from_slice(b"relay-me").unwrap();
+        sim.node_mut(0)
+            .network
+            .send_message(DroneId::new(4), payload)
+            .unwrap();
+        assert!(sim.run_until(2000, |sim| !sim.node(3).delivered.is_empty()));
+
+        sim.set_online(2, false);
+        sim.run_for(3500);
+
+        assert_eq!(sim.node(1).network.neighbor_count(), 1);
+        assert!(sim.node(0).network.route(DroneId::new(4)).is_none());
+        assert!(sim.node(1).network.route(DroneId::new(4)).is_none());
+    }
+
+    #[test]
+    fn test_link_state_routes_converge() {
+        let mut sim = NetworkSimulator::new(SimConfig {
+            seed: 5,
+            default_link: LinkModel {
+                range_m: 250.0,
+                ..LinkModel::default()
+            },
+            ..SimConfig::default()
+        });
+        for i in 0..5u64 {
+            sim.add_node_with(at(i as f32 * 200.0, 0.0), |index| {
+                SimMeshNetwork::with_routing(DroneId::new(i + 1), index, RoutingProtocol::LinkState)
+            });
+        }
+        sim.run_for(8000);
+
+        let route = sim.node(0).network.route(DroneId::new(5)).unwrap();
+        assert_eq!(route.next_hop, DroneId::new(2));
+        assert_eq!(route.hop_count, 4);
+        // Each interior drone is the only way onward, so it is a relay
+        assert_eq!(sim.node(2).network.mprs().len(), 2);
+
+        let payload = heapless::Vec::from_slice(b"proactive").unwrap();
+        sim.node_mut(0)
+            .network
+            .send_message(DroneId::new(5), payload)
+            .unwrap();
+        assert!(sim.run_until(500, |sim| !sim.node(4).delivered.is_empty()));
+    }
+
+    fn line_with(count: u64, protocol: RoutingProtocol) -> NetworkSimulator<SimMeshNetwork> {
+        let mut sim = NetworkSimulator::new(SimConfig {
+            seed: 9,
+            default_link: LinkModel {
+                range_m: 250.0,
+                ..LinkModel::default()
+            },
+            ..SimConfig::default()
+        });
+        for i in 0..count {
+            sim.add_node_with(at(i as f32 * 200.0, 0.0), |index| {
+                SimMeshNetwork::with_routing(DroneId::new(i + 1), index, protocol)
+            });
+        }
+        sim
+    }
+
+    #[test]
+    fn test_flooding_reaches_far_end() {
+        let mut sim = line_with(4, RoutingProtocol::Flooding);
+        sim.run_for(100);
+
+        let payload = heapless::Vec::from_slice(b"flooded").unwrap();
+        sim.node_mut(0)
+            .network
+            .send_message(DroneId::new(4), payload)
+            .unwrap();
+
+        assert!(sim.run_until(500, |sim| !sim.node(3).delivered.is_empty()));
+        sim.run_for(500);
+        // Delivered once despite every relay rebroadcasting
+        assert_eq!(sim.node(3).delivered.len(), 1);
+    }
+
+    #[test]
+    fn test_gradient_convergecast_to_sink() {
+        let mut sim = line_with(4, RoutingProtocol::GradientBased);
+        sim.node_mut(3).network.set_sink(true);
+        sim.run_for(3000);
+
+        for (index, hops) in [(0, 3), (1, 2), (2, 1)] {
+            let route = sim.node(index).network.route(DroneId::new(4)).unwrap();
+            assert_eq!(route.hop_count, hops);
+        }
+
+        for index in 0..3 {
+            let payload = heapless::Vec::from_slice(b"reading").unwrap();
+            sim.node_mut(index)
+                .network
+                .send_message(DroneId::new(4), payload)
+                .unwrap();
+        }
+        assert!(sim.run_until(500, |sim| sim.node(3).delivered.len() == 3));
+    }
+
+    #[test]
+    fn test_geographic_greedy_delivery() {
+        let mut sim = line_with(5, RoutingProtocol::Geographic);
+        sim.run_for(3000);
+        assert!(sim.node(0).network.location(DroneId::new(5)).is_some());
+
+        let payload = heapless::Vec::from_slice(b"greedy").unwrap();
+        sim.node_mut(0)
+            .network
+            .send_message(DroneId::new(5), payload)
+            .unwrap();
+
+        assert!(sim.run_until(500, |sim| !sim.node(4).delivered.is_empty()));
+    }
+
+    #[test]
+    fn test_geographic_perimeter_routes_around_void() {
+        let mut sim = NetworkSimulator::new(SimConfig {
+            seed: 13,
+            default_link: LinkModel {
+                range_m: 250.0,
+                ..LinkModel::ideal()
+            },
+            ..SimConfig::default()
+        });
+        // Drone 2 is a local minimum: the only way on is north around the void
+        let layout = [
+            at(0.0, -50.0),   // 1: source
+            at(200.0, 0.0),   // 2: stuck, all neighbors farther from 6
+            at(150.0, 200.0), // 3
+            at(330.0, 330.0), // 4
+            at(500.0, 200.0), // 5
+            at(600.0, 0.0),   // 6: destination
+        ];
+        for (i, position) in layout.iter().enumerate() {
+            sim.add_node_with(*position, |index| {
+                SimMeshNetwork::with_routing(
+                    DroneId::new(i as u64 + 1),
+                    index,
+                    RoutingProtocol::Geographic,
+                )
+            });
+        }
+        sim.run_for(5000);
+
+        let payload = heapless::Vec::from_slice(b"around").unwrap();
+        sim.node_mut(0)
+            .network
+            .send_message(DroneId::new(6), payload)
+            .unwrap();
+
+        assert!(sim.run_until(500, |sim| !sim.node(5).delivered.is_empty()));
+        assert_eq!(sim.node(5).delivered[0].0, DroneId::new(1));
+    }
+
+    /// Send a message too large for one frame from the first to the last
+    /// drone of the line and wait for it to be reassembled
+    fn send_fragmented_across(sim: &mut NetworkSimulator<SimMeshNetwork>) {
+        let last = sim.node_count() - 1;
+        let message: Vec<u8> = (0..3000u32).map(|i| i as u8).collect();
+        sim.node_mut(0)
+            .network
+            .send_large(DroneId::new(last as u64 + 1), &message)
+            .unwrap();
+
+        assert!(sim.run_until(6000, |sim| !sim.node(last).reassembled.is_empty()));
+        assert_eq!(sim.node(last).reassembled[0], (DroneId::new(1), message));
+    }
+
+    /// Wait for the end-to-end ACK to clear the sender's pending message
+    fn fragments_acknowledged(sim: &mut NetworkSimulator<SimMeshNetwork>) -> bool {
+        sim.run_until(2000, |sim| {
+            sim.node(0).network.fragmentation().pending_messages() == 0
+        })
+    }
+
+    #[test]
+    fn test_fragments_routed_in_adaptive_mesh() {
+        let mut sim = line_with(4, RoutingProtocol::AdaptiveMesh);
+        sim.run_for(1500);
+        send_fragmented_across(&mut sim);
+        assert!(fragments_acknowledged(&mut sim));
+    }
+
+    #[test]
+    fn test_fragments_routed_in_link_state() {
+        let mut sim = line_with(4, RoutingProtocol::LinkState);
+        sim.run_for(8000);
+        send_fragmented_across(&mut sim);
+        assert!(fragments_acknowledged(&mut sim));
+    }
+
+    #[test]
+    fn test_fragments_flooded() {
+        let mut sim = line_with(4, RoutingProtocol::Flooding);
+        sim.run_for(100);
+        send_fragmented_across(&mut sim);
+        assert!(fragments_acknowledged(&mut sim));
+        sim.run_for(500);
+        // Rebroadcast copies are not reassembled twice
+        assert_eq!(sim.node(3).reassembled.len(), 1);
+    }
+
+    #[test]
+    fn test_fragments_follow_gradient_to_sink() {
+        let mut sim = line_with(4, RoutingProtocol::GradientBased);
+        sim.node_mut(3).network.set_sink(true);
+        sim.run_for(3000);
+        // Convergecast only: the sink has no gradient back for the ACK
+        send_fragmented_across(&mut sim);
+    }
+
+    #[test]
+    fn test_fragments_forwarded_geographically() {
+        let mut sim = line_with(4, RoutingProtocol::Geographic);
+        sim.run_for(3000);
+        send_fragmented_across(&mut sim);
+        assert!(fragments_acknowledged(&mut sim));
+    }
+
+    #[test]
+    fn test_moving_out_of_range_stops_hellos() {
+        let mut sim = line(2, 50.0, 300.0);
+        sim.run_for(1500);
+        let before = sim.stats().frames_delivered;
+
+        sim.set_position(1, at(5000.0, 0.0));
+        sim.run_for(3000);
+
+        assert_eq!(sim.stats().frames_delivered, before);
+        assert!(sim.stats().dropped_range > 0);
+    }
+
+    #[test]
+    fn test_stable_relay_preferred_over_lossy_link() {
+        let mut sim = NetworkSimulator::new(SimConfig {
+            seed: 11,
+            default_link: LinkModel {
+                range_m: 250.0,
+                ..LinkModel::default()
+            },
+            ..SimConfig::default()
+        });
+        for (i, position) in [at(0.0, 0.0), at(100.0, 0.0), at(200.0, 0.0)]
+            .into_iter()
+            .enumerate()
+        {
+            sim.add_node_with(position, |index| {
+                SimMeshNetwork::with_routing(
+                    DroneId::new(i as u64 + 1),
+                    index,
+                    RoutingProtocol::LinkState,
+                )
+            });
+        }
+        // The direct link is in range but drops most frames
+        sim.set_link(
+            0,
+            2,
+            LinkModel {
+                loss_rate: 0.8,
+                range_m: 250.0,
+                ..LinkModel::default()
+            },
+        );
+        sim.run_for(8000);
+
+        // Once the relay has proven itself the route stays on it
+        for _ in 0..7 {
+            let route = sim.node(0).network.route(DroneId::new(3)).unwrap();
+            assert_eq!(route.next_hop, DroneId::new(2));
+            assert_eq!(route.hop_count, 2);
+            sim.run_for(1000);
+        }
+    }
+}
+
+#[cfg(test)]
+mod mesh_node_scenarios {
+    use super::*;
+
+    #[test]
+    fn test_heartbeats_build_neighbor_tables() {
+        let mut sim = NetworkSimulator::new(SimConfig {
+            seed: 3,
+            ..SimConfig::default()
+        });
+        for i in 0..4u8 {
+            sim.add_node(
+                SimMeshNode::new(MeshNode::new(MeshNodeId::new(i + 1))),
+                at(i as f32 * 20.0, 0.0),
+            );
+        }
+
+        sim.run_for(3000);
+
+        for i in 0..4 {
+            assert_eq!(sim.node(i).node.neighbor_count(), 3);
+        }
+        // Positions were pushed into the hosted nodes
+        assert_eq!(sim.node(2).node.position(), [40.0, 0.0, 10.0]);
+    }
+
+    #[test]
+    fn test_broadcast_command_reaches_swarm() {
+        let mut sim = NetworkSimulator::new(SimConfig {
+            seed: 11,
+            default_link: LinkModel::ideal(),
+            ..SimConfig::default()
+        });
+        for i in 0..3u8 {
+            sim.add_node(
+                SimMeshNode::new(MeshNode::new(MeshNodeId::new(i + 1))),
+                at(i as f32 * 20.0, 0.0),
+            );
+        }
+
+        sim.node_mut(0)
+            .node
+            .send_command(CommandTarget::Broadcast, CommandAction::ReturnToLaunch, 0)
+            .unwrap();
+        sim.run_for(100);
+
+        for i in 1..3 {
+            assert!(sim.node(i).events.iter().any(|event| matches!(
+                event,
+                ProcessResult::Command(CommandAction::ReturnToLaunch)
+            )));
+        }
+    }
+}
+
+#[cfg(test)]
+mod channel_scenarios {
+    use super::*;
+    use drone_swarm_system::esp32_mesh::MeshConfig;
+    use drone_swarm_system::mesh_channel::{channel_bit, ChannelConfig};
+
+    fn swarm(seed: u64, channels: ChannelConfig) -> NetworkSimulator<SimMeshNode> {
+        let mut sim = NetworkSimulator::new(SimConfig {
+            seed,
+            ..SimConfig::default()
+        });
+        for i in 0..3u8 {
+            let config = MeshConfig {
+                node_id: MeshNodeId::new(i + 1),
+                channels: channels.clone(),
+                ..MeshConfig::default()
+            };
+            sim.add_node(
+                SimMeshNode::new(MeshNode::with_config(config)),
+                at(i as f32 * 20.0, 0.0),
+            );
+        }
+        sim
+    }
+
+    #[test]
+    fn test_jammed_home_channel_migrates_whole_mesh() {
+        let mut sim = swarm(
+            5,
+            ChannelConfig {
+                adaptive: true,
+                ..ChannelConfig::default()
+            },
+        );
+        sim.run_for(3000);
+        assert_eq!(sim.node(0).node.current_channel(sim.now_ms()), 6);
+
+        // A jammer next to drone 1 wipes out most of what it hears on channel 6
+        sim.node_mut(0).jamming.insert(6, 0.7);
+        sim.run_for(20_000);
+
+        let now = sim.now_ms();
+        let channel = sim.node(0).node.current_channel(now);
+        assert_ne!(channel, 6);
+        for i in 0..3 {
+            let node = &sim.node(i).node;
+            assert_eq!(node.current_channel(now), channel);
+            assert_eq!(node.channels().statistics().switches, 1);
+            assert_eq!(node.neighbor_count(), 2);
+        }
+    }
+
+    #[test]
+    fn test_hopping_mesh_drops_jammed_channel() {
+        let mut sim = swarm(
+            9,
+            ChannelConfig {
+                hopping: true,
+                hop_secret: [0x5A; 32],
+                adaptive: true,
+                ..ChannelConfig::default()
+            },
+        );
+        for i in 0..3 {
+            sim.node_mut(i).jamming.insert(3, 1.0);
+        }
+
+        sim.run_for(5000);
+        // Hopping rides out the jammed channel
+        for i in 0..3 {
+            assert_eq!(sim.node(i).node.neighbor_count(), 2);
+        }
+
+        sim.run_for(25_000);
+        let plan = *sim.node(0).node.channels().plan();
+        assert_eq!(plan.hop_mask & channel_bit(3), 0);
+        for i in 0..3 {
+            let node = &sim.node(i).node;
+            assert_eq!(*node.channels().plan(), plan);
+            assert_eq!(node.neighbor_count(), 2);
+        }
+    }
+}
+
+#[cfg(test)]
+mod reliable_command_scenarios {
+    use super::*;
+    use drone_swarm_system::esp32_mesh::DeliveryStatus;
+    use drone_swarm_system::mesh_protocol::AckStatus;
+
+    #[test]
+    fn test_rtl_outcome_per_drone_over_lossy_link() {
+        let mut sim = NetworkSimulator::new(SimConfig {
+            seed: 17,
+            default_link: LinkModel {
+                loss_rate: 0.3,
+                ..LinkModel::default()
+            },
+            ..SimConfig::default()
+        });
+        for i in 0..5u8 {
+            sim.add_node(
+                SimMeshNode::new(MeshNode::new(MeshNodeId::new(i))),
+                at(i as f32 * 20.0, 0.0),
+            );
+        }
+        // Drone 4 has crashed
+        sim.set_online(4, false);
+
+        let members: Vec<MeshNodeId> = (1..5).map(MeshNodeId::new).collect();
+        let now = sim.now_ms();
+        sim.node_mut(0)
+            .node
+            .send_command_to(
+                CommandTarget::Broadcast,
+                CommandAction::ReturnToLaunch,
+                &members,
+                now,
+            )
+            .unwrap();
+
+        let mut report = None;
+        sim.run_until(10_000, |sim| sim.node(0).node.pending_command_count() == 0);
+        while let Some(delivery) = sim.node_mut(0).node.poll_delivery_report() {
+            report = Some(delivery);
+        }
+        let report = report.unwrap();
+
+        for i in 1..4 {
+            assert_eq!(
+                report.status(MeshNodeId::new(i)),
+                Some(DeliveryStatus::Acknowledged(AckStatus::Success))
+            );
+            // Executed exactly once despite retransmissions and flooding
+            let executed = sim
+                .node(i as usize)
+                .events
+                .iter()
+                .filter(|event| {
+                    matches!(event, ProcessResult::Command(CommandAction::ReturnToLaunch))
+                })
+                .count();
+            assert_eq!(executed, 1);
+        }
+        assert_eq!(
+            report.status(MeshNodeId::new(4)),
+            Some(DeliveryStatus::TimedOut)
+        );
+    }
+}
+
+#[cfg(test)]
+mod dtn_scenarios {
+    use super::*;
+    use drone_swarm_system::dtn::*;
+
+    /// Two sub-swarms far apart with a ferry drone parked next to the first
+    fn partitioned(strategy: DtnStrategy) -> NetworkSimulator<SimDtnNode> {
+        let mut sim = NetworkSimulator::new(SimConfig {
+            seed: 21,
+            default_link: LinkModel {
+                range_m: 100.0,
+                ..LinkModel::default()
+            },
+            ..SimConfig::default()
+        });
+        let layout = [
+            at(0.0, 0.0),    // 1: west sub-swarm, source
+            at(50.0, 0.0),   // 2: west sub-swarm
+            at(2000.0, 0.0), // 3: east sub-swarm
+            at(2050.0, 0.0), // 4: east sub-swarm, destination
+            at(60.0, 40.0),  // 5: ferry
+        ];
+        for (i, position) in layout.iter().enumerate() {
+            let config = DtnConfig {
+                strategy,
+                ..DtnConfig::default()
+            };
+            sim.add_node(
+                SimDtnNode::new(DtnNode::new(DroneId::new(i as u64 + 1), config)),
+                *position,
+            );
+        }
+        sim
+    }
+
+    fn ferry_across(sim: &mut NetworkSimulator<SimDtnNode>) {
+        sim.node_mut(0)
+            .dtn
+            .send(DroneId::new(4), b"map-tile-12", BundlePriority::Normal)
+            .unwrap();
+        sim.run_for(3000);
+        assert!(sim.node(3).delivered.is_empty());
+
+        sim.set_position(4, at(2030.0, 40.0));
+        assert!(sim.run_until(5000, |sim| !sim.node(3).delivered.is_empty()));
+        assert_eq!(&sim.node(3).delivered[0].payload[..], b"map-tile-12");
+    }
+
+    #[test]
+    fn test_spray_and_wait_ferries_across_partition() {
+        let mut sim = partitioned(DtnStrategy::SprayAndWait { copies: 4 });
+        ferry_across(&mut sim);
+    }
+
+    #[test]
+    fn test_prophet_ferries_across_partition() {
+        let mut sim = partitioned(DtnStrategy::Prophet);
+        // The ferry has shuttled before, so it is known to meet drone 4
+        sim.set_position(4, at(2030.0, 40.0));
+        sim.run_for(3000);
+        sim.set_position(4, at(60.0, 40.0));
+        sim.run_for(3000);
+
+        ferry_across(&mut sim);
+    }
+}
+
+#[cfg(test)]
+mod fragmentation_scenarios {
+    use super::*;
+    use drone_swarm_system::federated::{FederatedMessage, ModelUpdate, MAX_MODEL_PARAMS};
+    use drone_swarm_system::fragmentation::*;
+
+    /// LoRa-class link: small frames, low bandwidth, heavy loss
+    fn lora_pair(seed: u64) -> NetworkSimulator<SimFragmentNode> {
+        let mut sim = NetworkSimulator::new(SimConfig {
+            seed,
+            default_link: LinkModel {
+                loss_rate: 0.2,
+                latency_ms: 40,
+                jitter_ms: 10,
+                bandwidth_bps: 50_000,
+                range_m: 2000.0,
+            },
+            ..SimConfig::default()
+        });
+        for i in 0..2 {
+            let config = FragmentConfig {
+                mtu: MIN_MTU,
+                ..FragmentConfig::default()
+            };
+            let layer = FragmentationLayer::new(DroneId::new(i + 1), config);
+            sim.add_node(SimFragmentNode::new(layer), at(i as f32 * 500.0, 0.0));
+        }
+        sim.node_mut(0).add_peer(DroneId::new(2), 1);
+        sim.node_mut(1).add_peer(DroneId::new(1), 0);
+        sim
+    }
+
+    #[test]
+    fn test_model_update_crosses_lossy_small_mtu_link() {
+        let mut sim = lora_pair(31);
+        let update = FederatedMessage::SubmitUpdate(ModelUpdate {
+            drone_id: DroneId::new(1),
+            round: 4,
+            parameters: (0..MAX_MODEL_PARAMS).map(|i| i as f32 * 0.5).collect(),
+            sample_count: 128,
+            loss: 0.25,
+            signature: [7; 64],
+        });
+        sim.node_mut(0)
+            .layer
+            .send_serialized(DroneId::new(2), &update)
+            .unwrap();
+
+        assert!(sim.run_until(30_000, |sim| !sim.node(1).delivered.is_empty()));
+
+        let (source, bytes) = &sim.node(1).delivered[0];
+        assert_eq!(*source, DroneId::new(1));
+        let decoded: FederatedMessage = postcard::from_bytes(bytes).unwrap();
+        let FederatedMessage::SubmitUpdate(received) = decoded else {
+            panic!("expected a model update");
+        };
+        assert_eq!(received.parameters.len(), MAX_MODEL_PARAMS);
+        assert_eq!(received.parameters[999], 499.5);
+
+        // Every frame respected the MTU and lost fragments were repaired
+        assert!(sim.node(0).largest_frame <= MIN_MTU);
+        assert!(sim.node(0).layer.statistics().fragments_retransmitted > 0);
+        assert!(sim.run_until(5000, |sim| sim.node(0).layer.pending_messages() == 0));
+    }
+
+    #[test]
+    fn test_unreachable_destination_gives_up() {
+        let mut sim = lora_pair(32);
+        sim.partition(&[0]);
+        sim.node_mut(0)
+            .layer
+            .send(DroneId::new(2), &[0x5A; 2000])
+            .unwrap();
+
+        sim.run_for(10_000);
+
+        let stats = sim.node(0).layer.statistics();
+        assert_eq!(stats.messages_failed, 1);
+        assert_eq!(sim.node(0).layer.pending_messages(), 0);
+    }
+}
+
+#[cfg(test)]
+mod consensus_scenarios {
+    use super::*;
+    use drone_swarm_system::crypto::{CryptoContext, RekeyPolicy};
+    use drone_swarm_system::key_rotation::KeyRotation;
+
+    fn cluster(size: u64, seed: u64) -> NetworkSimulator<SimConsensus> {
+        let members: Vec<DroneId> = (1..=size).map(DroneId::new).collect();
+        let mut sim = NetworkSimulator::new(SimConfig {
+            seed,
+            ..SimConfig::default()
+        });
+        for (i, id) in members.iter().enumerate() {
+            // Staggered timeouts stand in for Raft's randomized election timer
+            let timeout = 150 + 60 * i as u32;
+            sim.add_node(
+                SimConsensus::new(*id, timeout, &members),
+                at(i as f32 * 10.0, 0.0),
+            );
+        }
+        sim
+    }
+
+    fn leaders(sim: &NetworkSimulator<SimConsensus>) -> Vec<DroneId> {
+        (0..sim.node_count())
+            .filter(|i| sim.is_online(*i))
+            .filter(|i| sim.node(*i).engine.state() == NodeState::Leader)
+            .map(|i| sim.node(i).engine.node_id())
+            .collect()
+    }
+
+    #[test]
+    fn test_leader_election() {
+        let mut sim = cluster(5, 1);
+        sim.run_for(1000);
+
+        assert_eq!(leaders(&sim), vec![DroneId::new(1)]);
+        for i in 1..5 {
+            assert_eq!(sim.node(i).engine.leader(), Some(DroneId::new(1)));
+        }
+    }
+
+    #[test]
+    fn test_leader_failover() {
+        let mut sim = cluster(5, 2);
+        sim.run_for(1000);
+        let first_term = sim.node(0).engine.current_term();
+
+        sim.set_online(0, false);
+        sim.run_for(2000);
+
+        let leader = leaders(&sim);
+        assert_eq!(leader.len(), 1);
+        assert_ne!(leader[0], DroneId::new(1));
+        for i in 1..5 {
+            assert_eq!(sim.node(i).engine.leader(), Some(leader[0]));
+            assert!(sim.node(i).engine.current_term() > first_term);
+        }
+    }
+
+    #[test]
+    fn test_minority_partition_cannot_elect() {
+        let mut sim = cluster(5, 3);
+        sim.run_for(1000);
+
+        // Leader (node 0) is cut off with one follower
+        sim.partition(&[0, 1]);
+        sim.run_for(2000);
+
+        let majority_leaders: Vec<DroneId> = leaders(&sim)
+            .into_iter()
+            .filter(|id| id.as_u64() > 2)
+            .collect();
+        assert_eq!(majority_leaders.len(), 1);
+        assert_ne!(sim.node(1).engine.state(), NodeState::Leader);
+    }
+
+    #[test]
+    fn test_network_key_rotation_through_consensus() {
+        let mut sim = cluster(3, 4);
+        sim.run_for(1000);
+        let leader = (0..3)
+            .find(|i| sim.node(*i).engine.state() == NodeState::Leader)
+            .unwrap();
+
+        let mut cryptos: Vec<CryptoContext> = (0..3u8)
+            .map(|i| CryptoContext::with_keys([7; 32], [i + 1; 32]))
+            .collect();
+        let mut rotations: Vec<KeyRotation> = (0..3).map(|_| KeyRotation::new()).collect();
+        for crypto in cryptos.iter_mut() {
+            crypto.set_rekey_policy(RekeyPolicy {
+                max_encryptions: 3,
+                ..RekeyPolicy::default()
+            });
+        }
+        // The leader uses up its key
+        for _ in 0..3 {
+            cryptos[leader].encrypt_and_sign(b"telemetry", b"").unwrap();
+        }
+        let in_flight = cryptos[leader].encrypt_and_sign(b"late", b"").unwrap();
+
+        for _ in 0..50 {
+            let now = sim.now_ms();
+            for i in 0..3 {
+                let engine = &mut sim.node_mut(i).engine;
+                rotations[i].tick(&mut cryptos[i], engine, now).unwrap();
+                for entry in engine.take_committed::<8>() {
+                    rotations[i]
+                        .apply(&entry.command, &mut cryptos[i], now)
+                        .unwrap();
+                }
+            }
+            sim.run_for(20);
+        }
+
+        assert_eq!(rotations[leader].statistics().proposed, 1);
+        for i in 0..3 {
+            assert_eq!(cryptos[i].epoch(), 1);
+            assert_eq!(rotations[i].statistics().applied, 1);
+        }
+        let follower = (leader + 1) % 3;
+        let sealed = cryptos[leader].encrypt_and_sign(b"new key", b"").unwrap();
+        let sender = *cryptos[leader].public_key();
+        assert!(cryptos[follower]
+            .verify_and_decrypt(&sealed, b"", &sender)
+            .is_ok());
+        // Sealed just before the rotation, still inside the acceptance window
+        assert!(cryptos[follower]
+            .verify_and_decrypt(&in_flight, b"", &sender)
+            .is_ok());
+    }
+
+    #[test]
+    fn test_scenarios_are_reproducible() {
+        let run = || {
+            let mut sim = cluster(5, 99);
+            sim.set_link(
+                0,
+                1,
+                LinkModel {
+                    loss_rate: 0.5,
+                    ..LinkModel::default()
+                },
+            );
+            sim.run_for(800);
+            sim.set_online(0, false);
+            sim.run_for(1500);
+            (*sim.stats(), leaders(&sim))
+        };
+
+        assert_eq!(run(), run());
+    }
+}
+
+#[cfg(test)]
+mod time_sync_scenarios {
+    use super::*;
+    use drone_swarm_system::time_abstraction::get_time_us;
+    use drone_swarm_system::time_sync::*;
+
+    /// Five drones in a line, 200 m apart, so drone 5 is four hops from drone 1
+    fn chain() -> NetworkSimulator<SimTimeSyncNode> {
+        let mut sim = NetworkSimulator::new(SimConfig {
+            seed: 13,
+            default_link: LinkModel {
+                loss_rate: 0.05,
+                latency_ms: 5,
+                jitter_ms: 0,
+                bandwidth_bps: 0,
+                range_m: 300.0,
+            },
+            ..SimConfig::default()
+        });
+        let clocks = [
+            (0, 0.0),
+            (3_000_000, 40.0),
+            (1_500_000, -35.0),
+            (12_345_678, 80.0),
+            (700_000, -60.0),
+        ];
+        for (i, (offset, skew)) in clocks.into_iter().enumerate() {
+            let config = TimeSyncConfig {
+                delay_compensation_us: 5_000,
+                ..TimeSyncConfig::default()
+            };
+            let sync = TimeSyncNode::new(DroneId::new(i as u64 + 1), config);
+            sim.add_node(
+                SimTimeSyncNode::new(sync, offset, skew),
+                at(i as f32 * 200.0, 0.0),
+            );
+        }
+        sim
+    }
+
+    /// Largest disagreement with `reference` across online drones (us)
+    fn worst_error(sim: &NetworkSimulator<SimTimeSyncNode>, reference: usize) -> u64 {
+        let now_us = get_time_us();
+        let expected = sim.node(reference).swarm_time_us(now_us);
+        (0..sim.node_count())
+            .filter(|&i| sim.is_online(i))
+            .map(|i| sim.node(i).swarm_time_us(now_us).abs_diff(expected))
+            .max()
+            .unwrap()
+    }
+
+    #[test]
+    fn test_multi_hop_chain_synchronizes() {
+        let mut sim = chain();
+        assert!(sim.run_until(40_000, |sim| {
+            (0..5).all(|i| sim.node(i).sync.status().synchronized)
+        }));
+        sim.run_for(10_000);
+
+        for i in 0..5 {
+            let status = sim.node(i).sync.status();
+            assert_eq!(status.root, DroneId::new(1));
+            assert_eq!(status.hops, i as u8);
+        }
+        let error = worst_error(&sim, 0);
+        assert!(error < 500, "worst error {error} us");
+
+        // The reported accuracy grows with distance from the root
+        let far = sim.node(4).sync.status().accuracy_us;
+        assert!(far >= sim.node(1).sync.status().accuracy_us);
+    }
+
+    #[test]
+    fn test_root_failure_elects_next_drone() {
+        let mut sim = chain();
+        sim.run_for(40_000);
+        let before_us = get_time_us();
+        let swarm_before = sim.node(2).swarm_time_us(before_us);
+
+        sim.set_online(0, false);
+        assert!(sim.run_until(30_000, |sim| {
+            (1..5).all(|i| {
+                let status = sim.node(i).sync.status();
+                status.root == DroneId::new(2) && status.synchronized
+            })
+        }));
+        sim.run_for(5_000);
+
+        assert!(worst_error(&sim, 1) < 500);
+        // Swarm time carried on across the takeover
+        let elapsed = get_time_us() - before_us;
+        let swarm_elapsed = sim.node(2).swarm_time_us(get_time_us()) - swarm_before;
+        assert!(swarm_elapsed.abs_diff(elapsed) < 5_000);
+    }
+}
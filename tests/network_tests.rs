@@ -22,6 +22,11 @@ mod neighbor_tests {
             link_quality: 0.8,
             last_seen: 1000,
             rtt_ms: 50,
+            link: LinkEstimator {
+                forward_ratio: 0.8,
+                reverse_ratio: 1.0,
+                ..LinkEstimator::default()
+            },
         }
     }
 
@@ -79,7 +84,7 @@ mod neighbor_tests {
     #[test]
     fn test_neighbor_update_quality_multiple_successes() {
         let mut neighbor = create_test_neighbor();
-        neighbor.link_quality = 0.5;
+        neighbor.link.forward_ratio = 0.5;
 
         for _ in 0..10 {
             neighbor.update_quality(true);
@@ -92,7 +97,7 @@ mod neighbor_tests {
     #[test]
     fn test_neighbor_update_quality_multiple_failures() {
         let mut neighbor = create_test_neighbor();
-        neighbor.link_quality = 0.5;
+        neighbor.link.forward_ratio = 0.5;
 
         for _ in 0..10 {
             neighbor.update_quality(false);
@@ -105,7 +110,7 @@ mod neighbor_tests {
     #[test]
     fn test_neighbor_update_quality_alternating() {
         let mut neighbor = create_test_neighbor();
-        neighbor.link_quality = 0.5;
+        neighbor.link.forward_ratio = 0.5;
 
         for i in 0..20 {
             neighbor.update_quality(i % 2 == 0);
@@ -152,12 +157,21 @@ mod network_message_tests {
         let msg = NetworkMessage::Heartbeat {
             sender: DroneId::new(2),
             timestamp: 1234567890,
+            sequence: 3,
+            reception: Vec::new(),
         };
 
         match msg {
-            NetworkMessage::Heartbeat { sender, timestamp } => {
+            NetworkMessage::Heartbeat {
+                sender,
+                timestamp,
+                sequence,
+                reception,
+            } => {
                 assert_eq!(sender, DroneId::new(2));
                 assert_eq!(timestamp, 1234567890);
+                assert_eq!(sequence, 3);
+                assert!(reception.is_empty());
             }
             _ => panic!("Wrong message type"),
         }
@@ -199,6 +213,8 @@ mod network_message_tests {
             source: DroneId::new(1),
             destination: DroneId::new(3),
             sequence: 100,
+            hop_count: 1,
+            metric: 1.0,
         };
 
         match msg {
@@ -206,10 +222,13 @@ mod network_message_tests {
                 source,
                 destination,
                 sequence,
+                hop_count,
+                ..
             } => {
                 assert_eq!(source, DroneId::new(1));
                 assert_eq!(destination, DroneId::new(3));
                 assert_eq!(sequence, 100);
+                assert_eq!(hop_count, 1);
             }
             _ => panic!("Wrong message type"),
         }
@@ -222,6 +241,8 @@ mod network_message_tests {
             destination: DroneId::new(3),
             next_hop: DroneId::new(2),
             hop_count: 2,
+            sequence: 7,
+            metric: 2.0,
         };
 
         match msg {
@@ -230,11 +251,14 @@ mod network_message_tests {
                 destination,
                 next_hop,
                 hop_count,
+                sequence,
+                ..
             } => {
                 assert_eq!(source, DroneId::new(1));
                 assert_eq!(destination, DroneId::new(3));
                 assert_eq!(next_hop, DroneId::new(2));
                 assert_eq!(hop_count, 2);
+                assert_eq!(sequence, 7);
             }
             _ => panic!("Wrong message type"),
         }
@@ -250,6 +274,8 @@ mod network_message_tests {
             sender: DroneId::new(1),
             neighbors: neighbors.clone(),
             sequence: 50,
+            mprs: Vec::new(),
+            hop_count: 0,
         };
 
         match msg {
@@ -257,10 +283,14 @@ mod network_message_tests {
                 sender,
                 neighbors: n,
                 sequence,
+                mprs,
+                hop_count,
             } => {
                 assert_eq!(sender, DroneId::new(1));
                 assert_eq!(n.len(), 2);
                 assert_eq!(sequence, 50);
+                assert!(mprs.is_empty());
+                assert_eq!(hop_count, 0);
             }
             _ => panic!("Wrong message type"),
         }
@@ -271,6 +301,8 @@ mod network_message_tests {
         let msg1 = NetworkMessage::Heartbeat {
             sender: DroneId::new(5),
             timestamp: 999,
+            sequence: 1,
+            reception: Vec::new(),
         };
 
         let msg2 = msg1.clone();
@@ -280,10 +312,12 @@ mod network_message_tests {
                 NetworkMessage::Heartbeat {
                     sender: s1,
                     timestamp: t1,
+                    ..
                 },
                 NetworkMessage::Heartbeat {
                     sender: s2,
                     timestamp: t2,
+                    ..
                 },
             ) => {
                 assert_eq!(s1, s2);
@@ -317,7 +351,7 @@ mod network_stats_tests {
             avg_rtt_ms: 50,
         };
 
-        let stats2 = stats1.clone();
+        let stats2 = stats1;
 
         assert_eq!(stats1.messages_sent, stats2.messages_sent);
         assert_eq!(stats1.messages_received, stats2.messages_received);
@@ -414,6 +448,8 @@ mod mesh_network_tests {
         let heartbeat = NetworkMessage::Heartbeat {
             sender: DroneId::new(2),
             timestamp: 1000,
+            sequence: 1,
+            reception: Vec::new(),
         };
 
         let result = network.process_message(heartbeat, addr);
@@ -449,6 +485,7 @@ mod mesh_network_tests {
     }
 
     #[test]
+    #[cfg(not(feature = "hardware"))]
     fn test_mesh_network_send_message_no_route() {
         let mut network = MeshNetwork::new(DroneId::new(1));
 
@@ -474,13 +511,13 @@ mod mesh_network_tests {
 
         let result = network.broadcast_hello(position);
 
-        // In simulation mode, this should succeed
+        // Without a radio attached, frames are discarded but still counted
         assert!(result.is_ok());
+        assert_eq!(network.statistics().messages_sent, 1);
     }
 
     #[test]
     #[cfg(feature = "hardware")]
-    #[should_panic(expected = "Hardware broadcast not yet implemented")]
     fn test_mesh_network_broadcast_hello_hardware() {
         let mut network = MeshNetwork::new(DroneId::new(1));
         let position = Position {
@@ -489,7 +526,10 @@ mod mesh_network_tests {
             z: 15.0,
         };
 
-        let _ = network.broadcast_hello(position);
+        // Hardware builds refuse to discard frames without a radio
+        let result = network.broadcast_hello(position);
+        assert_eq!(result.unwrap_err(), SwarmError::HardwareFault);
+        assert_eq!(network.statistics().messages_dropped, 1);
     }
 
     #[test]
@@ -499,17 +539,18 @@ mod mesh_network_tests {
 
         let result = network.send_heartbeat();
 
-        // In simulation mode, this should succeed
+        // Without a radio attached, frames are discarded but still counted
         assert!(result.is_ok());
+        assert_eq!(network.statistics().messages_sent, 1);
     }
 
     #[test]
     #[cfg(feature = "hardware")]
-    #[should_panic(expected = "Hardware heartbeat transmission not yet implemented")]
     fn test_mesh_network_send_heartbeat_hardware() {
         let mut network = MeshNetwork::new(DroneId::new(1));
 
-        let _ = network.send_heartbeat();
+        let result = network.send_heartbeat();
+        assert_eq!(result.unwrap_err(), SwarmError::HardwareFault);
     }
 
     #[test]
@@ -569,6 +610,942 @@ mod mesh_network_tests {
     }
 }
 
+#[cfg(test)]
+mod transport_tests {
+    use super::*;
+    use drone_swarm_system::transport::*;
+
+    fn origin() -> Position {
+        Position {
+            x: 0.0,
+            y: 0.0,
+            z: 10.0,
+        }
+    }
+
+    fn payload(bytes: &[u8]) -> Vec<u8, 1024> {
+        Vec::from_slice(bytes).unwrap()
+    }
+
+    fn drain<T: Transport>(network: &mut MeshNetwork<T>) {
+        while network.poll().unwrap().is_some() {}
+    }
+
+    #[test]
+    fn test_loopback_hello_discovers_neighbors() {
+        let hub = LoopbackHub::new();
+        let mut a = MeshNetwork::with_transport(
+            DroneId::new(1),
+            hub.connect(NetworkAddress::new([0; 16], 1)),
+        );
+        let mut b = MeshNetwork::with_transport(
+            DroneId::new(2),
+            hub.connect(NetworkAddress::new([0; 16], 2)),
+        );
+
+        a.broadcast_hello(origin()).unwrap();
+        b.broadcast_hello(origin()).unwrap();
+        drain(&mut a);
+        drain(&mut b);
+
+        assert_eq!(a.neighbor_count(), 1);
+        assert_eq!(b.neighbor_count(), 1);
+        assert_eq!(
+            b.neighbors().next().unwrap().address,
+            NetworkAddress::new([0; 16], 1)
+        );
+    }
+
+    #[test]
+    fn test_loopback_data_delivery() {
+        let hub = LoopbackHub::new();
+        let mut a = MeshNetwork::with_transport(
+            DroneId::new(1),
+            hub.connect(NetworkAddress::new([0; 16], 1)),
+        );
+        let mut b = MeshNetwork::with_transport(
+            DroneId::new(2),
+            hub.connect(NetworkAddress::new([0; 16], 2)),
+        );
+
+        b.broadcast_hello(origin()).unwrap();
+        drain(&mut a);
+
+        a.send_message(DroneId::new(2), payload(b"formation"))
+            .unwrap();
+
+        let (source, received) = b.poll().unwrap().expect("data should arrive");
+        assert_eq!(source, DroneId::new(1));
+        assert_eq!(&received[..], b"formation");
+        assert_eq!(b.statistics().messages_received, 1);
+    }
+
+    #[test]
+    fn test_loopback_large_message_fragmented() {
+        let hub = LoopbackHub::new();
+        let mut a = MeshNetwork::with_transport(
+            DroneId::new(1),
+            hub.connect(NetworkAddress::new([0; 16], 1)),
+        );
+        let mut b = MeshNetwork::with_transport(
+            DroneId::new(2),
+            hub.connect(NetworkAddress::new([0; 16], 2)),
+        );
+        a.broadcast_hello(origin()).unwrap();
+        b.broadcast_hello(origin()).unwrap();
+        drain(&mut a);
+        drain(&mut b);
+
+        let data: std::vec::Vec<u8> = (0..5000).map(|i| (i % 251) as u8).collect();
+        a.send_large(DroneId::new(2), &data).unwrap();
+        assert!(a.send_large(DroneId::new(1), &data).is_err());
+
+        // Fragments are not data payloads; poll pauses once the message is whole
+        assert!(b.poll().unwrap().is_none());
+        let (source, received) = b.take_reassembled().expect("message should reassemble");
+        assert_eq!(source, DroneId::new(1));
+        assert_eq!(&received[..], &data[..]);
+
+        drain(&mut a);
+        assert_eq!(a.fragmentation().pending_messages(), 0);
+        assert_eq!(a.fragmentation().statistics().messages_acked, 1);
+    }
+
+    #[test]
+    fn test_poll_drops_malformed_frames() {
+        let hub = LoopbackHub::new();
+        let mut raw = hub.connect(NetworkAddress::new([0; 16], 9));
+        let mut network = MeshNetwork::with_transport(
+            DroneId::new(1),
+            hub.connect(NetworkAddress::new([0; 16], 1)),
+        );
+
+        raw.broadcast(&[0xFF, 0xFF, 0xFF]).unwrap();
+
+        assert!(network.poll().unwrap().is_none());
+        assert_eq!(network.statistics().messages_dropped, 1);
+    }
+
+    #[test]
+    fn test_udp_transport_exchange() {
+        let mut a = UdpTransport::bind("127.0.0.1:0".parse().unwrap()).unwrap();
+        let mut b = UdpTransport::bind("127.0.0.1:0".parse().unwrap()).unwrap();
+        a.add_peer(b.local_addr().unwrap());
+        b.add_peer(a.local_addr().unwrap());
+
+        let a_addr = a.local_address().unwrap();
+        let mut net_a = MeshNetwork::with_transport(DroneId::new(1), a);
+        let mut net_b = MeshNetwork::with_transport(DroneId::new(2), b);
+
+        net_a.broadcast_hello(origin()).unwrap();
+
+        // UDP delivery over loopback is asynchronous; wait briefly
+        for _ in 0..100 {
+            drain(&mut net_b);
+            if net_b.neighbor_count() == 1 {
+                break;
+            }
+            std::thread::sleep(std::time::Duration::from_millis(5));
+        }
+
+        assert_eq!(net_b.neighbor_count(), 1);
+        assert_eq!(net_b.neighbors().next().unwrap().address, a_addr);
+    }
+}
+
+#[cfg(test)]
+mod aodv_tests {
+    use super::*;
+    use drone_swarm_system::time_abstraction::set_virtual_time_us;
+    use drone_swarm_system::transport::*;
+
+    fn origin() -> Position {
+        Position {
+            x: 0.0,
+            y: 0.0,
+            z: 10.0,
+        }
+    }
+
+    fn drain<T: Transport>(network: &mut MeshNetwork<T>) {
+        while network.poll().unwrap().is_some() {}
+    }
+
+    fn at_ms(ms: u64) {
+        set_virtual_time_us(Some(ms * 1000));
+    }
+
+    fn hello_from(network: &mut MeshNetwork, id: u64) {
+        let msg = NetworkMessage::Hello {
+            sender: DroneId::new(id),
+            position: origin(),
+            sequence: 1,
+        };
+        network
+            .process_message(msg, NetworkAddress::new([0; 16], id as u16))
+            .unwrap();
+    }
+
+    #[test]
+    fn test_route_request_rebroadcast_once() {
+        let hub = LoopbackHub::new();
+        let mut nodes: std::vec::Vec<_> = (1..=3)
+            .map(|i| {
+                MeshNetwork::with_transport(
+                    DroneId::new(i),
+                    hub.connect(NetworkAddress::new([0; 16], i as u16)),
+                )
+            })
+            .collect();
+        for node in nodes.iter_mut() {
+            node.broadcast_hello(origin()).unwrap();
+        }
+        for node in nodes.iter_mut() {
+            drain(node);
+        }
+        let sent_before = nodes[1].statistics().messages_sent;
+
+        nodes[0]
+            .send_message(DroneId::new(99), Vec::from_slice(b"x").unwrap())
+            .unwrap();
+        // Node 2 hears the original and node 3's rebroadcast
+        drain(&mut nodes[1]);
+        drain(&mut nodes[2]);
+        drain(&mut nodes[1]);
+
+        assert_eq!(nodes[1].statistics().messages_sent, sent_before + 1);
+        assert_eq!(nodes[0].queued_message_count(), 1);
+    }
+
+    #[test]
+    fn test_route_reply_flushes_queue() {
+        at_ms(0);
+        let hub = LoopbackHub::new();
+        let mut a = MeshNetwork::with_transport(
+            DroneId::new(1),
+            hub.connect(NetworkAddress::new([0; 16], 1)),
+        );
+        let mut b = MeshNetwork::with_transport(
+            DroneId::new(2),
+            hub.connect(NetworkAddress::new([0; 16], 2)),
+        );
+        a.broadcast_hello(origin()).unwrap();
+        b.broadcast_hello(origin()).unwrap();
+        drain(&mut a);
+        drain(&mut b);
+
+        // Direct route has expired, but the neighbor is still known
+        at_ms(ACTIVE_ROUTE_TIMEOUT_MS + 1);
+        a.send_message(DroneId::new(2), Vec::from_slice(b"waypoint").unwrap())
+            .unwrap();
+        assert!(a.route(DroneId::new(2)).is_none());
+        assert_eq!(a.queued_message_count(), 1);
+
+        drain(&mut b); // request -> reply
+        drain(&mut a); // reply -> route installed, queue flushed
+        let (source, received) = b.poll().unwrap().expect("queued data should arrive");
+
+        assert_eq!(source, DroneId::new(1));
+        assert_eq!(&received[..], b"waypoint");
+        assert_eq!(a.queued_message_count(), 0);
+        assert_eq!(a.route(DroneId::new(2)).unwrap().next_hop, DroneId::new(2));
+        set_virtual_time_us(None);
+    }
+
+    #[test]
+    fn test_route_expires_when_idle() {
+        at_ms(0);
+        let mut network = MeshNetwork::new(DroneId::new(1));
+        hello_from(&mut network, 2);
+        assert!(network.route(DroneId::new(2)).is_some());
+
+        at_ms(ACTIVE_ROUTE_TIMEOUT_MS + 1);
+        network.update().unwrap();
+
+        assert_eq!(network.routes().count(), 0);
+        set_virtual_time_us(None);
+    }
+
+    #[test]
+    #[cfg(not(feature = "hardware"))]
+    fn test_discovery_retries_then_drops() {
+        at_ms(0);
+        let mut network = MeshNetwork::new(DroneId::new(1));
+        network
+            .send_message(DroneId::new(9), Vec::from_slice(b"x").unwrap())
+            .unwrap();
+        network
+            .send_message(DroneId::new(9), Vec::from_slice(b"y").unwrap())
+            .unwrap();
+        // Second message joins the pending discovery
+        assert_eq!(network.statistics().messages_sent, 1);
+
+        let mut now = 0;
+        for retry in 0..MAX_ROUTE_REQUEST_RETRIES {
+            now += (ROUTE_DISCOVERY_TIMEOUT_MS << retry) + 1;
+            at_ms(now);
+            network.update().unwrap();
+            assert_eq!(network.statistics().messages_sent, 2 + retry as u64);
+        }
+
+        now += (ROUTE_DISCOVERY_TIMEOUT_MS << MAX_ROUTE_REQUEST_RETRIES) + 1;
+        at_ms(now);
+        network.update().unwrap();
+
+        assert_eq!(network.queued_message_count(), 0);
+        assert_eq!(network.statistics().messages_dropped, 2);
+        set_virtual_time_us(None);
+    }
+
+    #[test]
+    #[cfg(not(feature = "hardware"))]
+    fn test_route_error_removes_routes_via_sender() {
+        let mut network = MeshNetwork::new(DroneId::new(1));
+        hello_from(&mut network, 2);
+        hello_from(&mut network, 3);
+
+        let reply = NetworkMessage::RouteReply {
+            source: DroneId::new(5),
+            destination: DroneId::new(1),
+            next_hop: DroneId::new(2),
+            hop_count: 1,
+            sequence: 4,
+            metric: 1.0,
+        };
+        network
+            .process_message(reply, NetworkAddress::new([0; 16], 2))
+            .unwrap();
+        assert_eq!(network.route(DroneId::new(5)).unwrap().hop_count, 2);
+
+        // Errors from a drone that is not our next hop are ignored
+        let mut unreachable = Vec::new();
+        unreachable.push(DroneId::new(5)).unwrap();
+        let foreign = NetworkMessage::RouteError {
+            sender: DroneId::new(3),
+            unreachable: unreachable.clone(),
+        };
+        network
+            .process_message(foreign, NetworkAddress::new([0; 16], 3))
+            .unwrap();
+        assert!(network.route(DroneId::new(5)).is_some());
+
+        let sent_before = network.statistics().messages_sent;
+        let error = NetworkMessage::RouteError {
+            sender: DroneId::new(2),
+            unreachable,
+        };
+        network
+            .process_message(error, NetworkAddress::new([0; 16], 2))
+            .unwrap();
+
+        assert!(network.route(DroneId::new(5)).is_none());
+        assert!(network.route(DroneId::new(2)).is_some());
+        // The error is propagated to our own upstream
+        assert_eq!(network.statistics().messages_sent, sent_before + 1);
+    }
+}
+
+#[cfg(test)]
+mod link_state_tests {
+    use super::*;
+    use drone_swarm_system::config::{NetworkConfig, RoutingProtocol};
+    #[cfg(not(feature = "hardware"))]
+    use drone_swarm_system::reputation::{ReputationConfig, ReputationSystem};
+    use drone_swarm_system::transport::NullTransport;
+
+    fn addr(id: u64) -> NetworkAddress {
+        NetworkAddress::new([0; 16], id as u16)
+    }
+
+    fn link_state_network(id: u64) -> MeshNetwork {
+        let mut config = NetworkConfig::new(addr(id));
+        config.routing_protocol = RoutingProtocol::LinkState;
+        MeshNetwork::with_config(DroneId::new(id), NullTransport, &config)
+    }
+
+    fn hello_from(network: &mut MeshNetwork, id: u64) {
+        let msg = NetworkMessage::Hello {
+            sender: DroneId::new(id),
+            position: Position {
+                x: 0.0,
+                y: 0.0,
+                z: 10.0,
+            },
+            sequence: 1,
+        };
+        network.process_message(msg, addr(id)).unwrap();
+    }
+
+    fn update_from(
+        network: &mut MeshNetwork,
+        sender: u64,
+        links: &[(u64, f32)],
+        sequence: u32,
+        mprs: &[u64],
+    ) {
+        let mut neighbors = Vec::new();
+        for (id, quality) in links {
+            neighbors.push((DroneId::new(*id), *quality)).unwrap();
+        }
+        let mut relays = Vec::new();
+        for id in mprs {
+            relays.push(DroneId::new(*id)).unwrap();
+        }
+        let msg = NetworkMessage::LinkStateUpdate {
+            sender: DroneId::new(sender),
+            neighbors,
+            sequence,
+            mprs: relays,
+            hop_count: 0,
+        };
+        network.process_message(msg, addr(sender)).unwrap();
+    }
+
+    #[test]
+    fn test_protocol_selected_from_config() {
+        assert_eq!(
+            link_state_network(1).routing_protocol(),
+            RoutingProtocol::LinkState
+        );
+        assert_eq!(
+            MeshNetwork::new(DroneId::new(1)).routing_protocol(),
+            RoutingProtocol::AdaptiveMesh
+        );
+    }
+
+    #[test]
+    #[cfg(not(feature = "hardware"))]
+    fn test_mpr_selection_covers_two_hop_neighbors() {
+        let mut network = link_state_network(1);
+        for id in 2..=4 {
+            hello_from(&mut network, id);
+        }
+        update_from(&mut network, 2, &[(1, 1.0), (5, 1.0)], 1, &[]);
+        update_from(&mut network, 3, &[(1, 1.0), (5, 1.0), (6, 1.0)], 1, &[]);
+        update_from(&mut network, 4, &[(1, 1.0), (6, 1.0), (7, 1.0)], 1, &[]);
+
+        network.broadcast_link_state().unwrap();
+
+        // Drone 4 is the only way to 7; drone 5 still needs one of 2 or 3
+        let mprs = network.mprs();
+        assert_eq!(mprs.len(), 2);
+        assert!(mprs.contains(&DroneId::new(4)));
+    }
+
+    #[test]
+    #[cfg(not(feature = "hardware"))]
+    fn test_no_mprs_without_two_hop_neighbors() {
+        let mut network = link_state_network(1);
+        hello_from(&mut network, 2);
+        hello_from(&mut network, 3);
+        update_from(&mut network, 2, &[(1, 1.0), (3, 1.0)], 1, &[]);
+
+        network.broadcast_link_state().unwrap();
+
+        assert!(network.mprs().is_empty());
+    }
+
+    #[test]
+    fn test_dijkstra_prefers_quality_over_hops() {
+        let mut network = link_state_network(1);
+        hello_from(&mut network, 2);
+        hello_from(&mut network, 3);
+        // 1-2-4 is short but its second link is poor; 1-3-5-4 is clean
+        update_from(&mut network, 2, &[(1, 1.0), (4, 0.2)], 1, &[]);
+        update_from(&mut network, 3, &[(1, 1.0), (5, 1.0)], 1, &[]);
+        update_from(&mut network, 5, &[(3, 1.0), (4, 1.0)], 1, &[]);
+
+        let route = network.route(DroneId::new(4)).unwrap();
+        assert_eq!(route.next_hop, DroneId::new(3));
+        assert_eq!(route.hop_count, 3);
+        // The first hop is a fresh link, priced at its unproven delivery ratio
+        let first_hop = 1.0 / (NEW_LINK_RATIO * NEW_LINK_RATIO);
+        assert!((route.metric - (first_hop + 2.0)).abs() < 1e-3);
+        assert_eq!(network.route(DroneId::new(5)).unwrap().hop_count, 2);
+    }
+
+    #[test]
+    fn test_stale_update_ignored() {
+        let mut network = link_state_network(1);
+        hello_from(&mut network, 2);
+        update_from(&mut network, 2, &[(1, 1.0), (7, 1.0)], 5, &[]);
+        update_from(&mut network, 2, &[(1, 1.0)], 4, &[]);
+
+        let entry = network.topology_entry(DroneId::new(2)).unwrap();
+        assert_eq!(entry.sequence, 5);
+        assert_eq!(entry.neighbors.len(), 2);
+        assert!(network.route(DroneId::new(7)).is_some());
+    }
+
+    #[test]
+    #[cfg(not(feature = "hardware"))]
+    fn test_only_selected_relays_forward() {
+        let mut network = link_state_network(1);
+        hello_from(&mut network, 2);
+        hello_from(&mut network, 3);
+
+        update_from(&mut network, 2, &[(1, 1.0)], 1, &[]);
+        assert_eq!(network.statistics().messages_sent, 0);
+
+        update_from(&mut network, 3, &[(1, 1.0)], 1, &[1]);
+        assert_eq!(network.mpr_selectors(), &[DroneId::new(3)]);
+        assert_eq!(network.statistics().messages_sent, 1);
+
+        // A repeat of the same update is not forwarded again
+        update_from(&mut network, 3, &[(1, 1.0)], 1, &[1]);
+        assert_eq!(network.statistics().messages_sent, 1);
+    }
+
+    #[test]
+    #[cfg(not(feature = "hardware"))]
+    fn test_selector_copy_relayed_after_non_selector_copy() {
+        let mut network = link_state_network(1);
+        hello_from(&mut network, 2);
+        hello_from(&mut network, 3);
+        update_from(&mut network, 3, &[(1, 1.0)], 1, &[1]);
+        assert_eq!(network.statistics().messages_sent, 1);
+
+        // Drone 5's update reaches us through 2 first, which did not select us
+        let relayed = |network: &mut MeshNetwork, via: u64| {
+            let mut neighbors = Vec::new();
+            neighbors.push((DroneId::new(2), 1.0)).unwrap();
+            neighbors.push((DroneId::new(3), 1.0)).unwrap();
+            let msg = NetworkMessage::LinkStateUpdate {
+                sender: DroneId::new(5),
+                neighbors,
+                sequence: 7,
+                mprs: Vec::new(),
+                hop_count: 1,
+            };
+            network.process_message(msg, addr(via)).unwrap();
+        };
+        relayed(&mut network, 2);
+        assert_eq!(network.statistics().messages_sent, 1);
+
+        // The later copy from selector 3 is a duplicate but must still be relayed
+        relayed(&mut network, 3);
+        assert_eq!(network.statistics().messages_sent, 2);
+        assert!(network.topology_entry(DroneId::new(5)).unwrap().retransmitted);
+
+        relayed(&mut network, 3);
+        assert_eq!(network.statistics().messages_sent, 2);
+    }
+
+    #[test]
+    #[cfg(not(feature = "hardware"))]
+    fn test_untrusted_drones_never_relay() {
+        let mut network = link_state_network(1);
+        hello_from(&mut network, 2);
+        hello_from(&mut network, 3);
+        update_from(&mut network, 2, &[(1, 1.0), (4, 0.2)], 1, &[]);
+        update_from(&mut network, 3, &[(1, 1.0), (5, 1.0)], 1, &[]);
+        update_from(&mut network, 5, &[(3, 1.0), (4, 1.0)], 1, &[]);
+        assert_eq!(
+            network.route(DroneId::new(4)).unwrap().next_hop,
+            DroneId::new(3)
+        );
+
+        let mut reputation = ReputationSystem::new(DroneId::new(1), ReputationConfig::default());
+        reputation.record(DroneId::new(3), 0.0, 20.0).unwrap();
+        network.exclude_untrusted(&reputation);
+        assert!(network.route(DroneId::new(4)).is_none());
+        assert!(network.route(DroneId::new(5)).is_none());
+
+        // Recomputed routes take the poor link instead; 3 itself stays reachable
+        update_from(&mut network, 5, &[(3, 1.0), (4, 1.0)], 2, &[]);
+        assert_eq!(
+            network.route(DroneId::new(4)).unwrap().next_hop,
+            DroneId::new(2)
+        );
+        assert!(network.route(DroneId::new(5)).is_none());
+        assert_eq!(
+            network.route(DroneId::new(3)).unwrap().next_hop,
+            DroneId::new(3)
+        );
+
+        network.broadcast_link_state().unwrap();
+        assert!(!network.mprs().contains(&DroneId::new(3)));
+    }
+}
+
+#[cfg(test)]
+mod flooding_and_gradient_tests {
+    use super::*;
+    use drone_swarm_system::config::{NetworkConfig, RoutingProtocol};
+    use drone_swarm_system::transport::NullTransport;
+
+    fn addr(id: u64) -> NetworkAddress {
+        NetworkAddress::new([0; 16], id as u16)
+    }
+
+    fn network_with(id: u64, protocol: RoutingProtocol) -> MeshNetwork {
+        let mut config = NetworkConfig::new(addr(id));
+        config.routing_protocol = protocol;
+        MeshNetwork::with_config(DroneId::new(id), NullTransport, &config)
+    }
+
+    #[cfg(not(feature = "hardware"))]
+    fn hello_from(network: &mut MeshNetwork, id: u64) {
+        let msg = NetworkMessage::Hello {
+            sender: DroneId::new(id),
+            position: Position {
+                x: 0.0,
+                y: 0.0,
+                z: 10.0,
+            },
+            sequence: 1,
+        };
+        network.process_message(msg, addr(id)).unwrap();
+    }
+
+    fn flood(source: u64, destination: u64, sequence: u32, hop_count: u8) -> NetworkMessage {
+        NetworkMessage::Flood {
+            source: DroneId::new(source),
+            destination: DroneId::new(destination),
+            sequence,
+            payload: Vec::from_slice(b"flood").unwrap(),
+            hop_count,
+        }
+    }
+
+    #[cfg(not(feature = "hardware"))]
+    fn beacon(sink: u64, sequence: u32, hop_count: u8) -> NetworkMessage {
+        NetworkMessage::SinkBeacon {
+            sink: DroneId::new(sink),
+            sequence,
+            hop_count,
+            metric: hop_count as f32,
+        }
+    }
+
+    #[test]
+    #[cfg(not(feature = "hardware"))]
+    fn test_flooding_sends_without_route() {
+        let mut network = network_with(1, RoutingProtocol::Flooding);
+        network
+            .send_message(DroneId::new(9), Vec::from_slice(b"x").unwrap())
+            .unwrap();
+
+        assert_eq!(network.statistics().messages_sent, 1);
+        assert_eq!(network.queued_message_count(), 0);
+    }
+
+    #[test]
+    #[cfg(not(feature = "hardware"))]
+    fn test_flood_rebroadcast_once() {
+        let mut network = network_with(1, RoutingProtocol::Flooding);
+
+        let result = network.process_message(flood(2, 9, 1, 0), addr(2)).unwrap();
+        assert!(result.is_none());
+        assert_eq!(network.statistics().messages_sent, 1);
+
+        network.process_message(flood(2, 9, 1, 1), addr(3)).unwrap();
+        assert_eq!(network.statistics().messages_sent, 1);
+    }
+
+    #[test]
+    fn test_flood_delivered_to_destination() {
+        let mut network = network_with(1, RoutingProtocol::Flooding);
+
+        let result = network.process_message(flood(2, 1, 1, 3), addr(2)).unwrap();
+
+        assert_eq!(&result.unwrap()[..], b"flood");
+        assert_eq!(network.statistics().messages_received, 1);
+        assert_eq!(network.statistics().messages_sent, 0);
+    }
+
+    #[test]
+    fn test_flood_ttl_limit() {
+        let mut network = network_with(1, RoutingProtocol::Flooding);
+
+        network
+            .process_message(flood(2, 9, 1, MAX_NETWORK_HOPS - 1), addr(2))
+            .unwrap();
+
+        assert_eq!(network.statistics().messages_sent, 0);
+        assert_eq!(network.statistics().messages_dropped, 1);
+    }
+
+    #[test]
+    #[cfg(not(feature = "hardware"))]
+    fn test_gradient_follows_fewest_hops() {
+        let mut network = network_with(1, RoutingProtocol::GradientBased);
+        hello_from(&mut network, 2);
+        hello_from(&mut network, 3);
+
+        network.process_message(beacon(9, 1, 2), addr(2)).unwrap();
+        let route = network.route(DroneId::new(9)).unwrap();
+        assert_eq!((route.next_hop, route.hop_count), (DroneId::new(2), 3));
+        assert_eq!(network.statistics().messages_sent, 1);
+
+        // Same round, shorter path: switch and rebroadcast
+        network.process_message(beacon(9, 1, 1), addr(3)).unwrap();
+        let route = network.route(DroneId::new(9)).unwrap();
+        assert_eq!((route.next_hop, route.hop_count), (DroneId::new(3), 2));
+        assert_eq!(network.statistics().messages_sent, 2);
+
+        // Same round, longer path: ignored
+        network.process_message(beacon(9, 1, 3), addr(2)).unwrap();
+        assert_eq!(
+            network.route(DroneId::new(9)).unwrap().next_hop,
+            DroneId::new(3)
+        );
+        assert_eq!(network.statistics().messages_sent, 2);
+    }
+
+    #[test]
+    #[cfg(not(feature = "hardware"))]
+    fn test_sink_beacons_on_update() {
+        let mut network = network_with(1, RoutingProtocol::GradientBased);
+        network.update().unwrap();
+        assert_eq!(network.statistics().messages_sent, 0);
+
+        network.set_sink(true);
+        network.update().unwrap();
+        network.update().unwrap();
+
+        assert!(network.is_sink());
+        assert_eq!(network.statistics().messages_sent, 1);
+    }
+}
+
+#[cfg(test)]
+mod geographic_tests {
+    use super::*;
+    use drone_swarm_system::config::{NetworkConfig, RoutingProtocol};
+    use drone_swarm_system::transport::NullTransport;
+
+    fn at(x: f32, y: f32) -> Position {
+        Position { x, y, z: 10.0 }
+    }
+
+    fn geographic(id: u64) -> MeshNetwork {
+        let mut config = NetworkConfig::new(NetworkAddress::new([0; 16], id as u16));
+        config.routing_protocol = RoutingProtocol::Geographic;
+        MeshNetwork::with_config(DroneId::new(id), NullTransport, &config)
+    }
+
+    #[test]
+    #[cfg(not(feature = "hardware"))]
+    fn test_queued_until_location_known() {
+        let mut network = geographic(1);
+        network.set_position(at(0.0, 0.0));
+        let hello = NetworkMessage::Hello {
+            sender: DroneId::new(2),
+            position: at(100.0, 0.0),
+            sequence: 1,
+        };
+        network
+            .process_message(hello, NetworkAddress::new([0; 16], 2))
+            .unwrap();
+
+        network
+            .send_message(DroneId::new(9), Vec::from_slice(b"x").unwrap())
+            .unwrap();
+        assert_eq!(network.queued_message_count(), 1);
+        assert_eq!(network.statistics().messages_sent, 0);
+
+        let update = NetworkMessage::LocationUpdate {
+            drone: DroneId::new(9),
+            position: at(400.0, 0.0),
+            sequence: 1,
+            hop_count: 2,
+        };
+        network
+            .process_message(update, NetworkAddress::new([0; 16], 2))
+            .unwrap();
+
+        assert_eq!(network.location(DroneId::new(9)), Some(at(400.0, 0.0)));
+        assert_eq!(network.queued_message_count(), 0);
+        // Location update relayed, then the data sent greedily via drone 2
+        assert_eq!(network.statistics().messages_sent, 2);
+    }
+
+    #[test]
+    fn test_hello_feeds_location_service() {
+        let mut network = geographic(1);
+        let hello = NetworkMessage::Hello {
+            sender: DroneId::new(2),
+            position: at(5.0, 6.0),
+            sequence: 1,
+        };
+        network
+            .process_message(hello, NetworkAddress::new([0; 16], 2))
+            .unwrap();
+
+        assert_eq!(network.location(DroneId::new(2)), Some(at(5.0, 6.0)));
+    }
+
+    #[test]
+    #[cfg(not(feature = "hardware"))]
+    fn test_location_broadcast_needs_position() {
+        let mut network = geographic(1);
+        network.update().unwrap();
+        assert_eq!(network.statistics().messages_sent, 0);
+        assert!(network.broadcast_location().is_err());
+
+        network.set_position(at(1.0, 2.0));
+        network.update().unwrap();
+        assert_eq!(network.statistics().messages_sent, 1);
+    }
+}
+
+#[cfg(test)]
+mod link_estimator_tests {
+    use super::*;
+    use drone_swarm_system::time_abstraction::set_virtual_time_us;
+    use drone_swarm_system::transport::*;
+
+    fn origin() -> Position {
+        Position {
+            x: 0.0,
+            y: 0.0,
+            z: 10.0,
+        }
+    }
+
+    fn drain<T: Transport>(network: &mut MeshNetwork<T>) {
+        while network.poll().unwrap().is_some() {}
+    }
+
+    #[test]
+    fn test_etx_counts_missed_heartbeats() {
+        let mut link = LinkEstimator::default();
+        assert_eq!(link.etx(), 4.0);
+
+        link.forward_ratio = 1.0;
+        for sequence in 1..=30 {
+            link.record_heartbeat(sequence);
+        }
+        assert!(link.etx() < 1.01);
+
+        // Heartbeats 31-34 were lost
+        link.record_heartbeat(35);
+        assert!(link.reverse_ratio < 0.6);
+        assert!(link.etx() > 1.8);
+
+        // Duplicates and stragglers do not count as deliveries
+        let before = link;
+        link.record_heartbeat(35);
+        link.record_heartbeat(32);
+        assert_eq!(link, before);
+    }
+
+    #[test]
+    fn test_silence_charged_once() {
+        let mut link = LinkEstimator::default();
+        link.end_interval();
+        assert_eq!(link.reverse_ratio, NEW_LINK_RATIO);
+
+        link.record_heartbeat(1);
+        link.end_interval();
+        let heard = link.reverse_ratio;
+        link.end_interval();
+        link.end_interval();
+        assert!((link.reverse_ratio - heard * 0.64).abs() < 1e-4);
+
+        // The gap covers the two misses already charged
+        link.record_heartbeat(4);
+        assert!((link.reverse_ratio - (heard * 0.64 * 0.8 + 0.2)).abs() < 1e-4);
+    }
+
+    #[test]
+    fn test_failing_link_cost_is_bounded() {
+        let mut link = LinkEstimator {
+            reverse_ratio: 1.0,
+            ..LinkEstimator::default()
+        };
+        for _ in 0..100 {
+            link.record_delivery(false);
+        }
+        assert_eq!(link.etx(), 1.0 / MIN_DELIVERY_RATIO);
+        assert!(link.quality() > 0.0);
+    }
+
+    #[test]
+    fn test_fading_signal_costs_more() {
+        let proven = LinkEstimator {
+            reverse_ratio: 1.0,
+            forward_ratio: 1.0,
+            ..LinkEstimator::default()
+        };
+        let mut steady = proven;
+        let mut fading = proven;
+        for i in 0..10 {
+            steady.record_rssi(-80);
+            fading.record_rssi(-70 - 2 * i);
+        }
+
+        assert!(fading.rssi_trend < -1.0);
+        assert!(steady.rssi_trend.abs() < f32::EPSILON);
+        assert!(fading.cost() > steady.cost());
+
+        let mut strong = proven;
+        strong.record_rssi(-60);
+        assert_eq!(strong.cost(), 1.0);
+    }
+
+    #[test]
+    fn test_reception_report_sets_forward_ratio() {
+        let mut network = MeshNetwork::new(DroneId::new(1));
+        let address = NetworkAddress::new([0; 16], 2);
+        let hello = NetworkMessage::Hello {
+            sender: DroneId::new(2),
+            position: origin(),
+            sequence: 1,
+        };
+        network.process_message(hello, address).unwrap();
+
+        let mut reception = Vec::new();
+        reception.push((DroneId::new(1), 128)).unwrap();
+        let heartbeat = NetworkMessage::Heartbeat {
+            sender: DroneId::new(2),
+            timestamp: 0,
+            sequence: 1,
+            reception,
+        };
+        network.process_message(heartbeat, address).unwrap();
+
+        let neighbor = network.neighbors().next().unwrap();
+        assert!((neighbor.link.forward_ratio - 0.5).abs() < 0.01);
+        assert!((neighbor.link.reverse_ratio - 0.6).abs() < 0.01);
+        assert!((neighbor.link_quality - 0.3).abs() < 0.01);
+        // The direct route is priced by the estimator
+        let route = network.route(DroneId::new(2)).unwrap();
+        assert!((route.metric - neighbor.link.cost()).abs() < 1e-3);
+    }
+
+    #[test]
+    fn test_probe_echo_measures_rtt() {
+        set_virtual_time_us(Some(0));
+        let hub = LoopbackHub::new();
+        let mut a = MeshNetwork::with_transport(
+            DroneId::new(1),
+            hub.connect(NetworkAddress::new([0; 16], 1)),
+        );
+        let mut b = MeshNetwork::with_transport(
+            DroneId::new(2),
+            hub.connect(NetworkAddress::new([0; 16], 2)),
+        );
+        a.broadcast_hello(origin()).unwrap();
+        b.broadcast_hello(origin()).unwrap();
+        drain(&mut a);
+        drain(&mut b);
+
+        a.send_probe().unwrap();
+        drain(&mut b);
+        set_virtual_time_us(Some(40_000));
+        drain(&mut a);
+
+        let neighbor = a.neighbors().next().unwrap();
+        assert_eq!(neighbor.rtt_ms, 40);
+        assert_eq!(neighbor.link.rtt_ms, Some(40.0));
+        assert_eq!(a.statistics().avg_rtt_ms, 40);
+        assert!(neighbor.link.cost() > 1.0);
+        set_virtual_time_us(None);
+    }
+}
+
 #[cfg(test)]
 mod constants_tests {
     use super::*;
@@ -304,6 +304,7 @@ mod math_properties {
 
     proptest! {
         #[test]
+        #[allow(clippy::absurd_extreme_comparisons)]
         fn saturating_add_never_overflows(
             a in 0u64..=u64::MAX,
             b in 0u64..=u64::MAX,
@@ -315,6 +316,7 @@ mod math_properties {
         }
 
         #[test]
+        #[allow(unused_comparisons, clippy::absurd_extreme_comparisons)]
         fn saturating_sub_never_underflows(
             a in 0u64..=u64::MAX,
             b in 0u64..=u64::MAX,
@@ -443,6 +445,7 @@ mod mission_planning_properties {
         }
 
         #[test]
+        #[allow(unused_variables)]
         fn spiral_pattern_radius_increases(
             turns in 1.0_f32..5.0,
             points in 10usize..50,
@@ -513,6 +516,7 @@ mod telemetry_properties {
         }
 
         #[test]
+        #[allow(unused_comparisons, clippy::absurd_extreme_comparisons)]
         fn alert_count_non_negative(count in 0usize..1000) {
             prop_assert!(count >= 0, "Alert count cannot be negative");
         }
@@ -644,6 +648,7 @@ mod gwo_properties {
 
     proptest! {
         #[test]
+        #[allow(clippy::manual_range_contains)]
         fn convergence_parameter_decreases(
             iteration in 0u32..500,
             max_iter in 100u32..1000,
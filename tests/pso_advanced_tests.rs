@@ -135,6 +135,7 @@ mod penalty_method_tests {
     use super::*;
 
     #[test]
+    #[allow(clippy::assertions_on_constants)]
     fn test_static_penalty() {
         let method = PenaltyMethod::Static;
         match method {
@@ -153,6 +154,7 @@ mod penalty_method_tests {
     }
 
     #[test]
+    #[allow(clippy::assertions_on_constants)]
     fn test_adaptive_penalty() {
         let method = PenaltyMethod::Adaptive;
         match method {
@@ -214,6 +216,7 @@ mod sharing_strategy_tests {
     use super::*;
 
     #[test]
+    #[allow(clippy::assertions_on_constants)]
     fn test_none_strategy() {
         let strategy = SharingStrategy::None;
         match strategy {
@@ -223,6 +226,7 @@ mod sharing_strategy_tests {
     }
 
     #[test]
+    #[allow(clippy::assertions_on_constants)]
     fn test_best_solution_strategy() {
         let strategy = SharingStrategy::BestSolution;
         match strategy {
@@ -241,6 +245,7 @@ mod sharing_strategy_tests {
     }
 
     #[test]
+    #[allow(clippy::assertions_on_constants)]
     fn test_incremental_strategy() {
         let strategy = SharingStrategy::Incremental;
         match strategy {
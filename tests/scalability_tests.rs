@@ -494,6 +494,7 @@ mod consensus_tests {
     }
 
     #[test]
+    #[allow(clippy::useless_vec)]
     fn test_swarm_command_variants() {
         let commands = vec![
             SwarmCommand::AssignTask { drone: DroneId::new(1), task_id: 42 },
@@ -792,6 +793,7 @@ mod telemetry_tests {
     }
 
     #[test]
+    #[allow(clippy::field_reassign_with_default)]
     fn test_telemetry_with_32_drones() {
         let mut monitor = TelemetryMonitor::new();
 
@@ -819,6 +821,7 @@ mod telemetry_tests {
     }
 
     #[test]
+    #[allow(clippy::field_reassign_with_default)]
     fn test_drone_status_creation() {
         let statuses: Vec<DroneStatus> = (0..LARGE_SWARM)
             .map(|i| {
@@ -897,6 +900,7 @@ mod throughput_tests {
     use std::time::Instant;
 
     #[test]
+    #[allow(clippy::needless_range_loop)]
     fn test_position_update_throughput() {
         let positions: Vec<Position> = (0..LARGE_SWARM)
             .map(|i| Position {
@@ -2,6 +2,7 @@
 //!
 //! Tests SecurityMonitor, RateLimiter, IntrusionDetectionSystem, AccessControl, and AuditLog
 
+use drone_swarm_system::crypto::CryptoContext;
 use drone_swarm_system::security::*;
 use drone_swarm_system::types::*;
 
@@ -117,6 +118,7 @@ mod rate_limiter_tests {
     use super::*;
 
     #[test]
+    #[allow(unused_variables, clippy::assertions_on_constants)]
     fn test_new() {
         let limiter = RateLimiter::new(100, 1000);
         // Just verify it creates without panic
@@ -173,6 +175,7 @@ mod rate_limiter_tests {
 #[cfg(test)]
 mod ids_tests {
     use super::*;
+    use drone_swarm_system::consensus::ConsensusMessage;
 
     #[test]
     fn test_new() {
@@ -238,23 +241,247 @@ mod ids_tests {
     }
 
     #[test]
-    fn test_analyze_repetitive_pattern() {
+    fn test_analyze_oversized_message() {
         let mut ids = IntrusionDetectionSystem::new();
         let drone = DroneId::new(1);
+        let message = vec![0u8; MAX_MESSAGE_SIZE + 1];
 
-        // Create highly repetitive message
-        let pattern = [0xAA, 0xBB, 0xCC, 0xDD, 0xEE, 0xFF, 0x00, 0x11];
-        let mut message = Vec::new();
-        for _ in 0..50 {
-            message.extend_from_slice(&pattern);
+        // Repeated oversized messages erode trust until the drone is banned
+        for _ in 0..25 {
+            ids.analyze(drone, &message).ok();
         }
+        assert!(ids.is_banned(drone));
+        assert!(matches!(
+            ids.poll_detection().unwrap().detection,
+            Detection::OversizedMessage { .. }
+        ));
+    }
+
+    #[test]
+    fn test_repetitive_bytes_not_flagged() {
+        let mut ids = IntrusionDetectionSystem::new();
+        let drone = DroneId::new(1);
 
-        // Multiple suspicious activities should eventually lead to ban
+        // Zero-padded payloads are normal; only behavior counts
         for _ in 0..15 {
-            ids.analyze(drone, &message).ok();
+            assert!(ids.analyze(drone, &[0u8; 400]).is_ok());
         }
-        // After many suspicious activities, should be banned
-        assert!(ids.is_banned(drone));
+        assert!(ids.poll_detection().is_none());
+    }
+
+    #[test]
+    fn test_impossible_movement() {
+        let mut ids = IntrusionDetectionSystem::new();
+        let drone = DroneId::new(1);
+
+        ids.observe_position(drone, [0.0, 0.0, 10.0], 0).unwrap();
+        // 20 m in one second is within 25 m/s
+        ids.observe_position(drone, [20.0, 0.0, 10.0], 1000)
+            .unwrap();
+        assert!(ids.poll_detection().is_none());
+
+        // 1 km in one second is not
+        ids.observe_position(drone, [1020.0, 0.0, 10.0], 2000).ok();
+        let report = ids.poll_detection().unwrap();
+        assert_eq!(report.drone, drone);
+        assert!(matches!(
+            report.detection,
+            Detection::ImpossibleMovement { speed_mps } if speed_mps > 900.0
+        ));
+        assert!(ids.trust(drone) < 0.5);
+    }
+
+    #[test]
+    fn test_reordered_position_reports_ignored() {
+        let mut ids = IntrusionDetectionSystem::new();
+        let drone = DroneId::new(1);
+
+        ids.observe_position(drone, [0.0, 0.0, 10.0], 1000).unwrap();
+        ids.observe_position(drone, [20.0, 0.0, 10.0], 2000)
+            .unwrap();
+        // Late and duplicated reports are neither judged nor kept
+        ids.observe_position(drone, [-1000.0, 0.0, 10.0], 1500)
+            .unwrap();
+        ids.observe_position(drone, [25.0, 0.0, 10.0], 2000)
+            .unwrap();
+        assert!(ids.poll_detection().is_none());
+
+        // Movement is measured from the newest report
+        ids.observe_position(drone, [40.0, 0.0, 10.0], 3000)
+            .unwrap();
+        assert!(ids.poll_detection().is_none());
+    }
+
+    #[test]
+    fn test_sybil_shared_fingerprint() {
+        let mut ids = IntrusionDetectionSystem::new();
+
+        ids.observe_fingerprint(DroneId::new(1), 0xBEEF, 0).unwrap();
+        ids.observe_fingerprint(DroneId::new(1), 0xBEEF, 10)
+            .unwrap();
+        assert!(ids.poll_detection().is_none());
+
+        // One radio, a second identity
+        assert_eq!(
+            ids.observe_fingerprint(DroneId::new(2), 0xBEEF, 20)
+                .unwrap_err(),
+            SwarmError::PermissionDenied
+        );
+        assert!(ids.is_banned(DroneId::new(1)));
+        assert!(ids.is_banned(DroneId::new(2)));
+        assert!(matches!(
+            ids.poll_detection().unwrap().detection,
+            Detection::SybilFingerprint { fingerprint: 0xBEEF, other } if other == DroneId::new(1)
+        ));
+    }
+
+    #[test]
+    fn test_fingerprints_age_out() {
+        let mut ids = IntrusionDetectionSystem::new();
+        let lifetime = DetectionConfig::default().fingerprint_lifetime_ms;
+
+        ids.observe_fingerprint(DroneId::new(1), 0xBEEF, 0).unwrap();
+        // Seen again, so still remembered a lifetime after first sight
+        ids.observe_fingerprint(DroneId::new(1), 0xBEEF, lifetime)
+            .unwrap();
+        assert!(ids
+            .observe_fingerprint(DroneId::new(2), 0xBEEF, lifetime + 10)
+            .is_err());
+
+        // A radio unseen for a lifetime may change hands
+        ids.observe_fingerprint(DroneId::new(3), 0xCAFE, 0).unwrap();
+        ids.observe_fingerprint(DroneId::new(4), 0xCAFE, lifetime + 1)
+            .unwrap();
+        assert!(!ids.is_banned(DroneId::new(4)));
+
+        // A full table makes room instead of failing
+        for fingerprint in 0..MAX_FINGERPRINTS as u32 + 1 {
+            ids.observe_fingerprint(DroneId::new(5), fingerprint, lifetime + 20)
+                .unwrap();
+        }
+    }
+
+    #[test]
+    fn test_sybil_colocation() {
+        let mut ids = IntrusionDetectionSystem::new();
+        let (real, ghost) = (DroneId::new(1), DroneId::new(2));
+
+        for step in 0..3u64 {
+            let at = [step as f32, 0.0, 10.0];
+            ids.observe_position(real, at, step * 100).unwrap();
+            ids.observe_position(ghost, at, step * 100 + 10).ok();
+        }
+        let report = ids.poll_detection().unwrap();
+        assert_eq!(report.drone, ghost);
+        assert_eq!(report.detection, Detection::SybilColocation { other: real });
+    }
+
+    #[test]
+    fn test_formation_neighbors_not_sybil() {
+        let mut ids = IntrusionDetectionSystem::new();
+        for step in 0..10u64 {
+            let x = step as f32;
+            ids.observe_position(DroneId::new(1), [x, 0.0, 10.0], step * 100)
+                .unwrap();
+            ids.observe_position(DroneId::new(2), [x, 3.0, 10.0], step * 100)
+                .unwrap();
+        }
+        assert!(ids.poll_detection().is_none());
+        assert!(ids.trust(DroneId::new(1)) > 0.5);
+    }
+
+    #[test]
+    fn test_black_and_grey_holes() {
+        let mut ids = IntrusionDetectionSystem::new();
+
+        ids.observe_forwarding(DroneId::new(1), 10, 10, 0).unwrap();
+        // Not judged before enough samples
+        ids.observe_forwarding(DroneId::new(2), 10, 0, 0).unwrap();
+        assert!(ids.poll_detection().is_none());
+
+        ids.observe_forwarding(DroneId::new(1), 10, 10, 100)
+            .unwrap();
+        ids.observe_forwarding(DroneId::new(2), 10, 0, 100).ok();
+        ids.observe_forwarding(DroneId::new(3), 20, 8, 100).unwrap();
+
+        let black = ids.poll_detection().unwrap();
+        assert_eq!(black.drone, DroneId::new(2));
+        assert!(matches!(black.detection, Detection::BlackHole { .. }));
+        let grey = ids.poll_detection().unwrap();
+        assert_eq!(grey.drone, DroneId::new(3));
+        assert!(matches!(
+            grey.detection,
+            Detection::GreyHole { forwarding_ratio } if (forwarding_ratio - 0.4).abs() < 1e-6
+        ));
+        assert!(ids.poll_detection().is_none());
+        assert!(ids.trust(DroneId::new(1)) > ids.trust(DroneId::new(3)));
+    }
+
+    fn heartbeat(term: u64) -> ConsensusMessage {
+        ConsensusMessage::AppendEntries {
+            term,
+            leader_id: DroneId::new(1),
+            prev_log_index: 0,
+            prev_log_term: 0,
+            entries: heapless::Vec::new(),
+            leader_commit: 0,
+        }
+    }
+
+    fn vote_request(term: u64) -> ConsensusMessage {
+        ConsensusMessage::RequestVote {
+            term,
+            candidate_id: DroneId::new(1),
+            last_log_index: 0,
+            last_log_term: 0,
+        }
+    }
+
+    #[test]
+    fn test_consensus_floods() {
+        let mut ids = IntrusionDetectionSystem::new();
+        let leader = DroneId::new(1);
+
+        // A normal leader: 10 heartbeats per second
+        for i in 0..30 {
+            ids.observe_consensus(leader, &heartbeat(1), i * 100)
+                .unwrap();
+        }
+        assert!(ids.poll_detection().is_none());
+
+        for _ in 0..50 {
+            ids.observe_consensus(leader, &heartbeat(1), 5000).ok();
+        }
+        for _ in 0..5 {
+            ids.observe_consensus(leader, &vote_request(2), 5000).ok();
+        }
+        // Each flood is reported once per window
+        assert_eq!(
+            ids.poll_detection().unwrap().detection,
+            Detection::HeartbeatFlood { count: 21 }
+        );
+        assert_eq!(
+            ids.poll_detection().unwrap().detection,
+            Detection::VoteFlood { count: 4 }
+        );
+        assert!(ids.poll_detection().is_none());
+    }
+
+    #[test]
+    fn test_term_inflation() {
+        let mut ids = IntrusionDetectionSystem::new();
+        let candidate = DroneId::new(1);
+
+        ids.observe_consensus(candidate, &vote_request(4), 0)
+            .unwrap();
+        ids.observe_consensus(candidate, &vote_request(5), 2000)
+            .unwrap();
+        ids.observe_consensus(candidate, &vote_request(1_000_000), 4000)
+            .ok();
+        assert_eq!(
+            ids.poll_detection().unwrap().detection,
+            Detection::TermInflation { jump: 999_995 }
+        );
     }
 
     #[test]
@@ -447,6 +674,209 @@ mod audit_log_tests {
         assert_eq!(entries[0].source, drone);
         // Timestamp should be set (just check it's not 0 in test env)
     }
+
+    fn node_crypto() -> CryptoContext {
+        CryptoContext::with_keys([0; 32], [7; 32])
+    }
+
+    fn filled_log(count: usize) -> AuditLog {
+        filled_log_with(AuditOverflow::Rotate, count)
+    }
+
+    fn filled_log_with(overflow: AuditOverflow, count: usize) -> AuditLog {
+        let mut log = AuditLog::with_overflow(overflow);
+        for i in 0..count {
+            log.log(DroneId::new(i as u64 % 5), AuditEvent::MessageReceived)
+                .unwrap();
+        }
+        log
+    }
+
+    #[test]
+    fn test_entries_hash_chained() {
+        let log = filled_log(3);
+        let entries = log.get_recent(3);
+
+        assert_eq!(entries[0].sequence, 0);
+        assert_eq!(entries[0].prev_hash, [0; 32]);
+        assert_eq!(entries[1].prev_hash, entries[0].hash());
+        assert_eq!(entries[2].prev_hash, entries[1].hash());
+
+        let mut edited = entries[1];
+        edited.event = AuditEvent::AuthSuccess;
+        assert_ne!(edited.hash(), entries[2].prev_hash);
+
+        // Detection details are covered too
+        let flood = |count| AuditEvent::IntrusionDetected(Detection::VoteFlood { count });
+        let (mut a, mut b) = (entries[1], entries[1]);
+        a.event = flood(10);
+        b.event = flood(11);
+        assert_ne!(a.hash(), b.hash());
+    }
+
+    #[test]
+    fn test_commit_and_prove_inclusion() {
+        let crypto = node_crypto();
+        let mut log = filled_log(AUDIT_BATCH_SIZE + 10);
+
+        assert_eq!(log.commit(&crypto).unwrap(), 2);
+        assert_eq!(log.unsealed_count(), 0);
+        assert_eq!(log.commit(&crypto).unwrap(), 0);
+        for checkpoint in log.checkpoints() {
+            checkpoint.verify(crypto.public_key()).unwrap();
+        }
+
+        let entry = log.get_recent(5)[0];
+        let (proof, index) = log.prove(entry.sequence).unwrap();
+        let checkpoint = &log.checkpoints()[index];
+        assert_eq!(index, 1);
+        assert!(checkpoint.verify_inclusion(&entry, &proof));
+
+        let mut forged = entry;
+        forged.source = DroneId::new(99);
+        assert!(!checkpoint.verify_inclusion(&forged, &proof));
+        assert!(!log.checkpoints()[0].verify_inclusion(&entry, &proof));
+
+        // Unsealed entries have no proof yet
+        log.log(DroneId::new(1), AuditEvent::DroneLeft).unwrap();
+        assert!(log.prove(AUDIT_BATCH_SIZE as u64 + 10).is_err());
+    }
+
+    #[test]
+    fn test_full_log_rotates_by_default() {
+        let crypto = node_crypto();
+        let mut log = filled_log(AUDIT_LOG_CAPACITY);
+
+        log.log(DroneId::new(1), AuditEvent::AuthFailure).unwrap();
+        let recent = log.get_recent(usize::MAX);
+        assert_eq!(recent.len(), AUDIT_LOG_CAPACITY);
+        assert_eq!(recent[0].sequence, 1);
+
+        // The dropped entry is never sealed; the rest still are
+        assert_eq!(log.unsealed_count(), AUDIT_LOG_CAPACITY);
+        log.commit(&crypto).unwrap();
+        assert_eq!(log.checkpoints()[0].first_sequence, 1);
+        assert!(log.prove(1).is_ok());
+    }
+
+    #[test]
+    fn test_full_log_refuses_unexported_entries() {
+        let crypto = node_crypto();
+        let mut log = filled_log_with(AuditOverflow::Refuse, AUDIT_LOG_CAPACITY);
+
+        assert_eq!(
+            log.log(DroneId::new(1), AuditEvent::AuthFailure),
+            Err(SwarmError::BufferFull)
+        );
+
+        // Sealing alone is not enough; the entries must also be exported
+        log.commit(&crypto).unwrap();
+        assert!(log.log(DroneId::new(1), AuditEvent::AuthFailure).is_err());
+
+        let mut export = Vec::new();
+        log.export_jsonl(&mut export).unwrap();
+        log.log(DroneId::new(1), AuditEvent::AuthFailure).unwrap();
+
+        let newest = log.get_recent(1)[0];
+        assert_eq!(newest.sequence, AUDIT_LOG_CAPACITY as u64);
+        assert_eq!(log.get_recent(usize::MAX).len(), 1);
+    }
+
+    #[test]
+    fn test_detections_wait_for_room_in_log() {
+        let mut monitor = SecurityMonitor::new();
+        let mut log = filled_log_with(AuditOverflow::Refuse, AUDIT_LOG_CAPACITY);
+        monitor
+            .analyze_message(DroneId::new(9), &[0; MAX_MESSAGE_SIZE + 1])
+            .unwrap();
+
+        assert_eq!(monitor.audit_detections(&mut log).unwrap(), 0);
+
+        log.commit(&node_crypto()).unwrap();
+        log.export_jsonl(&mut Vec::new()).unwrap();
+        assert_eq!(monitor.audit_detections(&mut log).unwrap(), 1);
+        assert_eq!(
+            log.get_recent(1)[0].event,
+            AuditEvent::IntrusionDetected(Detection::OversizedMessage {
+                len: MAX_MESSAGE_SIZE + 1
+            })
+        );
+    }
+
+    #[test]
+    fn test_export_verifies_offline() {
+        let crypto = node_crypto();
+        let mut log = filled_log(100);
+        log.commit(&crypto).unwrap();
+        log.log(DroneId::new(3), AuditEvent::DroneJoined).unwrap();
+
+        let mut export = Vec::new();
+        assert_eq!(log.export_jsonl(&mut export).unwrap(), 101 + 2);
+        // Nothing new to export
+        assert_eq!(log.export_jsonl(&mut export).unwrap(), 0);
+
+        let summary = AuditLog::verify_export(export.as_slice(), crypto.public_key()).unwrap();
+        assert_eq!(
+            summary,
+            AuditSummary {
+                entries: 101,
+                sealed: 100,
+                checkpoints: 2,
+            }
+        );
+
+        let other = CryptoContext::with_keys([0; 32], [8; 32]);
+        assert!(AuditLog::verify_export(export.as_slice(), other.public_key()).is_err());
+
+        // Editing one entry breaks the chain
+        let text = String::from_utf8(export).unwrap();
+        let tampered = text.replacen("MessageReceived", "AuthSuccess", 1);
+        assert!(AuditLog::verify_export(tampered.as_bytes(), crypto.public_key()).is_err());
+
+        // Dropping one entry leaves a gap
+        let mut lines: Vec<&str> = text.lines().collect();
+        lines.remove(10);
+        let truncated = lines.join("\n");
+        assert!(AuditLog::verify_export(truncated.as_bytes(), crypto.public_key()).is_err());
+    }
+
+    #[test]
+    fn test_export_verifies_in_increments() {
+        let crypto = node_crypto();
+        let mut log = filled_log(10);
+        let mut first = Vec::new();
+        log.export_jsonl(&mut first).unwrap();
+
+        // The checkpoint seals entries exported in the first chunk
+        log.log(DroneId::new(3), AuditEvent::DroneJoined).unwrap();
+        log.commit(&crypto).unwrap();
+        log.log(DroneId::new(3), AuditEvent::DroneLeft).unwrap();
+        let mut second = Vec::new();
+        log.export_jsonl(&mut second).unwrap();
+
+        let mut verifier = AuditVerifier::new(crypto.public_key());
+        verifier.verify_chunk(first.as_slice()).unwrap();
+        assert_eq!(
+            verifier.verify_chunk(second.as_slice()).unwrap(),
+            AuditSummary {
+                entries: 12,
+                sealed: 11,
+                checkpoints: 1,
+            }
+        );
+
+        // Without the first chunk the checkpoint cannot be checked
+        let mut verifier = AuditVerifier::new(crypto.public_key());
+        assert_eq!(
+            verifier.verify_chunk(second.as_slice()),
+            Err(SwarmError::InvalidMessage)
+        );
+
+        // A chunk must continue the chain of the previous one
+        let mut verifier = AuditVerifier::new(crypto.public_key());
+        verifier.verify_chunk(first.as_slice()).unwrap();
+        assert!(verifier.verify_chunk(first.as_slice()).is_err());
+    }
 }
 
 // ═══════════════════════════════════════════════════════════════════════════
@@ -502,4 +932,25 @@ mod integration_tests {
         // Attacker should be banned
         assert!(monitor.is_banned(attacker));
     }
+
+    #[test]
+    fn test_detections_reach_audit_log() {
+        let mut monitor = SecurityMonitor::new();
+        let mut log = AuditLog::new();
+        let spoofer = DroneId::new(7);
+
+        let ids = monitor.ids_mut();
+        ids.observe_position(spoofer, [0.0; 3], 0).unwrap();
+        ids.observe_position(spoofer, [5000.0, 0.0, 0.0], 1000).ok();
+
+        assert_eq!(monitor.audit_detections(&mut log).unwrap(), 1);
+        let entry = log.get_recent(1)[0];
+        assert_eq!(entry.source, spoofer);
+        assert!(matches!(
+            entry.event,
+            AuditEvent::IntrusionDetected(Detection::ImpossibleMovement { .. })
+        ));
+        assert_eq!(monitor.audit_detections(&mut log).unwrap(), 0);
+        assert!(monitor.ids().trust(spoofer) < 0.5);
+    }
 }
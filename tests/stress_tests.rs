@@ -225,6 +225,8 @@ fn stress_network_message_flood() {
         let msg = NetworkMessage::Heartbeat {
             sender: DroneId::new((i % 10) + 2),
             timestamp: i,
+            sequence: i as u32,
+            reception: heapless::Vec::new(),
         };
 
         let addr = NetworkAddress::new([0u8; 16], 8080);
@@ -490,6 +492,7 @@ fn stress_pso_maximum_dimensions() {
 }
 
 #[test]
+#[allow(clippy::type_complexity)]
 fn stress_pso_pathological_cost_function() {
     println!("\n🔥 STRESS TEST: PSO with pathological cost functions");
 
@@ -897,6 +900,8 @@ fn bench_network_message_processing() {
         let msg = NetworkMessage::Heartbeat {
             sender: DroneId::new((i % 10) + 2),
             timestamp: i,
+            sequence: i as u32,
+            reception: heapless::Vec::new(),
         };
 
         let addr = NetworkAddress::new([0u8; 16], 8080);
@@ -50,6 +50,7 @@ mod formation_tests {
     }
 
     #[test]
+    #[allow(clippy::clone_on_copy)]
     fn test_formation_clone() {
         let formation = Formation::Circle { radius: 100 };
         let cloned = formation.clone();
@@ -109,6 +110,7 @@ mod behavior_mode_tests {
     }
 
     #[test]
+    #[allow(clippy::clone_on_copy)]
     fn test_behavior_clone() {
         let behavior = BehaviorMode::SearchRescue;
         let cloned = behavior.clone();
@@ -440,6 +442,7 @@ mod task_allocator_tests {
     }
 
     #[test]
+    #[allow(unused_variables, clippy::assertions_on_constants)]
     fn test_new() {
         let allocator = TaskAllocator::new();
         // Just verify creation
@@ -447,6 +450,7 @@ mod task_allocator_tests {
     }
 
     #[test]
+    #[allow(unused_variables, clippy::assertions_on_constants)]
     fn test_default() {
         let allocator = TaskAllocator::default();
         // Just verify creation
@@ -0,0 +1,177 @@
+//! Tests for mesh time synchronization
+//!
+//! Tests root election, leader pinning, neighbor regression and failover
+
+use drone_swarm_system::time_sync::*;
+use drone_swarm_system::types::*;
+
+fn node(id: u64) -> TimeSyncNode {
+    TimeSyncNode::new(DroneId::new(id), TimeSyncConfig::default())
+}
+
+/// Run `nodes` in lock step for `seconds`, every node hearing every other.
+/// Each local clock is `true_us + offset` scaled by `1 + skew_ppm`.
+fn run(nodes: &mut [(TimeSyncNode, i64, f64)], start_s: u64, seconds: u64) {
+    for step in 0..seconds * 10 {
+        let true_us = (start_s * 10 + step) * 100_000;
+        let local = |offset: i64, skew: f64| {
+            (true_us as i64 + offset + (true_us as f64 * skew * 1e-6) as i64) as u64
+        };
+        let beacons: std::vec::Vec<SyncBeacon> = nodes
+            .iter_mut()
+            .filter_map(|(sync, offset, skew)| sync.tick(local(*offset, *skew)))
+            .collect();
+        for beacon in beacons {
+            for (sync, offset, skew) in nodes.iter_mut() {
+                sync.process_beacon(beacon, local(*offset, *skew));
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod election_tests {
+    use super::*;
+
+    #[test]
+    fn test_lowest_id_becomes_root() {
+        let mut nodes = [
+            (node(7), 40_000_000, 20.0),
+            (node(3), 1_000_000, -30.0),
+            (node(5), 9_000_000, 0.0),
+        ];
+        run(&mut nodes, 0, 10);
+
+        for (sync, _, _) in &nodes {
+            let status = sync.status();
+            assert_eq!(status.root, DroneId::new(3));
+            assert!(status.synchronized);
+        }
+        assert!(nodes[1].0.is_root());
+        assert_eq!(nodes[0].0.status().parent, Some(DroneId::new(3)));
+        assert_eq!(nodes[0].0.status().hops, 1);
+    }
+
+    #[test]
+    fn test_pinned_leader_overrides_lowest_id() {
+        let mut nodes = [
+            (node(1), 0, 0.0),
+            (node(2), 5_000_000, 10.0),
+            (node(4), 2_000_000, -10.0),
+        ];
+        for (sync, offset, _) in nodes.iter_mut() {
+            sync.set_leader(Some(DroneId::new(4)), *offset as u64);
+        }
+        run(&mut nodes, 0, 10);
+
+        assert!(nodes[2].0.is_root());
+        assert_eq!(nodes[0].0.status().root, DroneId::new(4));
+        assert!(nodes[0].0.status().synchronized);
+        assert_eq!(nodes[0].0.status().parent, Some(DroneId::new(4)));
+    }
+
+    #[test]
+    fn test_unsynchronized_until_enough_beacons() {
+        let mut follower = node(2);
+        follower.tick(0);
+        let beacon = SyncBeacon {
+            sender: DroneId::new(1),
+            root: DroneId::new(1),
+            root_seq: 1,
+            hops: 0,
+            global_time_us: 5_000_000,
+            accuracy_us: 0,
+        };
+        follower.process_beacon(beacon, 1_000_000);
+
+        let status = follower.status();
+        assert_eq!(status.root, DroneId::new(1));
+        assert!(!status.synchronized);
+        assert_eq!(status.accuracy_us, u32::MAX);
+        // Unsynchronized drones stay quiet
+        assert!(follower.tick(2_000_000).is_none());
+    }
+}
+
+#[cfg(test)]
+mod accuracy_tests {
+    use super::*;
+
+    #[test]
+    fn test_offset_and_skew_tracked() {
+        let mut nodes = [(node(1), 0, 0.0), (node(2), 123_456_789, 75.0)];
+        run(&mut nodes, 0, 20);
+
+        let follower = &nodes[1].0;
+        let clock = follower.neighbor(DroneId::new(1)).unwrap();
+        assert!((clock.skew_ppm() + 75.0).abs() < 1.0);
+
+        // Compare both drones' swarm time at the same true instant
+        let true_us = 20_000_000u64;
+        let local = (true_us as i64 + 123_456_789 + (true_us as f64 * 75e-6) as i64) as u64;
+        let error = follower.swarm_time_us_at(local) as i64 - true_us as i64;
+        assert!(error.unsigned_abs() < 100, "error {error} us");
+        assert!(follower.status().accuracy_us < 100);
+    }
+
+    #[test]
+    fn test_outlier_resets_neighbor() {
+        let mut follower = node(2);
+        for i in 1..=4u64 {
+            let beacon = SyncBeacon {
+                sender: DroneId::new(1),
+                root: DroneId::new(1),
+                root_seq: i as u32,
+                hops: 0,
+                global_time_us: i * 1_000_000,
+                accuracy_us: 0,
+            };
+            follower.process_beacon(beacon, i * 1_000_000);
+        }
+        // The neighbor's clock jumps by a second
+        let jump = SyncBeacon {
+            sender: DroneId::new(1),
+            root: DroneId::new(1),
+            root_seq: 5,
+            hops: 0,
+            global_time_us: 6_000_000,
+            accuracy_us: 0,
+        };
+        follower.process_beacon(jump, 5_000_000);
+
+        assert_eq!(follower.statistics().outliers, 1);
+        assert_eq!(follower.neighbor(DroneId::new(1)).unwrap().entries(), 1);
+        assert!(!follower.status().synchronized);
+    }
+}
+
+#[cfg(test)]
+mod failover_tests {
+    use super::*;
+
+    #[test]
+    fn test_new_root_keeps_swarm_time_continuous() {
+        let mut nodes = vec![
+            (node(1), 0, 0.0),
+            (node(2), 3_000_000, 50.0),
+            (node(3), 8_000_000, -50.0),
+        ];
+        run(&mut nodes, 0, 10);
+
+        // Root 1 disappears; drone 2 takes over after the root timeout
+        nodes.remove(0);
+        run(&mut nodes, 10, 15);
+
+        let true_us = 25_000_000u64;
+        for (sync, offset, skew) in &nodes {
+            let status = sync.status();
+            assert_eq!(status.root, DroneId::new(2));
+            assert!(status.synchronized);
+
+            // Drone 1 defined swarm time as true time; it carries on
+            let local = (true_us as i64 + offset + (true_us as f64 * skew * 1e-6) as i64) as u64;
+            let error = sync.swarm_time_us_at(local) as i64 - true_us as i64;
+            assert!(error.unsigned_abs() < 2_000, "error {error} us");
+        }
+    }
+}
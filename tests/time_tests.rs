@@ -255,6 +255,7 @@ mod public_api_tests {
     }
 
     #[test]
+    #[allow(unused_comparisons, clippy::absurd_extreme_comparisons)]
     fn test_high_precision_timing() {
         // Measure a very short operation with microsecond precision
         let start = get_time_us();
@@ -278,6 +279,7 @@ mod time_source_trait_tests {
 
     /// Test that TimeSource trait is object-safe (can be used with dyn)
     #[test]
+    #[allow(unused_comparisons, clippy::absurd_extreme_comparisons)]
     fn test_time_source_is_object_safe() {
         fn use_time_source(source: &dyn TimeSource) -> u64 {
             source.get_time_ms()
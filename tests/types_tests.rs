@@ -32,6 +32,7 @@ mod drone_id_tests {
     }
 
     #[test]
+    #[allow(clippy::clone_on_copy)]
     fn test_drone_id_clone() {
         let id1 = DroneId::new(999);
         let id2 = id1.clone();
@@ -187,6 +188,7 @@ mod position_tests {
     }
 
     #[test]
+    #[allow(clippy::clone_on_copy)]
     fn test_position_clone() {
         let pos1 = Position {
             x: 1.0,
@@ -277,6 +279,7 @@ mod velocity_tests {
     }
 
     #[test]
+    #[allow(clippy::clone_on_copy)]
     fn test_velocity_clone() {
         let vel1 = Velocity {
             vx: 5.5,
@@ -342,6 +345,7 @@ mod drone_state_tests {
     }
 
     #[test]
+    #[allow(clippy::clone_on_copy)]
     fn test_drone_state_clone() {
         let state1 = DroneState {
             id: DroneId::new(3),
@@ -385,6 +389,7 @@ mod mission_status_tests {
     }
 
     #[test]
+    #[allow(clippy::clone_on_copy)]
     fn test_mission_status_clone() {
         let status1 = MissionStatus::Active;
         let status2 = status1.clone();
@@ -401,6 +406,7 @@ mod mission_status_tests {
     }
 
     #[test]
+    #[allow(clippy::clone_on_copy)]
     fn test_all_mission_statuses() {
         let statuses = [
             MissionStatus::Idle,
@@ -541,6 +547,7 @@ mod swarm_error_tests {
     }
 
     #[test]
+    #[allow(clippy::clone_on_copy)]
     fn test_swarm_error_clone() {
         let error1 = SwarmError::NetworkError;
         let error2 = error1.clone();
@@ -549,6 +556,7 @@ mod swarm_error_tests {
     }
 
     #[test]
+    #[allow(clippy::unnecessary_literal_unwrap)]
     fn test_result_type_ok() {
         let result: Result<u32> = Ok(42);
         assert!(result.is_ok());
@@ -556,6 +564,7 @@ mod swarm_error_tests {
     }
 
     #[test]
+    #[allow(clippy::unnecessary_literal_unwrap)]
     fn test_result_type_err() {
         let result: Result<u32> = Err(SwarmError::Timeout);
         assert!(result.is_err());
@@ -599,6 +608,7 @@ mod network_address_tests {
     }
 
     #[test]
+    #[allow(clippy::clone_on_copy)]
     fn test_network_address_clone() {
         let addr1 = NetworkAddress::new([0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 192, 168, 1, 1], 3000);
         let addr2 = addr1.clone();
@@ -640,6 +650,7 @@ mod security_level_tests {
     }
 
     #[test]
+    #[allow(clippy::clone_on_copy)]
     fn test_security_level_clone() {
         let level1 = SecurityLevel::Sensitive;
         let level2 = level1.clone();
@@ -675,6 +686,7 @@ mod task_priority_tests {
     }
 
     #[test]
+    #[allow(clippy::clone_on_copy)]
     fn test_task_priority_clone() {
         let priority1 = TaskPriority::High;
         let priority2 = priority1.clone();
@@ -10,6 +10,7 @@ pub struct DroneSwarmApp {
     /// Simulation state containing all visualizable data
     pub state: SimulationState,
     /// Show the settings window
+    #[allow(dead_code)]
     show_settings: bool,
     /// Show the about window
     show_about: bool,
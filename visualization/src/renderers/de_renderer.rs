@@ -11,7 +11,7 @@ where
     let center = world_to_screen(Pos2::ZERO);
 
     // Draw crossover visualization for active trials
-    if state.iteration % 2 == 0 {
+    if state.iteration.is_multiple_of(2) {
         // Show crossover happening
         for individual in &state.population {
             if individual.is_trial {
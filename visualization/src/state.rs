@@ -112,7 +112,7 @@ impl SimulationState {
             match demo.current_scenario {
                 DemoScenario::FormationShowcase => {
                     // Cycle through formations every 200 steps
-                    if demo.step % 200 == 0 {
+                    if demo.step.is_multiple_of(200) {
                         demo.formation_index = (demo.formation_index + 1) % 5;
                         Some(DemoAction::ChangeFormation(demo.formation_index))
                     } else if demo.step > 1000 {
@@ -151,7 +151,7 @@ impl SimulationState {
                     }
                 }
                 DemoScenario::ScaleTest => {
-                    if demo.step % 100 == 0 && self.formation_params.drone_count < 100 {
+                    if demo.step.is_multiple_of(100) && self.formation_params.drone_count < 100 {
                         Some(DemoAction::IncreaseDrones)
                     } else if demo.step > 500 {
                         demo.step = 0;
@@ -366,7 +366,7 @@ impl SimulationState {
             }
 
             // Drain battery slowly
-            if time_step % 100 == 0 && drone.battery > 0 {
+            if time_step.is_multiple_of(100) && drone.battery > 0 {
                 drone.battery = drone.battery.saturating_sub(1);
             }
 
@@ -488,6 +488,7 @@ pub struct DroneVisual {
     pub trail: Vec<Pos2>,
 }
 
+#[allow(dead_code)]
 #[derive(Clone, Copy, Debug, PartialEq)]
 pub enum DroneStatus {
     Idle,
@@ -531,6 +532,7 @@ impl Default for FormationParams {
 
 // ============ Algorithm Types ============
 
+#[allow(clippy::upper_case_acronyms)]
 #[derive(Clone, Copy, Debug, PartialEq)]
 pub enum AlgorithmType {
     PSO,
@@ -879,7 +881,7 @@ impl GWOVisualState {
         }
 
         // Get leaders
-        self.alpha = self.wolves.get(0).cloned();
+        self.alpha = self.wolves.first().cloned();
         self.beta = self.wolves.get(1).cloned();
         self.delta = self.wolves.get(2).cloned();
 
@@ -969,6 +971,7 @@ pub struct FederatedNode {
     pub id: usize,
     pub position: Pos2,
     pub local_weights: Vec<f32>,
+    #[allow(dead_code)]
     pub contribution: f32,
     pub is_selected: bool,
     pub training_progress: f32,
@@ -977,8 +980,6 @@ pub struct FederatedNode {
 impl FederatedVisualState {
     pub fn new(node_count: usize) -> Self {
         let mut nodes = Vec::new();
-        let bounds = 80.0;
-
         for i in 0..node_count {
             let angle = (i as f32 / node_count as f32) * std::f32::consts::TAU;
             let radius = 60.0;
@@ -1020,7 +1021,7 @@ impl FederatedVisualState {
         }
 
         // Aggregate models
-        if self.round % 10 == 0 {
+        if self.round.is_multiple_of(10) {
             let selected: Vec<&FederatedNode> =
                 self.nodes.iter().filter(|n| n.is_selected).collect();
             if !selected.is_empty() {
@@ -1074,6 +1075,7 @@ pub enum NodeState {
     Leader,
 }
 
+#[allow(dead_code)]
 #[derive(Clone, Debug)]
 pub struct LogEntry {
     pub term: u32,
@@ -1089,6 +1091,7 @@ pub struct ConsensusMessage {
     pub progress: f32,
 }
 
+#[allow(dead_code)]
 #[derive(Clone, Copy, Debug, PartialEq)]
 pub enum MessageType {
     RequestVote,
@@ -23,6 +23,7 @@ pub mod drone {
     pub const EMERGENCY: Color32 = Color32::from_rgb(255, 80, 80);
     pub const FAILED: Color32 = Color32::from_rgb(80, 80, 80);
 
+    #[allow(dead_code)]
     pub const TRAIL: Color32 = Color32::from_rgba_premultiplied(100, 150, 255, 80);
     pub const VELOCITY: Color32 = Color32::from_rgb(255, 255, 100);
     pub const TARGET: Color32 = Color32::from_rgba_premultiplied(255, 100, 100, 100);
@@ -109,5 +110,6 @@ pub mod graph {
 
     pub const COST_LINE: Color32 = Color32::from_rgb(100, 200, 255);
     pub const FITNESS_LINE: Color32 = Color32::from_rgb(255, 150, 100);
+    #[allow(dead_code)]
     pub const GRID: Color32 = Color32::from_rgba_premultiplied(50, 50, 60, 100);
 }